@@ -8,7 +8,20 @@ pub mod test_utils {
     use lazy_static::lazy_static;
     use tokio::sync::Mutex;
 
+    use crate::models::config_model::Config;
+
     lazy_static! {
         pub static ref MEMORY_MODE_TEST_MUTEX: Mutex<()> = Mutex::new(());
     }
+
+    /// [`Config::default`] as a TCP service forward with a generated alias,
+    /// which passes the validation run when configs are stored.
+    pub fn valid_config() -> Config {
+        Config {
+            workload_type: Some("service".to_string()),
+            protocol: "tcp".to_string(),
+            alias: None,
+            ..Config::default()
+        }
+    }
 }
@@ -0,0 +1,15 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// A named set of configs that can be started and stopped together. A config
+/// can belong to any number of groups.
+#[derive(Clone, Deserialize, PartialEq, Serialize, Debug, Default)]
+pub struct ConfigGroup {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub config_ids: Vec<i64>,
+}
@@ -0,0 +1,93 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::models::config_model::Config;
+
+/// Where a config change came from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChangeSource {
+    Gui,
+    Tui,
+    Import,
+    GitSync,
+    #[default]
+    Unknown,
+}
+
+impl ConfigChangeSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConfigChangeSource::Gui => "gui",
+            ConfigChangeSource::Tui => "tui",
+            ConfigChangeSource::Import => "import",
+            ConfigChangeSource::GitSync => "git_sync",
+            ConfigChangeSource::Unknown => "unknown",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "gui" => ConfigChangeSource::Gui,
+            "tui" => ConfigChangeSource::Tui,
+            "import" => ConfigChangeSource::Import,
+            "git_sync" => ConfigChangeSource::GitSync,
+            _ => ConfigChangeSource::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChangeAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+}
+
+impl ConfigChangeAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConfigChangeAction::Create => "create",
+            ConfigChangeAction::Update => "update",
+            ConfigChangeAction::Delete => "delete",
+            ConfigChangeAction::Restore => "restore",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(ConfigChangeAction::Create),
+            "update" => Some(ConfigChangeAction::Update),
+            "delete" => Some(ConfigChangeAction::Delete),
+            "restore" => Some(ConfigChangeAction::Restore),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded change of a config. `before` is empty for creations and
+/// `after` for deletions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigRevision {
+    pub id: i64,
+    pub config_id: i64,
+    pub action: ConfigChangeAction,
+    pub source: ConfigChangeSource,
+    pub label: String,
+    pub before: Option<Config>,
+    pub after: Option<Config>,
+    /// Unix timestamp in seconds.
+    pub changed_at: i64,
+}
+
+impl ConfigRevision {
+    /// The config as it was right after this change, or as it was when it
+    /// was deleted. Restoring the revision brings this version back.
+    pub fn snapshot(&self) -> Option<&Config> {
+        self.after.as_ref().or(self.before.as_ref())
+    }
+}
@@ -143,6 +143,102 @@ pub struct Config {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ingress_annotations: Option<String>,
+    /// Groups the config belongs to. `None`, a document without a `groups`
+    /// key, keeps the stored memberships when an existing config is written.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<String>>,
+    /// What to do when `local_port` is already bound: `fail` (the default),
+    /// `next` for the next free port or `random` for any free port.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_conflict_policy: Option<String>,
+    /// User to impersonate on every API request made for this config.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonate_user: Option<String>,
+    /// Groups to impersonate along with `impersonate_user`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub impersonate_groups: Vec<String>,
+    /// How to choose among the ready pods of a service or pod workload.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pod_selection: Option<PodSelectionPreferences>,
+    /// What to do with open connections when the target pod changes: `keep`
+    /// (default), `drain` or `close`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drain_policy: Option<String>,
+    /// Seconds `drain` keeps connections on the previous pod.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drain_timeout_secs: Option<u64>,
+    /// Forward through a kftray-server ephemeral container that reaches the
+    /// remote port on the pod's localhost: `auto` when no container declares
+    /// the port, or `always`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_container: Option<String>,
+    /// Relay pod used by tcp proxies: `dedicated`, a pod per config (the
+    /// default), or `shared`, one multiplexed pod per namespace for each
+    /// running kftray instance.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_relay: Option<String>,
+}
+
+/// Preferences for picking a pod when several are ready. Exclusions are
+/// applied first, then the remaining pods are ranked by node, zone,
+/// annotation and age. Without preferences the first ready pod is used.
+#[derive(Clone, Deserialize, PartialEq, Eq, Serialize, Debug, Default)]
+pub struct PodSelectionPreferences {
+    /// Skip pods that are being deleted, e.g. during a rollout.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub exclude_terminating: bool,
+    /// Avoid pods with a container that restarted within this many seconds.
+    /// They are still used when no other pod is ready.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_grace_secs: Option<u64>,
+    /// Container that must be ready in the chosen pod.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_container: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_node: Option<String>,
+    /// Matched against the `topology.kubernetes.io/zone` label of the node.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_zone: Option<String>,
+    /// `key` or `key=value` annotation carried by preferred pods.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_annotation: Option<String>,
+    /// `newest` or `oldest`, by pod creation time.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefer_age: Option<String>,
+}
+
+impl PodSelectionPreferences {
+    /// Whether any preference is set, i.e. selection differs from picking the
+    /// first ready pod.
+    pub fn is_set(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Whether pod annotations must be kept by watchers.
+    pub fn needs_annotations(&self) -> bool {
+        self.preferred_annotation.is_some()
+    }
+
+    /// Whether container statuses must be kept by watchers.
+    pub fn needs_container_statuses(&self) -> bool {
+        self.restart_grace_secs.is_some() || self.required_container.is_some()
+    }
 }
 
 impl Default for Config {
@@ -173,6 +269,15 @@ impl Default for Config {
             cert_issuer_kind: None,
             ingress_class: None,
             ingress_annotations: None,
+            groups: None,
+            port_conflict_policy: None,
+            impersonate_user: None,
+            impersonate_groups: Vec::new(),
+            pod_selection: None,
+            drain_policy: None,
+            drain_timeout_secs: None,
+            debug_container: None,
+            proxy_relay: None,
         }
     }
 }
@@ -221,6 +326,10 @@ impl Config {
             self.domain_enabled = None;
         }
 
+        if self.pod_selection.as_ref().is_some_and(|p| !p.is_set()) {
+            self.pod_selection = None;
+        }
+
         if self.http_logs_enabled == Some(true) {
             if self.http_logs_max_file_size == Some(10 * 1024 * 1024) {
                 self.http_logs_max_file_size = None;
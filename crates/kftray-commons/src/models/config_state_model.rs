@@ -9,6 +9,10 @@ pub struct ConfigState {
     pub config_id: i64,
     pub is_running: bool,
     pub process_id: Option<u32>,
+    /// Local port the running forward is bound to when its
+    /// `port_conflict_policy` moved it off the configured `local_port`.
+    #[serde(default)]
+    pub local_port: Option<u16>,
 }
 
 impl ConfigState {
@@ -18,6 +22,7 @@ impl ConfigState {
             config_id,
             is_running,
             process_id: Some(std::process::id()),
+            local_port: None,
         }
     }
 
@@ -27,6 +32,7 @@ impl ConfigState {
             config_id,
             is_running,
             process_id: None,
+            local_port: None,
         }
     }
 }
@@ -0,0 +1,124 @@
+use std::net::IpAddr;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// The proxy kftray uses for the API server of one kubeconfig context. It
+/// takes precedence over a `proxy-url` in the kubeconfig, so kubeconfigs
+/// regenerated by other tools don't need to be edited.
+#[derive(Clone, Deserialize, PartialEq, Eq, Serialize, Debug, Default)]
+pub struct ContextProxy {
+    pub context: String,
+    /// `http`, `https`, `socks5` or `socks5h` URL of the proxy.
+    pub proxy_url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Name of the keyring entry holding the proxy password, if any.
+    #[serde(default)]
+    pub credentials_ref: Option<String>,
+    /// Hosts reached without the proxy: `*`, exact host names or IPs, domain
+    /// suffixes like `.corp.example.com`, and CIDR ranges like `10.0.0.0/8`.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ContextProxy {
+    /// Whether `host` matches one of the `no_proxy` rules. Brackets around
+    /// IPv6 addresses are ignored.
+    pub fn bypasses(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let ip = host.parse::<IpAddr>().ok();
+
+        self.no_proxy.iter().any(|rule| {
+            let rule = rule.trim().to_ascii_lowercase();
+            if rule.is_empty() {
+                return false;
+            }
+            if rule == "*" {
+                return true;
+            }
+            if let Some((network, prefix)) = rule.split_once('/') {
+                return ip.is_some_and(|ip| cidr_contains(network, prefix, ip));
+            }
+            if let Ok(rule_ip) = rule.parse::<IpAddr>() {
+                return ip == Some(rule_ip);
+            }
+
+            let domain = rule.trim_start_matches("*.").trim_start_matches('.');
+            host == domain || host.ends_with(&format!(".{domain}"))
+        })
+    }
+}
+
+fn cidr_contains(network: &str, prefix: &str, ip: IpAddr) -> bool {
+    let (Ok(network), Ok(prefix)) = (network.parse::<IpAddr>(), prefix.parse::<u32>()) else {
+        return false;
+    };
+
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy(no_proxy: &[&str]) -> ContextProxy {
+        ContextProxy {
+            context: "prod".to_string(),
+            proxy_url: "http://proxy.corp:3128".to_string(),
+            no_proxy: no_proxy.iter().map(|rule| rule.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bypasses_domains_and_hosts() {
+        let proxy = proxy(&[".corp.example.com", "kubernetes.local", "*.internal"]);
+
+        assert!(proxy.bypasses("api.corp.example.com"));
+        assert!(proxy.bypasses("corp.example.com"));
+        assert!(proxy.bypasses("KUBERNETES.local"));
+        assert!(proxy.bypasses("eks.internal"));
+        assert!(!proxy.bypasses("notcorp.example.com"));
+        assert!(!proxy.bypasses("api.example.com"));
+    }
+
+    #[test]
+    fn test_bypasses_ips_and_cidrs() {
+        let proxy = proxy(&["10.0.0.0/8", "192.168.1.10", "fd00::/8"]);
+
+        assert!(proxy.bypasses("10.20.30.40"));
+        assert!(proxy.bypasses("192.168.1.10"));
+        assert!(proxy.bypasses("[fd12::1]"));
+        assert!(!proxy.bypasses("11.0.0.1"));
+        assert!(!proxy.bypasses("192.168.1.11"));
+        assert!(!proxy.bypasses("api.example.com"));
+    }
+
+    #[test]
+    fn test_bypasses_wildcard_and_empty() {
+        assert!(proxy(&["*"]).bypasses("anything.example.com"));
+        assert!(!proxy(&[]).bypasses("anything.example.com"));
+        assert!(!proxy(&[" "]).bypasses("anything.example.com"));
+        assert!(proxy(&["0.0.0.0/0"]).bypasses("8.8.8.8"));
+    }
+}
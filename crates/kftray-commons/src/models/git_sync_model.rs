@@ -0,0 +1,116 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::models::config_model::Config;
+use crate::utils::git_provider::GitProvider;
+
+/// A Git source that is polled for config changes. Changes are never applied
+/// directly; each check produces a [`SyncPreview`] that has to be approved.
+#[derive(Clone, Deserialize, PartialEq, Serialize, Debug, Default)]
+pub struct GitSyncSubscription {
+    pub id: Option<i64>,
+    pub name: String,
+    pub repo_url: String,
+    pub config_path: String,
+    #[serde(default)]
+    pub git_ref: Option<String>,
+    #[serde(default)]
+    pub provider: Option<GitProvider>,
+    /// Name of the keyring entry holding the access token, if any.
+    #[serde(default)]
+    pub credentials_ref: Option<String>,
+    #[serde(default)]
+    pub use_system_credentials: bool,
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+    #[serde(default)]
+    pub ca_bundle_path: Option<String>,
+    /// Polling interval in seconds. `0` means the subscription is only
+    /// checked on demand.
+    #[serde(default)]
+    pub interval_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub last_commit_sha: Option<String>,
+    /// Sync keys of the configs applied from this source by the last sync,
+    /// used to detect configs removed upstream.
+    #[serde(default)]
+    pub applied_keys: Vec<String>,
+    #[serde(default)]
+    pub last_checked_at: Option<i64>,
+    #[serde(default)]
+    pub last_synced_at: Option<i64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl GitSyncSubscription {
+    pub fn is_due(&self, now: i64) -> bool {
+        self.enabled
+            && self.interval_secs > 0
+            && self
+                .last_checked_at
+                .is_none_or(|checked| now - checked >= self.interval_secs as i64)
+    }
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize, Debug)]
+pub struct ConfigChange {
+    pub key: String,
+    pub before: Config,
+    pub after: Config,
+    pub fields: Vec<String>,
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize, Debug, Default)]
+pub struct ConfigDiff {
+    pub added: Vec<Config>,
+    pub removed: Vec<Config>,
+    pub changed: Vec<ConfigChange>,
+    pub unchanged: usize,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Ids of the stored configs the diff removes.
+    pub fn removed_config_ids(&self) -> Vec<i64> {
+        self.removed.iter().filter_map(|config| config.id).collect()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} removed, {} changed, {} unchanged",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len(),
+            self.unchanged
+        )
+    }
+}
+
+#[derive(Clone, Deserialize, PartialEq, Serialize, Debug)]
+pub struct SyncPreview {
+    pub subscription_id: i64,
+    pub commit_sha: String,
+    pub diff: ConfigDiff,
+    /// Sync keys of every config in the fetched file.
+    pub incoming_keys: Vec<String>,
+}
+
+/// Outcome of applying a [`SyncPreview`].
+#[derive(Clone, Deserialize, PartialEq, Serialize, Debug, Default)]
+pub struct SyncApplyResult {
+    /// Existing configs whose definition changed; running forwards for them
+    /// need a restart.
+    pub changed_config_ids: Vec<i64>,
+    pub removed_config_ids: Vec<i64>,
+    pub added: usize,
+}
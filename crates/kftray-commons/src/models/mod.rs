@@ -1,5 +1,9 @@
+pub mod config_group_model;
+pub mod config_history_model;
 pub mod config_model;
 pub mod config_state_model;
+pub mod context_proxy_model;
+pub mod git_sync_model;
 pub mod hostfile;
 pub mod http_logs_config_model;
 pub mod response;
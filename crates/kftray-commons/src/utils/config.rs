@@ -1,12 +1,18 @@
-use futures::stream::StreamExt;
 use log::{
     error,
     info,
+    warn,
 };
 use portpicker::pick_unused_port;
-use serde_json::json;
+use serde_json::Value as JsonValue;
+use sqlx::query::Query;
+use sqlx::sqlite::{
+    SqliteArguments,
+    SqliteRow,
+};
 use sqlx::{
     Row,
+    Sqlite,
     SqlitePool,
 };
 
@@ -16,20 +22,234 @@ use crate::db::{
 };
 use crate::hostsfile::HostsFile;
 use crate::migration::migrate_configs;
+use crate::models::config_history_model::{
+    ConfigChangeAction,
+    ConfigChangeSource,
+};
 use crate::models::config_model::Config;
+use crate::utils::config_format::{
+    ConfigFormat,
+    parse_document,
+    render_configs,
+};
+use crate::utils::config_group::set_config_groups;
+use crate::utils::config_history::{
+    record_config_change,
+    record_config_deletions,
+    with_default_change_source,
+};
+use crate::utils::config_schema::validate_config_entry;
+use crate::utils::config_template::expand_config_document;
+use crate::utils::config_validation::{
+    IssueSeverity,
+    validate_config_with_pool,
+    validate_configs,
+};
 use crate::utils::db_mode::{
     DatabaseManager,
     DatabaseMode,
 };
 use crate::utils::error::DbError;
+use crate::utils::local_port::local_endpoint;
+
+/// `Config` fields stored in typed `configs` columns. Every other field is kept
+/// in the `data` JSON column, so new optional fields need no migration.
+pub(crate) const CONFIG_COLUMNS: &[&str] = &[
+    "service",
+    "namespace",
+    "local_port",
+    "remote_port",
+    "context",
+    "workload_type",
+    "protocol",
+    "remote_address",
+    "local_address",
+    "auto_loopback_address",
+    "alias",
+    "domain_enabled",
+    "kubeconfig",
+    "target",
+];
+
+const TEXT_COLUMNS: &[&str] = &[
+    "service",
+    "namespace",
+    "context",
+    "workload_type",
+    "protocol",
+    "remote_address",
+    "local_address",
+    "alias",
+    "kubeconfig",
+    "target",
+];
+
+pub(crate) const SELECT_CONFIGS: &str = "SELECT id, service, namespace, local_port, remote_port, context, \
+     workload_type, protocol, remote_address, local_address, auto_loopback_address, alias, \
+     domain_enabled, kubeconfig, target, data, (SELECT json_group_array(g.name) FROM \
+     config_group_members m JOIN config_groups g ON g.id = m.group_id \
+     WHERE m.config_id = configs.id) AS groups FROM configs";
+
+const CONFIG_VALUES: &str = "service, namespace, local_port, remote_port, context, workload_type, \
+     protocol, remote_address, local_address, auto_loopback_address, alias, domain_enabled, \
+     kubeconfig, target, data";
+
+/// Reassembles the JSON form of a `configs` row. Typed columns win over the
+/// JSON extras; a NULL (or empty `namespace`/`protocol`) column falls back to
+/// the value in `data`, which keeps rows written before the columns existed
+/// readable.
+pub(crate) fn config_value_from_row(row: &SqliteRow) -> Result<(i64, JsonValue), String> {
+    let id: i64 = row.try_get("id").map_err(|e| e.to_string())?;
+    let data: String = row.try_get("data").map_err(|e| e.to_string())?;
+
+    let mut value: JsonValue =
+        serde_json::from_str(&data).map_err(|e| format!("Failed to parse config: {e}"))?;
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| format!("Config {id} data is not a JSON object"))?;
+
+    for column in TEXT_COLUMNS {
+        let text: Option<String> = row.try_get(*column).map_err(|e| e.to_string())?;
+        if let Some(text) = text.filter(|text| !text.is_empty()) {
+            object.insert(column.to_string(), JsonValue::from(text));
+        }
+    }
+    for column in ["local_port", "remote_port"] {
+        let port: Option<i64> = row.try_get(column).map_err(|e| e.to_string())?;
+        if let Some(port) = port {
+            object.insert(column.to_string(), JsonValue::from(port));
+        }
+    }
+    for column in ["auto_loopback_address", "domain_enabled"] {
+        let flag: Option<bool> = row.try_get(column).map_err(|e| e.to_string())?;
+        if let Some(flag) = flag {
+            object.insert(column.to_string(), JsonValue::from(flag));
+        }
+    }
+
+    let groups: Option<String> = row.try_get("groups").map_err(|e| e.to_string())?;
+    let mut groups: Vec<String> = match groups {
+        Some(groups) => serde_json::from_str(&groups).map_err(|e| e.to_string())?,
+        None => Vec::new(),
+    };
+    if !groups.is_empty() {
+        groups.sort();
+        object.insert("groups".to_string(), JsonValue::from(groups));
+    }
+
+    Ok((id, value))
+}
+
+pub(crate) fn config_from_row(row: &SqliteRow) -> Result<Config, String> {
+    let (id, value) = config_value_from_row(row)?;
+    let mut config: Config =
+        serde_json::from_value(value).map_err(|_| "Failed to decode config".to_string())?;
+    config.id = Some(id);
+    Ok(config)
+}
+
+fn configs_from_rows(rows: &[SqliteRow]) -> Result<Vec<Config>, String> {
+    rows.iter().map(config_from_row).collect()
+}
+
+/// Serializes the fields of `config` that have no typed column.
+pub(crate) fn config_extras(config: &Config) -> Result<String, String> {
+    let mut value = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove("id");
+        object.remove("groups");
+        for column in CONFIG_COLUMNS {
+            object.remove(*column);
+        }
+    }
+    Ok(value.to_string())
+}
+
+/// Binds `config` to the `?1`..`?15` placeholders of a statement listing
+/// the columns in `CONFIG_VALUES` order.
+fn bind_config<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>, config: &Config, extras: String,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query
+        .bind(config.service.clone())
+        .bind(config.namespace.clone())
+        .bind(config.local_port.map(i64::from))
+        .bind(config.remote_port.map(i64::from))
+        .bind(config.context.clone())
+        .bind(config.workload_type.clone())
+        .bind(config.protocol.clone())
+        .bind(config.remote_address.clone())
+        .bind(config.local_address.clone())
+        .bind(config.auto_loopback_address)
+        .bind(config.alias.clone())
+        .bind(config.domain_enabled)
+        .bind(config.kubeconfig.clone())
+        .bind(config.target.clone())
+        .bind(extras)
+}
+
+fn insert_config_sql(with_id: bool) -> String {
+    if with_id {
+        format!(
+            "INSERT INTO configs ({CONFIG_VALUES}, id) VALUES \
+             (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)"
+        )
+    } else {
+        format!(
+            "INSERT INTO configs ({CONFIG_VALUES}) VALUES \
+             (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)"
+        )
+    }
+}
+
+const UPDATE_CONFIG_SQL: &str = "UPDATE configs SET service = ?1, namespace = ?2, \
+     local_port = ?3, remote_port = ?4, context = ?5, workload_type = ?6, protocol = ?7, \
+     remote_address = ?8, local_address = ?9, auto_loopback_address = ?10, alias = ?11, \
+     domain_enabled = ?12, kubeconfig = ?13, target = ?14, data = ?15 WHERE id = ?16";
+
+/// Reads the config `id` on an already acquired connection.
+pub(crate) async fn fetch_config(
+    conn: &mut sqlx::SqliteConnection, id: i64,
+) -> Result<Option<Config>, String> {
+    let row = sqlx::query(&format!("{SELECT_CONFIGS} WHERE id = ?1"))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    row.as_ref().map(config_from_row).transpose()
+}
+
+/// Writes every field of `config` to the row `id`. Group memberships are only
+/// replaced when `config.groups` is set.
+pub(crate) async fn write_config_row(
+    conn: &mut sqlx::SqliteConnection, id: i64, config: &Config,
+) -> Result<(), String> {
+    let extras = config_extras(config)?;
+    bind_config(sqlx::query(UPDATE_CONFIG_SQL), config, extras)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    match &config.groups {
+        Some(groups) => set_config_groups(conn, id, groups).await,
+        None => Ok(()),
+    }
+}
 
 pub async fn delete_config_with_pool(id: i64, pool: &SqlitePool) -> Result<(), DbError> {
-    let mut conn = pool.acquire().await?;
+    let mut transaction = pool.begin().await?;
+    let before = fetch_config(&mut transaction, id)
+        .await
+        .map_err(DbError::QueryFailed)?;
     sqlx::query("DELETE FROM configs WHERE id = ?1")
         .bind(id)
-        .execute(&mut *conn)
+        .execute(&mut *transaction)
         .await
         .map_err(|e| DbError::QueryFailed(format!("Failed to delete config: {e}")))?;
+    record_config_deletions(&mut transaction, before.as_slice())
+        .await
+        .map_err(DbError::QueryFailed)?;
+    transaction.commit().await?;
     Ok(())
 }
 
@@ -42,16 +262,29 @@ pub async fn delete_config(id: i64) -> Result<(), String> {
 
 pub async fn delete_configs_with_pool(ids: Vec<i64>, pool: &SqlitePool) -> Result<(), DbError> {
     let mut transaction = pool.begin().await?;
-    for id in ids {
+    delete_config_rows(&mut transaction, &ids).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Deletes the configs `ids` on an already acquired connection and records
+/// the deletions.
+pub(crate) async fn delete_config_rows(
+    conn: &mut sqlx::SqliteConnection, ids: &[i64],
+) -> Result<(), DbError> {
+    for &id in ids {
+        let before = fetch_config(conn, id).await.map_err(DbError::QueryFailed)?;
         sqlx::query("DELETE FROM configs WHERE id = ?1")
             .bind(id)
-            .execute(&mut *transaction)
+            .execute(&mut *conn)
             .await
             .map_err(|e| {
                 DbError::QueryFailed(format!("Failed to delete config with id {id}: {e}"))
             })?;
+        record_config_deletions(conn, before.as_slice())
+            .await
+            .map_err(DbError::QueryFailed)?;
     }
-    transaction.commit().await?;
     Ok(())
 }
 
@@ -63,11 +296,19 @@ pub async fn delete_configs(ids: Vec<i64>) -> Result<(), String> {
 }
 
 pub async fn delete_all_configs_with_pool(pool: &SqlitePool) -> Result<(), DbError> {
-    let mut conn = pool.acquire().await?;
+    let mut transaction = pool.begin().await?;
+    let rows = sqlx::query(SELECT_CONFIGS)
+        .fetch_all(&mut *transaction)
+        .await?;
+    let before = configs_from_rows(&rows).map_err(DbError::DataDecodeFailed)?;
     sqlx::query("DELETE FROM configs")
-        .execute(&mut *conn)
+        .execute(&mut *transaction)
         .await
         .map_err(|e| DbError::QueryFailed(format!("Failed to delete all configs: {e}")))?;
+    record_config_deletions(&mut transaction, &before)
+        .await
+        .map_err(DbError::QueryFailed)?;
+    transaction.commit().await?;
     Ok(())
 }
 
@@ -79,82 +320,112 @@ pub async fn delete_all_configs() -> Result<(), String> {
 }
 
 pub async fn insert_config_with_pool(config: Config, pool: &SqlitePool) -> Result<(), String> {
-    let config = prepare_config(config);
-    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
-
-    create_db_table(pool).await.map_err(|e| e.to_string())?;
-
-    // Validate that file mode won't conflict with memory mode ID range
-    let memory_id_start = std::env::var("KFTRAY_MEMORY_ID_START")
-        .ok()
-        .and_then(|s| s.parse::<i64>().ok())
-        .unwrap_or(MEMORY_ID_START);
-
-    let next_id_row =
-        sqlx::query("SELECT COALESCE(MAX(id), 0) + 1 as next_id FROM configs WHERE id < ?")
-            .bind(memory_id_start)
-            .fetch_one(&mut *conn)
-            .await
-            .map_err(|e| e.to_string())?;
-
-    let next_id: i64 = next_id_row.try_get("next_id").map_err(|e| e.to_string())?;
-
-    if next_id >= memory_id_start {
-        return Err(format!(
-            "ID conflict detected: next file mode ID ({next_id}) would exceed memory mode start ({memory_id_start})"
-        ));
-    }
-
-    let data = json!(config).to_string();
-    let result = sqlx::query("INSERT INTO configs (data) VALUES (?1)")
-        .bind(data)
-        .execute(&mut *conn)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let inserted_id = result.last_insert_rowid();
-    sync_http_logs_config_from_config(&config, inserted_id, pool).await?;
-
-    Ok(())
+    insert_config_with_pool_and_mode(config, pool, DatabaseMode::File).await
 }
 
+/// Inserts `config` once it passes validation. Errors reject the config and
+/// warnings, such as a local address and port shared with a stored config,
+/// are only logged.
 pub(crate) async fn insert_config_with_pool_and_mode(
     config: Config, pool: &SqlitePool, mode: DatabaseMode,
 ) -> Result<(), String> {
-    match mode {
-        DatabaseMode::Memory => {
-            let config = prepare_config(config);
-            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
-            create_db_table(pool).await.map_err(|e| e.to_string())?;
-
-            let next_id = get_next_memory_id(pool).await?;
-            let data = json!(config).to_string();
+    create_db_table(pool).await.map_err(|e| e.to_string())?;
+    validate_config_with_pool(&config, pool)
+        .await?
+        .into_result()?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    insert_config_row(&mut conn, config, mode).await?;
+    Ok(())
+}
 
-            sqlx::query("INSERT INTO configs (id, data) VALUES (?1, ?2)")
+/// Inserts `config` on an already acquired connection, taking the next id of
+/// the `mode` id range, and returns that id.
+async fn insert_config_row(
+    conn: &mut sqlx::SqliteConnection, config: Config, mode: DatabaseMode,
+) -> Result<i64, String> {
+    let alias_generated = config
+        .alias
+        .as_deref()
+        .is_none_or(|alias| alias.trim().is_empty());
+    let mut config = prepare_config(config);
+    if alias_generated {
+        make_generated_alias_unique(conn, &mut config).await?;
+    }
+    let extras = config_extras(&config)?;
+
+    let id = match mode {
+        DatabaseMode::Memory => {
+            let next_id = get_next_memory_id(conn).await?;
+            bind_config(sqlx::query(&insert_config_sql(true)), &config, extras)
                 .bind(next_id)
-                .bind(data)
                 .execute(&mut *conn)
                 .await
                 .map_err(|e| e.to_string())?;
+            next_id
+        }
+        DatabaseMode::File => {
+            // Validate that file mode won't conflict with memory mode ID range
+            let memory_id_start = std::env::var("KFTRAY_MEMORY_ID_START")
+                .ok()
+                .and_then(|s| s.parse::<i64>().ok())
+                .unwrap_or(MEMORY_ID_START);
+
+            let next_id_row =
+                sqlx::query("SELECT COALESCE(MAX(id), 0) + 1 as next_id FROM configs WHERE id < ?")
+                    .bind(memory_id_start)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+            let next_id: i64 = next_id_row.try_get("next_id").map_err(|e| e.to_string())?;
+
+            if next_id >= memory_id_start {
+                return Err(format!(
+                    "ID conflict detected: next file mode ID ({next_id}) would exceed memory mode start ({memory_id_start})"
+                ));
+            }
 
-            sync_http_logs_config_from_config(&config, next_id, pool).await?;
-            Ok(())
+            bind_config(sqlx::query(&insert_config_sql(false)), &config, extras)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?
+                .last_insert_rowid()
         }
-        DatabaseMode::File => insert_config_with_pool(config, pool).await,
+    };
+
+    if let Some(groups) = &config.groups {
+        set_config_groups(conn, id, groups).await?;
     }
+    record_config_creation(conn, id).await?;
+    sync_http_logs_config_from_config(&config, id, conn).await?;
+
+    Ok(id)
 }
 
-const MEMORY_ID_START: i64 = 100000;
+async fn record_config_creation(
+    conn: &mut sqlx::SqliteConnection, config_id: i64,
+) -> Result<(), String> {
+    let created = fetch_config(conn, config_id).await?;
+    record_config_change(
+        conn,
+        config_id,
+        ConfigChangeAction::Create,
+        None,
+        created.as_ref(),
+    )
+    .await
+}
 
-async fn get_next_memory_id(pool: &SqlitePool) -> Result<i64, String> {
-    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+const MEMORY_ID_START: i64 = 100000;
 
+async fn get_next_memory_id(conn: &mut sqlx::SqliteConnection) -> Result<i64, String> {
     let memory_id_start = std::env::var("KFTRAY_MEMORY_ID_START")
         .ok()
         .and_then(|s| s.parse::<i64>().ok())
         .unwrap_or(MEMORY_ID_START);
 
-    // Validate that memory ID range doesn't conflict with existing file-based IDs
+    // Validate that memory ID range doesn't conflict with existing file-based
+    // IDs
     let file_max_row =
         sqlx::query("SELECT COALESCE(MAX(id), 0) as max_id FROM configs WHERE id < ?")
             .bind(memory_id_start)
@@ -191,36 +462,17 @@ pub async fn insert_config(config: Config) -> Result<(), String> {
 
 pub async fn read_configs_with_pool(pool: &SqlitePool) -> Result<Vec<Config>, String> {
     let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
-    let rows = sqlx::query("SELECT id, data FROM configs")
+    fetch_configs(&mut conn).await
+}
+
+/// Reads every config on an already acquired connection.
+async fn fetch_configs(conn: &mut sqlx::SqliteConnection) -> Result<Vec<Config>, String> {
+    let rows = sqlx::query(SELECT_CONFIGS)
         .fetch_all(&mut *conn)
         .await
         .map_err(|e| e.to_string())?;
 
-    let config_results: Vec<Result<Config, String>> = futures::stream::iter(rows.into_iter())
-        .map(|row| {
-            let id: Result<i64, String> = row.try_get("id").map_err(|e| e.to_string());
-            let data: Result<String, String> = row.try_get("data").map_err(|e| e.to_string());
-            async move {
-                let id = id?;
-                let data = data?;
-                let mut config = serde_json::from_str::<Config>(&data)
-                    .map_err(|_| "Failed to decode config".to_string())?;
-                config.id = Some(id);
-                Ok(config)
-            }
-        })
-        .buffer_unordered(8)
-        .collect::<Vec<Result<Config, String>>>()
-        .await;
-
-    let mut configs = Vec::new();
-    for result in config_results {
-        match result {
-            Ok(config) => configs.push(config),
-            Err(e) => return Err(e),
-        }
-    }
-    Ok(configs)
+    configs_from_rows(&rows)
 }
 
 pub async fn read_configs() -> Result<Vec<Config>, String> {
@@ -268,20 +520,13 @@ pub async fn get_configs() -> Result<Vec<Config>, String> {
 
 pub(crate) async fn get_config_with_pool(id: i64, pool: &SqlitePool) -> Result<Config, String> {
     let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
-    let row = sqlx::query("SELECT id, data FROM configs WHERE id = ?1")
+    let row = sqlx::query(&format!("{SELECT_CONFIGS} WHERE id = ?1"))
         .bind(id)
         .fetch_optional(&mut *conn)
         .await
         .map_err(|e| e.to_string())?;
     match row {
-        Some(row) => {
-            let id: i64 = row.try_get("id").map_err(|e| e.to_string())?;
-            let data: String = row.try_get("data").map_err(|e| e.to_string())?;
-            let mut config: Config =
-                serde_json::from_str(&data).map_err(|e| format!("Failed to parse config: {e}"))?;
-            config.id = Some(id);
-            Ok(config)
-        }
+        Some(row) => config_from_row(&row),
         None => Err(format!("No config found with id: {id}")),
     }
 }
@@ -291,24 +536,187 @@ pub async fn get_config(id: i64) -> Result<Config, String> {
     get_config_with_pool(id, &pool).await
 }
 
+pub(crate) async fn find_configs_with_pool(
+    pool: &SqlitePool, filter: &str, params: &[&str],
+) -> Result<Vec<Config>, String> {
+    let sql = format!("{SELECT_CONFIGS} WHERE {filter} ORDER BY id");
+    let mut query = sqlx::query(&sql);
+    for param in params {
+        query = query.bind(*param);
+    }
+
+    let rows = query.fetch_all(pool).await.map_err(|e| e.to_string())?;
+    configs_from_rows(&rows)
+}
+
+/// The local address a stored config binds, as SQL matching
+/// [`local_endpoint`].
+pub(crate) const LOCAL_ADDRESS_SQL: &str = "COALESCE(NULLIF(TRIM(local_address), ''), '127.0.0.1')";
+
+/// Stored configs that bind a fixed local address and port, see
+/// [`local_endpoint`].
+pub(crate) const HAS_LOCAL_ENDPOINT_SQL: &str =
+    "auto_loopback_address = 0 AND local_port IS NOT NULL AND local_port != 0";
+
+/// Other configs that bind the same local address and port as `config`.
+/// Configs using `auto_loopback_address` get a unique address when started
+/// and never conflict.
+pub(crate) async fn find_local_endpoint_conflicts_with_pool(
+    config: &Config, pool: &SqlitePool,
+) -> Result<Vec<Config>, String> {
+    let Some((local_address, local_port)) = local_endpoint(config) else {
+        return Ok(Vec::new());
+    };
+
+    let port = local_port.to_string();
+    let id = config.id.unwrap_or(-1).to_string();
+
+    find_configs_with_pool(
+        pool,
+        &format!(
+            "{HAS_LOCAL_ENDPOINT_SQL} AND local_port = ?1 AND {LOCAL_ADDRESS_SQL} = ?2 AND id != ?3"
+        ),
+        &[&port, local_address.trim(), &id],
+    )
+    .await
+}
+
+/// Other configs named `config.alias`. Configs without an alias get one
+/// generated when stored.
+pub(crate) async fn find_alias_conflicts_with_pool(
+    config: &Config, pool: &SqlitePool,
+) -> Result<Vec<Config>, String> {
+    let Some(alias) = config
+        .alias
+        .as_deref()
+        .map(str::trim)
+        .filter(|alias| !alias.is_empty())
+    else {
+        return Ok(Vec::new());
+    };
+
+    let id = config.id.unwrap_or(-1).to_string();
+    find_configs_with_pool(pool, "alias = ?1 AND id != ?2", &[alias, &id]).await
+}
+
+/// Suffixes an alias generated by [`prepare_config`] until no other stored
+/// config uses it, as configs on different addresses can share a port.
+async fn make_generated_alias_unique(
+    conn: &mut sqlx::SqliteConnection, config: &mut Config,
+) -> Result<(), String> {
+    let Some(base) = config.alias.clone() else {
+        return Ok(());
+    };
+    let id = config.id.unwrap_or(-1);
+
+    for suffix in 1.. {
+        let alias = if suffix == 1 {
+            base.clone()
+        } else {
+            format!("{base}-{suffix}")
+        };
+        let taken = sqlx::query("SELECT 1 FROM configs WHERE alias = ?1 AND id != ?2")
+            .bind(&alias)
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some();
+        if !taken {
+            config.alias = Some(alias);
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Updates `config` once it passes validation, like
+/// [`insert_config_with_pool_and_mode`].
 pub(crate) async fn update_config_with_pool(
     config: Config, pool: &SqlitePool,
 ) -> Result<(), String> {
-    let config = prepare_config(config);
-    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
-    let data = json!(config).to_string();
-    sqlx::query("UPDATE configs SET data = ?1 WHERE id = ?2")
-        .bind(data)
-        .bind(config.id.unwrap())
-        .execute(&mut *conn)
-        .await
-        .map_err(|e| e.to_string())?;
+    validate_config_with_pool(&config, pool)
+        .await?
+        .into_result()?;
+    let mut transaction = pool.begin().await.map_err(|e| e.to_string())?;
+    update_config_row(&mut transaction, config).await?;
+    transaction.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
 
-    if let Some(config_id) = config.id {
-        sync_http_logs_config_from_config(&config, config_id, pool).await?;
+/// Updates the row of `config` on an already acquired connection and records
+/// the change when anything differs.
+pub(crate) async fn update_config_row(
+    conn: &mut sqlx::SqliteConnection, config: Config,
+) -> Result<(), String> {
+    let alias_generated = config
+        .alias
+        .as_deref()
+        .is_none_or(|alias| alias.trim().is_empty());
+    let mut config = prepare_config(config);
+    if alias_generated {
+        make_generated_alias_unique(conn, &mut config).await?;
+    }
+    let id = config
+        .id
+        .ok_or_else(|| "Config id is required".to_string())?;
+    let before = fetch_config(conn, id).await?;
+    write_config_row(conn, id, &config).await?;
+    let after = fetch_config(conn, id).await?;
+    if before != after {
+        record_config_change(
+            conn,
+            id,
+            ConfigChangeAction::Update,
+            before.as_ref(),
+            after.as_ref(),
+        )
+        .await?;
+    }
+
+    sync_http_logs_config_from_config(&config, id, conn).await
+}
+
+/// Writes `config` back under its id, inserting the row again when it was
+/// deleted. Returns the version it replaced.
+pub(crate) async fn write_restored_config(
+    conn: &mut sqlx::SqliteConnection, config: &Config,
+) -> Result<Option<Config>, String> {
+    let id = config
+        .id
+        .ok_or_else(|| "Restored config has no id".to_string())?;
+    let mut config = prepare_config(config.clone());
+    // A revision without groups had none, so restoring it clears them.
+    config.groups.get_or_insert_with(Vec::new);
+    let before = fetch_config(conn, id).await?;
+
+    if before.is_some() {
+        write_config_row(conn, id, &config).await?;
+    } else {
+        let extras = config_extras(&config)?;
+        bind_config(sqlx::query(&insert_config_sql(true)), &config, extras)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        set_config_groups(conn, id, config.groups.as_deref().unwrap_or_default()).await?;
     }
 
-    Ok(())
+    Ok(before)
+}
+
+pub(crate) async fn sync_restored_http_logs_config(
+    config: &Config, pool: &SqlitePool,
+) -> Result<(), String> {
+    match config.id {
+        Some(id) => {
+            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+            sync_http_logs_config_from_config(config, id, &mut conn).await
+        }
+        None => Ok(()),
+    }
 }
 
 pub async fn update_config(config: Config) -> Result<(), String> {
@@ -332,79 +740,7 @@ pub async fn export_configs() -> Result<String, String> {
     export_configs_with_pool(&pool).await
 }
 
-fn validate_imported_config(config: &Config) -> Result<(), String> {
-    if config.namespace.is_empty() {
-        return Err("Namespace is required and cannot be empty".to_string());
-    }
-
-    match config.workload_type.as_deref() {
-        Some("service") => {
-            if config.service.is_none() || config.service.as_ref().unwrap().is_empty() {
-                return Err("Service name is required for service workload type".to_string());
-            }
-        }
-        Some("pod") => {
-            if config.target.is_none() || config.target.as_ref().unwrap().is_empty() {
-                return Err(
-                    "Target (pod label selector) is required for pod workload type".to_string(),
-                );
-            }
-        }
-        Some("proxy") => {
-            if config.remote_address.is_none() || config.remote_address.as_ref().unwrap().is_empty()
-            {
-                return Err("Remote address is required for proxy workload type".to_string());
-            }
-        }
-        Some("expose") => {
-            if config.alias.is_none() || config.alias.as_ref().unwrap().is_empty() {
-                return Err("Alias/Domain is required for expose workload type".to_string());
-            }
-            if config.local_port.is_none() {
-                return Err("Local port is required for expose workload type".to_string());
-            }
-            // Validate cert-manager requirements if enabled
-            if config.exposure_type.as_deref() == Some("public")
-                && config.cert_manager_enabled.unwrap_or(false)
-                && (config.cert_issuer.is_none() || config.cert_issuer.as_ref().unwrap().is_empty())
-            {
-                return Err(
-                    "Certificate issuer is required when cert-manager is enabled".to_string(),
-                );
-            }
-        }
-        Some(workload_type) => {
-            return Err(format!(
-                "Invalid workload type: {workload_type}. Must be 'service', 'pod', 'proxy', or 'expose'"
-            ));
-        }
-        None => {
-            return Err("Workload type is required".to_string());
-        }
-    }
-
-    if let Some(max_file_size) = config.http_logs_max_file_size {
-        if max_file_size == 0 {
-            return Err("HTTP logs max file size must be greater than 0".to_string());
-        }
-        if max_file_size > 100 * 1024 * 1024 {
-            return Err("HTTP logs max file size cannot exceed 100MB".to_string());
-        }
-    }
-
-    if let Some(retention_days) = config.http_logs_retention_days {
-        if retention_days == 0 {
-            return Err("HTTP logs retention days must be greater than 0".to_string());
-        }
-        if retention_days > 365 {
-            return Err("HTTP logs retention days cannot exceed 365 days".to_string());
-        }
-    }
-
-    Ok(())
-}
-
-fn configs_match_identity(existing: &Config, incoming: &Config) -> bool {
+pub(crate) fn configs_match_identity(existing: &Config, incoming: &Config) -> bool {
     if existing.context != incoming.context
         || existing.namespace != incoming.namespace
         || existing.workload_type != incoming.workload_type
@@ -446,6 +782,9 @@ fn configs_are_identical(existing: &Config, incoming: &Config) -> bool {
 
     existing_clone.id = None;
     incoming_clone.id = None;
+    if incoming_clone.groups.is_none() {
+        incoming_clone.groups = existing_clone.groups.clone();
+    }
 
     existing_clone == incoming_clone
 }
@@ -490,16 +829,19 @@ async fn merge_config_with_existing(
 pub(crate) async fn import_configs_with_pool(
     json: String, pool: &SqlitePool,
 ) -> Result<(), String> {
-    let configs = parse_config_json(&json)?;
+    let configs = parse_config_content(&json, None)?;
 
     let existing_configs = read_configs_with_pool(pool).await?;
 
-    for config in configs {
-        validate_imported_config(&config).map_err(|e| format!("Invalid config: {e}"))?;
-        merge_config_with_existing(config, &existing_configs, pool)
-            .await
-            .map_err(|e| format!("Failed to merge config: {e}"))?;
-    }
+    with_default_change_source(ConfigChangeSource::Import, async {
+        for config in configs {
+            merge_config_with_existing(config, &existing_configs, pool)
+                .await
+                .map_err(|e| format!("Failed to merge config: {e}"))?;
+        }
+        Ok::<(), String>(())
+    })
+    .await?;
 
     if let Err(e) = migrate_configs(Some(pool)).await {
         return Err(format!("Error migrating configs: {e}"));
@@ -508,21 +850,60 @@ pub(crate) async fn import_configs_with_pool(
     Ok(())
 }
 
-fn parse_config_json(json: &str) -> Result<Vec<Config>, String> {
-    match serde_json::from_str(json) {
-        Ok(configs) => Ok(configs),
-        Err(e) => {
-            info!("Failed to parse JSON as Vec<Config>: {e}. Trying as single Config.");
-            let config = serde_json::from_str::<Config>(json)
-                .map_err(|e| format!("Failed to parse config: {e}"))?;
-            Ok(vec![config])
+/// Parses an imported config document in JSON, YAML or TOML (detected from
+/// the content when `format` is `None`). Placeholders are expanded, every
+/// entry is checked against the config schema and the import rules, and all
+/// problems are reported together, one `[index].field: problem` per line.
+pub(crate) fn parse_config_content(
+    content: &str, format: Option<ConfigFormat>,
+) -> Result<Vec<Config>, String> {
+    let document =
+        parse_document(content, format).map_err(|e| format!("Failed to parse config: {e}"))?;
+    let entries = expand_config_document(document).map_err(invalid_configs_error)?;
+
+    let mut configs = Vec::with_capacity(entries.len());
+    let mut errors = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let path = format!("[{index}]");
+        let schema_errors = errors.len();
+        validate_config_entry(&entry, &path, &mut errors);
+        if errors.len() > schema_errors {
+            continue;
+        }
+
+        match serde_json::from_value::<Config>(entry) {
+            Ok(config) => configs.push((path, config)),
+            Err(e) => errors.push(format!("{path}: {e}")),
         }
     }
+
+    let (paths, configs): (Vec<String>, Vec<Config>) = configs.into_iter().unzip();
+    for (path, result) in paths.iter().zip(validate_configs(&configs)) {
+        for issue in &result.issues {
+            match issue.severity {
+                IssueSeverity::Error => errors.push(format!("{path}.{issue}")),
+                IssueSeverity::Warning => warn!("Importing config {path}.{issue}"),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(configs)
+    } else {
+        Err(invalid_configs_error(errors))
+    }
+}
+
+fn invalid_configs_error(errors: Vec<String>) -> String {
+    format!("Invalid config:\n{}", errors.join("\n"))
 }
 
+/// Writes `config` over the stored config with the same identity, or inserts
+/// it, and returns the id it is stored under.
 async fn merge_config_with_existing_and_mode(
-    config: Config, existing_configs: &[Config], pool: &SqlitePool, mode: DatabaseMode,
-) -> Result<(), String> {
+    conn: &mut sqlx::SqliteConnection, config: Config, existing_configs: &[Config],
+    mode: DatabaseMode,
+) -> Result<i64, String> {
     if let Some(existing) = existing_configs
         .iter()
         .find(|c| configs_match_identity(c, &config))
@@ -531,9 +912,12 @@ async fn merge_config_with_existing_and_mode(
             "Found matching config ID={}, checking if update needed",
             existing.id.unwrap_or(-1)
         );
+        let id = existing
+            .id
+            .ok_or_else(|| "Stored config has no id".to_string())?;
         if configs_are_identical(existing, &config) {
             info!("Config is identical, skipping");
-            return Ok(());
+            return Ok(id);
         }
 
         info!("Config has changes, updating");
@@ -548,34 +932,26 @@ async fn merge_config_with_existing_and_mode(
             updated_config.local_port = existing.local_port;
         }
 
-        update_config_with_pool(updated_config, pool).await?;
+        update_config_row(conn, updated_config).await?;
+        Ok(id)
     } else {
         info!("No matching config found, inserting new config");
-        insert_config_with_pool_and_mode(config, pool, mode).await?;
+        insert_config_row(conn, config, mode).await
     }
-
-    Ok(())
 }
 
+/// Imports a document and returns the ids of the configs it describes.
 pub(crate) async fn import_configs_with_pool_and_mode(
-    json: String, pool: &SqlitePool, mode: DatabaseMode,
-) -> Result<(), String> {
-    let configs = parse_config_json(&json)?;
-
-    let existing_configs = read_configs_with_pool(pool).await?;
-
-    for config in configs {
-        validate_imported_config(&config).map_err(|e| format!("Invalid config: {e}"))?;
-        merge_config_with_existing_and_mode(config, &existing_configs, pool, mode)
-            .await
-            .map_err(|e| format!("Failed to merge config: {e}"))?;
-    }
-
-    if let Err(e) = migrate_configs(Some(pool)).await {
-        return Err(format!("Error migrating configs: {e}"));
-    }
-
-    Ok(())
+    content: String, format: Option<ConfigFormat>, pool: &SqlitePool, mode: DatabaseMode,
+) -> Result<Vec<i64>, String> {
+    // Parsing already validated the configs.
+    let configs = parse_config_content(&content, format)?;
+
+    with_default_change_source(
+        ConfigChangeSource::Import,
+        write_upserted_configs(configs, pool, mode),
+    )
+    .await
 }
 
 pub async fn import_configs(json: String) -> Result<(), String> {
@@ -616,22 +992,54 @@ pub async fn upsert_configs_with_mode(
     upsert_configs_with_pool_and_mode(configs, &context.pool, mode).await
 }
 
+/// Inserts or updates `configs` by identity. Nothing is written when any of
+/// them fails validation.
 pub async fn upsert_configs_with_pool_and_mode(
     configs: Vec<Config>, pool: &SqlitePool, mode: DatabaseMode,
 ) -> Result<(), String> {
-    let existing_configs = read_configs_with_pool(pool).await?;
-
-    for config in configs {
-        merge_config_with_existing_and_mode(config, &existing_configs, pool, mode)
-            .await
-            .map_err(|e| format!("Failed to merge config: {e}"))?;
+    let errors: Vec<String> = validate_configs(&configs)
+        .into_iter()
+        .filter_map(|result| result.into_result().err())
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
     }
 
+    write_upserted_configs(configs, pool, mode).await?;
+    Ok(())
+}
+
+async fn write_upserted_configs(
+    configs: Vec<Config>, pool: &SqlitePool, mode: DatabaseMode,
+) -> Result<Vec<i64>, String> {
+    let mut transaction = pool.begin().await.map_err(|e| e.to_string())?;
+    let ids = upsert_config_rows(&mut transaction, configs, mode).await?;
+    transaction.commit().await.map_err(|e| e.to_string())?;
+
     if let Err(e) = migrate_configs(Some(pool)).await {
         return Err(format!("Error migrating configs: {e}"));
     }
 
-    Ok(())
+    Ok(ids)
+}
+
+/// Inserts or updates `configs` by identity on an already acquired
+/// connection and returns their ids. Callers run [`migrate_configs`] once the
+/// writes are committed.
+pub(crate) async fn upsert_config_rows(
+    conn: &mut sqlx::SqliteConnection, configs: Vec<Config>, mode: DatabaseMode,
+) -> Result<Vec<i64>, String> {
+    let existing_configs = fetch_configs(conn).await?;
+
+    let mut ids = Vec::with_capacity(configs.len());
+    for config in configs {
+        let id = merge_config_with_existing_and_mode(conn, config, &existing_configs, mode)
+            .await
+            .map_err(|e| format!("Failed to merge config: {e}"))?;
+        ids.push(id);
+    }
+
+    Ok(ids)
 }
 
 pub async fn read_configs_with_mode(mode: DatabaseMode) -> Result<Vec<Config>, String> {
@@ -654,9 +1062,25 @@ pub async fn export_configs_with_mode(mode: DatabaseMode) -> Result<String, Stri
     export_configs_with_pool(&context.pool).await
 }
 
+pub async fn export_configs_in_format_with_mode(
+    format: ConfigFormat, mode: DatabaseMode,
+) -> Result<String, String> {
+    let json = export_configs_with_mode(mode).await?;
+    render_configs(&json, format)
+}
+
 pub async fn import_configs_with_mode(json: String, mode: DatabaseMode) -> Result<(), String> {
+    import_configs_in_format_with_mode(json, None, mode).await
+}
+
+/// Imports a JSON, YAML or TOML document. `format` is usually derived from
+/// the file extension with `ConfigFormat::from_path`; `None` detects it.
+pub async fn import_configs_in_format_with_mode(
+    content: String, format: Option<ConfigFormat>, mode: DatabaseMode,
+) -> Result<(), String> {
     let context = DatabaseManager::get_context(mode).await?;
-    import_configs_with_pool_and_mode(json, &context.pool, mode).await
+    import_configs_with_pool_and_mode(content, format, &context.pool, mode).await?;
+    Ok(())
 }
 
 pub async fn get_configs_with_mode(mode: DatabaseMode) -> Result<Vec<Config>, String> {
@@ -718,10 +1142,10 @@ fn prepare_config(mut config: Config) -> Config {
 }
 
 async fn sync_http_logs_config_from_config(
-    config: &Config, config_id: i64, pool: &SqlitePool,
+    config: &Config, config_id: i64, conn: &mut sqlx::SqliteConnection,
 ) -> Result<(), String> {
     use crate::models::http_logs_config_model::HttpLogsConfig;
-    use crate::utils::http_logs_config::update_http_logs_config_with_pool;
+    use crate::utils::http_logs_config::write_http_logs_config;
 
     let http_config = HttpLogsConfig {
         config_id,
@@ -731,7 +1155,7 @@ async fn sync_http_logs_config_from_config(
         auto_cleanup: config.http_logs_auto_cleanup.unwrap_or(true),
     };
 
-    update_http_logs_config_with_pool(&http_config, pool).await
+    write_http_logs_config(conn, &http_config).await
 }
 
 #[cfg(test)]
@@ -747,6 +1171,7 @@ mod tests {
     use tokio::sync::Mutex;
 
     use super::*;
+    use crate::test_utils::valid_config;
 
     lazy_static! {
         static ref IO_TEST_MUTEX: Mutex<()> = Mutex::new(());
@@ -757,7 +1182,7 @@ mod tests {
         let config = Config {
             alias: Some("  alias  ".to_string()),
             kubeconfig: Some("  kube  ".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let prepared = prepare_config(config);
         assert_eq!(prepared.alias, Some("alias".to_string()));
@@ -768,14 +1193,14 @@ mod tests {
     fn test_prepare_config_sets_default_kubeconfig() {
         let config_empty = Config {
             kubeconfig: Some("".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let prepared_empty = prepare_config(config_empty);
         assert_eq!(prepared_empty.kubeconfig, Some("default".to_string()));
 
         let config_none = Config {
             kubeconfig: None,
-            ..Config::default()
+            ..valid_config()
         };
         let prepared_none = prepare_config(config_none);
         assert_eq!(prepared_none.kubeconfig, Some("default".to_string()));
@@ -788,7 +1213,7 @@ mod tests {
             workload_type: Some("deployment".to_string()),
             protocol: "TCP".to_string(),
             local_port: Some(8080),
-            ..Config::default()
+            ..valid_config()
         };
         let prepared_empty = prepare_config(config_empty);
         assert_eq!(
@@ -801,7 +1226,7 @@ mod tests {
             workload_type: Some("pod".to_string()),
             protocol: "UDP".to_string(),
             local_port: Some(9090),
-            ..Config::default()
+            ..valid_config()
         };
         let prepared_none = prepare_config(config_none);
         assert_eq!(prepared_none.alias, Some("pod-UDP-9090".to_string()));
@@ -812,7 +1237,7 @@ mod tests {
         let config0 = Config {
             local_port: Some(0),
             remote_port: Some(8000),
-            ..Config::default()
+            ..valid_config()
         };
         let prepared0 = prepare_config(config0);
         assert!(prepared0.local_port.is_some());
@@ -821,7 +1246,7 @@ mod tests {
         let config_none = Config {
             local_port: None,
             remote_port: Some(9000),
-            ..Config::default()
+            ..valid_config()
         };
         let prepared_none = prepare_config(config_none);
         assert!(prepared_none.local_port.is_some());
@@ -845,7 +1270,7 @@ mod tests {
         let pool = setup_test_db().await;
         let config = Config {
             service: Some("test-service".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config.clone(), &pool)
@@ -875,11 +1300,11 @@ mod tests {
         let pool = setup_test_db().await;
         let config1 = Config {
             service: Some("service1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let config2 = Config {
             service: Some("service2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config1, &pool).await.unwrap();
@@ -912,7 +1337,7 @@ mod tests {
         let pool = setup_test_db().await;
         let config = Config {
             service: Some("initial-service".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         insert_config_with_pool(config.clone(), &pool)
             .await
@@ -932,16 +1357,64 @@ mod tests {
         assert_eq!(updated_config.service, Some("updated-service".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_insert_update_and_upsert_reject_invalid_configs() {
+        let pool = setup_test_db().await;
+        let invalid = Config {
+            service: None,
+            ..valid_config()
+        };
+
+        let err = insert_config_with_pool(invalid.clone(), &pool)
+            .await
+            .unwrap_err();
+        assert!(err.contains("service: is required"), "{err}");
+
+        let err =
+            upsert_configs_with_pool_and_mode(vec![invalid.clone()], &pool, DatabaseMode::File)
+                .await
+                .unwrap_err();
+        assert!(err.contains("service: is required"), "{err}");
+        assert!(read_configs_with_pool(&pool).await.unwrap().is_empty());
+
+        insert_config_with_pool(
+            Config {
+                service: Some("valid-service".to_string()),
+                ..valid_config()
+            },
+            &pool,
+        )
+        .await
+        .unwrap();
+        let stored = read_configs_with_pool(&pool).await.unwrap().remove(0);
+
+        let err = update_config_with_pool(
+            Config {
+                id: stored.id,
+                ..invalid
+            },
+            &pool,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("service: is required"), "{err}");
+
+        let unchanged = get_config_with_pool(stored.id.unwrap(), &pool)
+            .await
+            .unwrap();
+        assert_eq!(unchanged.service, Some("valid-service".to_string()));
+    }
+
     #[tokio::test]
     async fn test_delete_config() {
         let pool = setup_test_db().await;
         let config1 = Config {
             service: Some("service1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let config2 = Config {
             service: Some("service2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         insert_config_with_pool(config1.clone(), &pool)
             .await
@@ -974,15 +1447,15 @@ mod tests {
         let pool = setup_test_db().await;
         let config1 = Config {
             service: Some("service1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let config2 = Config {
             service: Some("service2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let config3 = Config {
             service: Some("service3".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         insert_config_with_pool(config1, &pool).await.unwrap();
         insert_config_with_pool(config2, &pool).await.unwrap();
@@ -1011,10 +1484,10 @@ mod tests {
     #[tokio::test]
     async fn test_delete_all_configs() {
         let pool = setup_test_db().await;
-        insert_config_with_pool(Config::default(), &pool)
+        insert_config_with_pool(valid_config(), &pool)
             .await
             .unwrap();
-        insert_config_with_pool(Config::default(), &pool)
+        insert_config_with_pool(valid_config(), &pool)
             .await
             .unwrap();
 
@@ -1033,7 +1506,7 @@ mod tests {
             namespace: "default-namespace".to_string(),
             local_port: Some(8080),
             id: None,
-            ..Config::default()
+            ..valid_config()
         };
 
         let config2 = Config {
@@ -1041,7 +1514,7 @@ mod tests {
             namespace: "custom-ns".to_string(),
             alias: Some("".to_string()),
             id: None,
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config1.clone(), &pool)
@@ -1171,6 +1644,36 @@ mod tests {
         assert!(result.unwrap_err().contains("Failed to parse config"));
     }
 
+    #[tokio::test]
+    async fn test_import_configs_with_defaults_and_placeholders() {
+        let pool = setup_test_db().await;
+        let json = r#"{
+            "defaults": {"context": "kind-dev", "namespace": "${context}-apps", "protocol": "tcp"},
+            "configs": [
+                {"service": "api", "workload_type": "service", "local_port": "${API_PORT:-18080}", "remote_port": 80},
+                {"service": "db", "workload_type": "service", "namespace": "shared", "remote_port": 5432}
+            ]
+        }"#;
+        import_configs_with_pool(json.to_string(), &pool)
+            .await
+            .unwrap();
+
+        let configs = read_configs_with_pool(&pool).await.unwrap();
+        let api = configs
+            .iter()
+            .find(|c| c.service.as_deref() == Some("api"))
+            .unwrap();
+        assert_eq!(api.context.as_deref(), Some("kind-dev"));
+        assert_eq!(api.namespace, "kind-dev-apps");
+        assert_eq!(api.local_port, Some(18080));
+        let db = configs
+            .iter()
+            .find(|c| c.service.as_deref() == Some("db"))
+            .unwrap();
+        assert_eq!(db.namespace, "shared");
+        assert_eq!(db.context.as_deref(), Some("kind-dev"));
+    }
+
     #[tokio::test]
     async fn test_import_multiple_configs_same_service_different_ports() {
         let pool = setup_test_db().await;
@@ -1251,13 +1754,13 @@ mod tests {
         let config1 = Config {
             service: Some("host-service1".to_string()),
             id: Some(1),
-            ..Config::default()
+            ..valid_config()
         };
 
         let config2 = Config {
             service: Some("host-service2".to_string()),
             id: Some(2),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config1, &pool).await.unwrap();
@@ -1286,7 +1789,7 @@ mod tests {
 
         let config1 = Config {
             service: Some("get-configs-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config1, &pool).await.unwrap();
@@ -1302,7 +1805,7 @@ mod tests {
 
         let config = Config {
             service: Some("delete-test-public".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config, &pool).await.unwrap();
@@ -1324,12 +1827,12 @@ mod tests {
 
         let config1 = Config {
             service: Some("delete-multi-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         let config2 = Config {
             service: Some("delete-multi-2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config1, &pool).await.unwrap();
@@ -1352,12 +1855,12 @@ mod tests {
 
         let config1 = Config {
             service: Some("delete-all-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         let config2 = Config {
             service: Some("delete-all-2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config1, &pool).await.unwrap();
@@ -1378,7 +1881,7 @@ mod tests {
 
         let config = Config {
             service: Some("insert-public-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         let result = insert_config_with_pool(config.clone(), &pool).await;
@@ -1395,7 +1898,7 @@ mod tests {
 
         let config = Config {
             service: Some("read-public-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config, &pool).await.unwrap();
@@ -1414,7 +1917,7 @@ mod tests {
 
         let config = Config {
             service: Some("get-single-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config, &pool).await.unwrap();
@@ -1444,7 +1947,7 @@ mod tests {
 
         let config = Config {
             service: Some("update-public-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config, &pool).await.unwrap();
@@ -1472,7 +1975,7 @@ mod tests {
         let config = Config {
             service: Some("export-public-test".to_string()),
             namespace: "test-namespace".to_string(),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(config, &pool).await.unwrap();
@@ -1514,7 +2017,7 @@ mod tests {
         let config = Config {
             local_port: Some(0),
             remote_port: Some(8080),
-            ..Config::default()
+            ..valid_config()
         };
 
         let prepared = prepare_config(config);
@@ -1545,7 +2048,7 @@ mod tests {
 
         let config = Config {
             service: Some("memory-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_mode(config.clone(), DatabaseMode::Memory)
@@ -1578,11 +2081,11 @@ mod tests {
 
         let config1 = Config {
             service: Some("memory-test-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let config2 = Config {
             service: Some("memory-test-2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_mode(config1, DatabaseMode::Memory)
@@ -1617,7 +2120,8 @@ mod tests {
 
         let config1 = Config {
             service: Some("memory-test-1".to_string()),
-            ..Config::default()
+            workload_type: Some("pod".to_string()),
+            ..valid_config()
         };
 
         insert_config_with_mode(config1, DatabaseMode::Memory)
@@ -1626,8 +2130,9 @@ mod tests {
 
         let new_config = Config {
             service: Some("memory-test-1".to_string()),
+            workload_type: Some("pod".to_string()),
             local_port: Some(9090),
-            ..Config::default()
+            ..valid_config()
         };
 
         upsert_configs_with_mode(vec![new_config], DatabaseMode::Memory)
@@ -1649,7 +2154,7 @@ mod tests {
 
         let config1 = Config {
             service: Some("insert-test-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         upsert_configs_with_mode(vec![config1], DatabaseMode::Memory)
@@ -1662,7 +2167,7 @@ mod tests {
 
         let config2 = Config {
             service: Some("insert-test-2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         upsert_configs_with_mode(vec![config2], DatabaseMode::Memory)
@@ -1684,7 +2189,7 @@ mod tests {
 
         let config1 = Config {
             service: Some("same-service-test-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         upsert_configs_with_mode(vec![config1], DatabaseMode::Memory)
@@ -1698,7 +2203,7 @@ mod tests {
         let config2 = Config {
             service: Some("same-service-test-1".to_string()),
             namespace: "different-namespace".to_string(),
-            ..Config::default()
+            ..valid_config()
         };
 
         upsert_configs_with_mode(vec![config2], DatabaseMode::Memory)
@@ -1728,7 +2233,7 @@ mod tests {
                 namespace: "default".to_string(),
                 local_port: Some(10148),
                 remote_port: Some(4646),
-                ..Config::default()
+                ..valid_config()
             },
             Config {
                 service: Some("my-service".to_string()),
@@ -1739,7 +2244,7 @@ mod tests {
                 namespace: "default".to_string(),
                 local_port: Some(20148),
                 remote_port: Some(8500),
-                ..Config::default()
+                ..valid_config()
             },
             Config {
                 service: Some("my-service".to_string()),
@@ -1750,7 +2255,7 @@ mod tests {
                 namespace: "default".to_string(),
                 local_port: Some(30148),
                 remote_port: Some(5432),
-                ..Config::default()
+                ..valid_config()
             },
         ];
 
@@ -1869,4 +2374,173 @@ mod tests {
             "All configs should have the same service name"
         );
     }
+
+    #[tokio::test]
+    async fn test_config_fields_are_stored_in_typed_columns() {
+        let pool = setup_test_db().await;
+        let config = Config {
+            service: Some("typed-service".to_string()),
+            namespace: "typed-ns".to_string(),
+            local_port: Some(18080),
+            alias: Some("typed-alias".to_string()),
+            exposure_type: Some("cluster".to_string()),
+            ..valid_config()
+        };
+        insert_config_with_pool(config, &pool).await.unwrap();
+
+        let row = sqlx::query("SELECT service, namespace, local_port, alias, data FROM configs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("service"), "typed-service");
+        assert_eq!(row.get::<String, _>("namespace"), "typed-ns");
+        assert_eq!(row.get::<i64, _>("local_port"), 18080);
+        assert_eq!(row.get::<String, _>("alias"), "typed-alias");
+
+        let extras: Value = serde_json::from_str(&row.get::<String, _>("data")).unwrap();
+        assert!(extras.get("service").is_none());
+        assert!(extras.get("local_port").is_none());
+        assert_eq!(extras.get("exposure_type"), Some(&json!("cluster")));
+
+        let configs = read_configs_with_pool(&pool).await.unwrap();
+        assert_eq!(configs[0].service, Some("typed-service".to_string()));
+        assert_eq!(configs[0].exposure_type, Some("cluster".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_find_alias_conflicts() {
+        let pool = setup_test_db().await;
+        let api = Config {
+            alias: Some("api".to_string()),
+            ..valid_config()
+        };
+        insert_config_with_pool(api, &pool).await.unwrap();
+
+        let candidate = Config {
+            alias: Some(" api ".to_string()),
+            ..valid_config()
+        };
+        let conflicts = find_alias_conflicts_with_pool(&candidate, &pool)
+            .await
+            .unwrap();
+        assert_eq!(conflicts.len(), 1);
+
+        let itself = Config {
+            id: conflicts[0].id,
+            ..candidate
+        };
+        assert!(
+            find_alias_conflicts_with_pool(&itself, &pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let err = insert_config_with_pool(
+            Config {
+                alias: Some("api".to_string()),
+                ..valid_config()
+            },
+            &pool,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("alias: is already used by config #"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_generated_aliases_are_unique() {
+        let pool = setup_test_db().await;
+        for local_address in ["127.0.0.2", "127.0.0.3", "127.0.0.4"] {
+            let config = Config {
+                alias: None,
+                local_port: Some(18080),
+                local_address: Some(local_address.to_string()),
+                ..valid_config()
+            };
+            insert_config_with_pool(config, &pool).await.unwrap();
+        }
+
+        let aliases: Vec<_> = read_configs_with_pool(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|config| config.alias)
+            .collect();
+        assert_eq!(
+            aliases,
+            vec![
+                "service-tcp-18080",
+                "service-tcp-18080-2",
+                "service-tcp-18080-3"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_local_endpoint_conflicts() {
+        let pool = setup_test_db().await;
+        let existing = Config {
+            local_port: Some(15432),
+            local_address: None,
+            ..valid_config()
+        };
+        insert_config_with_pool(existing, &pool).await.unwrap();
+        let auto_loopback = Config {
+            local_port: Some(15432),
+            local_address: None,
+            auto_loopback_address: true,
+            ..valid_config()
+        };
+        insert_config_with_pool(auto_loopback, &pool).await.unwrap();
+
+        let err = insert_config_with_pool(
+            Config {
+                local_port: Some(15432),
+                local_address: Some(" ".to_string()),
+                ..valid_config()
+            },
+            &pool,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.contains("local_port: local address and port are also used by"),
+            "{err}"
+        );
+
+        let candidate = Config {
+            local_port: Some(15432),
+            local_address: Some("127.0.0.1".to_string()),
+            ..valid_config()
+        };
+        let conflicts = find_local_endpoint_conflicts_with_pool(&candidate, &pool)
+            .await
+            .unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert!(!conflicts[0].auto_loopback_address);
+
+        let conflict_id = conflicts[0].id;
+        let itself = Config {
+            id: conflict_id,
+            ..candidate.clone()
+        };
+        assert!(
+            find_local_endpoint_conflicts_with_pool(&itself, &pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let other_address = Config {
+            local_address: Some("127.0.0.2".to_string()),
+            ..candidate
+        };
+        assert!(
+            find_local_endpoint_conflicts_with_pool(&other_address, &pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sqlx::SqlitePool;
+
+use crate::models::config_model::Config;
+use crate::utils::config::read_configs_with_pool;
+use crate::utils::db_mode::{
+    DatabaseManager,
+    DatabaseMode,
+};
+use crate::utils::local_port::{
+    DEFAULT_LOCAL_ADDRESS,
+    local_endpoint,
+};
+
+const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+
+/// kubectl flags that take a value in the following argument when not
+/// written as `--flag=value`.
+const VALUE_FLAGS: &[&str] = &[
+    "-n",
+    "--namespace",
+    "--context",
+    "--kubeconfig",
+    "--address",
+    "--pod-running-timeout",
+    "--cluster",
+    "--user",
+    "-s",
+    "--server",
+    "--token",
+    "--as",
+    "--as-group",
+    "--as-uid",
+    "--request-timeout",
+    "-v",
+    "--v",
+    "--certificate-authority",
+    "--client-certificate",
+    "--client-key",
+    "--tls-server-name",
+    "--cache-dir",
+];
+
+/// One `LOCAL:REMOTE` argument of `kubectl port-forward`. The remote side
+/// may be a port name.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KubectlPortMapping {
+    pub local_port: Option<u16>,
+    pub remote_port: String,
+}
+
+/// A `kubectl port-forward` invocation parsed from a process command line.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KubectlPortForward {
+    pub context: Option<String>,
+    pub kubeconfig: Option<String>,
+    pub namespace: Option<String>,
+    pub address: Option<String>,
+    /// Normalized resource kind: `service`, `pod`, `deployment`,
+    /// `statefulset` or `replicaset`.
+    pub resource_kind: String,
+    pub resource_name: String,
+    pub ports: Vec<KubectlPortMapping>,
+}
+
+/// Parses the argv of a `kubectl port-forward` process. Returns `None` for
+/// any other command.
+pub fn parse_kubectl_port_forward(args: &[String]) -> Option<KubectlPortForward> {
+    let program = Path::new(args.first()?).file_stem()?.to_str()?;
+    if program != "kubectl" {
+        return None;
+    }
+
+    let mut context = None;
+    let mut kubeconfig = None;
+    let mut namespace = None;
+    let mut address = None;
+    let mut positional = Vec::new();
+    let mut is_port_forward = false;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with('-') || arg == "-" {
+            if !is_port_forward && arg == "port-forward" {
+                is_port_forward = true;
+            } else if is_port_forward {
+                positional.push(arg.clone());
+            } else {
+                return None;
+            }
+            continue;
+        }
+
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None if arg.starts_with("-n") && arg.len() > 2 && !arg.starts_with("--") => {
+                ("-n", Some(arg[2..].to_string()))
+            }
+            None => (arg.as_str(), None),
+        };
+        if !VALUE_FLAGS.contains(&flag) {
+            continue;
+        }
+        let value = match inline_value {
+            Some(value) => value,
+            None => iter.next()?.clone(),
+        };
+
+        match flag {
+            "-n" | "--namespace" => namespace = Some(value),
+            "--context" => context = Some(value),
+            "--kubeconfig" => kubeconfig = Some(value),
+            "--address" => address = value.split(',').next().map(str::to_string),
+            _ => {}
+        }
+    }
+
+    if !is_port_forward || positional.len() < 2 {
+        return None;
+    }
+
+    let (resource_kind, resource_name) = match positional[0].split_once('/') {
+        Some((kind, name)) => (normalize_kind(kind)?, name.to_string()),
+        None => ("pod".to_string(), positional[0].clone()),
+    };
+
+    let ports = positional[1..]
+        .iter()
+        .map(|spec| parse_port_mapping(spec))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(KubectlPortForward {
+        context,
+        kubeconfig,
+        namespace,
+        address,
+        resource_kind,
+        resource_name,
+        ports,
+    })
+}
+
+fn normalize_kind(kind: &str) -> Option<String> {
+    let kind = match kind.to_lowercase().as_str() {
+        "svc" | "service" | "services" => "service",
+        "po" | "pod" | "pods" => "pod",
+        "deploy" | "deployment" | "deployments" => "deployment",
+        "sts" | "statefulset" | "statefulsets" => "statefulset",
+        "rs" | "replicaset" | "replicasets" => "replicaset",
+        _ => return None,
+    };
+    Some(kind.to_string())
+}
+
+fn parse_port_mapping(spec: &str) -> Option<KubectlPortMapping> {
+    let (local, remote) = match spec.split_once(':') {
+        Some((local, remote)) => (local, remote),
+        None => (spec, spec),
+    };
+    if remote.is_empty() {
+        return None;
+    }
+
+    let local_port = if local.is_empty() {
+        None
+    } else {
+        Some(local.parse::<u16>().ok()?)
+    };
+
+    Some(KubectlPortMapping {
+        local_port,
+        remote_port: remote.to_string(),
+    })
+}
+
+/// Gives every config a local port not bound by `existing` or by an earlier
+/// config in `configs`. The requested port, or the remote port when none is
+/// set, is kept when free; otherwise the next free port is taken.
+pub fn assign_local_ports(configs: &mut [Config], existing: &[Config]) {
+    let mut taken: HashSet<(String, u16)> = existing.iter().filter_map(local_endpoint).collect();
+
+    for config in configs.iter_mut() {
+        if config.auto_loopback_address {
+            continue;
+        }
+
+        let address = config
+            .local_address
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LOCAL_ADDRESS.to_string());
+        let wanted = config
+            .local_port
+            .filter(|port| *port != 0)
+            .or(config.remote_port.filter(|port| *port != 0))
+            .unwrap_or(FIRST_UNPRIVILEGED_PORT);
+
+        let port = (wanted..=u16::MAX)
+            .chain(FIRST_UNPRIVILEGED_PORT..wanted)
+            .find(|port| !taken.contains(&(address.clone(), *port)))
+            .unwrap_or(wanted);
+
+        taken.insert((address, port));
+        config.local_port = Some(port);
+    }
+}
+
+pub(crate) async fn assign_local_ports_with_pool(
+    configs: &mut [Config], pool: &SqlitePool,
+) -> Result<(), String> {
+    let existing = read_configs_with_pool(pool).await?;
+    assign_local_ports(configs, &existing);
+    Ok(())
+}
+
+pub async fn assign_local_ports_with_mode(
+    configs: &mut [Config], mode: DatabaseMode,
+) -> Result<(), String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    assign_local_ports_with_pool(configs, &context.pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_kubectl_port_forward_service() {
+        let parsed = parse_kubectl_port_forward(&argv(
+            "/usr/local/bin/kubectl --context=dev port-forward -n payments svc/api 8080:80 :9090",
+        ))
+        .unwrap();
+
+        assert_eq!(parsed.context.as_deref(), Some("dev"));
+        assert_eq!(parsed.namespace.as_deref(), Some("payments"));
+        assert_eq!(parsed.resource_kind, "service");
+        assert_eq!(parsed.resource_name, "api");
+        assert_eq!(
+            parsed.ports,
+            vec![
+                KubectlPortMapping {
+                    local_port: Some(8080),
+                    remote_port: "80".to_string(),
+                },
+                KubectlPortMapping {
+                    local_port: None,
+                    remote_port: "9090".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_kubectl_port_forward_pod_and_flags() {
+        let parsed = parse_kubectl_port_forward(&argv(
+            "kubectl port-forward web-0 --address 0.0.0.0,::1 --kubeconfig /tmp/kc 5000:http",
+        ))
+        .unwrap();
+
+        assert_eq!(parsed.resource_kind, "pod");
+        assert_eq!(parsed.resource_name, "web-0");
+        assert_eq!(parsed.address.as_deref(), Some("0.0.0.0"));
+        assert_eq!(parsed.kubeconfig.as_deref(), Some("/tmp/kc"));
+        assert_eq!(parsed.ports[0].remote_port, "http");
+    }
+
+    #[test]
+    fn test_parse_kubectl_port_forward_rejects_other_commands() {
+        assert!(parse_kubectl_port_forward(&argv("kubectl get pods")).is_none());
+        assert!(parse_kubectl_port_forward(&argv("ssh -L 8080:localhost:80 host")).is_none());
+        assert!(parse_kubectl_port_forward(&argv("kubectl port-forward svc/api")).is_none());
+        assert!(parse_kubectl_port_forward(&argv("kubectl port-forward job/x 80")).is_none());
+    }
+
+    #[test]
+    fn test_assign_local_ports_avoids_conflicts() {
+        let existing = vec![Config {
+            local_port: Some(8080),
+            local_address: None,
+            ..Config::default()
+        }];
+        let mut configs = vec![
+            Config {
+                local_port: Some(8080),
+                ..Config::default()
+            },
+            Config {
+                local_port: None,
+                remote_port: Some(8081),
+                ..Config::default()
+            },
+            Config {
+                local_port: Some(8080),
+                local_address: Some("127.0.0.5".to_string()),
+                ..Config::default()
+            },
+        ];
+
+        assign_local_ports(&mut configs, &existing);
+
+        assert_eq!(configs[0].local_port, Some(8081));
+        assert_eq!(configs[1].local_port, Some(8082));
+        assert_eq!(configs[2].local_port, Some(8080));
+    }
+}
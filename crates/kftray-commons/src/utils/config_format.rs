@@ -0,0 +1,217 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::{
+    Value,
+    json,
+};
+
+/// File formats accepted for config import and export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConfigFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?;
+        extension.parse().ok()
+    }
+
+    /// Guesses the format from the first line that is not blank or a comment.
+    /// TOML is recognised by table headers and `key = value` pairs, JSON by
+    /// a leading brace or bracket, and anything else is read as YAML.
+    pub fn detect(content: &str) -> Self {
+        let Some(line) = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+        else {
+            return ConfigFormat::Json;
+        };
+
+        if line.starts_with('{') {
+            ConfigFormat::Json
+        } else if line.starts_with('[') {
+            if is_toml_table_header(line) {
+                ConfigFormat::Toml
+            } else {
+                ConfigFormat::Json
+            }
+        } else if is_toml_key_value(line) {
+            ConfigFormat::Toml
+        } else {
+            ConfigFormat::Yaml
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "json",
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Toml => "toml",
+        }
+    }
+}
+
+impl FromStr for ConfigFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(ConfigFormat::Json),
+            "yaml" | "yml" => Ok(ConfigFormat::Yaml),
+            "toml" => Ok(ConfigFormat::Toml),
+            other => Err(format!(
+                "Unsupported config format '{other}', expected json, yaml or toml"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+fn is_toml_table_header(line: &str) -> bool {
+    let line = line.split(" #").next().unwrap_or(line).trim_end();
+    let inner = line
+        .strip_prefix("[[")
+        .and_then(|rest| rest.strip_suffix("]]"))
+        .or_else(|| {
+            line.strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+        });
+
+    inner.is_some_and(|name| {
+        let name = name.trim();
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '"'))
+    })
+}
+
+fn is_toml_key_value(line: &str) -> bool {
+    line.split_once('=').is_some_and(|(key, _)| {
+        let key = key.trim();
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '"'))
+    })
+}
+
+/// Parses a config document into JSON, detecting the format from the
+/// content when none is given.
+pub(crate) fn parse_document(content: &str, format: Option<ConfigFormat>) -> Result<Value, String> {
+    let format = format.unwrap_or_else(|| ConfigFormat::detect(content));
+
+    match format {
+        ConfigFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        ConfigFormat::Yaml => {
+            serde_yaml::from_str(content).map_err(|e| format!("invalid YAML: {e}"))
+        }
+        ConfigFormat::Toml => toml::from_str(content).map_err(|e| format!("invalid TOML: {e}")),
+    }
+}
+
+/// Renders exported configs in `format`. TOML has no top-level arrays, so the
+/// configs are written as a `[[configs]]` table list there.
+pub fn render_configs(configs_json: &str, format: ConfigFormat) -> Result<String, String> {
+    match format {
+        ConfigFormat::Json => Ok(configs_json.to_string()),
+        ConfigFormat::Yaml => serde_yaml::to_string(&exportable_configs(configs_json)?)
+            .map_err(|e| format!("Failed to render configs as YAML: {e}")),
+        ConfigFormat::Toml => {
+            toml::to_string_pretty(&json!({ "configs": exportable_configs(configs_json)? }))
+                .map_err(|e| format!("Failed to render configs as TOML: {e}"))
+        }
+    }
+}
+
+/// YAML and TOML exports leave unset fields out, TOML cannot express null.
+fn exportable_configs(configs_json: &str) -> Result<Value, String> {
+    let mut configs: Value = serde_json::from_str(configs_json)
+        .map_err(|e| format!("Failed to read exported configs: {e}"))?;
+    strip_nulls(&mut configs);
+    Ok(configs)
+}
+
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, value| !value.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_format_from_content_and_path() {
+        assert_eq!(
+            ConfigFormat::detect("[{\"service\": \"api\"}]"),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::detect("  {\"service\": \"api\"}"),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::detect("# shared\n[[configs]]\nservice = \"api\""),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::detect("service = \"api\""),
+            ConfigFormat::Toml
+        );
+        assert_eq!(ConfigFormat::detect("- service: api\n"), ConfigFormat::Yaml);
+        assert_eq!(
+            ConfigFormat::detect("configs:\n  - service: api"),
+            ConfigFormat::Yaml
+        );
+
+        assert_eq!(ConfigFormat::from_path("dev.yml"), Some(ConfigFormat::Yaml));
+        assert_eq!(
+            ConfigFormat::from_path("dev.TOML"),
+            Some(ConfigFormat::Toml)
+        );
+        assert_eq!(ConfigFormat::from_path("dev.txt"), None);
+    }
+
+    #[test]
+    fn test_yaml_and_toml_parse_to_the_same_document() {
+        let yaml = "- service: api\n  local_port: 8080\n  domain_enabled: true\n";
+        let toml = "[[configs]]\nservice = \"api\"\nlocal_port = 8080\ndomain_enabled = true\n";
+
+        let from_yaml = parse_document(yaml, None).unwrap();
+        let from_toml = parse_document(toml, None).unwrap();
+        assert_eq!(from_yaml, from_toml["configs"]);
+    }
+
+    #[test]
+    fn test_render_configs_round_trips() {
+        let json = r#"[{"service": "api", "workload_type": null, "local_port": 8080}]"#;
+
+        for format in [ConfigFormat::Yaml, ConfigFormat::Toml] {
+            let rendered = render_configs(json, format).unwrap();
+            let parsed = parse_document(&rendered, Some(format)).unwrap();
+            let configs = parsed.get("configs").unwrap_or(&parsed);
+            assert_eq!(configs[0]["service"], "api");
+            assert_eq!(configs[0]["local_port"], 8080);
+            assert!(configs[0].get("workload_type").is_none());
+        }
+    }
+}
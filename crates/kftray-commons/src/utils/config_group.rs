@@ -0,0 +1,372 @@
+use log::error;
+use sqlx::{
+    Row,
+    SqliteConnection,
+    SqlitePool,
+};
+
+use crate::models::config_group_model::ConfigGroup;
+use crate::models::config_model::Config;
+use crate::utils::config::find_configs_with_pool;
+use crate::utils::db_mode::{
+    DatabaseManager,
+    DatabaseMode,
+};
+
+fn normalize_group_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Group name cannot be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Replaces the groups of a config with `groups`, creating groups that do not
+/// exist yet. Used by the config writers so `Config::groups` round-trips.
+pub(crate) async fn set_config_groups(
+    conn: &mut SqliteConnection, config_id: i64, groups: &[String],
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM config_group_members WHERE config_id = ?1")
+        .bind(config_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to clear groups of config {config_id}: {e}"))?;
+
+    for group in groups {
+        let name = normalize_group_name(group)?;
+
+        sqlx::query("INSERT OR IGNORE INTO config_groups (name) VALUES (?1)")
+            .bind(&name)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to create group '{name}': {e}"))?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO config_group_members (group_id, config_id)
+             SELECT id, ?2 FROM config_groups WHERE name = ?1",
+        )
+        .bind(&name)
+        .bind(config_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to add config {config_id} to group '{name}': {e}"))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn list_groups_with_pool(pool: &SqlitePool) -> Result<Vec<ConfigGroup>, String> {
+    let rows = sqlx::query(
+        "SELECT g.id, g.name,
+            (SELECT json_group_array(m.config_id) FROM config_group_members m
+             WHERE m.group_id = g.id) AS config_ids
+         FROM config_groups g ORDER BY g.name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to list config groups: {e}");
+        e.to_string()
+    })?;
+
+    rows.iter()
+        .map(|row| {
+            let config_ids: String = row.try_get("config_ids").map_err(|e| e.to_string())?;
+            let mut config_ids: Vec<i64> =
+                serde_json::from_str(&config_ids).map_err(|e| e.to_string())?;
+            config_ids.sort_unstable();
+
+            Ok(ConfigGroup {
+                id: Some(row.try_get("id").map_err(|e| e.to_string())?),
+                name: row.try_get("name").map_err(|e| e.to_string())?,
+                config_ids,
+            })
+        })
+        .collect()
+}
+
+/// Creates or renames a group and replaces its members with
+/// `group.config_ids`. Ids of configs that do not exist are ignored.
+pub(crate) async fn save_group_with_pool(
+    group: &ConfigGroup, pool: &SqlitePool,
+) -> Result<i64, String> {
+    let name = normalize_group_name(&group.name)?;
+    let mut transaction = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let id = match group.id {
+        Some(id) => {
+            let result = sqlx::query("UPDATE config_groups SET name = ?1 WHERE id = ?2")
+                .bind(&name)
+                .bind(id)
+                .execute(&mut *transaction)
+                .await
+                .map_err(|e| format!("Failed to rename group to '{name}': {e}"))?;
+            if result.rows_affected() == 0 {
+                return Err(format!("No group found with id: {id}"));
+            }
+            id
+        }
+        None => sqlx::query("INSERT INTO config_groups (name) VALUES (?1)")
+            .bind(&name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| format!("Failed to create group '{name}': {e}"))?
+            .last_insert_rowid(),
+    };
+
+    sqlx::query("DELETE FROM config_group_members WHERE group_id = ?1")
+        .bind(id)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| format!("Failed to clear members of group '{name}': {e}"))?;
+
+    for config_id in &group.config_ids {
+        sqlx::query(
+            "INSERT OR IGNORE INTO config_group_members (group_id, config_id)
+             SELECT ?1, id FROM configs WHERE id = ?2",
+        )
+        .bind(id)
+        .bind(config_id)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| format!("Failed to add config {config_id} to group '{name}': {e}"))?;
+    }
+
+    transaction.commit().await.map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+pub(crate) async fn delete_group_with_pool(id: i64, pool: &SqlitePool) -> Result<(), String> {
+    let mut transaction = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query("DELETE FROM config_group_members WHERE group_id = ?1")
+        .bind(id)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| format!("Failed to delete members of group {id}: {e}"))?;
+    sqlx::query("DELETE FROM config_groups WHERE id = ?1")
+        .bind(id)
+        .execute(&mut *transaction)
+        .await
+        .map_err(|e| format!("Failed to delete group {id}: {e}"))?;
+
+    transaction.commit().await.map_err(|e| e.to_string())
+}
+
+pub(crate) async fn get_group_configs_with_pool(
+    name: &str, pool: &SqlitePool,
+) -> Result<Vec<Config>, String> {
+    let name = normalize_group_name(name)?;
+    let configs = find_configs_with_pool(
+        pool,
+        "id IN (SELECT m.config_id FROM config_group_members m
+                JOIN config_groups g ON g.id = m.group_id WHERE g.name = ?1)",
+        &[&name],
+    )
+    .await?;
+
+    if configs.is_empty() {
+        let exists = sqlx::query("SELECT 1 FROM config_groups WHERE name = ?1")
+            .bind(&name)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some();
+        if !exists {
+            return Err(format!("No group found with name: {name}"));
+        }
+    }
+
+    Ok(configs)
+}
+
+pub async fn list_groups_with_mode(mode: DatabaseMode) -> Result<Vec<ConfigGroup>, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    list_groups_with_pool(&context.pool).await
+}
+
+pub async fn save_group_with_mode(group: &ConfigGroup, mode: DatabaseMode) -> Result<i64, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    save_group_with_pool(group, &context.pool).await
+}
+
+pub async fn delete_group_with_mode(id: i64, mode: DatabaseMode) -> Result<(), String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    delete_group_with_pool(id, &context.pool).await
+}
+
+pub async fn get_group_configs_with_mode(
+    name: &str, mode: DatabaseMode,
+) -> Result<Vec<Config>, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    get_group_configs_with_pool(name, &context.pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_db_table;
+    use crate::utils::config::{
+        delete_config_with_pool,
+        export_configs_with_pool,
+        import_configs_with_pool,
+        insert_config_with_pool,
+        read_configs_with_pool,
+    };
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("Failed to connect to in-memory database");
+        create_db_table(&pool)
+            .await
+            .expect("Failed to create tables");
+        crate::utils::migration::migrate_configs(Some(&pool))
+            .await
+            .expect("Failed to run migrations");
+        pool
+    }
+
+    fn grouped_config(service: &str, groups: &[&str]) -> Config {
+        Config {
+            service: Some(service.to_string()),
+            alias: Some(service.to_string()),
+            namespace: "payments-ns".to_string(),
+            workload_type: Some("service".to_string()),
+            groups: Some(groups.iter().map(|group| group.to_string()).collect()),
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_config_groups_round_trip_through_configs() {
+        let pool = setup_test_db().await;
+        insert_config_with_pool(grouped_config("api", &["payments", " backend "]), &pool)
+            .await
+            .unwrap();
+        insert_config_with_pool(grouped_config("db", &["payments"]), &pool)
+            .await
+            .unwrap();
+
+        let configs = read_configs_with_pool(&pool).await.unwrap();
+        let api = configs
+            .iter()
+            .find(|c| c.service.as_deref() == Some("api"))
+            .unwrap();
+        assert_eq!(
+            api.groups,
+            Some(vec!["backend".to_string(), "payments".to_string()])
+        );
+
+        let groups = list_groups_with_pool(&pool).await.unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].name, "payments");
+        assert_eq!(groups[1].config_ids.len(), 2);
+
+        let payments = get_group_configs_with_pool("payments", &pool)
+            .await
+            .unwrap();
+        assert_eq!(payments.len(), 2);
+
+        delete_config_with_pool(api.id.unwrap(), &pool)
+            .await
+            .unwrap();
+        let payments = get_group_configs_with_pool("payments", &pool)
+            .await
+            .unwrap();
+        assert_eq!(payments.len(), 1);
+        assert!(get_group_configs_with_pool("missing", &pool).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_and_delete_group() {
+        let pool = setup_test_db().await;
+        insert_config_with_pool(grouped_config("api", &[]), &pool)
+            .await
+            .unwrap();
+        let config_id = read_configs_with_pool(&pool).await.unwrap()[0].id.unwrap();
+
+        let id = save_group_with_pool(
+            &ConfigGroup {
+                id: None,
+                name: "stack".to_string(),
+                config_ids: vec![config_id, 999],
+            },
+            &pool,
+        )
+        .await
+        .unwrap();
+
+        let groups = list_groups_with_pool(&pool).await.unwrap();
+        assert_eq!(groups[0].config_ids, vec![config_id]);
+
+        save_group_with_pool(
+            &ConfigGroup {
+                id: Some(id),
+                name: "renamed".to_string(),
+                config_ids: vec![config_id],
+            },
+            &pool,
+        )
+        .await
+        .unwrap();
+        let config = read_configs_with_pool(&pool).await.unwrap().remove(0);
+        assert_eq!(config.groups, Some(vec!["renamed".to_string()]));
+
+        delete_group_with_pool(id, &pool).await.unwrap();
+        assert!(list_groups_with_pool(&pool).await.unwrap().is_empty());
+        let config = read_configs_with_pool(&pool).await.unwrap().remove(0);
+        assert!(config.groups.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_groups_are_carried_through_export_and_import() {
+        let pool = setup_test_db().await;
+        insert_config_with_pool(grouped_config("api", &["payments"]), &pool)
+            .await
+            .unwrap();
+
+        let exported = export_configs_with_pool(&pool).await.unwrap();
+        assert!(exported.contains("\"groups\""));
+
+        let target = setup_test_db().await;
+        import_configs_with_pool(exported, &target).await.unwrap();
+        let groups = list_groups_with_pool(&target).await.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name, "payments");
+        assert_eq!(groups[0].config_ids.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_without_groups_keeps_memberships() {
+        let pool = setup_test_db().await;
+        let config = Config {
+            context: Some("dev".to_string()),
+            protocol: "tcp".to_string(),
+            local_port: Some(8080),
+            remote_port: Some(80),
+            ..grouped_config("api", &["payments"])
+        };
+        insert_config_with_pool(config, &pool).await.unwrap();
+
+        let exported = export_configs_with_pool(&pool).await.unwrap();
+        let mut document: serde_json::Value = serde_json::from_str(&exported).unwrap();
+        let entry = document[0].as_object_mut().unwrap();
+        entry.remove("groups");
+        entry.insert("alias".to_string(), "api-renamed".into());
+
+        import_configs_with_pool(document.to_string(), &pool)
+            .await
+            .unwrap();
+        let config = read_configs_with_pool(&pool).await.unwrap().remove(0);
+        assert_eq!(config.alias.as_deref(), Some("api-renamed"));
+        assert_eq!(config.groups, Some(vec!["payments".to_string()]));
+
+        document[0]["groups"] = serde_json::json!([]);
+        import_configs_with_pool(document.to_string(), &pool)
+            .await
+            .unwrap();
+        let config = read_configs_with_pool(&pool).await.unwrap().remove(0);
+        assert!(config.groups.is_none());
+    }
+}
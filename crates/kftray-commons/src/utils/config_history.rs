@@ -0,0 +1,414 @@
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+use log::info;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{
+    Row,
+    SqliteConnection,
+    SqlitePool,
+};
+
+use crate::db::get_db_pool;
+use crate::models::config_history_model::{
+    ConfigChangeAction,
+    ConfigChangeSource,
+    ConfigRevision,
+};
+use crate::models::config_model::Config;
+use crate::utils::config::{
+    sync_restored_http_logs_config,
+    write_restored_config,
+};
+use crate::utils::config_validation::config_label;
+use crate::utils::db_mode::{
+    DatabaseManager,
+    DatabaseMode,
+};
+
+static DEFAULT_CHANGE_SOURCE: OnceLock<ConfigChangeSource> = OnceLock::new();
+
+tokio::task_local! {
+    static CHANGE_SOURCE: ConfigChangeSource;
+}
+
+/// Sets the source recorded for config changes made outside any
+/// `with_change_source` scope. Each frontend calls this once at startup.
+pub fn set_default_change_source(source: ConfigChangeSource) {
+    let _ = DEFAULT_CHANGE_SOURCE.set(source);
+}
+
+/// Runs `future` with config changes recorded as coming from `source`.
+pub async fn with_change_source<F: Future>(source: ConfigChangeSource, future: F) -> F::Output {
+    CHANGE_SOURCE.scope(source, future).await
+}
+
+/// Like `with_change_source`, but keeps a source set by an enclosing scope,
+/// so a Git sync that imports configs is still recorded as a Git sync.
+pub(crate) async fn with_default_change_source<F: Future>(
+    source: ConfigChangeSource, future: F,
+) -> F::Output {
+    if CHANGE_SOURCE.try_with(|_| ()).is_ok() {
+        future.await
+    } else {
+        with_change_source(source, future).await
+    }
+}
+
+pub fn current_change_source() -> ConfigChangeSource {
+    CHANGE_SOURCE
+        .try_with(|source| *source)
+        .unwrap_or_else(|_| DEFAULT_CHANGE_SOURCE.get().copied().unwrap_or_default())
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn snapshot_json(config: Option<&Config>) -> Result<Option<String>, String> {
+    config
+        .map(|config| serde_json::to_string(config).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+/// Appends a revision of `config_id` on the connection or transaction that
+/// made the change.
+pub(crate) async fn record_config_change(
+    conn: &mut SqliteConnection, config_id: i64, action: ConfigChangeAction,
+    before: Option<&Config>, after: Option<&Config>,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO config_history (config_id, action, source, before, after, changed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )
+    .bind(config_id)
+    .bind(action.as_str())
+    .bind(current_change_source().as_str())
+    .bind(snapshot_json(before)?)
+    .bind(snapshot_json(after)?)
+    .bind(now_secs())
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to record history of config {config_id}: {e}"))?;
+    Ok(())
+}
+
+pub(crate) async fn record_config_deletions(
+    conn: &mut SqliteConnection, configs: &[Config],
+) -> Result<(), String> {
+    for config in configs {
+        if let Some(id) = config.id {
+            record_config_change(conn, id, ConfigChangeAction::Delete, Some(config), None).await?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_snapshot(row: &SqliteRow, column: &str) -> Result<Option<Config>, String> {
+    let json: Option<String> = row.try_get(column).map_err(|e| e.to_string())?;
+    json.map(|json| {
+        serde_json::from_str(&json).map_err(|e| format!("Invalid {column} snapshot: {e}"))
+    })
+    .transpose()
+}
+
+fn revision_from_row(row: &SqliteRow) -> Result<ConfigRevision, String> {
+    let action: String = row.try_get("action").map_err(|e| e.to_string())?;
+    let source: String = row.try_get("source").map_err(|e| e.to_string())?;
+    let before = parse_snapshot(row, "before")?;
+    let after = parse_snapshot(row, "after")?;
+    let label = after
+        .as_ref()
+        .or(before.as_ref())
+        .map(config_label)
+        .unwrap_or_default();
+
+    Ok(ConfigRevision {
+        id: row.try_get("id").map_err(|e| e.to_string())?,
+        config_id: row.try_get("config_id").map_err(|e| e.to_string())?,
+        action: ConfigChangeAction::parse(&action)
+            .ok_or_else(|| format!("Unknown config history action '{action}'"))?,
+        source: ConfigChangeSource::parse(&source),
+        label,
+        before,
+        after,
+        changed_at: row.try_get("changed_at").map_err(|e| e.to_string())?,
+    })
+}
+
+const SELECT_REVISIONS: &str =
+    "SELECT id, config_id, action, source, before, after, changed_at FROM config_history";
+
+/// Revisions of `config_id`, or of every config, newest first.
+pub(crate) async fn list_config_history_with_pool(
+    config_id: Option<i64>, pool: &SqlitePool,
+) -> Result<Vec<ConfigRevision>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let rows = match config_id {
+        Some(config_id) => {
+            sqlx::query(&format!(
+                "{SELECT_REVISIONS} WHERE config_id = ?1 ORDER BY id DESC"
+            ))
+            .bind(config_id)
+            .fetch_all(&mut *conn)
+            .await
+        }
+        None => {
+            sqlx::query(&format!("{SELECT_REVISIONS} ORDER BY id DESC"))
+                .fetch_all(&mut *conn)
+                .await
+        }
+    }
+    .map_err(|e| e.to_string())?;
+
+    rows.iter().map(revision_from_row).collect()
+}
+
+/// The last deletion of every config that is not in `configs` anymore,
+/// newest first.
+pub(crate) async fn list_deleted_configs_with_pool(
+    pool: &SqlitePool,
+) -> Result<Vec<ConfigRevision>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let rows = sqlx::query(&format!(
+        "{SELECT_REVISIONS} WHERE id IN (SELECT MAX(id) FROM config_history GROUP BY config_id) \
+         AND action = 'delete' AND config_id NOT IN (SELECT id FROM configs) ORDER BY id DESC"
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    rows.iter().map(revision_from_row).collect()
+}
+
+/// Brings back the version of the config recorded by `revision_id`, see
+/// `ConfigRevision::snapshot`. A deleted config is inserted again under its
+/// old id. The restore is itself recorded, so it can be undone.
+pub(crate) async fn restore_config_revision_with_pool(
+    revision_id: i64, pool: &SqlitePool,
+) -> Result<Config, String> {
+    let mut transaction = pool.begin().await.map_err(|e| e.to_string())?;
+    let row = sqlx::query(&format!("{SELECT_REVISIONS} WHERE id = ?1"))
+        .bind(revision_id)
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No config revision found with id: {revision_id}"))?;
+    let revision = revision_from_row(&row)?;
+
+    let mut config = revision
+        .snapshot()
+        .cloned()
+        .ok_or_else(|| format!("Config revision {revision_id} has no snapshot"))?;
+    config.id = Some(revision.config_id);
+
+    let before = write_restored_config(&mut transaction, &config).await?;
+    record_config_change(
+        &mut transaction,
+        revision.config_id,
+        ConfigChangeAction::Restore,
+        before.as_ref(),
+        Some(&config),
+    )
+    .await?;
+    transaction.commit().await.map_err(|e| e.to_string())?;
+
+    sync_restored_http_logs_config(&config, pool).await?;
+    info!(
+        "Restored config {} from revision {revision_id}",
+        revision.config_id
+    );
+
+    Ok(config)
+}
+
+pub async fn list_config_history(config_id: Option<i64>) -> Result<Vec<ConfigRevision>, String> {
+    let pool = get_db_pool().await.map_err(|e| e.to_string())?;
+    list_config_history_with_pool(config_id, &pool).await
+}
+
+pub async fn restore_config_revision(revision_id: i64) -> Result<Config, String> {
+    let pool = get_db_pool().await.map_err(|e| e.to_string())?;
+    restore_config_revision_with_pool(revision_id, &pool).await
+}
+
+pub async fn list_deleted_configs() -> Result<Vec<ConfigRevision>, String> {
+    let pool = get_db_pool().await.map_err(|e| e.to_string())?;
+    list_deleted_configs_with_pool(&pool).await
+}
+
+pub async fn list_config_history_with_mode(
+    config_id: Option<i64>, mode: DatabaseMode,
+) -> Result<Vec<ConfigRevision>, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    list_config_history_with_pool(config_id, &context.pool).await
+}
+
+pub async fn list_deleted_configs_with_mode(
+    mode: DatabaseMode,
+) -> Result<Vec<ConfigRevision>, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    list_deleted_configs_with_pool(&context.pool).await
+}
+
+pub async fn restore_config_revision_with_mode(
+    revision_id: i64, mode: DatabaseMode,
+) -> Result<Config, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    restore_config_revision_with_pool(revision_id, &context.pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::create_db_table;
+    use crate::migration::migrate_configs;
+    use crate::test_utils::valid_config;
+    use crate::utils::config::{
+        delete_all_configs_with_pool,
+        get_config_with_pool,
+        insert_config_with_pool,
+        read_configs_with_pool,
+        update_config_with_pool,
+    };
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        create_db_table(&pool).await.unwrap();
+        migrate_configs(Some(&pool)).await.unwrap();
+        pool
+    }
+
+    fn service_config(service: &str, local_port: u16) -> Config {
+        Config {
+            service: Some(service.to_string()),
+            alias: Some(service.to_string()),
+            local_port: Some(local_port),
+            ..valid_config()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_records_changes_with_source() {
+        let pool = setup_test_db().await;
+        with_change_source(
+            ConfigChangeSource::Tui,
+            insert_config_with_pool(service_config("api", 8080), &pool),
+        )
+        .await
+        .unwrap();
+        let mut config = read_configs_with_pool(&pool).await.unwrap().remove(0);
+        let id = config.id.unwrap();
+
+        config.local_port = Some(9090);
+        with_change_source(
+            ConfigChangeSource::Gui,
+            update_config_with_pool(config.clone(), &pool),
+        )
+        .await
+        .unwrap();
+        // Saving without changes adds no revision.
+        update_config_with_pool(config, &pool).await.unwrap();
+
+        let history = list_config_history_with_pool(Some(id), &pool)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action, ConfigChangeAction::Update);
+        assert_eq!(history[0].source, ConfigChangeSource::Gui);
+        assert_eq!(history[0].before.as_ref().unwrap().local_port, Some(8080));
+        assert_eq!(history[0].after.as_ref().unwrap().local_port, Some(9090));
+        assert_eq!(history[1].action, ConfigChangeAction::Create);
+        assert_eq!(history[1].source, ConfigChangeSource::Tui);
+        assert_eq!(history[1].label, "api");
+
+        let restored = restore_config_revision_with_pool(history[1].id, &pool)
+            .await
+            .unwrap();
+        assert_eq!(restored.local_port, Some(8080));
+        assert_eq!(
+            get_config_with_pool(id, &pool).await.unwrap().local_port,
+            Some(8080)
+        );
+        let history = list_config_history_with_pool(Some(id), &pool)
+            .await
+            .unwrap();
+        assert_eq!(history[0].action, ConfigChangeAction::Restore);
+    }
+
+    #[tokio::test]
+    async fn test_restore_deleted_configs_after_flush() {
+        let pool = setup_test_db().await;
+        insert_config_with_pool(service_config("api", 8080), &pool)
+            .await
+            .unwrap();
+        insert_config_with_pool(service_config("db", 5432), &pool)
+            .await
+            .unwrap();
+
+        with_change_source(
+            ConfigChangeSource::Import,
+            delete_all_configs_with_pool(&pool),
+        )
+        .await
+        .unwrap();
+        assert!(read_configs_with_pool(&pool).await.unwrap().is_empty());
+
+        let deleted = list_deleted_configs_with_pool(&pool).await.unwrap();
+        assert_eq!(deleted.len(), 2);
+        assert!(
+            deleted
+                .iter()
+                .all(|revision| revision.source == ConfigChangeSource::Import)
+        );
+
+        let api = deleted
+            .iter()
+            .find(|revision| revision.label == "api")
+            .unwrap();
+        let restored = restore_config_revision_with_pool(api.id, &pool)
+            .await
+            .unwrap();
+        assert_eq!(restored.id, Some(api.config_id));
+        assert_eq!(
+            get_config_with_pool(api.config_id, &pool)
+                .await
+                .unwrap()
+                .service
+                .as_deref(),
+            Some("api")
+        );
+        assert_eq!(
+            list_deleted_configs_with_pool(&pool).await.unwrap().len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_history_is_append_only() {
+        let pool = setup_test_db().await;
+        insert_config_with_pool(service_config("api", 8080), &pool)
+            .await
+            .unwrap();
+
+        assert!(
+            sqlx::query("DELETE FROM config_history")
+                .execute(&pool)
+                .await
+                .is_err()
+        );
+        assert!(
+            sqlx::query("UPDATE config_history SET source = 'gui'")
+                .execute(&pool)
+                .await
+                .is_err()
+        );
+    }
+}
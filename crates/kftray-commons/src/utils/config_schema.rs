@@ -0,0 +1,457 @@
+use serde_json::{
+    Map,
+    Value,
+    json,
+};
+
+/// Shape of a `Config` field, used both to generate the JSON Schema and to
+/// validate imported entries with the same rules.
+#[derive(Clone, Copy)]
+enum FieldKind {
+    String,
+    StringList,
+    /// Integer within an inclusive range.
+    Integer(u64, u64),
+    Bool,
+    /// Boolean that also accepts the strings `"true"` and `"false"`.
+    LenientBool,
+    Enum(&'static [&'static str]),
+    /// Nested object with its own fields.
+    Object(&'static [FieldSpec]),
+}
+
+struct FieldSpec {
+    name: &'static str,
+    kind: FieldKind,
+    description: &'static str,
+}
+
+const fn field(name: &'static str, kind: FieldKind, description: &'static str) -> FieldSpec {
+    FieldSpec {
+        name,
+        kind,
+        description,
+    }
+}
+
+const WORKLOAD_TYPES: &[&str] = &["service", "pod", "proxy", "expose"];
+const PROTOCOLS: &[&str] = &["tcp", "udp"];
+const EXPOSURE_TYPES: &[&str] = &["cluster", "public"];
+const CERT_ISSUER_KINDS: &[&str] = &["ClusterIssuer", "Issuer"];
+const PORT_CONFLICT_POLICIES: &[&str] = &["fail", "next", "random"];
+const DRAIN_POLICIES: &[&str] = &["keep", "drain", "close"];
+const DEBUG_CONTAINER_MODES: &[&str] = &["auto", "always"];
+const PROXY_RELAYS: &[&str] = &["shared", "dedicated"];
+const POD_AGES: &[&str] = &["newest", "oldest"];
+
+const POD_SELECTION_FIELDS: &[FieldSpec] = &[
+    field(
+        "exclude_terminating",
+        FieldKind::Bool,
+        "Skip pods that are being deleted",
+    ),
+    field(
+        "restart_grace_secs",
+        FieldKind::Integer(0, u64::MAX),
+        "Avoid pods restarted within this many seconds",
+    ),
+    field(
+        "required_container",
+        FieldKind::String,
+        "Container that must be ready",
+    ),
+    field(
+        "preferred_node",
+        FieldKind::String,
+        "Prefer pods on this node",
+    ),
+    field(
+        "preferred_zone",
+        FieldKind::String,
+        "Prefer pods on nodes in this zone",
+    ),
+    field(
+        "preferred_annotation",
+        FieldKind::String,
+        "Prefer pods with this key or key=value annotation",
+    ),
+    field(
+        "prefer_age",
+        FieldKind::Enum(POD_AGES),
+        "Prefer the newest or oldest pod",
+    ),
+];
+
+const CONFIG_FIELDS: &[FieldSpec] = &[
+    field(
+        "id",
+        FieldKind::Integer(0, i64::MAX as u64),
+        "Database id, ignored on import",
+    ),
+    field(
+        "service",
+        FieldKind::String,
+        "Service name for service workloads",
+    ),
+    field("namespace", FieldKind::String, "Kubernetes namespace"),
+    field(
+        "local_port",
+        FieldKind::Integer(0, 65535),
+        "Local port, 0 picks a free one",
+    ),
+    field(
+        "remote_port",
+        FieldKind::Integer(0, 65535),
+        "Port on the target",
+    ),
+    field("context", FieldKind::String, "Kubeconfig context"),
+    field(
+        "workload_type",
+        FieldKind::Enum(WORKLOAD_TYPES),
+        "Kind of target to forward to",
+    ),
+    field("protocol", FieldKind::Enum(PROTOCOLS), "Forwarded protocol"),
+    field(
+        "remote_address",
+        FieldKind::String,
+        "Remote host for proxy workloads",
+    ),
+    field("local_address", FieldKind::String, "Local address to bind"),
+    field(
+        "auto_loopback_address",
+        FieldKind::Bool,
+        "Allocate a dedicated loopback address",
+    ),
+    field("alias", FieldKind::String, "Display name and hosts entry"),
+    field(
+        "domain_enabled",
+        FieldKind::LenientBool,
+        "Add the alias to the hosts file",
+    ),
+    field(
+        "kubeconfig",
+        FieldKind::String,
+        "Path to the kubeconfig file",
+    ),
+    field(
+        "target",
+        FieldKind::String,
+        "Pod label selector for pod workloads",
+    ),
+    field(
+        "http_logs_enabled",
+        FieldKind::LenientBool,
+        "Record HTTP traffic",
+    ),
+    field(
+        "http_logs_max_file_size",
+        FieldKind::Integer(0, u64::MAX),
+        "Maximum HTTP log size in bytes",
+    ),
+    field(
+        "http_logs_retention_days",
+        FieldKind::Integer(0, u64::MAX),
+        "Days to keep HTTP logs",
+    ),
+    field(
+        "http_logs_auto_cleanup",
+        FieldKind::LenientBool,
+        "Remove expired HTTP logs",
+    ),
+    field(
+        "exposure_type",
+        FieldKind::Enum(EXPOSURE_TYPES),
+        "Exposure scope for expose workloads",
+    ),
+    field(
+        "cert_manager_enabled",
+        FieldKind::LenientBool,
+        "Request a certificate from cert-manager",
+    ),
+    field("cert_issuer", FieldKind::String, "cert-manager issuer name"),
+    field(
+        "cert_issuer_kind",
+        FieldKind::Enum(CERT_ISSUER_KINDS),
+        "cert-manager issuer kind",
+    ),
+    field("ingress_class", FieldKind::String, "Ingress class name"),
+    field(
+        "ingress_annotations",
+        FieldKind::String,
+        "Extra ingress annotations as a JSON object string",
+    ),
+    field("groups", FieldKind::StringList, "Config groups"),
+    field(
+        "port_conflict_policy",
+        FieldKind::Enum(PORT_CONFLICT_POLICIES),
+        "What to do when local_port is taken: fail, next or random",
+    ),
+    field(
+        "impersonate_user",
+        FieldKind::String,
+        "User to impersonate on API requests",
+    ),
+    field(
+        "impersonate_groups",
+        FieldKind::StringList,
+        "Groups to impersonate along with impersonate_user",
+    ),
+    field(
+        "pod_selection",
+        FieldKind::Object(POD_SELECTION_FIELDS),
+        "How to choose among ready pods",
+    ),
+    field(
+        "drain_policy",
+        FieldKind::Enum(DRAIN_POLICIES),
+        "What to do with open connections when the pod changes: keep, drain or close",
+    ),
+    field(
+        "drain_timeout_secs",
+        FieldKind::Integer(0, u64::MAX),
+        "Seconds drain keeps connections on the previous pod",
+    ),
+    field(
+        "debug_container",
+        FieldKind::Enum(DEBUG_CONTAINER_MODES),
+        "Relay pod-local ports through an ephemeral container: auto or always",
+    ),
+    field(
+        "proxy_relay",
+        FieldKind::Enum(PROXY_RELAYS),
+        "Relay pod of tcp proxies: dedicated per config (default) or shared per namespace",
+    ),
+];
+
+fn field_schema(spec: &FieldSpec) -> Value {
+    let mut schema = match spec.kind {
+        FieldKind::String => json!({ "type": ["string", "null"] }),
+        FieldKind::StringList => json!({ "type": "array", "items": { "type": "string" } }),
+        FieldKind::Integer(min, max) => {
+            let mut schema = json!({ "type": ["integer", "null"], "minimum": min });
+            if max < u64::MAX && max < i64::MAX as u64 {
+                schema["maximum"] = json!(max);
+            }
+            schema
+        }
+        FieldKind::Bool => json!({ "type": "boolean" }),
+        FieldKind::LenientBool => json!({
+            "anyOf": [
+                { "type": ["boolean", "null"] },
+                { "enum": ["true", "false"] }
+            ]
+        }),
+        FieldKind::Enum(values) => json!({ "enum": values }),
+        FieldKind::Object(fields) => json!({
+            "type": ["object", "null"],
+            "properties": fields
+                .iter()
+                .map(|spec| (spec.name.to_string(), field_schema(spec)))
+                .collect::<Map<String, Value>>(),
+            "additionalProperties": false
+        }),
+    };
+    schema["description"] = json!(spec.description);
+    schema
+}
+
+/// Generates the JSON Schema of an import document: a list of configs, a
+/// single config, or a `configs` list with shared `defaults`.
+pub fn config_schema() -> Value {
+    let properties: Map<String, Value> = CONFIG_FIELDS
+        .iter()
+        .map(|spec| (spec.name.to_string(), field_schema(spec)))
+        .collect();
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": "https://kftray.app/schemas/kftray-configs.schema.json",
+        "title": "kftray configs",
+        "anyOf": [
+            { "type": "array", "items": { "$ref": "#/$defs/config" } },
+            {
+                "type": "object",
+                "properties": {
+                    "$schema": { "type": "string" },
+                    "defaults": { "$ref": "#/$defs/defaults" },
+                    "configs": { "type": "array", "items": { "$ref": "#/$defs/config" } }
+                },
+                "required": ["configs"],
+                "additionalProperties": false
+            },
+            { "$ref": "#/$defs/config" }
+        ],
+        "$defs": {
+            "defaults": {
+                "type": "object",
+                "properties": properties.clone()
+            },
+            "config": {
+                "type": "object",
+                "properties": properties,
+                "required": ["namespace"]
+            }
+        }
+    })
+}
+
+pub fn config_schema_json() -> String {
+    serde_json::to_string_pretty(&config_schema()).expect("config schema is valid JSON")
+}
+
+fn describe_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn check_field(kind: FieldKind, value: &Value) -> Result<(), String> {
+    let expected = |what: &str| Err(format!("expected {what}, got {}", describe_type(value)));
+
+    match kind {
+        FieldKind::String => match value {
+            Value::String(_) | Value::Null => Ok(()),
+            _ => expected("a string"),
+        },
+        FieldKind::StringList => match value {
+            Value::Array(items) => match items.iter().position(|item| !item.is_string()) {
+                Some(index) => Err(format!("[{index}]: expected a string")),
+                None => Ok(()),
+            },
+            _ => expected("a list of strings"),
+        },
+        FieldKind::Integer(min, max) => match value {
+            Value::Null => Ok(()),
+            Value::Number(number) => match number.as_u64() {
+                Some(n) if (min..=max).contains(&n) => Ok(()),
+                _ if max == u64::MAX => Err(format!("expected an integer >= {min}")),
+                _ => Err(format!("expected {min}..{max}")),
+            },
+            _ => expected("an integer"),
+        },
+        FieldKind::Bool => match value {
+            Value::Bool(_) => Ok(()),
+            _ => expected("true or false"),
+        },
+        FieldKind::LenientBool => match value {
+            Value::Bool(_) | Value::Null => Ok(()),
+            Value::String(s) if s == "true" || s == "false" => Ok(()),
+            _ => expected("true or false"),
+        },
+        FieldKind::Enum(values) => match value {
+            Value::String(s) if values.contains(&s.as_str()) => Ok(()),
+            Value::Null => Ok(()),
+            _ => Err(format!("expected one of {}", values.join(", "))),
+        },
+        FieldKind::Object(fields) => match value {
+            Value::Null => Ok(()),
+            Value::Object(map) => {
+                if let Some(key) = map
+                    .keys()
+                    .find(|key| !fields.iter().any(|f| f.name == *key))
+                {
+                    return Err(format!(".{key}: is not a known field"));
+                }
+                fields
+                    .iter()
+                    .filter_map(|spec| Some((spec, map.get(spec.name)?)))
+                    .try_for_each(|(spec, value)| {
+                        check_field(spec.kind, value).map_err(|e| {
+                            let separator = if e.starts_with(['[', '.']) { "" } else { ": " };
+                            format!(".{}{separator}{e}", spec.name)
+                        })
+                    })
+            }
+            _ => expected("an object"),
+        },
+    }
+}
+
+/// Checks an expanded config entry against the schema, appending one
+/// `<path>.<field>: <problem>` message per violation to `errors`.
+pub(crate) fn validate_config_entry(entry: &Value, path: &str, errors: &mut Vec<String>) {
+    let Value::Object(map) = entry else {
+        errors.push(format!("{path}: expected a config object"));
+        return;
+    };
+
+    for spec in CONFIG_FIELDS {
+        match map.get(spec.name) {
+            Some(value) => {
+                if let Err(e) = check_field(spec.kind, value) {
+                    let separator = if e.starts_with(['[', '.']) { "" } else { ": " };
+                    errors.push(format!("{path}.{}{separator}{e}", spec.name));
+                }
+            }
+            None if spec.name == "namespace" => {
+                errors.push(format!("{path}.namespace: is required"));
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_schema_is_up_to_date() {
+        let published = include_str!("../../schema/kftray-configs.schema.json");
+        assert_eq!(
+            published.trim_end(),
+            config_schema_json(),
+            "regenerate schema/kftray-configs.schema.json from config_schema_json()"
+        );
+    }
+
+    #[test]
+    fn test_validate_config_entry_reports_every_problem() {
+        let entry = json!({
+            "service": "api",
+            "remote_port": 70000,
+            "local_port": "8080",
+            "protocol": "sctp",
+            "domain_enabled": "yes",
+            "groups": ["ok", 3],
+            "pod_selection": { "prefer_age": "middle" }
+        });
+
+        let mut errors = Vec::new();
+        validate_config_entry(&entry, "[3]", &mut errors);
+
+        assert_eq!(
+            errors,
+            vec![
+                "[3].namespace: is required",
+                "[3].local_port: expected an integer, got a string",
+                "[3].remote_port: expected 0..65535",
+                "[3].protocol: expected one of tcp, udp",
+                "[3].domain_enabled: expected true or false, got a string",
+                "[3].groups[1]: expected a string",
+                "[3].pod_selection.prefer_age: expected one of newest, oldest",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_config_entry_accepts_exported_configs() {
+        let entry = serde_json::to_value(crate::models::config_model::Config {
+            workload_type: Some("service".to_string()),
+            protocol: "tcp".to_string(),
+            local_port: Some(8080),
+            remote_port: Some(80),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut errors = Vec::new();
+        validate_config_entry(&entry, "[0]", &mut errors);
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+}
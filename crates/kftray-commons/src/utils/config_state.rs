@@ -15,13 +15,17 @@ pub async fn update_config_state_with_pool(
     config_state: &ConfigState, pool: &SqlitePool,
 ) -> Result<(), String> {
     let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
-    sqlx::query("UPDATE config_state SET is_running = ?1, process_id = ?2 WHERE config_id = ?3")
-        .bind(config_state.is_running)
-        .bind(config_state.process_id)
-        .bind(config_state.config_id)
-        .execute(&mut *conn)
-        .await
-        .map_err(|e| e.to_string())?;
+    sqlx::query(
+        "UPDATE config_state SET is_running = ?1, process_id = ?2, local_port = ?3 \
+         WHERE config_id = ?4",
+    )
+    .bind(config_state.is_running)
+    .bind(config_state.process_id)
+    .bind(config_state.local_port)
+    .bind(config_state.config_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
@@ -37,13 +41,14 @@ pub async fn read_config_states_with_pool(
         error!("Failed to acquire database connection: {e}");
         e
     })?;
-    let rows = sqlx::query("SELECT id, config_id, is_running, process_id FROM config_state")
-        .fetch_all(&mut *conn)
-        .await
-        .map_err(|e| {
-            error!("Failed to fetch config states: {e}");
-            e
-        })?;
+    let rows =
+        sqlx::query("SELECT id, config_id, is_running, process_id, local_port FROM config_state")
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch config states: {e}");
+                e
+            })?;
 
     let config_states = rows
         .into_iter()
@@ -58,11 +63,13 @@ pub async fn read_config_states_with_pool(
                 e
             })?;
             let process_id: Option<u32> = row.try_get("process_id").ok().flatten();
+            let local_port: Option<u16> = row.try_get("local_port").ok().flatten();
             Ok(ConfigState {
                 id,
                 config_id,
                 is_running,
                 process_id,
+                local_port,
             })
         })
         .collect::<Result<Vec<_>, sqlx::Error>>()?;
@@ -121,7 +128,8 @@ pub async fn cleanup_current_process_config_states() -> Result<(), String> {
     let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
 
     let affected_rows = sqlx::query(
-        "UPDATE config_state SET is_running = false, process_id = NULL WHERE process_id = ?1",
+        "UPDATE config_state SET is_running = false, process_id = NULL, local_port = NULL \
+         WHERE process_id = ?1",
     )
     .bind(current_process_id)
     .execute(&mut *conn)
@@ -144,7 +152,8 @@ pub async fn cleanup_current_process_config_states_with_mode(
     let mut conn = context.pool.acquire().await.map_err(|e| e.to_string())?;
 
     let affected_rows = sqlx::query(
-        "UPDATE config_state SET is_running = false, process_id = NULL WHERE process_id = ?1",
+        "UPDATE config_state SET is_running = false, process_id = NULL, local_port = NULL \
+         WHERE process_id = ?1",
     )
     .bind(current_process_id)
     .execute(&mut *conn)
@@ -168,6 +177,7 @@ mod tests {
     use super::*;
     use crate::db::create_db_table;
     use crate::models::config_model::Config;
+    use crate::test_utils::valid_config;
     use crate::utils::config;
 
     async fn setup_test_db() -> SqlitePool {
@@ -188,7 +198,7 @@ mod tests {
         let pool = setup_test_db().await;
         let config_data = Config {
             service: Some("state-test-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -208,7 +218,7 @@ mod tests {
         let pool = setup_test_db().await;
         let config_data = Config {
             service: Some("state-test-2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -226,6 +236,7 @@ mod tests {
             config_id,
             is_running: true,
             process_id: Some(1234),
+            local_port: None,
         };
         update_config_state_with_pool(&state_to_update, &pool)
             .await
@@ -241,16 +252,51 @@ mod tests {
         assert_eq!(updated_state.id, initial_state.id);
     }
 
+    #[tokio::test]
+    async fn test_config_state_running_local_port() {
+        let pool = setup_test_db().await;
+        let config_data = Config {
+            service: Some("state-port-test".to_string()),
+            local_port: Some(8080),
+            ..valid_config()
+        };
+        config::insert_config_with_pool(config_data, &pool)
+            .await
+            .unwrap();
+        let config_id = config::read_configs_with_pool(&pool).await.unwrap()[0]
+            .id
+            .unwrap();
+
+        let running = ConfigState {
+            local_port: Some(8081),
+            ..ConfigState::new(config_id, true)
+        };
+        update_config_state_with_pool(&running, &pool)
+            .await
+            .unwrap();
+        let states = read_config_states_with_pool(&pool).await.unwrap();
+        assert_eq!(states[0].local_port, Some(8081));
+        let configs = config::read_configs_with_pool(&pool).await.unwrap();
+        assert_eq!(configs[0].local_port, Some(8080));
+
+        let stopped = ConfigState::new_without_process(config_id, false);
+        update_config_state_with_pool(&stopped, &pool)
+            .await
+            .unwrap();
+        let states = read_config_states_with_pool(&pool).await.unwrap();
+        assert_eq!(states[0].local_port, None);
+    }
+
     #[tokio::test]
     async fn test_read_multiple_config_states() {
         let pool = setup_test_db().await;
         let config1 = Config {
             service: Some("state-test-3".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let config2 = Config {
             service: Some("state-test-4".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config1.clone(), &pool)
             .await
@@ -276,6 +322,7 @@ mod tests {
             config_id: config1_id,
             is_running: true,
             process_id: Some(1234),
+            local_port: None,
         };
         update_config_state_with_pool(&state_to_update, &pool)
             .await
@@ -293,7 +340,7 @@ mod tests {
         let pool = setup_test_db().await;
         let config_data = Config {
             service: Some("state-test-wrapper".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -310,7 +357,7 @@ mod tests {
         let pool = setup_test_db().await;
         let config_data = Config {
             service: Some("state-test-5".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -323,6 +370,7 @@ mod tests {
             config_id,
             is_running: true,
             process_id: Some(1234),
+            local_port: None,
         };
 
         tokio::task::yield_now().await;
@@ -340,7 +388,7 @@ mod tests {
         let pool = setup_test_db().await;
         let config_data = Config {
             service: Some("state-test-6".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -356,7 +404,7 @@ mod tests {
         let pool = setup_test_db().await;
         let config_data = Config {
             service: Some("state-test-7".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -381,6 +429,7 @@ mod tests {
             config_id: 1,
             is_running: true,
             process_id: Some(1234),
+            local_port: None,
         };
 
         let result = update_config_state_with_pool(&config_state, &pool).await;
@@ -402,11 +451,11 @@ mod tests {
 
         let config1 = Config {
             service: Some("row-test-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let config2 = Config {
             service: Some("row-test-2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         config::insert_config_with_pool(config1.clone(), &pool)
@@ -429,6 +478,7 @@ mod tests {
             config_id: config2_id,
             is_running: true,
             process_id: Some(1234),
+            local_port: None,
         };
 
         update_config_state_with_pool(&state, &pool).await.unwrap();
@@ -449,7 +499,7 @@ mod tests {
 
         let config_data = Config {
             service: Some("public-test-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -469,6 +519,7 @@ mod tests {
             config_id,
             is_running: true,
             process_id: Some(1234),
+            local_port: None,
         };
 
         let result = update_config_state_with_pool(&state, &pool).await;
@@ -485,7 +536,7 @@ mod tests {
 
         let config_data = Config {
             service: Some("public-read-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -502,7 +553,7 @@ mod tests {
 
         let config_data = Config {
             service: Some("get-wrapper-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -520,7 +571,7 @@ mod tests {
 
         let config_data = Config {
             service: Some("error-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         config::insert_config_with_pool(config_data.clone(), &pool)
             .await
@@ -539,7 +590,7 @@ mod tests {
 
         let config_data = Config {
             service: Some("memory-state-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         config::insert_config_with_mode(config_data, DatabaseMode::Memory)
@@ -563,6 +614,7 @@ mod tests {
             config_id,
             is_running: true,
             process_id: Some(1234),
+            local_port: None,
         };
 
         update_config_state_with_mode(&state_update, DatabaseMode::Memory)
@@ -584,7 +636,7 @@ mod tests {
 
         let config_data = Config {
             service: Some("get-state-memory-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         config::insert_config_with_mode(config_data, DatabaseMode::Memory)
@@ -0,0 +1,306 @@
+use serde_json::{
+    Map,
+    Value,
+};
+
+use crate::utils::migration::merge_json_values;
+
+/// Config fields holding numbers or booleans. A value made of a single
+/// placeholder, like `"${API_PORT:-8080}"`, is converted for these fields so
+/// the expanded document still deserializes into `Config`.
+const TYPED_FIELDS: &[&str] = &[
+    "local_port",
+    "remote_port",
+    "http_logs_max_file_size",
+    "http_logs_retention_days",
+    "auto_loopback_address",
+    "domain_enabled",
+    "http_logs_enabled",
+    "http_logs_auto_cleanup",
+    "cert_manager_enabled",
+];
+
+/// Expands an imported config document into one JSON object per config.
+///
+/// The document is either a list of configs, a single config, or an object
+/// with a `configs` list and a `defaults` object merged into every entry.
+/// String values may reference `${VAR}` and `${VAR:-default}`; `${USER}`
+/// falls back to `USERNAME` and `${context}` is the entry's own context.
+/// `$${` is kept as a literal `${`. Errors are collected for all entries and
+/// prefixed with the entry path, e.g. `[2].namespace`.
+pub(crate) fn expand_config_document(document: Value) -> Result<Vec<Value>, Vec<String>> {
+    expand_config_document_with(document, &|name| std::env::var(name).ok())
+}
+
+fn expand_config_document_with(
+    document: Value, env: &dyn Fn(&str) -> Option<String>,
+) -> Result<Vec<Value>, Vec<String>> {
+    let (defaults, entries) = match document {
+        Value::Array(entries) => (None, entries),
+        Value::Object(mut map) if map.contains_key("configs") => {
+            let defaults = map.remove("defaults");
+            if defaults.as_ref().is_some_and(|d| !d.is_object()) {
+                return Err(vec!["defaults: expected an object".to_string()]);
+            }
+            match map.remove("configs") {
+                Some(Value::Array(entries)) => (defaults, entries),
+                _ => return Err(vec!["configs: expected a list".to_string()]),
+            }
+        }
+        Value::Object(map) if map.contains_key("defaults") => {
+            return Err(vec!["defaults: requires a `configs` list".to_string()]);
+        }
+        entry @ Value::Object(_) => (None, vec![entry]),
+        _ => {
+            return Err(vec![
+                "expected a config object or a list of configs".to_string(),
+            ]);
+        }
+    };
+
+    let mut expanded = Vec::with_capacity(entries.len());
+    let mut errors = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        if !entry.is_object() {
+            errors.push(format!("[{index}]: expected a config object"));
+            continue;
+        }
+        let entry = match &defaults {
+            Some(defaults) => merge_json_values(defaults.clone(), entry),
+            None => entry,
+        };
+        match expand_entry(entry, env) {
+            Ok(entry) => expanded.push(entry),
+            Err(e) => errors.push(format!("[{index}].{e}")),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(expanded)
+    } else {
+        Err(errors)
+    }
+}
+
+fn expand_entry(entry: Value, env: &dyn Fn(&str) -> Option<String>) -> Result<Value, String> {
+    let Value::Object(mut map) = entry else {
+        return Ok(entry);
+    };
+
+    // The context is expanded first so other fields can refer to it.
+    let context = match map.remove("context") {
+        Some(Value::String(raw)) => {
+            let lookup = |name: &str| builtin_or_env(name, None, env);
+            let value = expand_string(&raw, &lookup).map_err(|e| format!("context: {e}"))?;
+            Some(value)
+        }
+        Some(other) => {
+            map.insert("context".to_string(), other);
+            None
+        }
+        None => None,
+    };
+
+    let lookup = |name: &str| builtin_or_env(name, context.as_deref(), env);
+    let mut expanded = Map::with_capacity(map.len() + 1);
+    for (key, value) in map {
+        let value = expand_value(value, &lookup, TYPED_FIELDS.contains(&key.as_str()))
+            .map_err(|e| format!("{key}: {e}"))?;
+        expanded.insert(key, value);
+    }
+    if let Some(context) = context {
+        expanded.insert("context".to_string(), Value::String(context));
+    }
+
+    Ok(Value::Object(expanded))
+}
+
+fn builtin_or_env(
+    name: &str, context: Option<&str>, env: &dyn Fn(&str) -> Option<String>,
+) -> Option<String> {
+    match name {
+        "context" => context.map(String::from),
+        "USER" => env("USER").or_else(|| env("USERNAME")),
+        _ => env(name),
+    }
+}
+
+fn expand_value(
+    value: Value, lookup: &dyn Fn(&str) -> Option<String>, typed: bool,
+) -> Result<Value, String> {
+    match value {
+        Value::String(raw) => {
+            let expanded = expand_string(&raw, lookup)?;
+            if typed && is_single_placeholder(&raw) {
+                if let Ok(number) = expanded.parse::<u64>() {
+                    return Ok(Value::from(number));
+                }
+                if let Ok(flag) = expanded.parse::<bool>() {
+                    return Ok(Value::Bool(flag));
+                }
+            }
+            Ok(Value::String(expanded))
+        }
+        Value::Array(items) => items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                expand_value(item, lookup, false).map_err(|e| format!("[{index}] {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, item)| {
+                expand_value(item, lookup, false)
+                    .map(|item| (key.clone(), item))
+                    .map_err(|e| format!("{key}: {e}"))
+            })
+            .collect::<Result<Map<_, _>, _>>()
+            .map(Value::Object),
+        other => Ok(other),
+    }
+}
+
+fn is_single_placeholder(raw: &str) -> bool {
+    raw.starts_with("${") && raw.find('}') == Some(raw.len() - 1)
+}
+
+fn expand_string(raw: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut output = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start..];
+
+        if let Some(escaped) = after.strip_prefix("$${") {
+            output.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let Some(body) = after.strip_prefix("${") else {
+            output.push('$');
+            rest = &after[1..];
+            continue;
+        };
+        let end = body
+            .find('}')
+            .ok_or_else(|| format!("unterminated placeholder in '{raw}'"))?;
+
+        let expression = &body[..end];
+        let (name, default) = match expression.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expression, None),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("invalid placeholder '${{{expression}}}'"));
+        }
+
+        match (lookup(name).filter(|value| !value.is_empty()), default) {
+            (Some(value), _) => output.push_str(&value),
+            (None, Some(default)) => output.push_str(default),
+            (None, None) => return Err(format!("variable '{name}' is not set")),
+        }
+        rest = &body[end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn expand(json: &str, vars: &[(&str, &str)]) -> Result<Vec<Value>, Vec<String>> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let document = serde_json::from_str(json).unwrap();
+        expand_config_document_with(document, &|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_expands_variables_defaults_and_builtins() {
+        let configs = expand(
+            r#"[{
+                "namespace": "dev-${USER}",
+                "context": "${CLUSTER:-kind-local}",
+                "alias": "${context}-api",
+                "local_port": "${API_PORT:-8080}",
+                "domain_enabled": "${DOMAINS:-true}",
+                "remote_address": "$${literal}"
+            }]"#,
+            &[("USERNAME", "alice")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            configs[0],
+            json!({
+                "namespace": "dev-alice",
+                "context": "kind-local",
+                "alias": "kind-local-api",
+                "local_port": 8080,
+                "domain_enabled": true,
+                "remote_address": "${literal}"
+            })
+        );
+    }
+
+    #[test]
+    fn test_defaults_are_merged_into_every_entry() {
+        let configs = expand(
+            r#"{
+                "defaults": {"context": "staging", "namespace": "team-${TEAM}"},
+                "configs": [
+                    {"service": "api"},
+                    {"service": "db", "namespace": "shared"}
+                ]
+            }"#,
+            &[("TEAM", "payments")],
+        )
+        .unwrap();
+
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0]["namespace"], "team-payments");
+        assert_eq!(configs[0]["context"], "staging");
+        assert_eq!(configs[1]["namespace"], "shared");
+    }
+
+    #[test]
+    fn test_missing_variables_report_their_paths() {
+        let errors = expand(
+            r#"[{"namespace": "${TEAM}"}, {"service": "api"}, {"alias": "${NAME}"}]"#,
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "[0].namespace: variable 'TEAM' is not set",
+                "[2].alias: variable 'NAME' is not set",
+            ]
+        );
+
+        let errors = expand(r#"{"namespace": "${TEAM"}"#, &[]).unwrap_err();
+        assert!(errors[0].contains("unterminated placeholder"));
+    }
+
+    #[test]
+    fn test_single_config_and_plain_strings_are_kept() {
+        let configs = expand(
+            r#"{"service": "api", "alias": "price $5", "local_port": "8080"}"#,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(configs[0]["alias"], "price $5");
+        assert_eq!(configs[0]["local_port"], "8080");
+    }
+}
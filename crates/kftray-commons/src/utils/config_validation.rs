@@ -0,0 +1,760 @@
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet,
+};
+
+use log::warn;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sqlx::SqlitePool;
+
+use crate::models::config_model::Config;
+use crate::utils::config::{
+    find_alias_conflicts_with_pool,
+    find_local_endpoint_conflicts_with_pool,
+    read_configs_with_pool,
+};
+use crate::utils::connection_drain::DrainPolicy;
+use crate::utils::db_mode::{
+    DatabaseManager,
+    DatabaseMode,
+};
+use crate::utils::local_port::{
+    PortConflictPolicy,
+    local_endpoint,
+    next_free_port,
+    reserved_local_ports_with_pool,
+};
+
+const MAX_HTTP_LOGS_FILE_SIZE: u64 = 100 * 1024 * 1024;
+const MAX_HTTP_LOGS_RETENTION_DAYS: u64 = 365;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// The config is accepted but may not behave as intended: a setting has
+    /// no effect or a value is missing.
+    Warning,
+    /// The config cannot be started as written, or cannot be stored because
+    /// another config uses the same alias or local address and port.
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub severity: IssueSeverity,
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Issues found for a single config.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigValidation {
+    pub config_id: Option<i64>,
+    pub label: String,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ConfigValidation {
+    fn new(config: &Config) -> Self {
+        ConfigValidation {
+            config_id: config.id,
+            label: config_label(config),
+            issues: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, field: &str, message: impl Into<String>) {
+        self.push(IssueSeverity::Error, field, message.into());
+    }
+
+    fn warning(&mut self, field: &str, message: impl Into<String>) {
+        self.push(IssueSeverity::Warning, field, message.into());
+    }
+
+    fn push(&mut self, severity: IssueSeverity, field: &str, message: String) {
+        self.issues.push(ValidationIssue {
+            severity,
+            field: field.to_string(),
+            message,
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Logs the warnings and turns the errors into one message, for callers
+    /// that refuse to save or start an invalid config.
+    pub fn into_result(self) -> Result<Self, String> {
+        for issue in self.warnings() {
+            warn!("Config '{}': {issue}", self.label);
+        }
+
+        if !self.has_errors() {
+            return Ok(self);
+        }
+
+        let errors = self
+            .errors()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(format!("Invalid config '{}': {errors}", self.label))
+    }
+}
+
+/// Human-readable name for a config: alias, service, target or id.
+pub fn config_label(config: &Config) -> String {
+    [&config.alias, &config.service, &config.target]
+        .into_iter()
+        .flatten()
+        .find(|value| !value.trim().is_empty())
+        .cloned()
+        .or_else(|| config.id.map(|id| format!("#{id}")))
+        .unwrap_or_else(|| "new config".to_string())
+}
+
+fn is_blank(value: &Option<String>) -> bool {
+    value.as_deref().is_none_or(|value| value.trim().is_empty())
+}
+
+/// Checks a config on its own: required fields per workload type, settings
+/// that have no effect, and HTTP log limits.
+pub fn validate_config(config: &Config) -> ConfigValidation {
+    let mut result = ConfigValidation::new(config);
+    let workload_type = config.workload_type.as_deref();
+
+    if config.namespace.trim().is_empty() {
+        result.error("namespace", "is required");
+    }
+
+    match workload_type {
+        Some("service") => {
+            if is_blank(&config.service) {
+                result.error("service", "is required for service workloads");
+            }
+        }
+        Some("pod") => {
+            if is_blank(&config.target) {
+                result.error(
+                    "target",
+                    "a pod label selector is required for pod workloads",
+                );
+            }
+        }
+        Some("proxy") => {
+            if is_blank(&config.remote_address) {
+                result.error("remote_address", "is required for proxy workloads");
+            }
+        }
+        Some("expose") => {
+            if is_blank(&config.alias) {
+                result.error("alias", "a domain is required for expose workloads");
+            }
+            if config.local_port.is_none_or(|port| port == 0) {
+                result.error("local_port", "is required for expose workloads");
+            }
+            if config.exposure_type.as_deref() == Some("public")
+                && config.cert_manager_enabled.unwrap_or(false)
+                && is_blank(&config.cert_issuer)
+            {
+                result.error("cert_issuer", "is required when cert-manager is enabled");
+            }
+        }
+        Some(other) => result.error(
+            "workload_type",
+            format!("'{other}' is not one of service, pod, proxy or expose"),
+        ),
+        None => result.error("workload_type", "is required"),
+    }
+
+    if matches!(workload_type, Some("service" | "pod" | "proxy")) {
+        // Older exports often omit these, so they are reported without
+        // rejecting the config.
+        if config.remote_port.is_none_or(|port| port == 0) {
+            result.warning("remote_port", "is not set, the forward cannot start");
+        }
+        if !matches!(config.protocol.as_str(), "tcp" | "udp") {
+            result.warning(
+                "protocol",
+                format!(
+                    "'{}' is not tcp or udp, the forward cannot start",
+                    config.protocol
+                ),
+            );
+        }
+    }
+
+    if workload_type.is_some() && workload_type != Some("expose") {
+        let expose_only = [
+            ("exposure_type", config.exposure_type.is_some()),
+            (
+                "cert_manager_enabled",
+                config.cert_manager_enabled.unwrap_or(false),
+            ),
+            ("cert_issuer", !is_blank(&config.cert_issuer)),
+            ("ingress_class", !is_blank(&config.ingress_class)),
+        ];
+        for (field, set) in expose_only {
+            if set {
+                result.warning(field, "only applies to expose workloads and is ignored");
+            }
+        }
+    }
+
+    if config.domain_enabled.unwrap_or(false) && is_blank(&config.alias) {
+        result.warning(
+            "domain_enabled",
+            "has no effect without an alias to add to the hosts file",
+        );
+    }
+
+    if config.auto_loopback_address && !is_blank(&config.local_address) {
+        result.warning(
+            "local_address",
+            "is replaced by the address allocated for auto_loopback_address",
+        );
+    }
+
+    if let Err(message) = PortConflictPolicy::parse(config.port_conflict_policy.as_deref()) {
+        result.error("port_conflict_policy", message);
+    }
+
+    match DrainPolicy::parse(config.drain_policy.as_deref(), config.drain_timeout_secs) {
+        Err(message) => result.error("drain_policy", message),
+        Ok(DrainPolicy::Drain(_)) => {}
+        Ok(_) if config.drain_timeout_secs.is_some() => {
+            result.warning("drain_timeout_secs", "only applies to the drain policy")
+        }
+        Ok(_) => {}
+    }
+
+    if !config.impersonate_groups.is_empty() && is_blank(&config.impersonate_user) {
+        result.error(
+            "impersonate_groups",
+            "requires impersonate_user, Kubernetes cannot impersonate groups alone",
+        );
+    }
+
+    match config.debug_container.as_deref() {
+        None | Some("auto" | "always") => {}
+        Some(other) => result.error(
+            "debug_container",
+            format!("'{other}' is not one of auto or always"),
+        ),
+    }
+    if config.debug_container.is_some() {
+        match (workload_type, config.protocol.as_str()) {
+            (Some("pod"), _) | (Some("service"), "tcp") => {}
+            (Some("service"), _) => result.warning(
+                "debug_container",
+                "is ignored for udp services, which already use a relay pod",
+            ),
+            _ => result.warning(
+                "debug_container",
+                "only applies to service and pod workloads and is ignored",
+            ),
+        }
+    }
+
+    match config.proxy_relay.as_deref() {
+        None | Some("shared" | "dedicated") => {}
+        Some(other) => result.error(
+            "proxy_relay",
+            format!("'{other}' is not one of shared or dedicated"),
+        ),
+    }
+    if config.proxy_relay.is_some() {
+        match (workload_type, config.protocol.as_str()) {
+            (Some("proxy" | "service"), "tcp") => {}
+            (Some("proxy" | "service"), _) => result.warning(
+                "proxy_relay",
+                "is ignored for udp, which always uses a dedicated relay pod",
+            ),
+            _ => result.warning(
+                "proxy_relay",
+                "only applies to proxy workloads and is ignored",
+            ),
+        }
+    }
+
+    if let Some(selection) = &config.pod_selection {
+        if !matches!(workload_type, Some("service" | "pod")) {
+            result.warning(
+                "pod_selection",
+                "only applies to service and pod workloads and is ignored",
+            );
+        }
+        if let Some(age) = selection.prefer_age.as_deref()
+            && !matches!(age, "newest" | "oldest")
+        {
+            result.error(
+                "pod_selection.prefer_age",
+                format!("'{age}' is not newest or oldest"),
+            );
+        }
+    }
+
+    if let Some(size) = config.http_logs_max_file_size {
+        if size == 0 {
+            result.error("http_logs_max_file_size", "must be greater than 0");
+        } else if size > MAX_HTTP_LOGS_FILE_SIZE {
+            result.error("http_logs_max_file_size", "cannot exceed 100MB");
+        }
+    }
+
+    if let Some(days) = config.http_logs_retention_days {
+        if days == 0 {
+            result.error("http_logs_retention_days", "must be greater than 0");
+        } else if days > MAX_HTTP_LOGS_RETENTION_DAYS {
+            result.error("http_logs_retention_days", "cannot exceed 365 days");
+        }
+    }
+
+    result
+}
+
+fn duplicate_endpoint_error(
+    result: &mut ConfigValidation, others: &[String], suggestion: Option<u16>,
+) {
+    let mut message = format!(
+        "local address and port are also used by {}",
+        others.join(", ")
+    );
+    if let Some(port) = suggestion {
+        message.push_str(&format!(", port {port} is free"));
+    }
+    result.error("local_port", message);
+}
+
+/// Validates a batch of configs, such as an import, including duplicate
+/// aliases and `local_address:local_port` pairs within the batch. Every
+/// duplicate endpoint after the first is offered a port that is free on this
+/// machine.
+pub fn validate_configs(configs: &[Config]) -> Vec<ConfigValidation> {
+    let mut results: Vec<ConfigValidation> = configs.iter().map(validate_config).collect();
+
+    let mut endpoints: BTreeMap<(String, u16), Vec<usize>> = BTreeMap::new();
+    for (index, config) in configs.iter().enumerate() {
+        if let Some(endpoint) = local_endpoint(config) {
+            endpoints.entry(endpoint).or_default().push(index);
+        }
+    }
+
+    let mut reserved: HashMap<String, HashSet<u16>> = HashMap::new();
+    for (address, port) in endpoints.keys() {
+        reserved.entry(address.clone()).or_default().insert(*port);
+    }
+
+    for ((address, port), indexes) in endpoints.iter().filter(|(_, indexes)| indexes.len() > 1) {
+        for (position, &index) in indexes.iter().enumerate() {
+            let others: Vec<String> = indexes
+                .iter()
+                .filter(|&&other| other != index)
+                .map(|&other| results[other].label.clone())
+                .collect();
+            let suggestion = if position == 0 {
+                None
+            } else {
+                let taken = reserved.entry(address.clone()).or_default();
+                let free = next_free_port(address, *port, &configs[index].protocol, taken);
+                taken.extend(free);
+                free
+            };
+            duplicate_endpoint_error(&mut results[index], &others, suggestion);
+        }
+    }
+
+    let mut aliases: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, config) in configs.iter().enumerate() {
+        if let Some(alias) = config.alias.as_deref().map(str::trim)
+            && !alias.is_empty()
+        {
+            aliases.entry(alias).or_default().push(index);
+        }
+    }
+
+    for &index in aliases
+        .values()
+        .filter(|indexes| indexes.len() > 1)
+        .flatten()
+    {
+        results[index].error("alias", "is used by more than one config");
+    }
+
+    results
+}
+
+/// Validates `config` and checks it against the stored configs for a
+/// duplicate alias or local address and port.
+pub(crate) async fn validate_config_with_pool(
+    config: &Config, pool: &SqlitePool,
+) -> Result<ConfigValidation, String> {
+    let mut result = validate_config(config);
+
+    let conflicts = find_local_endpoint_conflicts_with_pool(config, pool).await?;
+    if let (false, Some((address, port))) = (conflicts.is_empty(), local_endpoint(config)) {
+        let others: Vec<String> = conflicts.iter().map(config_label).collect();
+        let reserved = reserved_local_ports_with_pool(&address, config.id, pool).await?;
+        let suggestion = next_free_port(&address, port, &config.protocol, &reserved);
+        duplicate_endpoint_error(&mut result, &others, suggestion);
+    }
+
+    let conflicts = find_alias_conflicts_with_pool(config, pool).await?;
+    if !conflicts.is_empty() {
+        let others: Vec<String> = conflicts
+            .iter()
+            .filter_map(|config| config.id)
+            .map(|id| format!("#{id}"))
+            .collect();
+        result.error(
+            "alias",
+            format!("is already used by config {}", others.join(", ")),
+        );
+    }
+
+    Ok(result)
+}
+
+pub(crate) async fn validate_stored_configs_with_pool(
+    pool: &SqlitePool,
+) -> Result<Vec<ConfigValidation>, String> {
+    let configs = read_configs_with_pool(pool).await?;
+    Ok(validate_configs(&configs))
+}
+
+pub async fn validate_config_with_mode(
+    config: &Config, mode: DatabaseMode,
+) -> Result<ConfigValidation, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    validate_config_with_pool(config, &context.pool).await
+}
+
+pub async fn validate_stored_configs_with_mode(
+    mode: DatabaseMode,
+) -> Result<Vec<ConfigValidation>, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    validate_stored_configs_with_pool(&context.pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config_model::PodSelectionPreferences;
+    use crate::utils::config::insert_config_with_pool;
+    use crate::utils::db::create_db_table;
+    use crate::utils::migration::migrate_configs;
+
+    fn service_config(alias: &str, local_port: u16) -> Config {
+        Config {
+            alias: Some(alias.to_string()),
+            service: Some(alias.to_string()),
+            namespace: "default".to_string(),
+            workload_type: Some("service".to_string()),
+            protocol: "tcp".to_string(),
+            local_port: Some(local_port),
+            remote_port: Some(80),
+            remote_address: None,
+            target: None,
+            ..Default::default()
+        }
+    }
+
+    fn messages(result: &ConfigValidation, severity: IssueSeverity) -> Vec<String> {
+        result
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_config_reports_errors_and_warnings() {
+        let config = Config {
+            service: None,
+            alias: None,
+            domain_enabled: Some(true),
+            exposure_type: Some("public".to_string()),
+            port_conflict_policy: Some("closest".to_string()),
+            impersonate_groups: vec!["developers".to_string()],
+            ..service_config("api", 8080)
+        };
+
+        let result = validate_config(&config);
+        assert_eq!(
+            messages(&result, IssueSeverity::Error),
+            vec![
+                "service: is required for service workloads",
+                "port_conflict_policy: 'closest' is not one of fail, next or random",
+                "impersonate_groups: requires impersonate_user, Kubernetes cannot impersonate groups alone",
+            ]
+        );
+        assert_eq!(
+            messages(&result, IssueSeverity::Warning),
+            vec![
+                "exposure_type: only applies to expose workloads and is ignored",
+                "domain_enabled: has no effect without an alias to add to the hosts file",
+            ]
+        );
+
+        let error = result.into_result().unwrap_err();
+        assert!(error.contains("service: is required"), "{error}");
+    }
+
+    #[test]
+    fn test_validate_debug_container() {
+        let config = Config {
+            debug_container: Some("sometimes".to_string()),
+            ..service_config("api", 8080)
+        };
+        assert_eq!(
+            messages(&validate_config(&config), IssueSeverity::Error),
+            vec!["debug_container: 'sometimes' is not one of auto or always"]
+        );
+
+        let config = Config {
+            debug_container: Some("auto".to_string()),
+            protocol: "udp".to_string(),
+            ..service_config("api", 8080)
+        };
+        assert_eq!(
+            messages(&validate_config(&config), IssueSeverity::Warning),
+            vec!["debug_container: is ignored for udp services, which already use a relay pod"]
+        );
+
+        let config = Config {
+            debug_container: Some("always".to_string()),
+            ..service_config("api", 8080)
+        };
+        assert!(validate_config(&config).issues.is_empty());
+    }
+
+    #[test]
+    fn test_validate_proxy_relay() {
+        let config = Config {
+            proxy_relay: Some("pooled".to_string()),
+            ..service_config("api", 8080)
+        };
+        assert_eq!(
+            messages(&validate_config(&config), IssueSeverity::Error),
+            vec!["proxy_relay: 'pooled' is not one of shared or dedicated"]
+        );
+
+        let config = Config {
+            proxy_relay: Some("dedicated".to_string()),
+            protocol: "udp".to_string(),
+            ..service_config("api", 8080)
+        };
+        assert_eq!(
+            messages(&validate_config(&config), IssueSeverity::Warning),
+            vec!["proxy_relay: is ignored for udp, which always uses a dedicated relay pod"]
+        );
+
+        let config = Config {
+            proxy_relay: Some("shared".to_string()),
+            ..service_config("api", 8080)
+        };
+        assert!(validate_config(&config).issues.is_empty());
+    }
+
+    #[test]
+    fn test_validate_drain_policy() {
+        let config = Config {
+            drain_policy: Some("migrate".to_string()),
+            ..service_config("api", 8080)
+        };
+        assert_eq!(
+            messages(&validate_config(&config), IssueSeverity::Error),
+            vec!["drain_policy: 'migrate' is not one of keep, drain or close"]
+        );
+
+        let config = Config {
+            drain_timeout_secs: Some(60),
+            ..service_config("api", 8080)
+        };
+        assert_eq!(
+            messages(&validate_config(&config), IssueSeverity::Warning),
+            vec!["drain_timeout_secs: only applies to the drain policy"]
+        );
+
+        let config = Config {
+            drain_policy: Some("drain".to_string()),
+            drain_timeout_secs: Some(60),
+            ..service_config("api", 8080)
+        };
+        assert!(validate_config(&config).issues.is_empty());
+    }
+
+    #[test]
+    fn test_validate_pod_selection() {
+        let selection = PodSelectionPreferences {
+            prefer_age: Some("middle".to_string()),
+            ..Default::default()
+        };
+        let config = Config {
+            pod_selection: Some(selection.clone()),
+            ..service_config("api", 8080)
+        };
+        assert_eq!(
+            messages(&validate_config(&config), IssueSeverity::Error),
+            vec!["pod_selection.prefer_age: 'middle' is not newest or oldest"]
+        );
+
+        let config = Config {
+            workload_type: Some("proxy".to_string()),
+            remote_address: Some("db.internal".to_string()),
+            pod_selection: Some(PodSelectionPreferences {
+                prefer_age: Some("newest".to_string()),
+                ..selection
+            }),
+            ..service_config("api", 8080)
+        };
+        let result = validate_config(&config);
+        assert!(messages(&result, IssueSeverity::Error).is_empty());
+        assert_eq!(
+            messages(&result, IssueSeverity::Warning),
+            vec!["pod_selection: only applies to service and pod workloads and is ignored"]
+        );
+    }
+
+    #[test]
+    fn test_validate_config_checks_required_fields_per_workload() {
+        let proxy = Config {
+            workload_type: Some("proxy".to_string()),
+            remote_address: None,
+            remote_port: Some(0),
+            ..service_config("db", 5432)
+        };
+        let result = validate_config(&proxy);
+        assert_eq!(
+            messages(&result, IssueSeverity::Error),
+            vec!["remote_address: is required for proxy workloads"]
+        );
+        assert_eq!(
+            messages(&result, IssueSeverity::Warning),
+            vec!["remote_port: is not set, the forward cannot start"]
+        );
+
+        let expose = Config {
+            workload_type: Some("expose".to_string()),
+            namespace: String::new(),
+            alias: None,
+            local_port: Some(0),
+            remote_port: None,
+            ..service_config("web", 0)
+        };
+        assert_eq!(
+            messages(&validate_config(&expose), IssueSeverity::Error),
+            vec![
+                "namespace: is required",
+                "alias: a domain is required for expose workloads",
+                "local_port: is required for expose workloads",
+            ]
+        );
+
+        assert!(
+            validate_config(&service_config("api", 8080))
+                .issues
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_validate_configs_flags_duplicate_local_endpoints() {
+        let auto_loopback = Config {
+            auto_loopback_address: true,
+            local_address: None,
+            ..service_config("loopback", 8080)
+        };
+        let configs = vec![
+            service_config("api", 8080),
+            service_config("web", 8080),
+            service_config("db", 5432),
+            auto_loopback,
+        ];
+
+        let results = validate_configs(&configs);
+        assert_eq!(
+            messages(&results[0], IssueSeverity::Error),
+            vec!["local_port: local address and port are also used by web"]
+        );
+        let web = messages(&results[1], IssueSeverity::Error);
+        assert_eq!(web.len(), 1);
+        assert!(
+            web[0].starts_with("local_port: local address and port are also used by api, port ")
+        );
+        assert!(!web[0].contains("port 8080 is free"));
+        assert!(results[2].issues.is_empty());
+        assert!(results[3].issues.is_empty());
+    }
+
+    #[test]
+    fn test_validate_configs_flags_duplicate_aliases() {
+        let configs = vec![
+            service_config("api", 8080),
+            Config {
+                alias: Some(" api ".to_string()),
+                ..service_config("web", 8081)
+            },
+            service_config("db", 5432),
+        ];
+
+        let results = validate_configs(&configs);
+        for result in &results[..2] {
+            assert_eq!(
+                messages(result, IssueSeverity::Error),
+                vec!["alias: is used by more than one config"]
+            );
+        }
+        assert!(results[2].issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_config_with_pool_checks_stored_configs() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        create_db_table(&pool).await.unwrap();
+        migrate_configs(Some(&pool)).await.unwrap();
+        insert_config_with_pool(service_config("api", 8080), &pool)
+            .await
+            .unwrap();
+
+        let result = validate_config_with_pool(&service_config("web", 8080), &pool)
+            .await
+            .unwrap();
+        let errors = messages(&result, IssueSeverity::Error);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("local_port: local address and port are also used by api"));
+        assert!(!errors[0].contains("port 8080 is free"));
+        assert!(result.into_result().is_err());
+
+        let result = validate_config_with_pool(&service_config("api", 8081), &pool)
+            .await
+            .unwrap();
+        let errors = messages(&result, IssueSeverity::Error);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("alias: is already used by config #"));
+    }
+}
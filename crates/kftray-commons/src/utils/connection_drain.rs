@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use crate::models::config_model::Config;
+
+/// How long `drain` keeps connections on the previous pod when the config
+/// sets no `drain_timeout_secs`.
+pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+/// What happens to open connections when the target pod of a forward changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DrainPolicy {
+    /// Leave connections on the previous pod until it goes away.
+    #[default]
+    Keep,
+    /// Route new connections to the new pod and close the remaining ones on
+    /// the previous pod after the timeout.
+    Drain(Duration),
+    /// Close connections on the previous pod right away.
+    Close,
+}
+
+impl DrainPolicy {
+    pub fn parse(value: Option<&str>, timeout_secs: Option<u64>) -> Result<Self, String> {
+        match value.map(str::trim) {
+            None | Some("") | Some("keep") => Ok(DrainPolicy::Keep),
+            Some("drain") => Ok(DrainPolicy::Drain(Duration::from_secs(
+                timeout_secs.unwrap_or(DEFAULT_DRAIN_TIMEOUT_SECS),
+            ))),
+            Some("close") => Ok(DrainPolicy::Close),
+            Some(other) => Err(format!("'{other}' is not one of keep, drain or close")),
+        }
+    }
+
+    /// The policy of `config`, falling back to `Keep` for unknown values.
+    pub fn of(config: &Config) -> Self {
+        Self::parse(config.drain_policy.as_deref(), config.drain_timeout_secs).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_drain_policy() {
+        assert_eq!(DrainPolicy::parse(None, Some(5)), Ok(DrainPolicy::Keep));
+        assert_eq!(
+            DrainPolicy::parse(Some("close"), None),
+            Ok(DrainPolicy::Close)
+        );
+        assert_eq!(
+            DrainPolicy::parse(Some("drain"), None),
+            Ok(DrainPolicy::Drain(Duration::from_secs(
+                DEFAULT_DRAIN_TIMEOUT_SECS
+            )))
+        );
+        assert_eq!(
+            DrainPolicy::parse(Some(" drain "), Some(120)),
+            Ok(DrainPolicy::Drain(Duration::from_secs(120)))
+        );
+        assert!(DrainPolicy::parse(Some("migrate"), None).is_err());
+
+        let config = Config {
+            drain_policy: Some("migrate".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(DrainPolicy::of(&config), DrainPolicy::Keep);
+    }
+}
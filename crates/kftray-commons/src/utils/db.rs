@@ -30,6 +30,7 @@ use crate::utils::manifests::{
     expose_service_manifest_exists,
     proxy_deployment_manifest_exists,
 };
+use crate::utils::migration::ensure_supported_schema;
 
 lazy_static! {
     static ref ENV_TEST_MUTEX: Mutex<()> = Mutex::new(());
@@ -85,6 +86,7 @@ pub async fn get_db_pool() -> Result<Arc<SqlitePool>, String> {
                 error!("Failed to connect to DB: {e}");
                 e.to_string()
             })?;
+            ensure_supported_schema(&pool).await?;
             Ok(Arc::new(pool))
         })
         .await
@@ -125,6 +127,7 @@ pub async fn create_db_table(pool: &SqlitePool) -> Result<(), sqlx::Error> {
             config_id INTEGER NOT NULL,
             is_running BOOLEAN NOT NULL DEFAULT false,
             process_id INTEGER,
+            local_port INTEGER,
             FOREIGN KEY(config_id) REFERENCES configs(id) ON DELETE CASCADE
         )",
     )
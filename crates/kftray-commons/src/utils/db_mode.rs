@@ -4,7 +4,12 @@ use std::sync::{
     Mutex,
 };
 
-use sqlx::SqlitePool;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{
+    ConnectOptions,
+    SqliteConnection,
+    SqlitePool,
+};
 
 use crate::db::{
     create_db_table,
@@ -25,8 +30,15 @@ pub struct DatabaseContext {
 
 pub struct DatabaseManager;
 
-static MEMORY_DB_POOL: LazyLock<Mutex<Option<Arc<SqlitePool>>>> =
-    LazyLock::new(|| Mutex::new(None));
+/// The in-memory database is dropped by SQLite once its last connection
+/// closes, so `keeper` stays open, outside the pool, for as long as the pool
+/// is in use.
+struct MemoryDatabase {
+    pool: Arc<SqlitePool>,
+    _keeper: SqliteConnection,
+}
+
+static MEMORY_DB_POOL: LazyLock<Mutex<Option<MemoryDatabase>>> = LazyLock::new(|| Mutex::new(None));
 
 impl DatabaseManager {
     pub async fn get_context(mode: DatabaseMode) -> Result<DatabaseContext, String> {
@@ -38,18 +50,21 @@ impl DatabaseManager {
             DatabaseMode::Memory => {
                 {
                     let pool_guard = MEMORY_DB_POOL.lock().unwrap();
-                    if let Some(pool) = pool_guard.as_ref() {
+                    if let Some(database) = pool_guard.as_ref() {
                         return Ok(DatabaseContext {
-                            pool: pool.clone(),
+                            pool: database.pool.clone(),
                             mode,
                         });
                     }
                 }
 
-                let connection_string = "sqlite::memory:";
+                let options: SqliteConnectOptions = "sqlite::memory:"
+                    .parse()
+                    .map_err(|e: sqlx::Error| e.to_string())?;
 
+                let keeper = options.connect().await.map_err(|e| e.to_string())?;
                 let pool = Arc::new(
-                    SqlitePool::connect(connection_string)
+                    SqlitePool::connect_with(options)
                         .await
                         .map_err(|e| e.to_string())?,
                 );
@@ -60,7 +75,10 @@ impl DatabaseManager {
 
                 {
                     let mut pool_guard = MEMORY_DB_POOL.lock().unwrap();
-                    *pool_guard = Some(pool.clone());
+                    *pool_guard = Some(MemoryDatabase {
+                        pool: pool.clone(),
+                        _keeper: keeper,
+                    });
                 }
 
                 Ok(DatabaseContext { pool, mode })
@@ -0,0 +1,788 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use log::{
+    debug,
+    info,
+};
+use reqwest::header::{
+    ACCEPT,
+    AUTHORIZATION,
+    ETAG,
+    IF_MODIFIED_SINCE,
+    IF_NONE_MATCH,
+    LAST_MODIFIED,
+};
+use reqwest::{
+    Client,
+    RequestBuilder,
+    StatusCode,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+use sha1::{
+    Digest,
+    Sha1,
+};
+use sqlx::{
+    Row,
+    SqlitePool,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hosting service a config source is fetched from. API providers fetch a
+/// single file at a resolved commit instead of cloning the repository; `Http`
+/// fetches a plain URL and caches it by ETag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitProvider {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    Gitea,
+    Http,
+}
+
+impl GitProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GitProvider::GitHub => "github",
+            GitProvider::GitLab => "gitlab",
+            GitProvider::Bitbucket => "bitbucket",
+            GitProvider::Gitea => "gitea",
+            GitProvider::Http => "http",
+        }
+    }
+
+    /// Guesses the provider from well-known hosts. Self-hosted servers on
+    /// other domains need the provider set explicitly.
+    pub fn detect(repo_url: &str) -> Option<Self> {
+        let host = RepoLocation::parse(repo_url).ok()?.host;
+
+        if host == "github.com" {
+            Some(GitProvider::GitHub)
+        } else if host == "gitlab.com" || host.starts_with("gitlab.") {
+            Some(GitProvider::GitLab)
+        } else if host == "bitbucket.org" {
+            Some(GitProvider::Bitbucket)
+        } else if host == "codeberg.org" || host.starts_with("gitea.") {
+            Some(GitProvider::Gitea)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for GitProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GitProvider {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "github" => Ok(GitProvider::GitHub),
+            "gitlab" => Ok(GitProvider::GitLab),
+            "bitbucket" => Ok(GitProvider::Bitbucket),
+            "gitea" | "forgejo" => Ok(GitProvider::Gitea),
+            "http" | "https" => Ok(GitProvider::Http),
+            other => Err(format!(
+                "Unknown provider '{other}', expected github, gitlab, bitbucket, gitea or http"
+            )),
+        }
+    }
+}
+
+/// Why an API or HTTP fetch failed. Verification failures are never retried
+/// with a clone, mirroring the git fetch path.
+#[derive(Debug)]
+pub(crate) enum SourceError {
+    Verification(String),
+    Other(String),
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Verification(e) | SourceError::Other(e) => f.write_str(e),
+        }
+    }
+}
+
+type SourceResult<T> = Result<T, SourceError>;
+
+/// `https://host[:port]` plus the repository path of an HTTP(S) repo URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RepoLocation {
+    origin: String,
+    host: String,
+    path: String,
+}
+
+impl RepoLocation {
+    fn parse(repo_url: &str) -> Result<Self, String> {
+        let url = repo_url.trim().split(['?', '#']).next().unwrap_or_default();
+        let (scheme, rest) = url
+            .split_once("://")
+            .filter(|(scheme, _)| matches!(*scheme, "http" | "https"))
+            .ok_or_else(|| format!("'{repo_url}' is not an HTTP(S) repository URL"))?;
+
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let authority = authority.rsplit('@').next().unwrap_or(authority);
+        let path = path.trim_matches('/').trim_end_matches(".git").to_string();
+
+        if authority.is_empty() || path.split('/').filter(|p| !p.is_empty()).count() < 2 {
+            return Err(format!(
+                "'{repo_url}' does not look like an <owner>/<repo> repository URL"
+            ));
+        }
+
+        Ok(Self {
+            origin: format!("{scheme}://{authority}"),
+            host: authority
+                .split(':')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase(),
+            path,
+        })
+    }
+
+    fn owner_and_repo(&self) -> (&str, &str) {
+        self.path.split_once('/').unwrap_or((&self.path, ""))
+    }
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters.
+fn encode_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Encodes each segment of a file path, keeping the separators.
+fn encode_path(path: &str) -> String {
+    path.trim_matches('/')
+        .split('/')
+        .map(encode_component)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_full_sha(value: &str) -> bool {
+    value.len() == 40 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Whether `git_ref` could be a shortened commit SHA. It may as well be a
+/// branch or tag name, so it is only rejected once neither exists.
+pub(crate) fn is_abbreviated_sha(git_ref: &str) -> bool {
+    (7..40).contains(&git_ref.len()) && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub(crate) fn abbreviated_sha_error(git_ref: &str) -> String {
+    format!(
+        "No branch or tag '{git_ref}' found and it looks like an abbreviated commit SHA; pin commits using the full 40-character SHA"
+    )
+}
+
+/// Normalizes a ref for the provider APIs, which take plain branch or tag
+/// names rather than full ref paths.
+fn api_ref_name(git_ref: &str) -> &str {
+    git_ref
+        .strip_prefix("refs/heads/")
+        .or_else(|| git_ref.strip_prefix("refs/tags/"))
+        .unwrap_or(git_ref)
+}
+
+fn content_sha(content: &str) -> String {
+    format!("{:x}", Sha1::digest(content.as_bytes()))
+}
+
+fn is_verification_error(error: &reqwest::Error) -> bool {
+    let mut source: Option<&dyn std::error::Error> = Some(error);
+    while let Some(err) = source {
+        let message = err.to_string().to_ascii_lowercase();
+        if message.contains("certificate") || message.contains("self signed") {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+fn request_error(url: &str, error: reqwest::Error) -> SourceError {
+    if is_verification_error(&error) {
+        SourceError::Verification(crate::utils::github::verification_error(url, &error))
+    } else {
+        SourceError::Other(format!("Request to {url} failed: {error}"))
+    }
+}
+
+/// Builds the HTTP client for a source, honouring the same CA bundle and
+/// verification settings as git fetches.
+pub(crate) fn build_client(
+    insecure_skip_verify: bool, ca_bundle_path: Option<&str>,
+) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .user_agent(concat!("kftray/", env!("CARGO_PKG_VERSION")))
+        .timeout(REQUEST_TIMEOUT)
+        .danger_accept_invalid_certs(insecure_skip_verify);
+
+    if let Some(path) = ca_bundle_path {
+        let bundle = crate::utils::github::validate_ca_bundle(path)?;
+        let pem = std::fs::read(&bundle)
+            .map_err(|e| format!("Failed to read CA bundle '{path}': {e}"))?;
+        for certificate in reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Invalid CA bundle '{path}': {e}"))?
+        {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))
+}
+
+/// A repository on one of the supported hosting APIs.
+pub(crate) struct ApiSource {
+    provider: GitProvider,
+    location: RepoLocation,
+    token: Option<String>,
+}
+
+impl ApiSource {
+    pub(crate) fn new(
+        provider: GitProvider, repo_url: &str, token: Option<String>,
+    ) -> Result<Self, String> {
+        if provider == GitProvider::Http {
+            return Err("Plain HTTP sources are not repositories".to_string());
+        }
+
+        let location = RepoLocation::parse(repo_url)?;
+        if provider == GitProvider::Bitbucket && location.host != "bitbucket.org" {
+            return Err("Only Bitbucket Cloud is supported by the Bitbucket API".to_string());
+        }
+
+        Ok(Self {
+            provider,
+            location,
+            token: token.filter(|t| !t.is_empty()),
+        })
+    }
+
+    fn api_base(&self) -> String {
+        let origin = &self.location.origin;
+        match self.provider {
+            GitProvider::GitHub if self.location.host == "github.com" => {
+                "https://api.github.com".to_string()
+            }
+            GitProvider::GitHub => format!("{origin}/api/v3"),
+            GitProvider::GitLab => format!("{origin}/api/v4"),
+            GitProvider::Bitbucket => "https://api.bitbucket.org/2.0".to_string(),
+            GitProvider::Gitea => format!("{origin}/api/v1"),
+            GitProvider::Http => origin.clone(),
+        }
+    }
+
+    /// API path of the repository itself, used for metadata lookups.
+    fn repo_url(&self) -> String {
+        let base = self.api_base();
+        let (owner, repo) = self.location.owner_and_repo();
+        match self.provider {
+            GitProvider::GitLab => {
+                format!("{base}/projects/{}", encode_component(&self.location.path))
+            }
+            GitProvider::Bitbucket => format!("{base}/repositories/{owner}/{repo}"),
+            _ => format!("{base}/repos/{owner}/{repo}"),
+        }
+    }
+
+    fn default_branch_url(&self) -> (String, &'static str) {
+        let pointer = match self.provider {
+            GitProvider::Bitbucket => "/mainbranch/name",
+            _ => "/default_branch",
+        };
+        (self.repo_url(), pointer)
+    }
+
+    fn commit_url(&self, git_ref: &str) -> (String, &'static str) {
+        let repo = self.repo_url();
+        let encoded = encode_component(git_ref);
+        match self.provider {
+            GitProvider::GitHub => (format!("{repo}/commits/{encoded}"), "/sha"),
+            GitProvider::GitLab => (format!("{repo}/repository/commits/{encoded}"), "/id"),
+            GitProvider::Bitbucket => (format!("{repo}/commit/{encoded}"), "/hash"),
+            _ => (format!("{repo}/commits?sha={encoded}&limit=1"), "/0/sha"),
+        }
+    }
+
+    pub(crate) fn file_url(&self, commit_sha: &str, config_path: &str) -> String {
+        let repo = self.repo_url();
+        let path = encode_path(config_path);
+        match self.provider {
+            GitProvider::GitHub => format!("{repo}/contents/{path}?ref={commit_sha}"),
+            GitProvider::GitLab => format!(
+                "{repo}/repository/files/{}/raw?ref={commit_sha}",
+                encode_component(config_path.trim_matches('/'))
+            ),
+            GitProvider::Bitbucket => format!("{repo}/src/{commit_sha}/{path}"),
+            _ => format!("{repo}/raw/{path}?ref={commit_sha}"),
+        }
+    }
+
+    /// Adds the provider's token header. Bitbucket app passwords are given
+    /// as `username:app_password` and sent with basic auth.
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        let Some(token) = self.token.as_deref() else {
+            return request;
+        };
+
+        match self.provider {
+            GitProvider::GitLab => request.header("PRIVATE-TOKEN", token),
+            GitProvider::Gitea => request.header(AUTHORIZATION, format!("token {token}")),
+            GitProvider::Bitbucket if token.contains(':') => {
+                let (user, password) = token.split_once(':').unwrap_or_default();
+                request.basic_auth(user, Some(password))
+            }
+            _ => request.bearer_auth(token),
+        }
+    }
+
+    async fn get(&self, client: &Client, url: &str, raw: bool) -> SourceResult<String> {
+        let mut request = self.authorize(client.get(url));
+        if self.provider == GitProvider::GitHub {
+            let accept = if raw {
+                "application/vnd.github.raw"
+            } else {
+                "application/vnd.github+json"
+            };
+            request = request.header(ACCEPT, accept);
+        }
+
+        debug!("Fetching {url}");
+        let response = request.send().await.map_err(|e| request_error(url, e))?;
+        let status = response.status();
+        if !status.is_success() {
+            let hint = match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN if self.token.is_none() => {
+                    " (the repository may be private; provide a token)"
+                }
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    " (check the token's permissions)"
+                }
+                StatusCode::NOT_FOUND => " (repository, ref or file not found)",
+                _ => "",
+            };
+            return Err(SourceError::Other(format!(
+                "{} API returned {status} for {url}{hint}",
+                self.provider
+            )));
+        }
+
+        response
+            .text()
+            .await
+            .map_err(|e| SourceError::Other(format!("Failed to read response from {url}: {e}")))
+    }
+
+    async fn get_json_string(
+        &self, client: &Client, url: &str, pointer: &str,
+    ) -> SourceResult<String> {
+        let body = self.get(client, url, false).await?;
+        let value: Value = serde_json::from_str(&body)
+            .map_err(|e| SourceError::Other(format!("Invalid JSON from {url}: {e}")))?;
+
+        value
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .map(String::from)
+            .ok_or_else(|| SourceError::Other(format!("Unexpected response from {url}")))
+    }
+
+    /// Resolves a branch, tag or the default branch to a commit SHA so the
+    /// file is read from exactly that commit.
+    pub(crate) async fn resolve_commit(
+        &self, client: &Client, git_ref: Option<&str>,
+    ) -> SourceResult<String> {
+        let git_ref = match git_ref.map(str::trim).filter(|r| !r.is_empty()) {
+            Some(git_ref) if is_full_sha(git_ref) => return Ok(git_ref.to_lowercase()),
+            Some(git_ref) => api_ref_name(git_ref).to_string(),
+            None => {
+                let (url, pointer) = self.default_branch_url();
+                self.get_json_string(client, &url, pointer).await?
+            }
+        };
+
+        let (url, pointer) = self.commit_url(&git_ref);
+        let sha = self.get_json_string(client, &url, pointer).await?;
+
+        // The commit endpoints also accept shortened SHAs, which only pin
+        // until another commit shares the prefix. A branch or tag whose
+        // commit starts with its own name is not worth telling apart.
+        if is_abbreviated_sha(&git_ref) && sha.starts_with(&git_ref.to_lowercase()) {
+            return Err(SourceError::Other(abbreviated_sha_error(&git_ref)));
+        }
+
+        Ok(sha)
+    }
+
+    /// Fetches `config_path` at the resolved commit, returning the content and
+    /// the commit SHA.
+    pub(crate) async fn fetch(
+        &self, client: &Client, config_path: &str, git_ref: Option<&str>,
+    ) -> SourceResult<(String, String)> {
+        let commit_sha = self.resolve_commit(client, git_ref).await?;
+        let content = self
+            .get(client, &self.file_url(&commit_sha, config_path), true)
+            .await?;
+
+        info!(
+            "Fetched {config_path} from {} via the {} API",
+            self.location.path, self.provider
+        );
+        Ok((content, commit_sha))
+    }
+}
+
+/// Joins a plain HTTP source URL with an optional relative file path.
+pub(crate) fn http_source_url(base_url: &str, config_path: &str) -> String {
+    let path = config_path.trim_matches('/');
+    if path.is_empty() {
+        base_url.to_string()
+    } else {
+        format!("{}/{path}", base_url.trim_end_matches('/'))
+    }
+}
+
+struct CachedResponse {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content: String,
+}
+
+async fn read_http_cache(pool: &SqlitePool, url: &str) -> Option<CachedResponse> {
+    let row =
+        sqlx::query("SELECT etag, last_modified, content FROM http_source_cache WHERE url = ?1")
+            .bind(url)
+            .fetch_optional(pool)
+            .await
+            .ok()??;
+
+    Some(CachedResponse {
+        etag: row.try_get("etag").ok()?,
+        last_modified: row.try_get("last_modified").ok()?,
+        content: row.try_get("content").ok()?,
+    })
+}
+
+async fn write_http_cache(
+    pool: &SqlitePool, url: &str, cached: &CachedResponse,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO http_source_cache (url, etag, last_modified, content, fetched_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+         ON CONFLICT(url) DO UPDATE SET
+             etag=excluded.etag,
+             last_modified=excluded.last_modified,
+             content=excluded.content,
+             fetched_at=excluded.fetched_at",
+    )
+    .bind(url)
+    .bind(&cached.etag)
+    .bind(&cached.last_modified)
+    .bind(&cached.content)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to cache {url}: {e}"))?;
+    Ok(())
+}
+
+/// Fetches a config file from a plain URL. Responses carrying an ETag or
+/// Last-Modified header are cached, and later fetches send conditional
+/// requests so an unchanged file is served from the cache. The returned
+/// revision is the SHA-1 of the content.
+pub(crate) async fn fetch_http_source(
+    client: &Client, url: &str, token: Option<&str>, pool: &SqlitePool,
+) -> SourceResult<(String, String)> {
+    let cached = read_http_cache(pool, url).await;
+
+    let mut request = client.get(url);
+    if let Some(token) = token.filter(|t| !t.is_empty()) {
+        request = request.bearer_auth(token);
+    }
+    if let Some(cached) = &cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await.map_err(|e| request_error(url, e))?;
+    let status = response.status();
+
+    if status == StatusCode::NOT_MODIFIED {
+        let cached = cached.ok_or_else(|| {
+            SourceError::Other(format!("{url} returned 304 without a cached copy"))
+        })?;
+        info!("{url} not modified, using cached copy");
+        let sha = content_sha(&cached.content);
+        return Ok((cached.content, sha));
+    }
+
+    if !status.is_success() {
+        return Err(SourceError::Other(format!("{url} returned {status}")));
+    }
+
+    let header = |name: reqwest::header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+    };
+    let etag = header(ETAG);
+    let last_modified = header(LAST_MODIFIED);
+
+    let content = response
+        .text()
+        .await
+        .map_err(|e| SourceError::Other(format!("Failed to read response from {url}: {e}")))?;
+
+    if etag.is_some() || last_modified.is_some() {
+        let entry = CachedResponse {
+            etag,
+            last_modified,
+            content,
+        };
+        write_http_cache(pool, url, &entry)
+            .await
+            .map_err(SourceError::Other)?;
+        let sha = content_sha(&entry.content);
+        return Ok((entry.content, sha));
+    }
+
+    let sha = content_sha(&content);
+    Ok((content, sha))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{
+        BufRead,
+        BufReader,
+        Write,
+    };
+    use std::net::TcpListener;
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_provider_detection_and_parsing() {
+        assert_eq!(
+            GitProvider::detect("https://github.com/org/repo"),
+            Some(GitProvider::GitHub)
+        );
+        assert_eq!(
+            GitProvider::detect("https://gitlab.example.com/group/sub/repo.git"),
+            Some(GitProvider::GitLab)
+        );
+        assert_eq!(
+            GitProvider::detect("https://bitbucket.org/team/repo"),
+            Some(GitProvider::Bitbucket)
+        );
+        assert_eq!(
+            GitProvider::detect("https://codeberg.org/org/repo"),
+            Some(GitProvider::Gitea)
+        );
+        assert_eq!(GitProvider::detect("https://git.corp.internal/a/b"), None);
+        assert_eq!(GitProvider::detect("git@github.com:org/repo.git"), None);
+
+        assert_eq!("GitLab".parse::<GitProvider>(), Ok(GitProvider::GitLab));
+        assert_eq!("forgejo".parse::<GitProvider>(), Ok(GitProvider::Gitea));
+        assert!("svn".parse::<GitProvider>().is_err());
+    }
+
+    #[test]
+    fn test_api_urls() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+
+        let github =
+            ApiSource::new(GitProvider::GitHub, "https://github.com/org/repo", None).unwrap();
+        assert_eq!(
+            github.file_url(sha, "configs/dev.json"),
+            format!("https://api.github.com/repos/org/repo/contents/configs/dev.json?ref={sha}")
+        );
+
+        let ghe = ApiSource::new(GitProvider::GitHub, "https://ghe.corp/org/repo", None).unwrap();
+        assert_eq!(ghe.api_base(), "https://ghe.corp/api/v3");
+
+        let gitlab = ApiSource::new(
+            GitProvider::GitLab,
+            "https://git.corp:8443/group/sub/repo.git",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            gitlab.file_url(sha, "/configs/dev.json"),
+            format!(
+                "https://git.corp:8443/api/v4/projects/group%2Fsub%2Frepo/repository/files/configs%2Fdev.json/raw?ref={sha}"
+            )
+        );
+        assert_eq!(
+            gitlab.commit_url("release/1.0").0,
+            "https://git.corp:8443/api/v4/projects/group%2Fsub%2Frepo/repository/commits/release%2F1.0"
+        );
+
+        let bitbucket = ApiSource::new(
+            GitProvider::Bitbucket,
+            "https://bitbucket.org/team/repo",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            bitbucket.file_url(sha, "dev.json"),
+            format!("https://api.bitbucket.org/2.0/repositories/team/repo/src/{sha}/dev.json")
+        );
+        assert!(
+            ApiSource::new(GitProvider::Bitbucket, "https://bitbucket.corp/a/b", None).is_err()
+        );
+
+        let gitea =
+            ApiSource::new(GitProvider::Gitea, "https://codeberg.org/org/repo", None).unwrap();
+        assert_eq!(
+            gitea.file_url(sha, "dev.json"),
+            format!("https://codeberg.org/api/v1/repos/org/repo/raw/dev.json?ref={sha}")
+        );
+        assert_eq!(
+            gitea.commit_url("main").0,
+            "https://codeberg.org/api/v1/repos/org/repo/commits?sha=main&limit=1"
+        );
+    }
+
+    #[test]
+    fn test_api_ref_name() {
+        assert_eq!(api_ref_name("refs/heads/main"), "main");
+        assert_eq!(api_ref_name("refs/tags/v1.0.0"), "v1.0.0");
+        assert_eq!(api_ref_name("feature/x"), "feature/x");
+        assert_eq!(api_ref_name("refs/heads/abc1234"), "abc1234");
+    }
+
+    #[test]
+    fn test_is_abbreviated_sha() {
+        assert!(is_abbreviated_sha("abc1234"));
+        assert!(is_abbreviated_sha("ABCDEF0123"));
+        assert!(!is_abbreviated_sha("abc123"));
+        assert!(!is_abbreviated_sha(
+            "abcdef0123456789abcdef0123456789abcdef01"
+        ));
+        assert!(!is_abbreviated_sha("release"));
+    }
+
+    #[test]
+    fn test_repo_location_rejects_invalid_urls() {
+        assert!(RepoLocation::parse("ftp://example.com/a/b").is_err());
+        assert!(RepoLocation::parse("https://github.com/only-owner").is_err());
+
+        let location = RepoLocation::parse("https://user:pw@github.com/org/repo/?x=1").unwrap();
+        assert_eq!(location.origin, "https://github.com");
+        assert_eq!(location.path, "org/repo");
+    }
+
+    #[test]
+    fn test_http_source_url() {
+        assert_eq!(
+            http_source_url("https://cdn.example.com/kftray/", "/dev.json"),
+            "https://cdn.example.com/kftray/dev.json"
+        );
+        assert_eq!(
+            http_source_url("https://cdn.example.com/dev.json", ""),
+            "https://cdn.example.com/dev.json"
+        );
+    }
+
+    /// Serves `body` with an ETag and answers matching conditional requests
+    /// with 304. Records whether each request was conditional.
+    fn spawn_etag_server(body: &'static str) -> (String, Arc<Mutex<Vec<bool>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/configs.json", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut conditional = false;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if line
+                        .to_ascii_lowercase()
+                        .starts_with("if-none-match: \"v1\"")
+                    {
+                        conditional = true;
+                    }
+                }
+                seen.lock().unwrap().push(conditional);
+
+                let response = if conditional {
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n"
+                        .to_string()
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_http_source_uses_etag_cache() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::db::create_db_table(&pool).await.unwrap();
+        crate::utils::migration::migrate_configs(Some(&pool))
+            .await
+            .unwrap();
+
+        let body = r#"[{"service":"api","namespace":"default"}]"#;
+        let (url, requests) = spawn_etag_server(body);
+        let client = build_client(false, None).unwrap();
+
+        let (first, first_sha) = fetch_http_source(&client, &url, None, &pool).await.unwrap();
+        let (second, second_sha) = fetch_http_source(&client, &url, None, &pool).await.unwrap();
+
+        assert_eq!(first, body);
+        assert_eq!(second, body);
+        assert_eq!(first_sha, second_sha);
+        assert_eq!(*requests.lock().unwrap(), vec![false, true]);
+    }
+}
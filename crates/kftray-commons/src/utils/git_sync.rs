@@ -0,0 +1,864 @@
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+use keyring::Entry;
+use log::{
+    error,
+    info,
+    warn,
+};
+use sqlx::{
+    Row,
+    SqliteConnection,
+    SqlitePool,
+};
+
+use crate::migration::migrate_configs;
+use crate::models::config_history_model::ConfigChangeSource;
+use crate::models::config_model::Config;
+use crate::models::git_sync_model::{
+    ConfigChange,
+    ConfigDiff,
+    GitSyncSubscription,
+    SyncApplyResult,
+    SyncPreview,
+};
+use crate::utils::config::{
+    configs_match_identity,
+    delete_config_rows,
+    parse_config_content,
+    read_configs_with_pool,
+    update_config_row,
+    upsert_config_rows,
+};
+use crate::utils::config_format::ConfigFormat;
+use crate::utils::config_history::with_change_source;
+use crate::utils::db_mode::{
+    DatabaseManager,
+    DatabaseMode,
+};
+use crate::utils::github::{
+    GitHubConfig,
+    GitHubRepository,
+    GitImportRecord,
+    record_config_git_sources,
+};
+
+const KEYRING_SERVICE: &str = "kftray";
+
+const SUBSCRIPTION_COLUMNS: &str = "id, name, repo_url, config_path, git_ref, provider, \
+     credentials_ref, use_system_credentials, insecure_skip_verify, ca_bundle_path, interval_secs, enabled, \
+     last_commit_sha, applied_keys, last_checked_at, last_synced_at";
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Key used to match configs between the Git source and the database: the
+/// alias when set, otherwise the service identity.
+pub fn config_sync_key(config: &Config) -> String {
+    if let Some(alias) = config.alias.as_deref().filter(|a| !a.is_empty()) {
+        return format!("alias:{alias}");
+    }
+
+    let target = match config.workload_type.as_deref() {
+        Some("pod") => config.target.as_deref(),
+        Some("proxy") => config.remote_address.as_deref(),
+        _ => config.service.as_deref(),
+    };
+
+    format!(
+        "service:{}/{}/{}/{}/{}/{}",
+        config.context.as_deref().unwrap_or_default(),
+        config.namespace,
+        config.workload_type.as_deref().unwrap_or_default(),
+        target.unwrap_or_default(),
+        config.protocol,
+        config.remote_port.unwrap_or_default()
+    )
+}
+
+fn changed_fields(before: &Config, after: &Config) -> Vec<String> {
+    let before = serde_json::to_value(before).unwrap_or_default();
+    let after = serde_json::to_value(after).unwrap_or_default();
+    let empty = serde_json::Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let mut fields: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| key.as_str() != "id" && before.get(*key) != after.get(*key))
+        .cloned()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    fields.sort();
+    fields
+}
+
+/// Compares the configs fetched from a Git source with the database.
+/// Only configs previously applied from the same source (`applied_keys`) are
+/// reported as removed, so local configs are never touched.
+pub fn diff_configs(
+    existing: &[Config], incoming: &[Config], applied_keys: &[String],
+) -> ConfigDiff {
+    let mut existing_by_key: HashMap<String, &Config> = HashMap::new();
+    for config in existing {
+        existing_by_key
+            .entry(config_sync_key(config))
+            .or_insert(config);
+    }
+
+    let mut diff = ConfigDiff::default();
+    let mut incoming_keys = HashSet::new();
+
+    for config in incoming {
+        let key = config_sync_key(config);
+        if !incoming_keys.insert(key.clone()) {
+            continue;
+        }
+
+        match existing_by_key.get(&key) {
+            Some(current) => {
+                let mut after = config.clone();
+                after.id = current.id;
+                // A source without `groups` leaves the local memberships alone.
+                if after.groups.is_none() {
+                    after.groups = current.groups.clone();
+                }
+
+                let fields = changed_fields(current, &after);
+                if fields.is_empty() {
+                    diff.unchanged += 1;
+                } else {
+                    diff.changed.push(ConfigChange {
+                        key,
+                        before: (*current).clone(),
+                        after,
+                        fields,
+                    });
+                }
+            }
+            None => diff.added.push(config.clone()),
+        }
+    }
+
+    for key in applied_keys {
+        if !incoming_keys.contains(key)
+            && let Some(current) = existing_by_key.get(key)
+        {
+            diff.removed.push((*current).clone());
+        }
+    }
+
+    diff
+}
+
+fn subscription_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<GitSyncSubscription, String> {
+    let applied_keys: String = row.try_get("applied_keys").map_err(|e| e.to_string())?;
+    let interval_secs: i64 = row.try_get("interval_secs").map_err(|e| e.to_string())?;
+
+    Ok(GitSyncSubscription {
+        id: row.try_get("id").map_err(|e| e.to_string())?,
+        name: row.try_get("name").map_err(|e| e.to_string())?,
+        repo_url: row.try_get("repo_url").map_err(|e| e.to_string())?,
+        config_path: row.try_get("config_path").map_err(|e| e.to_string())?,
+        git_ref: row.try_get("git_ref").map_err(|e| e.to_string())?,
+        provider: row
+            .try_get::<Option<String>, _>("provider")
+            .map_err(|e| e.to_string())?
+            .and_then(|provider| provider.parse().ok()),
+        credentials_ref: row.try_get("credentials_ref").map_err(|e| e.to_string())?,
+        use_system_credentials: row
+            .try_get("use_system_credentials")
+            .map_err(|e| e.to_string())?,
+        insecure_skip_verify: row
+            .try_get("insecure_skip_verify")
+            .map_err(|e| e.to_string())?,
+        ca_bundle_path: row.try_get("ca_bundle_path").map_err(|e| e.to_string())?,
+        interval_secs: interval_secs.max(0) as u64,
+        enabled: row.try_get("enabled").map_err(|e| e.to_string())?,
+        last_commit_sha: row.try_get("last_commit_sha").map_err(|e| e.to_string())?,
+        applied_keys: serde_json::from_str(&applied_keys).unwrap_or_default(),
+        last_checked_at: row.try_get("last_checked_at").map_err(|e| e.to_string())?,
+        last_synced_at: row.try_get("last_synced_at").map_err(|e| e.to_string())?,
+    })
+}
+
+pub(crate) async fn list_subscriptions_with_pool(
+    pool: &SqlitePool,
+) -> Result<Vec<GitSyncSubscription>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let rows = sqlx::query(&format!(
+        "SELECT {SUBSCRIPTION_COLUMNS} FROM git_sync_subscriptions ORDER BY id"
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch git sync subscriptions: {e}");
+        e.to_string()
+    })?;
+
+    rows.iter().map(subscription_from_row).collect()
+}
+
+pub(crate) async fn get_subscription_with_pool(
+    id: i64, pool: &SqlitePool,
+) -> Result<GitSyncSubscription, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let row = sqlx::query(&format!(
+        "SELECT {SUBSCRIPTION_COLUMNS} FROM git_sync_subscriptions WHERE id = ?1"
+    ))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Git sync subscription {id} not found"))?;
+
+    subscription_from_row(&row)
+}
+
+/// Inserts or updates a subscription and returns its id.
+pub(crate) async fn save_subscription_with_pool(
+    subscription: &GitSyncSubscription, pool: &SqlitePool,
+) -> Result<i64, String> {
+    if subscription.repo_url.trim().is_empty() {
+        return Err("Repository URL is required".to_string());
+    }
+    if subscription.config_path.trim().is_empty() {
+        return Err("Config path is required".to_string());
+    }
+
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    write_subscription(&mut conn, subscription).await
+}
+
+/// Writes `subscription` on an already acquired connection and returns its
+/// id.
+async fn write_subscription(
+    conn: &mut SqliteConnection, subscription: &GitSyncSubscription,
+) -> Result<i64, String> {
+    let applied_keys =
+        serde_json::to_string(&subscription.applied_keys).map_err(|e| e.to_string())?;
+    let result = sqlx::query(
+        "INSERT INTO git_sync_subscriptions (id, name, repo_url, config_path, git_ref,
+             credentials_ref, use_system_credentials, insecure_skip_verify, ca_bundle_path,
+             interval_secs, enabled, last_commit_sha, applied_keys, last_checked_at, last_synced_at,
+             provider)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+         ON CONFLICT(id) DO UPDATE SET
+             name=excluded.name,
+             repo_url=excluded.repo_url,
+             config_path=excluded.config_path,
+             git_ref=excluded.git_ref,
+             provider=excluded.provider,
+             credentials_ref=excluded.credentials_ref,
+             use_system_credentials=excluded.use_system_credentials,
+             insecure_skip_verify=excluded.insecure_skip_verify,
+             ca_bundle_path=excluded.ca_bundle_path,
+             interval_secs=excluded.interval_secs,
+             enabled=excluded.enabled,
+             last_commit_sha=excluded.last_commit_sha,
+             applied_keys=excluded.applied_keys,
+             last_checked_at=excluded.last_checked_at,
+             last_synced_at=excluded.last_synced_at",
+    )
+    .bind(subscription.id)
+    .bind(&subscription.name)
+    .bind(&subscription.repo_url)
+    .bind(&subscription.config_path)
+    .bind(&subscription.git_ref)
+    .bind(&subscription.credentials_ref)
+    .bind(subscription.use_system_credentials)
+    .bind(subscription.insecure_skip_verify)
+    .bind(&subscription.ca_bundle_path)
+    .bind(subscription.interval_secs as i64)
+    .bind(subscription.enabled)
+    .bind(&subscription.last_commit_sha)
+    .bind(applied_keys)
+    .bind(subscription.last_checked_at)
+    .bind(subscription.last_synced_at)
+    .bind(subscription.provider.map(|provider| provider.as_str()))
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to save git sync subscription: {e}");
+        e.to_string()
+    })?;
+
+    Ok(subscription.id.unwrap_or(result.last_insert_rowid()))
+}
+
+pub(crate) async fn delete_subscription_with_pool(
+    id: i64, pool: &SqlitePool,
+) -> Result<(), String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM git_sync_subscriptions WHERE id = ?1")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Reads the token of a keyring entry, which either holds the raw token or
+/// the JSON credentials saved by the Git sync settings.
+fn token_from_secret(secret: String) -> Option<String> {
+    match serde_json::from_str::<serde_json::Value>(&secret) {
+        Ok(value) if value.is_object() => value
+            .get("token")
+            .and_then(|token| token.as_str())
+            .filter(|token| !token.is_empty())
+            .map(String::from),
+        _ => Some(secret).filter(|token| !token.is_empty()),
+    }
+}
+
+/// Host of an `https://`, `ssh://` or scp-like `git@host:path` URL.
+fn repo_host(repo_url: &str) -> Option<&str> {
+    let rest = repo_url
+        .split_once("://")
+        .map_or(repo_url, |(_, rest)| rest);
+    let authority = rest.split('/').next()?;
+    let host = authority.rsplit('@').next()?;
+    host.split(':').next().filter(|host| !host.is_empty())
+}
+
+/// Token used to fetch a subscription: the keyring entry named by its
+/// `credentials_ref`, else `GITHUB_TOKEN` when the source is on github.com,
+/// so that token is never sent to another host.
+pub fn resolve_subscription_token(subscription: &GitSyncSubscription) -> Option<String> {
+    if let Some(name) = subscription
+        .credentials_ref
+        .as_deref()
+        .filter(|name| !name.is_empty())
+    {
+        match Entry::new(KEYRING_SERVICE, name).and_then(|entry| entry.get_password()) {
+            Ok(secret) => return token_from_secret(secret),
+            Err(e) => warn!("Failed to read git sync credentials '{name}': {e}"),
+        }
+    }
+
+    repo_host(&subscription.repo_url)
+        .filter(|host| host.eq_ignore_ascii_case("github.com"))
+        .and_then(|_| std::env::var("GITHUB_TOKEN").ok())
+        .filter(|token| !token.is_empty())
+}
+
+async fn mark_checked_with_pool(id: i64, pool: &SqlitePool) -> Result<(), String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE git_sync_subscriptions SET last_checked_at = ?1 WHERE id = ?2")
+        .bind(now_secs())
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Fetches the subscription's source and diffs it against the database
+/// without changing any config.
+pub(crate) async fn check_subscription_with_pool(
+    id: i64, pool: &SqlitePool,
+) -> Result<SyncPreview, String> {
+    let subscription = get_subscription_with_pool(id, pool).await?;
+
+    let github_config = GitHubConfig {
+        use_system_credentials: subscription.use_system_credentials,
+        github_token: resolve_subscription_token(&subscription),
+        git_ref: subscription.git_ref.clone(),
+        insecure_skip_verify: subscription.insecure_skip_verify,
+        ca_bundle_path: subscription.ca_bundle_path.clone(),
+        provider: subscription.provider,
+        ..GitHubConfig::new(
+            subscription.repo_url.clone(),
+            subscription.config_path.clone(),
+        )
+    };
+
+    let (content, commit_sha) =
+        GitHubRepository::fetch_config_with_pool(&github_config, pool).await?;
+
+    mark_checked_with_pool(id, pool).await?;
+
+    let format = ConfigFormat::from_path(&subscription.config_path);
+    let incoming = parse_config_content(&content, format)?;
+
+    let existing = read_configs_with_pool(pool).await?;
+    let diff = diff_configs(&existing, &incoming, &subscription.applied_keys);
+
+    info!(
+        "Git sync '{}' at {commit_sha}: {}",
+        subscription.name,
+        diff.summary()
+    );
+
+    Ok(SyncPreview {
+        subscription_id: id,
+        commit_sha,
+        diff,
+        incoming_keys: incoming.iter().map(config_sync_key).collect(),
+    })
+}
+
+/// Applies an approved preview. Configs whose identity is unchanged go through
+/// the regular upsert path; renamed services are updated in place so running
+/// forwards keep their config id. The config writes and the subscription
+/// state are committed together, so a failure leaves both untouched.
+pub(crate) async fn apply_sync_preview_with_pool(
+    preview: &SyncPreview, pool: &SqlitePool, mode: DatabaseMode,
+) -> Result<SyncApplyResult, String> {
+    let mut subscription = get_subscription_with_pool(preview.subscription_id, pool).await?;
+    let mut result = SyncApplyResult::default();
+    let mut transaction = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut upserts: Vec<Config> = preview.diff.added.clone();
+    result.added = preview.diff.added.len();
+
+    let removed_ids = preview.diff.removed_config_ids();
+    let source = GitImportRecord {
+        repo_url: subscription.repo_url.clone(),
+        config_path: subscription.config_path.clone(),
+        git_ref: subscription.git_ref.clone(),
+        commit_sha: preview.commit_sha.clone(),
+    };
+
+    with_change_source(ConfigChangeSource::GitSync, async {
+        let mut written_ids = Vec::new();
+        for change in &preview.diff.changed {
+            if let Some(id) = change.before.id {
+                result.changed_config_ids.push(id);
+            }
+
+            if configs_match_identity(&change.before, &change.after) {
+                let mut config = change.after.clone();
+                config.id = None;
+                upserts.push(config);
+            } else {
+                update_config_row(&mut transaction, change.after.clone()).await?;
+                written_ids.extend(change.after.id);
+            }
+        }
+
+        written_ids.extend(upsert_config_rows(&mut transaction, upserts, mode).await?);
+        record_config_git_sources(&mut transaction, &written_ids, &source).await?;
+
+        delete_config_rows(&mut transaction, &removed_ids)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok::<(), String>(())
+    })
+    .await?;
+    result.removed_config_ids = removed_ids;
+
+    subscription.last_commit_sha = Some(preview.commit_sha.clone());
+    subscription.applied_keys = preview.incoming_keys.clone();
+    subscription.last_synced_at = Some(now_secs());
+    write_subscription(&mut transaction, &subscription).await?;
+
+    transaction.commit().await.map_err(|e| e.to_string())?;
+
+    if let Err(e) = migrate_configs(Some(pool)).await {
+        return Err(format!("Error migrating configs: {e}"));
+    }
+
+    info!(
+        "Applied git sync '{}' at {}: {}",
+        subscription.name,
+        preview.commit_sha,
+        preview.diff.summary()
+    );
+
+    Ok(result)
+}
+
+pub async fn list_subscriptions_with_mode(
+    mode: DatabaseMode,
+) -> Result<Vec<GitSyncSubscription>, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    list_subscriptions_with_pool(&context.pool).await
+}
+
+pub async fn get_subscription_with_mode(
+    id: i64, mode: DatabaseMode,
+) -> Result<GitSyncSubscription, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    get_subscription_with_pool(id, &context.pool).await
+}
+
+pub async fn save_subscription_with_mode(
+    subscription: &GitSyncSubscription, mode: DatabaseMode,
+) -> Result<i64, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    save_subscription_with_pool(subscription, &context.pool).await
+}
+
+pub async fn delete_subscription_with_mode(id: i64, mode: DatabaseMode) -> Result<(), String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    delete_subscription_with_pool(id, &context.pool).await
+}
+
+/// Subscriptions whose polling interval has elapsed.
+pub async fn due_subscriptions_with_mode(
+    mode: DatabaseMode,
+) -> Result<Vec<GitSyncSubscription>, String> {
+    let now = now_secs();
+    Ok(list_subscriptions_with_mode(mode)
+        .await?
+        .into_iter()
+        .filter(|subscription| subscription.is_due(now))
+        .collect())
+}
+
+pub async fn check_subscription_with_mode(
+    id: i64, mode: DatabaseMode,
+) -> Result<SyncPreview, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    check_subscription_with_pool(id, &context.pool).await
+}
+
+pub async fn apply_sync_preview_with_mode(
+    preview: &SyncPreview, mode: DatabaseMode,
+) -> Result<SyncApplyResult, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    apply_sync_preview_with_pool(preview, &context.pool, mode).await
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use super::*;
+    use crate::db::create_db_table;
+    use crate::utils::config::insert_config_with_pool;
+    use crate::utils::github::get_config_git_source_with_pool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        create_db_table(&pool).await.unwrap();
+        crate::utils::migration::migrate_configs(Some(&pool))
+            .await
+            .unwrap();
+        pool
+    }
+
+    fn service_config(service: &str, alias: Option<&str>, local_port: u16) -> Config {
+        Config {
+            service: Some(service.to_string()),
+            namespace: "default".to_string(),
+            local_port: Some(local_port),
+            remote_port: Some(80),
+            context: Some("dev".to_string()),
+            workload_type: Some("service".to_string()),
+            protocol: "tcp".to_string(),
+            alias: alias.map(String::from),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_config_sync_key() {
+        assert_eq!(
+            config_sync_key(&service_config("api", Some("api-dev"), 8080)),
+            "alias:api-dev"
+        );
+        assert_eq!(
+            config_sync_key(&service_config("api", None, 8080)),
+            "service:dev/default/service/api/tcp/80"
+        );
+        assert_eq!(
+            config_sync_key(&service_config("api", Some(""), 8080)),
+            "service:dev/default/service/api/tcp/80"
+        );
+    }
+
+    #[test]
+    fn test_diff_configs() {
+        let mut existing = vec![
+            service_config("api", Some("api"), 8080),
+            service_config("web", Some("web"), 8081),
+            service_config("db", Some("db"), 5432),
+            service_config("local-only", Some("local"), 9000),
+        ];
+        for (id, config) in existing.iter_mut().enumerate() {
+            config.id = Some(id as i64 + 1);
+        }
+
+        let incoming = vec![
+            service_config("api", Some("api"), 8080),
+            service_config("web", Some("web"), 9081),
+            service_config("cache", Some("cache"), 6379),
+        ];
+        let applied_keys = vec![
+            "alias:api".to_string(),
+            "alias:web".to_string(),
+            "alias:db".to_string(),
+        ];
+
+        let diff = diff_configs(&existing, &incoming, &applied_keys);
+
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].service.as_deref(), Some("cache"));
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].fields, vec!["local_port"]);
+        assert_eq!(diff.changed[0].after.id, Some(2));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].service.as_deref(), Some("db"));
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_token_from_secret() {
+        assert_eq!(
+            token_from_secret("ghp_raw".to_string()).as_deref(),
+            Some("ghp_raw")
+        );
+        assert_eq!(
+            token_from_secret(r#"{"token":"ghp_json","username":"me"}"#.to_string()).as_deref(),
+            Some("ghp_json")
+        );
+        assert_eq!(token_from_secret(r#"{"token":""}"#.to_string()), None);
+        assert_eq!(token_from_secret(String::new()), None);
+    }
+
+    #[test]
+    fn test_repo_host() {
+        assert_eq!(repo_host("https://github.com/org/repo"), Some("github.com"));
+        assert_eq!(
+            repo_host("https://user@gitlab.example.com:8443/org/repo.git"),
+            Some("gitlab.example.com")
+        );
+        assert_eq!(
+            repo_host("ssh://git@github.com/org/repo.git"),
+            Some("github.com")
+        );
+        assert_eq!(repo_host("git@github.com:org/repo.git"), Some("github.com"));
+        assert_eq!(
+            repo_host("https://github.com.evil.example/org/repo"),
+            Some("github.com.evil.example")
+        );
+    }
+
+    #[test]
+    fn test_diff_configs_keeps_groups_missing_from_source() {
+        let mut existing = service_config("api", Some("api"), 8080);
+        existing.id = Some(1);
+        existing.groups = Some(vec!["payments".to_string()]);
+
+        let diff = diff_configs(
+            std::slice::from_ref(&existing),
+            &[service_config("api", Some("api"), 8080)],
+            &[],
+        );
+        assert_eq!(diff.unchanged, 1);
+
+        let mut incoming = service_config("api", Some("api"), 8080);
+        incoming.groups = Some(Vec::new());
+        let diff = diff_configs(&[existing], &[incoming], &[]);
+        assert_eq!(diff.changed[0].fields, vec!["groups".to_string()]);
+    }
+
+    #[test]
+    fn test_subscription_is_due() {
+        let mut subscription = GitSyncSubscription {
+            interval_secs: 60,
+            enabled: true,
+            ..GitSyncSubscription::default()
+        };
+        assert!(subscription.is_due(1_000));
+
+        subscription.last_checked_at = Some(970);
+        assert!(!subscription.is_due(1_000));
+        assert!(subscription.is_due(1_030));
+
+        subscription.interval_secs = 0;
+        assert!(!subscription.is_due(10_000));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_crud() {
+        let pool = setup_test_db().await;
+
+        let mut subscription = GitSyncSubscription {
+            name: "team".to_string(),
+            repo_url: "https://github.com/example/configs".to_string(),
+            config_path: "configs.json".to_string(),
+            git_ref: Some("main".to_string()),
+            interval_secs: 300,
+            enabled: true,
+            ..GitSyncSubscription::default()
+        };
+
+        let id = save_subscription_with_pool(&subscription, &pool)
+            .await
+            .unwrap();
+        subscription.id = Some(id);
+        subscription.applied_keys = vec!["alias:api".to_string()];
+        save_subscription_with_pool(&subscription, &pool)
+            .await
+            .unwrap();
+
+        let stored = list_subscriptions_with_pool(&pool).await.unwrap();
+        assert_eq!(stored, vec![subscription]);
+
+        delete_subscription_with_pool(id, &pool).await.unwrap();
+        assert!(get_subscription_with_pool(id, &pool).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_apply_sync_preview() {
+        let pool = setup_test_db().await;
+
+        insert_config_with_pool(service_config("api", Some("api"), 8080), &pool)
+            .await
+            .unwrap();
+        insert_config_with_pool(service_config("db", Some("db"), 5432), &pool)
+            .await
+            .unwrap();
+
+        let subscription_id = save_subscription_with_pool(
+            &GitSyncSubscription {
+                name: "team".to_string(),
+                repo_url: "https://github.com/example/configs".to_string(),
+                config_path: "configs.json".to_string(),
+                applied_keys: vec!["alias:api".to_string(), "alias:db".to_string()],
+                ..GitSyncSubscription::default()
+            },
+            &pool,
+        )
+        .await
+        .unwrap();
+
+        let existing = read_configs_with_pool(&pool).await.unwrap();
+        let incoming = vec![
+            service_config("api-v2", Some("api"), 8080),
+            service_config("cache", Some("cache"), 6379),
+        ];
+        let preview = SyncPreview {
+            subscription_id,
+            commit_sha: "abc".to_string(),
+            diff: diff_configs(
+                &existing,
+                &incoming,
+                &["alias:api".to_string(), "alias:db".to_string()],
+            ),
+            incoming_keys: incoming.iter().map(config_sync_key).collect(),
+        };
+
+        let result = apply_sync_preview_with_pool(&preview, &pool, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        let api_id = existing[0].id.unwrap();
+        assert_eq!(result.changed_config_ids, vec![api_id]);
+        assert_eq!(result.removed_config_ids, vec![existing[1].id.unwrap()]);
+        assert_eq!(preview.diff.removed_config_ids(), result.removed_config_ids);
+        assert_eq!(result.added, 1);
+
+        let configs = read_configs_with_pool(&pool).await.unwrap();
+        assert_eq!(configs.len(), 2);
+        let api = configs.iter().find(|c| c.id == Some(api_id)).unwrap();
+        assert_eq!(api.service.as_deref(), Some("api-v2"));
+        assert!(
+            configs
+                .iter()
+                .any(|c| c.service.as_deref() == Some("cache"))
+        );
+
+        for config in &configs {
+            let source = get_config_git_source_with_pool(config.id.unwrap(), &pool)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(source.repo_url, "https://github.com/example/configs");
+            assert_eq!(source.commit_sha, "abc");
+        }
+
+        let subscription = get_subscription_with_pool(subscription_id, &pool)
+            .await
+            .unwrap();
+        assert_eq!(subscription.last_commit_sha.as_deref(), Some("abc"));
+        assert_eq!(
+            subscription.applied_keys,
+            vec!["alias:api".to_string(), "alias:cache".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_sync_preview_rolls_back_on_error() {
+        let pool = setup_test_db().await;
+
+        insert_config_with_pool(service_config("api", Some("api"), 8080), &pool)
+            .await
+            .unwrap();
+        insert_config_with_pool(service_config("db", Some("db"), 5432), &pool)
+            .await
+            .unwrap();
+
+        let subscription_id = save_subscription_with_pool(
+            &GitSyncSubscription {
+                name: "team".to_string(),
+                repo_url: "https://github.com/example/configs".to_string(),
+                config_path: "configs.json".to_string(),
+                last_commit_sha: Some("old".to_string()),
+                ..GitSyncSubscription::default()
+            },
+            &pool,
+        )
+        .await
+        .unwrap();
+
+        let existing = read_configs_with_pool(&pool).await.unwrap();
+        let mut broken = existing[1].clone();
+        broken.service = Some("db-v2".to_string());
+        broken.id = None;
+        let preview = SyncPreview {
+            subscription_id,
+            commit_sha: "new".to_string(),
+            diff: ConfigDiff {
+                added: vec![service_config("cache", Some("cache"), 6379)],
+                changed: vec![
+                    ConfigChange {
+                        key: "alias:api".to_string(),
+                        before: existing[0].clone(),
+                        after: Config {
+                            service: Some("api-v2".to_string()),
+                            ..existing[0].clone()
+                        },
+                        fields: vec!["service".to_string()],
+                    },
+                    ConfigChange {
+                        key: "alias:db".to_string(),
+                        before: existing[1].clone(),
+                        after: broken,
+                        fields: vec!["service".to_string()],
+                    },
+                ],
+                ..ConfigDiff::default()
+            },
+            incoming_keys: Vec::new(),
+        };
+
+        assert!(
+            apply_sync_preview_with_pool(&preview, &pool, DatabaseMode::Memory)
+                .await
+                .is_err()
+        );
+
+        assert_eq!(read_configs_with_pool(&pool).await.unwrap(), existing);
+        let subscription = get_subscription_with_pool(subscription_id, &pool)
+            .await
+            .unwrap();
+        assert_eq!(subscription.last_commit_sha.as_deref(), Some("old"));
+    }
+}
@@ -5,20 +5,90 @@ use log::{
     info,
     warn,
 };
-use sqlx::SqlitePool;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sqlx::{
+    Row,
+    SqliteConnection,
+    SqlitePool,
+};
 
 use crate::db::get_db_pool;
+use crate::models::config_history_model::ConfigChangeSource;
+use crate::utils::config::delete_all_configs_with_pool;
+use crate::utils::config_format::ConfigFormat;
+use crate::utils::config_history::with_change_source;
 use crate::utils::db_mode::{
     DatabaseManager,
     DatabaseMode,
 };
+use crate::utils::git_provider::{
+    ApiSource,
+    GitProvider,
+    SourceError,
+    abbreviated_sha_error,
+    build_client,
+    fetch_http_source,
+    http_source_url,
+    is_abbreviated_sha,
+};
 
+#[derive(Clone)]
 pub struct GitHubConfig {
     pub repo_url: String,
     pub config_path: String,
     pub use_system_credentials: bool,
     pub github_token: Option<String>,
     pub flush_existing: bool,
+    /// Branch, tag or full commit SHA to import from. The remote default
+    /// branch is used when unset.
+    pub git_ref: Option<String>,
+    /// Disables TLS certificate and SSH host key verification for this
+    /// source. Only meant for throwaway test servers.
+    pub insecure_skip_verify: bool,
+    /// PEM bundle of additional CAs trusted for this source, for internal
+    /// GitLab/Gitea servers signed by a private CA.
+    pub ca_bundle_path: Option<String>,
+    /// Hosting provider of `repo_url`. Detected from well-known hosts when
+    /// unset; `Http` treats `repo_url` as a plain file URL.
+    pub provider: Option<GitProvider>,
+}
+
+impl GitHubConfig {
+    pub fn new(repo_url: String, config_path: String) -> Self {
+        Self {
+            repo_url,
+            config_path,
+            use_system_credentials: false,
+            github_token: None,
+            flush_existing: false,
+            git_ref: None,
+            insecure_skip_verify: false,
+            ca_bundle_path: None,
+            provider: None,
+        }
+    }
+}
+
+/// Where a Git import came from, including the commit it resolved to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitImportRecord {
+    pub repo_url: String,
+    pub config_path: String,
+    pub git_ref: Option<String>,
+    pub commit_sha: String,
+}
+
+const LAST_GIT_IMPORT_KEY: &str = "last_git_import";
+const PINNED_REF: &str = "refs/kftray/pinned";
+
+enum FetchFailure {
+    /// The server certificate or host key could not be verified. Never
+    /// retried with the system git fallback.
+    Verification(String),
+    Other(String),
 }
 
 pub type GitHubResult<T> = Result<T, String>;
@@ -26,36 +96,233 @@ pub type GitHubResult<T> = Result<T, String>;
 pub struct GitHubRepository;
 
 impl GitHubRepository {
-    pub async fn import_configs(config: GitHubConfig, mode: DatabaseMode) -> GitHubResult<()> {
-        let config_content = Self::clone_and_read_config(
-            &config.repo_url,
-            &config.config_path,
-            config.use_system_credentials,
-            config.github_token,
-        )?;
+    pub async fn import_configs(
+        config: GitHubConfig, mode: DatabaseMode,
+    ) -> GitHubResult<GitImportRecord> {
+        let context = DatabaseManager::get_context(mode).await?;
+        let (config_content, commit_sha) =
+            Self::fetch_config_with_pool(&config, &context.pool).await?;
 
-        Self::process_config_content(&config_content, config.flush_existing, mode).await
+        info!(
+            "Resolved {} at {} to commit {commit_sha}",
+            config.repo_url,
+            config.git_ref.as_deref().unwrap_or("HEAD")
+        );
+
+        let format = ConfigFormat::from_path(&config.config_path);
+        let config_ids = with_change_source(
+            ConfigChangeSource::GitSync,
+            Self::process_config_content(&config_content, format, config.flush_existing, mode),
+        )
+        .await?;
+
+        let record = GitImportRecord {
+            repo_url: config.repo_url,
+            config_path: config.config_path,
+            git_ref: config.git_ref,
+            commit_sha,
+        };
+        let mut conn = context.pool.acquire().await.map_err(|e| e.to_string())?;
+        record_config_git_sources(&mut conn, &config_ids, &record).await?;
+        save_git_import_record(&record, mode).await?;
+
+        Ok(record)
     }
 
-    fn clone_and_read_config(
-        repo_url: &str, config_path: &str, use_system_credentials: bool,
-        github_token: Option<String>,
-    ) -> GitHubResult<String> {
+    /// Fetches the config file of a source without importing it, returning
+    /// its content and the resolved commit SHA (the content SHA-1 for plain
+    /// HTTP sources). Repositories on a known hosting provider are read
+    /// through its API and only cloned when the API request fails.
+    pub(crate) async fn fetch_config_with_pool(
+        config: &GitHubConfig, pool: &SqlitePool,
+    ) -> GitHubResult<(String, String)> {
+        let provider = config
+            .provider
+            .or_else(|| GitProvider::detect(&config.repo_url));
+
+        if provider == Some(GitProvider::Http) {
+            let client = build_client(
+                config.insecure_skip_verify,
+                config.ca_bundle_path.as_deref(),
+            )?;
+            let url = http_source_url(&config.repo_url, &config.config_path);
+            return fetch_http_source(&client, &url, config.github_token.as_deref(), pool)
+                .await
+                .map_err(|e| e.to_string());
+        }
+
+        // Private repositories reached with SSH keys or credential helpers
+        // are not visible to the API without a token.
+        if let Some(provider) = provider
+            && (config.github_token.is_some() || !config.use_system_credentials)
+        {
+            match Self::fetch_with_api(provider, config).await {
+                Ok(result) => return Ok(result),
+                Err(SourceError::Verification(e)) => {
+                    error!("{e}");
+                    return Err(e);
+                }
+                Err(SourceError::Other(e)) => {
+                    warn!("{provider} API fetch failed: {e}, falling back to git fetch");
+                }
+            }
+        }
+
+        let config = config.clone();
+        tokio::task::spawn_blocking(move || Self::clone_and_read_config(&config))
+            .await
+            .map_err(|e| format!("Git fetch task failed: {e}"))?
+    }
+
+    async fn fetch_with_api(
+        provider: GitProvider, config: &GitHubConfig,
+    ) -> Result<(String, String), SourceError> {
+        let source = ApiSource::new(provider, &config.repo_url, config.github_token.clone())
+            .map_err(SourceError::Other)?;
+        let client = build_client(
+            config.insecure_skip_verify,
+            config.ca_bundle_path.as_deref(),
+        )
+        .map_err(SourceError::Other)?;
+
+        source
+            .fetch(&client, &config.config_path, config.git_ref.as_deref())
+            .await
+    }
+
+    /// Shallow-fetches only the requested ref into a bare temporary repository
+    /// and reads the config file straight from the resolved commit.
+    fn clone_and_read_config(config: &GitHubConfig) -> GitHubResult<(String, String)> {
+        use tempfile::TempDir;
+
+        let candidates = ref_candidates(config.git_ref.as_deref());
+        let ca_bundle = config
+            .ca_bundle_path
+            .as_deref()
+            .map(validate_ca_bundle)
+            .transpose()?;
+        let temp_dir = TempDir::new().map_err(|e| format!("Failed to create temp dir: {e}"))?;
+
+        if config.insecure_skip_verify {
+            warn!(
+                "TLS certificate and SSH host key verification is disabled for {}",
+                config.repo_url
+            );
+        }
+
+        info!("Attempting to fetch repository: {}", config.repo_url);
+
+        // libgit2 only offers a process wide CA location that can't be reset,
+        // so a custom bundle is handed to system git for this fetch alone.
+        if let Some(bundle) = ca_bundle
+            .as_deref()
+            .filter(|_| !config.insecure_skip_verify)
+        {
+            info!("Using system git with CA bundle {}", bundle.display());
+            return Self::try_fetch_with_system_git(config, temp_dir.path(), Some(bundle)).map_err(
+                |e| {
+                    if e.starts_with("Failed to execute git command") {
+                        format!("{e}. A custom CA bundle needs the git command line tool installed")
+                    } else {
+                        e
+                    }
+                },
+            );
+        }
+
+        match Self::fetch_with_git2(config, temp_dir.path(), &candidates) {
+            Ok(result) => {
+                info!("Successfully fetched repository");
+                Ok(result)
+            }
+            Err(FetchFailure::Verification(e)) => {
+                error!("{e}");
+                Err(e)
+            }
+            Err(FetchFailure::Other(e)) => {
+                warn!("Repository fetch failed: {e}, trying fallback with system git command");
+                let fallback_dir =
+                    TempDir::new().map_err(|e| format!("Failed to create temp dir: {e}"))?;
+                Self::try_fetch_with_system_git(config, fallback_dir.path(), None)
+            }
+        }
+    }
+
+    fn fetch_with_git2(
+        config: &GitHubConfig, path: &Path, candidates: &[String],
+    ) -> Result<(String, String), FetchFailure> {
+        use git2::{
+            AutotagOption,
+            FetchOptions,
+            Repository,
+        };
+
+        let repo_url = config.repo_url.as_str();
+        let repo = Repository::init_bare(path)
+            .map_err(|e| FetchFailure::Other(format!("Failed to initialize repository: {e}")))?;
+        let mut remote = repo
+            .remote_anonymous(repo_url)
+            .map_err(|e| FetchFailure::Other(format!("Invalid repository URL: {e}")))?;
+
+        let mut last_error = None;
+        for candidate in candidates {
+            let mut fetch_opts = FetchOptions::new();
+            fetch_opts.remote_callbacks(Self::remote_callbacks(
+                config.use_system_credentials,
+                config.github_token.clone(),
+                config.insecure_skip_verify,
+            ));
+            fetch_opts.depth(1);
+            fetch_opts.download_tags(AutotagOption::None);
+
+            let refspec = format!("+{candidate}:{PINNED_REF}");
+            if let Err(e) = remote.fetch(&[refspec.as_str()], Some(&mut fetch_opts), None) {
+                if e.code() == git2::ErrorCode::Certificate {
+                    return Err(FetchFailure::Verification(verification_error(repo_url, &e)));
+                }
+                last_error = Some(format!("Failed to fetch {candidate}: {e}"));
+                continue;
+            }
+
+            if repo.find_reference(PINNED_REF).is_ok() {
+                break;
+            }
+        }
+
+        let reference = repo.find_reference(PINNED_REF).map_err(|_| {
+            FetchFailure::Other(missing_ref_error(
+                config.git_ref.as_deref(),
+                last_error.unwrap_or_else(|| {
+                    format!(
+                        "Ref '{}' not found in {repo_url}",
+                        candidates.first().map(String::as_str).unwrap_or("HEAD")
+                    )
+                }),
+            ))
+        })?;
+        let commit = reference
+            .peel_to_commit()
+            .map_err(|e| FetchFailure::Other(format!("Ref does not point to a commit: {e}")))?;
+
+        let content = Self::read_config_from_commit(&repo, &commit, &config.config_path)
+            .map_err(FetchFailure::Other)?;
+
+        Ok((content, commit.id().to_string()))
+    }
+
+    fn remote_callbacks(
+        use_system_credentials: bool, github_token: Option<String>, insecure_skip_verify: bool,
+    ) -> git2::RemoteCallbacks<'static> {
         use git2::{
             CertificateCheckStatus,
             Cred,
-            FetchOptions,
             RemoteCallbacks,
-            build::RepoBuilder,
         };
-        use tempfile::TempDir;
-
-        let temp_dir = TempDir::new().map_err(|e| format!("Failed to create temp dir: {e}"))?;
 
         let mut callbacks = RemoteCallbacks::new();
 
         if use_system_credentials || github_token.is_some() {
-            let token = github_token.clone();
+            let token = github_token;
             let attempts = std::sync::atomic::AtomicUsize::new(0);
 
             callbacks.credentials(move |url, username_from_url, allowed_types| {
@@ -92,26 +359,35 @@ impl GitHubRepository {
             });
         }
 
-        callbacks.certificate_check(|_cert, _hostname| Ok(CertificateCheckStatus::CertificateOk));
-
-        let mut fetch_opts = FetchOptions::new();
-        fetch_opts.remote_callbacks(callbacks);
-
-        let mut builder = RepoBuilder::new();
-        builder.fetch_options(fetch_opts);
+        // Without a callback libgit2 validates certificates against the system
+        // trust store and SSH host keys against known_hosts.
+        if insecure_skip_verify {
+            callbacks
+                .certificate_check(|_cert, _hostname| Ok(CertificateCheckStatus::CertificateOk));
+        }
 
-        info!("Attempting to clone repository: {repo_url}");
+        callbacks
+    }
 
-        match builder.clone(repo_url, temp_dir.path()) {
-            Ok(_) => {
-                info!("Successfully cloned repository");
-                Self::read_config_file(temp_dir.path(), config_path)
-            }
-            Err(e) => {
-                warn!("Repository clone failed: {e}, trying fallback with system git command");
-                Self::try_clone_with_system_git(repo_url, temp_dir.path(), config_path)
-            }
-        }
+    fn read_config_from_commit(
+        repo: &git2::Repository, commit: &git2::Commit, config_path: &str,
+    ) -> GitHubResult<String> {
+        let tree = commit
+            .tree()
+            .map_err(|e| format!("Failed to read commit tree: {e}"))?;
+        let entry = tree.get_path(Path::new(config_path)).map_err(|_| {
+            format!(
+                "Config file '{config_path}' not found at commit {}",
+                commit.id()
+            )
+        })?;
+        let blob = entry
+            .to_object(repo)
+            .and_then(|object| object.peel_to_blob())
+            .map_err(|e| format!("Failed to read config file '{config_path}': {e}"))?;
+
+        String::from_utf8(blob.content().to_vec())
+            .map_err(|e| format!("Config file '{config_path}' is not valid UTF-8: {e}"))
     }
 
     fn get_system_credentials(url: &str, username: &str) -> Result<git2::Cred, git2::Error> {
@@ -134,46 +410,100 @@ impl GitHubRepository {
         Err(git2::Error::from_str("No valid credentials found"))
     }
 
-    fn try_clone_with_system_git(
-        repo_url: &str, path: &Path, config_path: &str,
-    ) -> GitHubResult<String> {
+    fn try_fetch_with_system_git(
+        config: &GitHubConfig, path: &Path, ca_bundle: Option<&Path>,
+    ) -> GitHubResult<(String, String)> {
+        let git_ref = config.git_ref.as_deref().unwrap_or("HEAD");
+        let config_path = config.config_path.as_str();
+
+        let mut fetch_args: Vec<String> = Vec::new();
+        if config.insecure_skip_verify {
+            fetch_args.extend(["-c".to_string(), "http.sslVerify=false".to_string()]);
+        } else if let Some(bundle) = ca_bundle {
+            fetch_args.extend([
+                "-c".to_string(),
+                format!("http.sslCAInfo={}", bundle.display()),
+            ]);
+        }
+        fetch_args.extend(
+            [
+                "fetch",
+                "--depth=1",
+                "--no-tags",
+                "--filter=blob:none",
+                "--recurse-submodules=no",
+                &config.repo_url,
+                git_ref,
+            ]
+            .map(String::from),
+        );
+
+        Self::run_git(path, &["init", "--quiet", "--bare"], false)?;
+        Self::run_git(
+            path,
+            &fetch_args.iter().map(String::as_str).collect::<Vec<_>>(),
+            config.insecure_skip_verify,
+        )
+        .map_err(|error_msg| {
+            if is_verification_message(&error_msg) {
+                return verification_error(&config.repo_url, &error_msg);
+            }
+            if error_msg.contains("couldn't find remote ref") {
+                return missing_ref_error(Some(git_ref), error_msg);
+            }
+            format!(
+                "Failed to clone repository at '{git_ref}'. Please check your credentials, repository URL and ref. Error: {error_msg}"
+            )
+        })?;
+
+        let commit_sha = Self::run_git(path, &["rev-parse", "FETCH_HEAD^{commit}"], false)?
+            .trim()
+            .to_string();
+        let content = Self::run_git(
+            path,
+            &["show", &format!("{commit_sha}:{config_path}")],
+            false,
+        )
+        .map_err(|e| format!("Failed to read config file '{config_path}': {e}"))?;
+
+        info!("Successfully fetched repository using system git");
+        Ok((content, commit_sha))
+    }
+
+    fn run_git(path: &Path, args: &[&str], insecure_skip_verify: bool) -> GitHubResult<String> {
         use std::process::Command;
 
-        let output = Command::new("git")
-            .arg("clone")
-            .arg("--depth=1")
-            .arg("--single-branch")
-            .arg("--no-tags")
-            .arg("--filter=blob:none")
-            .arg("--recurse-submodules=no")
-            .arg(repo_url)
-            .arg(path)
+        let mut command = Command::new("git");
+        command.arg("-C").arg(path).args(args);
+        command.env("GIT_TERMINAL_PROMPT", "0");
+        if std::env::var_os("GIT_SSH_COMMAND").is_none() {
+            command.env(
+                "GIT_SSH_COMMAND",
+                if insecure_skip_verify {
+                    "ssh -o StrictHostKeyChecking=no -o UserKnownHostsFile=/dev/null"
+                } else {
+                    "ssh -o StrictHostKeyChecking=yes"
+                },
+            );
+        }
+
+        let output = command
             .output()
             .map_err(|e| format!("Failed to execute git command: {e}"))?;
 
         if output.status.success() {
-            info!("Successfully cloned repository using system git");
-            Self::read_config_file(path, config_path)
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
         } else {
             let error_msg = String::from_utf8_lossy(&output.stderr);
-            error!("System git clone failed: {error_msg}");
-            Err(format!(
-                "Failed to clone repository. Please check your credentials and repository URL. Error: {error_msg}"
-            ))
+            error!("System git {} failed: {error_msg}", args[0]);
+            Err(error_msg.trim().to_string())
         }
     }
 
-    fn read_config_file(temp_dir: &Path, config_path: &str) -> GitHubResult<String> {
-        let config_path = Path::new(config_path);
-        let full_path = temp_dir.join(config_path);
-
-        std::fs::read_to_string(&full_path)
-            .map_err(|e| format!("Failed to read config file at {}: {e}", full_path.display()))
-    }
-
     async fn process_config_content(
-        config_content: &str, flush_existing: bool, mode: DatabaseMode,
-    ) -> GitHubResult<()> {
+        config_content: &str, format: Option<ConfigFormat>, flush_existing: bool,
+        mode: DatabaseMode,
+    ) -> GitHubResult<Vec<i64>> {
         if flush_existing && mode == DatabaseMode::File {
             info!("Flushing existing configurations before import");
             clear_existing_configs_with_mode(mode).await?;
@@ -183,8 +513,9 @@ impl GitHubRepository {
 
         info!("Importing configurations using incremental merge");
 
-        crate::utils::config::import_configs_with_pool_and_mode(
+        let config_ids = crate::utils::config::import_configs_with_pool_and_mode(
             config_content.to_string(),
+            format,
             &context.pool,
             mode,
         )
@@ -192,19 +523,161 @@ impl GitHubRepository {
         .map_err(|e| format!("Failed to import configs: {e}"))?;
 
         info!("Configuration import completed successfully");
-        Ok(())
+        Ok(config_ids)
     }
 }
 
-/// Clear existing configurations from database
-async fn clear_existing_configs_with_pool(pool: &SqlitePool) -> Result<(), sqlx::Error> {
-    let mut conn = pool.acquire().await?;
-    sqlx::query("DELETE FROM configs")
+/// Expands a user supplied ref into the remote refs to try, in order. Full
+/// commit SHAs are fetched directly; anything else is looked up as a branch
+/// first and then as a tag, including names that look like a shortened SHA.
+fn ref_candidates(git_ref: Option<&str>) -> Vec<String> {
+    let Some(git_ref) = git_ref.map(str::trim).filter(|r| !r.is_empty()) else {
+        return vec!["HEAD".to_string()];
+    };
+
+    if git_ref.starts_with("refs/") {
+        return vec![git_ref.to_string()];
+    }
+
+    if git_ref.len() == 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit()) {
+        return vec![git_ref.to_lowercase()];
+    }
+
+    vec![
+        format!("refs/heads/{git_ref}"),
+        format!("refs/tags/{git_ref}"),
+    ]
+}
+
+/// The error for a ref that matched no branch or tag, explaining that
+/// shortened SHAs can't be fetched when the ref looks like one.
+fn missing_ref_error(git_ref: Option<&str>, error: String) -> String {
+    match git_ref.map(str::trim) {
+        Some(git_ref) if is_abbreviated_sha(git_ref) => abbreviated_sha_error(git_ref),
+        _ => error,
+    }
+}
+
+pub(crate) fn validate_ca_bundle(path: &str) -> GitHubResult<std::path::PathBuf> {
+    let path = Path::new(path);
+    if !path.is_file() {
+        return Err(format!("CA bundle not found at {}", path.display()));
+    }
+
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read CA bundle: {e}"))?;
+    if !contents.contains("-----BEGIN CERTIFICATE-----") {
+        return Err(format!(
+            "CA bundle at {} does not contain any PEM certificates",
+            path.display()
+        ));
+    }
+
+    Ok(path.to_path_buf())
+}
+
+fn is_verification_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["certificate", "host key verification failed"]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+pub(crate) fn verification_error(repo_url: &str, error: &dyn std::fmt::Display) -> String {
+    let is_ssh = repo_url.starts_with("git@") || repo_url.starts_with("ssh://");
+
+    if is_ssh {
+        format!(
+            "SSH host key verification failed for {repo_url}: {error}. Add the server's host key to ~/.ssh/known_hosts (for example with `ssh-keyscan`), or disable verification for this source."
+        )
+    } else {
+        format!(
+            "TLS certificate verification failed for {repo_url}: {error}. Provide the server's CA with a custom CA bundle, or disable verification for this source."
+        )
+    }
+}
+
+async fn save_git_import_record(record: &GitImportRecord, mode: DatabaseMode) -> GitHubResult<()> {
+    let value = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    crate::utils::settings::set_setting_with_mode(LAST_GIT_IMPORT_KEY, &value, mode)
+        .await
+        .map_err(|e| format!("Failed to record imported commit: {e}"))
+}
+
+/// Records `record` as the source of `config_ids`, replacing where they were
+/// imported from before.
+pub(crate) async fn record_config_git_sources(
+    conn: &mut SqliteConnection, config_ids: &[i64], record: &GitImportRecord,
+) -> GitHubResult<()> {
+    for config_id in config_ids {
+        sqlx::query(
+            "INSERT OR REPLACE INTO config_git_sources \
+             (config_id, repo_url, config_path, git_ref, commit_sha) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(config_id)
+        .bind(&record.repo_url)
+        .bind(&record.config_path)
+        .bind(&record.git_ref)
+        .bind(&record.commit_sha)
         .execute(&mut *conn)
-        .await?;
+        .await
+        .map_err(|e| format!("Failed to record the Git source of config {config_id}: {e}"))?;
+    }
+
     Ok(())
 }
 
+/// Returns the repository, ref and commit config `config_id` was last
+/// imported or synced from, or `None` when it was not.
+pub(crate) async fn get_config_git_source_with_pool(
+    config_id: i64, pool: &SqlitePool,
+) -> GitHubResult<Option<GitImportRecord>> {
+    let row = sqlx::query(
+        "SELECT repo_url, config_path, git_ref, commit_sha FROM config_git_sources \
+         WHERE config_id = ?1",
+    )
+    .bind(config_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    row.map(|row| {
+        Ok(GitImportRecord {
+            repo_url: row.try_get("repo_url").map_err(|e| e.to_string())?,
+            config_path: row.try_get("config_path").map_err(|e| e.to_string())?,
+            git_ref: row.try_get("git_ref").map_err(|e| e.to_string())?,
+            commit_sha: row.try_get("commit_sha").map_err(|e| e.to_string())?,
+        })
+    })
+    .transpose()
+}
+
+pub async fn get_config_git_source_with_mode(
+    config_id: i64, mode: DatabaseMode,
+) -> GitHubResult<Option<GitImportRecord>> {
+    let context = DatabaseManager::get_context(mode).await?;
+    get_config_git_source_with_pool(config_id, &context.pool).await
+}
+
+/// Returns the source and resolved commit of the most recent Git import.
+pub async fn get_last_git_import_with_mode(
+    mode: DatabaseMode,
+) -> GitHubResult<Option<GitImportRecord>> {
+    let value = crate::utils::settings::get_setting_with_mode(LAST_GIT_IMPORT_KEY, mode)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(value.and_then(|value| serde_json::from_str(&value).ok()))
+}
+
+/// Clear existing configurations from database
+async fn clear_existing_configs_with_pool(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    delete_all_configs_with_pool(pool)
+        .await
+        .map_err(|e| sqlx::Error::Protocol(e.to_string()))
+}
+
 pub async fn clear_existing_configs() -> Result<(), sqlx::Error> {
     let pool = get_db_pool()
         .await
@@ -258,7 +731,7 @@ mod tests {
 
     use super::*;
     use crate::db::create_db_table;
-    use crate::models::config_model::Config;
+    use crate::test_utils::valid_config;
     use crate::utils::config::insert_config_with_pool;
 
     async fn setup_test_db() -> SqlitePool {
@@ -278,10 +751,10 @@ mod tests {
     async fn test_clear_existing_configs() {
         let pool = setup_test_db().await;
 
-        insert_config_with_pool(Config::default(), &pool)
+        insert_config_with_pool(valid_config(), &pool)
             .await
             .unwrap();
-        insert_config_with_pool(Config::default(), &pool)
+        insert_config_with_pool(valid_config(), &pool)
             .await
             .unwrap();
 
@@ -298,11 +771,48 @@ mod tests {
         assert!(configs_after.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_config_git_sources() {
+        let pool = setup_test_db().await;
+        insert_config_with_pool(valid_config(), &pool)
+            .await
+            .unwrap();
+        let id = crate::utils::config::read_configs_with_pool(&pool)
+            .await
+            .unwrap()[0]
+            .id
+            .unwrap();
+        assert_eq!(get_config_git_source_with_pool(id, &pool).await, Ok(None));
+
+        let mut record = GitImportRecord {
+            repo_url: "https://github.com/example/configs".to_string(),
+            config_path: "dev.json".to_string(),
+            git_ref: Some("staging".to_string()),
+            commit_sha: "a".repeat(40),
+        };
+        let mut conn = pool.acquire().await.unwrap();
+        record_config_git_sources(&mut conn, &[id], &record)
+            .await
+            .unwrap();
+        record.commit_sha = "b".repeat(40);
+        record_config_git_sources(&mut conn, &[id], &record)
+            .await
+            .unwrap();
+        drop(conn);
+        assert_eq!(
+            get_config_git_source_with_pool(id, &pool).await,
+            Ok(Some(record))
+        );
+
+        clear_existing_configs_with_pool(&pool).await.unwrap();
+        assert_eq!(get_config_git_source_with_pool(id, &pool).await, Ok(None));
+    }
+
     #[tokio::test]
     async fn test_clear_existing_configs_public_function() {
         let pool = setup_test_db().await;
 
-        insert_config_with_pool(Config::default(), &pool)
+        insert_config_with_pool(valid_config(), &pool)
             .await
             .unwrap();
 
@@ -329,6 +839,179 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn init_source_repo(path: &Path) -> (String, String) {
+        let repo = git2::Repository::init(path).unwrap();
+        let signature = git2::Signature::now("kftray", "kftray@example.com").unwrap();
+
+        let commit_file = |content: &str, parent: Option<git2::Oid>| {
+            std::fs::write(path.join("configs.json"), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("configs.json")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = parent
+                .map(|oid| vec![repo.find_commit(oid).unwrap()])
+                .unwrap_or_default();
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "update",
+                &tree,
+                &parent_refs,
+            )
+            .unwrap()
+        };
+
+        let first = commit_file(r#"[{"service":"v1"}]"#, None);
+        let first_commit = repo.find_commit(first).unwrap();
+        repo.branch("staging", &first_commit, false).unwrap();
+        repo.branch("c0ffee1", &first_commit, false).unwrap();
+        repo.tag_lightweight("v1.0.0", first_commit.as_object(), false)
+            .unwrap();
+        let second = commit_file(r#"[{"service":"v2"}]"#, Some(first));
+
+        (first.to_string(), second.to_string())
+    }
+
+    #[test]
+    fn test_ref_candidates() {
+        assert_eq!(ref_candidates(None), vec!["HEAD"]);
+        assert_eq!(ref_candidates(Some("  ")), vec!["HEAD"]);
+        assert_eq!(
+            ref_candidates(Some("main")),
+            vec!["refs/heads/main", "refs/tags/main"]
+        );
+        assert_eq!(ref_candidates(Some("refs/tags/v1")), vec!["refs/tags/v1"]);
+
+        let sha = "ABCDEF0123456789abcdef0123456789abcdef01";
+        assert_eq!(ref_candidates(Some(sha)), vec![sha.to_lowercase()]);
+        assert_eq!(
+            ref_candidates(Some("abcdef0")),
+            vec!["refs/heads/abcdef0", "refs/tags/abcdef0"]
+        );
+    }
+
+    #[test]
+    fn test_clone_and_read_config_pins_refs() {
+        let source = tempfile::TempDir::new().unwrap();
+        let (first, second) = init_source_repo(source.path());
+        let url = format!("file://{}", source.path().display());
+
+        let read = |git_ref: Option<&str>| {
+            let mut config = GitHubConfig::new(url.clone(), "configs.json".to_string());
+            config.git_ref = git_ref.map(String::from);
+            GitHubRepository::clone_and_read_config(&config).unwrap()
+        };
+
+        assert_eq!(read(None), (r#"[{"service":"v2"}]"#.to_string(), second));
+        assert_eq!(
+            read(Some("staging")),
+            (r#"[{"service":"v1"}]"#.to_string(), first.clone())
+        );
+        assert_eq!(read(Some("v1.0.0")).1, first);
+        assert_eq!(read(Some(&first)).1, first);
+        assert_eq!(read(Some("c0ffee1")).1, first);
+    }
+
+    #[test]
+    fn test_clone_and_read_config_abbreviated_sha() {
+        let source = tempfile::TempDir::new().unwrap();
+        let (first, _) = init_source_repo(source.path());
+        let url = format!("file://{}", source.path().display());
+
+        let mut config = GitHubConfig::new(url, "configs.json".to_string());
+        config.git_ref = Some(first[..7].to_string());
+
+        let error = GitHubRepository::clone_and_read_config(&config).unwrap_err();
+        assert!(error.contains("full 40-character SHA"), "{error}");
+    }
+
+    #[test]
+    fn test_clone_and_read_config_missing_ref() {
+        let source = tempfile::TempDir::new().unwrap();
+        init_source_repo(source.path());
+        let url = format!("file://{}", source.path().display());
+
+        let mut config = GitHubConfig::new(url, "configs.json".to_string());
+        config.git_ref = Some("does-not-exist".to_string());
+
+        let result = GitHubRepository::clone_and_read_config(&config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_clone_and_read_config_with_ca_bundle() {
+        let source = tempfile::TempDir::new().unwrap();
+        let (_, second) = init_source_repo(source.path());
+        let bundle = source.path().join("ca.pem");
+        std::fs::write(
+            &bundle,
+            "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+        )
+        .unwrap();
+
+        let mut config = GitHubConfig::new(
+            format!("file://{}", source.path().display()),
+            "configs.json".to_string(),
+        );
+        config.ca_bundle_path = Some(bundle.to_str().unwrap().to_string());
+
+        assert_eq!(
+            GitHubRepository::clone_and_read_config(&config).unwrap(),
+            (r#"[{"service":"v2"}]"#.to_string(), second)
+        );
+    }
+
+    #[test]
+    fn test_validate_ca_bundle() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let missing = dir.path().join("missing.pem");
+        assert!(
+            validate_ca_bundle(missing.to_str().unwrap())
+                .unwrap_err()
+                .contains("CA bundle not found")
+        );
+
+        let not_pem = dir.path().join("not.pem");
+        std::fs::write(&not_pem, "hello").unwrap();
+        assert!(
+            validate_ca_bundle(not_pem.to_str().unwrap())
+                .unwrap_err()
+                .contains("does not contain any PEM certificates")
+        );
+
+        let pem = dir.path().join("ca.pem");
+        std::fs::write(
+            &pem,
+            "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+        )
+        .unwrap();
+        assert_eq!(validate_ca_bundle(pem.to_str().unwrap()).unwrap(), pem);
+    }
+
+    #[test]
+    fn test_verification_errors() {
+        assert!(is_verification_message(
+            "fatal: unable to access 'https://git.internal/': SSL certificate problem: self-signed certificate"
+        ));
+        assert!(is_verification_message("Host key verification failed."));
+        assert!(!is_verification_message(
+            "fatal: couldn't find remote ref main"
+        ));
+
+        assert!(
+            verification_error("git@git.internal:team/configs.git", &"mismatch")
+                .starts_with("SSH host key verification failed")
+        );
+        assert!(
+            verification_error("https://git.internal/team/configs.git", &"untrusted")
+                .starts_with("TLS certificate verification failed")
+        );
+    }
+
     #[test]
     fn test_build_github_api_url_edge_cases() {
         let url1 =
@@ -1,6 +1,7 @@
 use log::error;
 use sqlx::{
     Row,
+    SqliteConnection,
     SqlitePool,
 };
 
@@ -67,7 +68,14 @@ pub(crate) async fn update_http_logs_config_with_pool(
     config: &HttpLogsConfig, pool: &SqlitePool,
 ) -> Result<(), String> {
     let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    write_http_logs_config(&mut conn, config).await
+}
 
+/// Writes `config` and mirrors it into the config's data on an already
+/// acquired connection.
+pub(crate) async fn write_http_logs_config(
+    conn: &mut SqliteConnection, config: &HttpLogsConfig,
+) -> Result<(), String> {
     sqlx::query(
         "INSERT INTO http_logs_config (config_id, enabled, max_file_size, retention_days, auto_cleanup, updated_at)
          VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)
@@ -102,7 +110,7 @@ pub(crate) async fn update_http_logs_config_with_pool(
                 ),
                 '$.http_logs_auto_cleanup', json(?5)
             )
-         WHERE json_extract(data, '$.id') = ?1",
+         WHERE id = ?1",
     )
     .bind(config.config_id)
     .bind(if config.enabled { "true" } else { "false" })
@@ -235,6 +243,7 @@ pub async fn read_all_http_logs_configs_with_mode(
 mod tests {
     use super::*;
     use crate::db::create_db_table;
+    use crate::test_utils::valid_config;
     use crate::utils::migration::migrate_configs;
 
     async fn setup_test_db() -> SqlitePool {
@@ -260,7 +269,7 @@ mod tests {
 
         let test_config = Config {
             service: Some("test-service".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(test_config, &pool).await.unwrap();
@@ -317,11 +326,11 @@ mod tests {
 
         let test_config1 = Config {
             service: Some("test-service-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let test_config2 = Config {
             service: Some("test-service-2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_with_pool(test_config1, &pool).await.unwrap();
@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+use std::net::{
+    TcpListener,
+    UdpSocket,
+};
+
+use sqlx::SqlitePool;
+
+use crate::models::config_model::Config;
+use crate::utils::config::read_configs_with_pool;
+use crate::utils::db_mode::{
+    DatabaseManager,
+    DatabaseMode,
+};
+
+pub const DEFAULT_LOCAL_ADDRESS: &str = "127.0.0.1";
+const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+const RANDOM_PORT_ATTEMPTS: usize = 32;
+
+/// What to do when a config's `local_port` is already bound at start.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PortConflictPolicy {
+    /// Keep the configured port and let the start fail.
+    #[default]
+    Fail,
+    /// Take the next free port above the configured one.
+    Next,
+    /// Take any free port assigned by the operating system.
+    Random,
+}
+
+impl PortConflictPolicy {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(str::trim) {
+            None | Some("") | Some("fail") => Ok(PortConflictPolicy::Fail),
+            Some("next") => Ok(PortConflictPolicy::Next),
+            Some("random") => Ok(PortConflictPolicy::Random),
+            Some(other) => Err(format!("'{other}' is not one of fail, next or random")),
+        }
+    }
+
+    /// The policy of `config`, falling back to `Fail` for unknown values.
+    pub fn of(config: &Config) -> Self {
+        Self::parse(config.port_conflict_policy.as_deref()).unwrap_or_default()
+    }
+}
+
+/// The local address and port a config binds, or `None` when it never
+/// conflicts with other configs.
+pub fn local_endpoint(config: &Config) -> Option<(String, u16)> {
+    if config.auto_loopback_address {
+        return None;
+    }
+    let port = config.local_port.filter(|port| *port != 0)?;
+    let address = config
+        .local_address
+        .clone()
+        .filter(|address| !address.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_LOCAL_ADDRESS.to_string());
+    Some((address, port))
+}
+
+/// Ports at `address` claimed by `configs`, except the config with
+/// `exclude_id`.
+pub fn reserved_local_ports(
+    configs: &[Config], address: &str, exclude_id: Option<i64>,
+) -> HashSet<u16> {
+    configs
+        .iter()
+        .filter(|config| exclude_id.is_none() || config.id != exclude_id)
+        .filter_map(local_endpoint)
+        .filter(|(config_address, _)| config_address == address)
+        .map(|(_, port)| port)
+        .collect()
+}
+
+pub(crate) async fn reserved_local_ports_with_pool(
+    address: &str, exclude_id: Option<i64>, pool: &SqlitePool,
+) -> Result<HashSet<u16>, String> {
+    let configs = read_configs_with_pool(pool).await?;
+    Ok(reserved_local_ports(&configs, address, exclude_id))
+}
+
+pub async fn reserved_local_ports_with_mode(
+    address: &str, exclude_id: Option<i64>, mode: DatabaseMode,
+) -> Result<HashSet<u16>, String> {
+    let context = DatabaseManager::get_context(mode).await?;
+    reserved_local_ports_with_pool(address, exclude_id, &context.pool).await
+}
+
+/// Whether `port` can be bound at `address` for `protocol` right now.
+pub fn is_local_port_free(address: &str, port: u16, protocol: &str) -> bool {
+    if protocol == "udp" {
+        UdpSocket::bind((address, port)).is_ok()
+    } else {
+        TcpListener::bind((address, port)).is_ok()
+    }
+}
+
+/// The first port after `port` that is neither reserved nor bound, wrapping
+/// around to the unprivileged range below it.
+pub fn next_free_port(
+    address: &str, port: u16, protocol: &str, reserved: &HashSet<u16>,
+) -> Option<u16> {
+    (port.saturating_add(1)..=u16::MAX)
+        .chain(FIRST_UNPRIVILEGED_PORT..port)
+        .find(|candidate| {
+            !reserved.contains(candidate) && is_local_port_free(address, *candidate, protocol)
+        })
+}
+
+/// A free port assigned by the operating system that is not reserved.
+pub fn random_free_port(address: &str, protocol: &str, reserved: &HashSet<u16>) -> Option<u16> {
+    (0..RANDOM_PORT_ATTEMPTS)
+        .filter_map(|_| {
+            if protocol == "udp" {
+                UdpSocket::bind((address, 0)).ok()?.local_addr().ok()
+            } else {
+                TcpListener::bind((address, 0)).ok()?.local_addr().ok()
+            }
+        })
+        .map(|addr| addr.port())
+        .find(|port| !reserved.contains(port))
+}
+
+/// Applies the port conflict policy of `config` at `address`. Returns the
+/// port to bind instead of `local_port`, or `None` when the configured port
+/// should be used as is.
+pub fn resolve_local_port(
+    config: &Config, address: &str, reserved: &HashSet<u16>,
+) -> Result<Option<u16>, String> {
+    let Some(port) = config.local_port.filter(|port| *port != 0) else {
+        return Ok(None);
+    };
+    if is_local_port_free(address, port, &config.protocol) {
+        return Ok(None);
+    }
+
+    let replacement = match PortConflictPolicy::of(config) {
+        PortConflictPolicy::Fail => return Ok(None),
+        PortConflictPolicy::Next => next_free_port(address, port, &config.protocol, reserved),
+        PortConflictPolicy::Random => random_free_port(address, &config.protocol, reserved),
+    };
+
+    replacement
+        .map(Some)
+        .ok_or_else(|| format!("Local port {address}:{port} is in use and no free port was found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_policy(port: u16, policy: Option<&str>) -> Config {
+        Config {
+            local_port: Some(port),
+            protocol: "tcp".to_string(),
+            port_conflict_policy: policy.map(str::to_string),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_port_conflict_policy_parse() {
+        assert_eq!(
+            PortConflictPolicy::parse(None),
+            Ok(PortConflictPolicy::Fail)
+        );
+        assert_eq!(
+            PortConflictPolicy::parse(Some("next")),
+            Ok(PortConflictPolicy::Next)
+        );
+        assert_eq!(
+            PortConflictPolicy::parse(Some("random")),
+            Ok(PortConflictPolicy::Random)
+        );
+        assert!(PortConflictPolicy::parse(Some("closest")).is_err());
+    }
+
+    #[test]
+    fn test_reserved_local_ports_filters_by_address_and_id() {
+        let configs = vec![
+            Config {
+                id: Some(1),
+                local_port: Some(8080),
+                local_address: None,
+                ..Config::default()
+            },
+            Config {
+                id: Some(2),
+                local_port: Some(8081),
+                ..Config::default()
+            },
+            Config {
+                id: Some(3),
+                local_port: Some(8082),
+                local_address: Some("127.0.0.5".to_string()),
+                ..Config::default()
+            },
+        ];
+
+        let reserved = reserved_local_ports(&configs, DEFAULT_LOCAL_ADDRESS, Some(2));
+        assert_eq!(reserved, HashSet::from([8080]));
+    }
+
+    #[test]
+    fn test_resolve_local_port_applies_policy() {
+        let listener = TcpListener::bind((DEFAULT_LOCAL_ADDRESS, 0)).unwrap();
+        let taken = listener.local_addr().unwrap().port();
+
+        let fail = config_with_policy(taken, None);
+        assert_eq!(
+            resolve_local_port(&fail, DEFAULT_LOCAL_ADDRESS, &HashSet::new()),
+            Ok(None)
+        );
+
+        let next = config_with_policy(taken, Some("next"));
+        let reserved = HashSet::from([taken.wrapping_add(1)]);
+        let port = resolve_local_port(&next, DEFAULT_LOCAL_ADDRESS, &reserved)
+            .unwrap()
+            .unwrap();
+        assert_ne!(port, taken);
+        assert!(!reserved.contains(&port));
+
+        let random = config_with_policy(taken, Some("random"));
+        let port = resolve_local_port(&random, DEFAULT_LOCAL_ADDRESS, &HashSet::new())
+            .unwrap()
+            .unwrap();
+        assert_ne!(port, taken);
+    }
+}
@@ -1,3 +1,6 @@
+use std::fs;
+use std::path::PathBuf;
+
 use log::{
     error,
     info,
@@ -14,6 +17,14 @@ use sqlx::{
 
 use crate::db::get_db_pool;
 use crate::models::config_model::Config;
+use crate::utils::config::{
+    CONFIG_COLUMNS,
+    HAS_LOCAL_ENDPOINT_SQL,
+    LOCAL_ADDRESS_SQL,
+    SELECT_CONFIGS,
+    config_value_from_row,
+    write_config_row,
+};
 
 async fn migrate_configs_with_pool(pool: &SqlitePool) -> Result<(), String> {
     info!("Starting configuration migration with provided pool.");
@@ -26,7 +37,7 @@ async fn migrate_configs_with_pool(pool: &SqlitePool) -> Result<(), String> {
         e.to_string()
     })?;
 
-    let rows = sqlx::query("SELECT id, data FROM configs")
+    let rows = sqlx::query(SELECT_CONFIGS)
         .fetch_all(&mut *transaction)
         .await
         .map_err(|e| {
@@ -35,36 +46,25 @@ async fn migrate_configs_with_pool(pool: &SqlitePool) -> Result<(), String> {
         })?;
 
     for row in rows {
-        let id: i64 = row.try_get("id").map_err(|e| {
-            error!("Failed to get id: {e}");
-            e.to_string()
-        })?;
-        let data: String = row.try_get("data").map_err(|e| {
-            error!("Failed to get data: {e}");
-            e.to_string()
-        })?;
-        let config_json: JsonValue = serde_json::from_str(&data).map_err(|e| {
-            error!("Failed to parse JSON: {e}");
-            e.to_string()
+        let (id, config_json) = config_value_from_row(&row).map_err(|e| {
+            error!("Failed to read config: {e}");
+            e
         })?;
         let default_config_json = serde_json::to_value(Config::default()).map_err(|e| {
             error!("Failed to serialize default config: {e}");
             e.to_string()
         })?;
         let merged_config_json = merge_json_values(default_config_json, config_json);
-        let updated_data = serde_json::to_string(&merged_config_json).map_err(|e| {
-            error!("Failed to serialize merged config: {e}");
+        let merged_config: Config = serde_json::from_value(merged_config_json).map_err(|e| {
+            error!("Failed to decode merged config: {e}");
             e.to_string()
         })?;
 
-        sqlx::query("UPDATE configs SET data = ?1 WHERE id = ?2")
-            .bind(updated_data)
-            .bind(id)
-            .execute(&mut *transaction)
+        write_config_row(&mut transaction, id, &merged_config)
             .await
             .map_err(|e| {
                 error!("Failed to update config: {e}");
-                e.to_string()
+                e
             })?;
     }
 
@@ -118,7 +118,249 @@ pub async fn migrate_configs(pool_opt: Option<&SqlitePool>) -> Result<(), String
 
     migrate_schema(&pool).await?;
 
-    migrate_configs_with_pool(&pool).await
+    migrate_configs_with_pool(&pool).await?;
+
+    let mut conn = pool.acquire().await.map_err(|e| {
+        error!("Failed to acquire connection for unique config indexes: {e}");
+        e.to_string()
+    })?;
+    create_unique_config_indexes(&mut conn).await
+}
+
+/// Configs sharing a local address and port, or an alias, one line per
+/// shared value.
+async fn duplicate_config_keys(conn: &mut SqliteConnection) -> Result<Vec<String>, String> {
+    let endpoints = sqlx::query(&format!(
+        "SELECT {LOCAL_ADDRESS_SQL} AS address, local_port, group_concat(id, ', ') AS ids \
+         FROM configs WHERE {HAS_LOCAL_ENDPOINT_SQL} \
+         GROUP BY address, local_port HAVING COUNT(*) > 1"
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to look for configs sharing a local endpoint: {e}");
+        e.to_string()
+    })?;
+
+    let aliases = sqlx::query(
+        "SELECT alias, group_concat(id, ', ') AS ids FROM configs \
+         WHERE alias IS NOT NULL AND alias != '' GROUP BY alias HAVING COUNT(*) > 1",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to look for configs sharing an alias: {e}");
+        e.to_string()
+    })?;
+
+    Ok(endpoints
+        .iter()
+        .map(|row| {
+            format!(
+                "configs {} use {}:{}",
+                row.get::<String, _>("ids"),
+                row.get::<String, _>("address"),
+                row.get::<i64, _>("local_port")
+            )
+        })
+        .chain(aliases.iter().map(|row| {
+            format!(
+                "configs {} use alias '{}'",
+                row.get::<String, _>("ids"),
+                row.get::<String, _>("alias")
+            )
+        }))
+        .collect())
+}
+
+/// Makes local endpoints and aliases unique. Runs on every start rather than
+/// as a schema migration: configs saved before the check may already share
+/// one, and they are reported instead of failing the migration until they are
+/// changed.
+async fn create_unique_config_indexes(conn: &mut SqliteConnection) -> Result<(), String> {
+    let duplicates = duplicate_config_keys(conn).await?;
+    if !duplicates.is_empty() {
+        error!(
+            "Local endpoints and aliases are not enforced as unique until these configs are changed: {}",
+            duplicates.join("; ")
+        );
+        return Ok(());
+    }
+
+    for statement in [
+        format!(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_configs_unique_local_endpoint \
+             ON configs({LOCAL_ADDRESS_SQL}, local_port) WHERE {HAS_LOCAL_ENDPOINT_SQL}"
+        ),
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_configs_unique_alias \
+         ON configs(alias) WHERE alias IS NOT NULL AND alias != ''"
+            .to_string(),
+    ] {
+        sqlx::query(&statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create unique configs index: {e}");
+                e.to_string()
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Ordered up-migrations. Each entry runs once, in its own transaction, and
+/// is recorded in `schema_version`. Append new migrations, never reorder.
+const MIGRATIONS: &[(i64, &str)] = &[
+    (1, "config_state_process_id"),
+    (2, "http_logs_config"),
+    (3, "shortcuts"),
+    (4, "git_sync_subscriptions"),
+    (5, "http_source_cache"),
+    (6, "configs_typed_columns"),
+    (7, "config_groups"),
+    (8, "config_history"),
+    (9, "context_proxies"),
+    (10, "config_state_local_port"),
+    (11, "config_git_sources"),
+];
+
+/// Schema version produced by this build.
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+async fn apply_migration(version: i64, conn: &mut SqliteConnection) -> Result<(), String> {
+    match version {
+        1 => migrate_config_state_process_id(conn).await,
+        2 => migrate_http_logs_config_table(conn).await,
+        3 => migrate_shortcuts_table(conn).await,
+        4 => migrate_git_sync_subscriptions_table(conn).await,
+        5 => migrate_http_source_cache_table(conn).await,
+        6 => migrate_configs_typed_columns(conn).await,
+        7 => migrate_config_groups_tables(conn).await,
+        8 => migrate_config_history_table(conn).await,
+        9 => migrate_context_proxies_table(conn).await,
+        10 => migrate_config_state_local_port(conn).await,
+        11 => migrate_config_git_sources_table(conn).await,
+        _ => Err(format!("Unknown schema migration {version}")),
+    }
+}
+
+async fn create_schema_version_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to create schema_version table: {e}");
+        e.to_string()
+    })?;
+
+    Ok(())
+}
+
+/// Returns the highest applied migration, or 0 for databases created before
+/// schema versioning existed.
+async fn current_schema_version(conn: &mut SqliteConnection) -> Result<i64, String> {
+    let table_exists = sqlx::query(
+        "SELECT COUNT(*) as count FROM sqlite_master WHERE type='table' AND name='schema_version'",
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to check schema_version table: {e}");
+        e.to_string()
+    })?
+    .get::<i64, _>("count")
+        > 0;
+
+    if !table_exists {
+        return Ok(0);
+    }
+
+    sqlx::query("SELECT COALESCE(MAX(version), 0) as version FROM schema_version")
+        .fetch_one(&mut *conn)
+        .await
+        .map(|row| row.get::<i64, _>("version"))
+        .map_err(|e| {
+            error!("Failed to read schema version: {e}");
+            e.to_string()
+        })
+}
+
+fn newer_schema_error(version: i64) -> String {
+    format!(
+        "Database schema version {version} is newer than the version supported by this build \
+         ({SCHEMA_VERSION}). Refusing to open it; upgrade kftray or restore a backup"
+    )
+}
+
+/// Fails when the database was migrated by a newer kftray, so an older build
+/// never reads or writes a schema it does not understand.
+pub async fn ensure_supported_schema(pool: &SqlitePool) -> Result<i64, String> {
+    let mut conn = pool.acquire().await.map_err(|e| {
+        error!("Failed to acquire connection for schema check: {e}");
+        e.to_string()
+    })?;
+
+    let version = current_schema_version(&mut conn).await?;
+    if version > SCHEMA_VERSION {
+        error!("{}", newer_schema_error(version));
+        return Err(newer_schema_error(version));
+    }
+
+    Ok(version)
+}
+
+/// Copies the database next to its file as `<file>.v<version>.bak` using
+/// `VACUUM INTO`, which produces a consistent snapshot of an open database.
+/// In-memory databases have no file and are not backed up.
+async fn backup_database(
+    conn: &mut SqliteConnection, version: i64,
+) -> Result<Option<PathBuf>, String> {
+    let file: String = sqlx::query("SELECT file FROM pragma_database_list WHERE name = 'main'")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| {
+            error!("Failed to resolve database file: {e}");
+            e.to_string()
+        })?
+        .get("file");
+
+    if file.is_empty() {
+        return Ok(None);
+    }
+
+    let backup_path = PathBuf::from(format!("{file}.v{version}.bak"));
+    if backup_path.exists() {
+        fs::remove_file(&backup_path).map_err(|e| {
+            format!(
+                "Failed to replace database backup {}: {e}",
+                backup_path.display()
+            )
+        })?;
+    }
+
+    sqlx::query("VACUUM INTO ?1")
+        .bind(backup_path.to_string_lossy().to_string())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to back up database to {}: {e}",
+                backup_path.display()
+            );
+            format!(
+                "Failed to back up database before migration to {}: {e}",
+                backup_path.display()
+            )
+        })?;
+
+    info!("Backed up database to {}", backup_path.display());
+    Ok(Some(backup_path))
 }
 
 async fn migrate_schema(pool: &SqlitePool) -> Result<(), String> {
@@ -128,6 +370,62 @@ async fn migrate_schema(pool: &SqlitePool) -> Result<(), String> {
         e.to_string()
     })?;
 
+    let current = current_schema_version(&mut conn).await?;
+    if current > SCHEMA_VERSION {
+        error!("{}", newer_schema_error(current));
+        return Err(newer_schema_error(current));
+    }
+
+    let pending: Vec<_> = MIGRATIONS
+        .iter()
+        .filter(|(version, _)| *version > current)
+        .collect();
+
+    if pending.is_empty() {
+        info!("Database schema is up to date at version {current}");
+        return Ok(());
+    }
+
+    backup_database(&mut conn, current).await?;
+    create_schema_version_table(&mut conn).await?;
+
+    for (version, name) in pending {
+        info!("Applying schema migration {version} ({name})");
+        let mut transaction = conn.begin().await.map_err(|e| {
+            error!("Failed to begin transaction for migration {version}: {e}");
+            e.to_string()
+        })?;
+
+        // Another process may have applied it since the version was read.
+        if current_schema_version(&mut transaction).await? >= *version {
+            continue;
+        }
+
+        apply_migration(*version, &mut transaction)
+            .await
+            .map_err(|e| format!("Schema migration {version} ({name}) failed: {e}"))?;
+
+        sqlx::query("INSERT INTO schema_version (version, name) VALUES (?1, ?2)")
+            .bind(version)
+            .bind(name)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| {
+                error!("Failed to record schema migration {version}: {e}");
+                e.to_string()
+            })?;
+
+        transaction.commit().await.map_err(|e| {
+            error!("Failed to commit schema migration {version}: {e}");
+            e.to_string()
+        })?;
+    }
+
+    info!("Database schema migrated from version {current} to {SCHEMA_VERSION}");
+    Ok(())
+}
+
+async fn migrate_config_state_process_id(conn: &mut SqliteConnection) -> Result<(), String> {
     let column_exists = sqlx::query(
         "SELECT COUNT(*) as count FROM pragma_table_info('config_state') WHERE name = 'process_id'",
     )
@@ -154,10 +452,6 @@ async fn migrate_schema(pool: &SqlitePool) -> Result<(), String> {
         info!("process_id column already exists, skipping migration");
     }
 
-    migrate_http_logs_config_table(&mut conn).await?;
-
-    migrate_shortcuts_table(&mut conn).await?;
-
     Ok(())
 }
 
@@ -363,6 +657,357 @@ async fn migrate_shortcuts_table(conn: &mut sqlx::SqliteConnection) -> Result<()
     Ok(())
 }
 
+async fn migrate_git_sync_subscriptions_table(
+    conn: &mut sqlx::SqliteConnection,
+) -> Result<(), String> {
+    info!("Running git sync subscriptions table migration");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS git_sync_subscriptions (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            repo_url TEXT NOT NULL,
+            config_path TEXT NOT NULL,
+            git_ref TEXT,
+            credentials_ref TEXT,
+            use_system_credentials BOOLEAN NOT NULL DEFAULT false,
+            insecure_skip_verify BOOLEAN NOT NULL DEFAULT false,
+            ca_bundle_path TEXT,
+            interval_secs INTEGER NOT NULL DEFAULT 0,
+            enabled BOOLEAN NOT NULL DEFAULT true,
+            last_commit_sha TEXT,
+            applied_keys TEXT NOT NULL DEFAULT '[]',
+            last_checked_at INTEGER,
+            last_synced_at INTEGER,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to create git_sync_subscriptions table: {e}");
+        e.to_string()
+    })?;
+
+    let provider_exists = sqlx::query(
+        "SELECT COUNT(*) as count FROM pragma_table_info('git_sync_subscriptions') WHERE name = 'provider'",
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to check provider column: {e}");
+        e.to_string()
+    })?
+    .get::<i64, _>("count")
+        > 0;
+
+    if !provider_exists {
+        info!("Adding provider column to git_sync_subscriptions table");
+        sqlx::query("ALTER TABLE git_sync_subscriptions ADD COLUMN provider TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to add provider column: {e}");
+                e.to_string()
+            })?;
+    }
+
+    Ok(())
+}
+
+async fn migrate_http_source_cache_table(conn: &mut sqlx::SqliteConnection) -> Result<(), String> {
+    info!("Running HTTP source cache table migration");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS http_source_cache (
+            url TEXT PRIMARY KEY,
+            etag TEXT,
+            last_modified TEXT,
+            content TEXT NOT NULL,
+            fetched_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to create http_source_cache table: {e}");
+        e.to_string()
+    })?;
+
+    Ok(())
+}
+
+/// Moves the `Config` fields listed in `CONFIG_COLUMNS` out of the `data`
+/// JSON blob into typed, indexed columns. `data` keeps the remaining fields.
+async fn migrate_configs_typed_columns(conn: &mut SqliteConnection) -> Result<(), String> {
+    info!("Running configs typed columns migration");
+
+    let columns = [
+        ("service", "TEXT"),
+        ("namespace", "TEXT NOT NULL DEFAULT ''"),
+        (
+            "local_port",
+            "INTEGER CHECK (local_port IS NULL OR local_port BETWEEN 0 AND 65535)",
+        ),
+        (
+            "remote_port",
+            "INTEGER CHECK (remote_port IS NULL OR remote_port BETWEEN 0 AND 65535)",
+        ),
+        ("context", "TEXT"),
+        ("workload_type", "TEXT"),
+        ("protocol", "TEXT NOT NULL DEFAULT ''"),
+        ("remote_address", "TEXT"),
+        ("local_address", "TEXT"),
+        ("auto_loopback_address", "BOOLEAN NOT NULL DEFAULT false"),
+        ("alias", "TEXT"),
+        ("domain_enabled", "BOOLEAN"),
+        ("kubeconfig", "TEXT"),
+        ("target", "TEXT"),
+    ];
+    debug_assert_eq!(columns.len(), CONFIG_COLUMNS.len());
+
+    for (name, definition) in columns {
+        let column_exists = sqlx::query(
+            "SELECT COUNT(*) as count FROM pragma_table_info('configs') WHERE name = ?1",
+        )
+        .bind(name)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| {
+            error!("Failed to check configs.{name} column: {e}");
+            e.to_string()
+        })?
+        .get::<i64, _>("count")
+            > 0;
+
+        if !column_exists {
+            sqlx::query(&format!(
+                "ALTER TABLE configs ADD COLUMN {name} {definition}"
+            ))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to add configs.{name} column: {e}");
+                e.to_string()
+            })?;
+        }
+    }
+
+    // Columns keep their value when `data` no longer holds the field, so the
+    // backfill can run again safely. Booleans were accepted as JSON strings
+    // ("true"/"false") on import.
+    let assignments = CONFIG_COLUMNS
+        .iter()
+        .map(|name| match *name {
+            "auto_loopback_address" | "domain_enabled" => format!(
+                "{name} = COALESCE(CASE json_type(data, '$.{name}') \
+                 WHEN 'true' THEN 1 WHEN 'false' THEN 0 \
+                 WHEN 'text' THEN json_extract(data, '$.{name}') = 'true' END, {name})"
+            ),
+            _ => format!("{name} = COALESCE(json_extract(data, '$.{name}'), {name})"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let removed_paths = std::iter::once("'$.id'".to_string())
+        .chain(CONFIG_COLUMNS.iter().map(|name| format!("'$.{name}'")))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    sqlx::query(&format!(
+        "UPDATE configs SET {assignments}, data = json_remove(data, {removed_paths}) \
+         WHERE json_valid(data) AND json_type(data) = 'object'"
+    ))
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to move config fields into columns: {e}");
+        e.to_string()
+    })?;
+
+    for statement in [
+        "CREATE INDEX IF NOT EXISTS idx_configs_alias ON configs(alias)",
+        "CREATE INDEX IF NOT EXISTS idx_configs_context_namespace ON configs(context, namespace)",
+        "CREATE INDEX IF NOT EXISTS idx_configs_namespace ON configs(namespace)",
+        "CREATE INDEX IF NOT EXISTS idx_configs_local_endpoint ON configs(local_port, local_address)",
+    ] {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create configs index: {e}");
+                e.to_string()
+            })?;
+    }
+
+    info!("Successfully moved config fields into typed columns");
+    Ok(())
+}
+
+async fn migrate_config_groups_tables(conn: &mut SqliteConnection) -> Result<(), String> {
+    info!("Running config groups tables migration");
+
+    for statement in [
+        "CREATE TABLE IF NOT EXISTS config_groups (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        "CREATE TABLE IF NOT EXISTS config_group_members (
+            group_id INTEGER NOT NULL,
+            config_id INTEGER NOT NULL,
+            PRIMARY KEY (group_id, config_id),
+            FOREIGN KEY(group_id) REFERENCES config_groups(id) ON DELETE CASCADE,
+            FOREIGN KEY(config_id) REFERENCES configs(id) ON DELETE CASCADE
+        )",
+        "CREATE INDEX IF NOT EXISTS idx_config_group_members_config_id
+         ON config_group_members(config_id)",
+        // Foreign keys are only enforced on connections that enable them.
+        "CREATE TRIGGER IF NOT EXISTS after_delete_config_groups
+         AFTER DELETE ON configs
+         FOR EACH ROW
+         BEGIN
+             DELETE FROM config_group_members WHERE config_id = OLD.id;
+         END;",
+    ] {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create config groups schema: {e}");
+                e.to_string()
+            })?;
+    }
+
+    Ok(())
+}
+
+async fn migrate_config_history_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    info!("Running config history table migration");
+
+    for statement in [
+        "CREATE TABLE IF NOT EXISTS config_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            config_id INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            source TEXT NOT NULL,
+            before TEXT,
+            after TEXT,
+            changed_at INTEGER NOT NULL
+        )",
+        "CREATE INDEX IF NOT EXISTS idx_config_history_config_id
+         ON config_history(config_id)",
+        // Revisions outlive their config and are never rewritten.
+        "CREATE TRIGGER IF NOT EXISTS config_history_no_update
+         BEFORE UPDATE ON config_history
+         BEGIN
+             SELECT RAISE(ABORT, 'config_history is append-only');
+         END;",
+        "CREATE TRIGGER IF NOT EXISTS config_history_no_delete
+         BEFORE DELETE ON config_history
+         BEGIN
+             SELECT RAISE(ABORT, 'config_history is append-only');
+         END;",
+    ] {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create config history schema: {e}");
+                e.to_string()
+            })?;
+    }
+
+    Ok(())
+}
+
+async fn migrate_context_proxies_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    info!("Running context proxies table migration");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS context_proxies (
+            context TEXT PRIMARY KEY,
+            proxy_url TEXT NOT NULL,
+            username TEXT,
+            credentials_ref TEXT,
+            no_proxy TEXT NOT NULL DEFAULT '[]',
+            enabled BOOLEAN NOT NULL DEFAULT true,
+            updated_at INTEGER
+        )",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to create context_proxies table: {e}");
+        e.to_string()
+    })?;
+
+    Ok(())
+}
+
+/// Adds the local port a running forward moved to when its configured port
+/// was taken. The config keeps the port it was saved with.
+async fn migrate_config_state_local_port(conn: &mut SqliteConnection) -> Result<(), String> {
+    let column_exists = sqlx::query(
+        "SELECT COUNT(*) as count FROM pragma_table_info('config_state') WHERE name = 'local_port'",
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| {
+        error!("Failed to check config_state.local_port column: {e}");
+        e.to_string()
+    })?
+    .get::<i64, _>("count")
+        > 0;
+
+    if !column_exists {
+        info!("Adding local_port column to config_state table");
+        sqlx::query("ALTER TABLE config_state ADD COLUMN local_port INTEGER")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to add config_state.local_port column: {e}");
+                e.to_string()
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Records the repository, ref and commit each config was last imported or
+/// synced from.
+async fn migrate_config_git_sources_table(conn: &mut SqliteConnection) -> Result<(), String> {
+    info!("Running config git sources table migration");
+
+    for statement in [
+        "CREATE TABLE IF NOT EXISTS config_git_sources (
+            config_id INTEGER PRIMARY KEY,
+            repo_url TEXT NOT NULL,
+            config_path TEXT NOT NULL,
+            git_ref TEXT,
+            commit_sha TEXT NOT NULL,
+            imported_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(config_id) REFERENCES configs(id) ON DELETE CASCADE
+        )",
+        // Foreign keys are only enforced on connections that enable them.
+        "CREATE TRIGGER IF NOT EXISTS after_delete_config_git_sources
+         AFTER DELETE ON configs
+         FOR EACH ROW
+         BEGIN
+             DELETE FROM config_git_sources WHERE config_id = OLD.id;
+         END;",
+    ] {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create config git sources schema: {e}");
+                e.to_string()
+            })?;
+    }
+
+    Ok(())
+}
+
 async fn drop_triggers(transaction: &mut Transaction<'_, Sqlite>) -> Result<(), sqlx::Error> {
     info!("Dropping triggers.");
     sqlx::query("DROP TRIGGER IF EXISTS after_insert_config;")
@@ -479,6 +1124,7 @@ mod tests {
     use super::*;
     use crate::db::create_db_table;
     use crate::models::config_model::Config;
+    use crate::test_utils::valid_config;
     use crate::utils::config::{
         insert_config_with_pool,
         read_configs_with_pool,
@@ -588,7 +1234,7 @@ mod tests {
 
     #[test]
     fn test_merge_json_values_auto_loopback_address_default() {
-        let default_config_json = serde_json::to_value(Config::default()).unwrap();
+        let default_config_json = serde_json::to_value(valid_config()).unwrap();
         let old_config_without_auto_loopback = json!({
             "service": "old-service",
             "namespace": "old-namespace"
@@ -662,7 +1308,7 @@ mod tests {
 
         let new_config_full_data = Config {
             service: Some("new-full".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         insert_config_with_pool(new_config_full_data.clone(), &pool)
             .await
@@ -714,7 +1360,7 @@ mod tests {
             .find(|c| c.id == Some(new_config_id))
             .unwrap();
         assert_eq!(migrated_new_config.service, new_config_full_data.service);
-        assert_eq!(migrated_new_config.protocol, Config::default().protocol);
+        assert_eq!(migrated_new_config.protocol, valid_config().protocol);
 
         let states_after = read_config_states_with_pool(&pool).await.unwrap();
         assert_eq!(states_after.len(), 2, "Expected 2 states after migration");
@@ -826,4 +1472,180 @@ mod tests {
         assert!(check_trigger_exists(&pool, "after_delete_config").await);
         assert!(check_trigger_exists(&pool, "after_insert_config_http_logs").await);
     }
+
+    #[tokio::test]
+    async fn test_schema_migrations_are_versioned() {
+        let pool = setup_test_db().await;
+
+        let versions: Vec<i64> = sqlx::query("SELECT version FROM schema_version ORDER BY version")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get("version"))
+            .collect();
+        let expected: Vec<i64> = (1..=SCHEMA_VERSION).collect();
+        assert_eq!(versions, expected);
+
+        migrate_configs(Some(&pool)).await.unwrap();
+        let count = sqlx::query("SELECT COUNT(*) as count FROM schema_version")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+            .get::<i64, _>("count");
+        assert_eq!(count, SCHEMA_VERSION);
+        assert_eq!(
+            ensure_supported_schema(&pool).await.unwrap(),
+            SCHEMA_VERSION
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refuses_newer_schema_version() {
+        let pool = setup_test_db().await;
+
+        sqlx::query("INSERT INTO schema_version (version, name) VALUES (?1, 'future')")
+            .bind(SCHEMA_VERSION + 1)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let err = ensure_supported_schema(&pool).await.unwrap_err();
+        assert!(err.contains("newer"), "unexpected error: {err}");
+        assert!(migrate_configs(Some(&pool)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_database_is_backed_up_before_migrating() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("kftray.db");
+        let pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", db_path.display()))
+            .await
+            .unwrap();
+        create_db_table(&pool).await.unwrap();
+        sqlx::query("INSERT INTO configs (data) VALUES (?1)")
+            .bind("{\"service\": \"legacy\"}")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        migrate_configs(Some(&pool)).await.unwrap();
+
+        let backup_path = dir.path().join("kftray.db.v0.bak");
+        assert!(backup_path.exists());
+
+        let backup = SqlitePool::connect(&format!("sqlite://{}", backup_path.display()))
+            .await
+            .unwrap();
+        let count = sqlx::query("SELECT COUNT(*) as count FROM configs")
+            .fetch_one(&backup)
+            .await
+            .unwrap()
+            .get::<i64, _>("count");
+        assert_eq!(count, 1);
+        let versioned = sqlx::query(
+            "SELECT COUNT(*) as count FROM sqlite_master WHERE type='table' AND name='schema_version'",
+        )
+        .fetch_one(&backup)
+        .await
+        .unwrap()
+        .get::<i64, _>("count");
+        assert_eq!(versioned, 0);
+        backup.close().await;
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_migrate_configs_typed_columns_moves_legacy_data() {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("Failed to connect");
+        sqlx::query("CREATE TABLE configs (id INTEGER PRIMARY KEY, data TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO configs (data) VALUES (?1)")
+            .bind(
+                json!({
+                    "service": "legacy",
+                    "namespace": "legacy-ns",
+                    "local_port": 8080,
+                    "domain_enabled": "true",
+                    "http_logs_enabled": true
+                })
+                .to_string(),
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        migrate_configs_typed_columns(&mut conn).await.unwrap();
+        migrate_configs_typed_columns(&mut conn).await.unwrap();
+
+        let row =
+            sqlx::query("SELECT service, namespace, local_port, domain_enabled, data FROM configs")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(row.get::<String, _>("service"), "legacy");
+        assert_eq!(row.get::<String, _>("namespace"), "legacy-ns");
+        assert_eq!(row.get::<i64, _>("local_port"), 8080);
+        assert!(row.get::<bool, _>("domain_enabled"));
+
+        let extras: JsonValue = serde_json::from_str(&row.get::<String, _>("data")).unwrap();
+        assert_eq!(extras, json!({ "http_logs_enabled": true }));
+    }
+
+    async fn index_exists(conn: &mut SqliteConnection, name: &str) -> bool {
+        sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'index' AND name = ?1")
+            .bind(name)
+            .fetch_optional(&mut *conn)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    #[tokio::test]
+    async fn test_unique_config_indexes_wait_for_duplicates() {
+        let pool = setup_test_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+        assert!(index_exists(&mut conn, "idx_configs_unique_local_endpoint").await);
+        assert!(index_exists(&mut conn, "idx_configs_unique_alias").await);
+
+        for statement in [
+            "DROP INDEX idx_configs_unique_local_endpoint",
+            "DROP INDEX idx_configs_unique_alias",
+            "INSERT INTO configs (id, data, local_port, local_address, alias) \
+             VALUES (1, '{}', 8080, NULL, 'db'), (2, '{}', 8080, '127.0.0.1', 'db'), \
+             (3, '{}', 8080, '127.0.0.2', 'web')",
+        ] {
+            sqlx::query(statement).execute(&mut *conn).await.unwrap();
+        }
+
+        assert_eq!(
+            duplicate_config_keys(&mut conn).await.unwrap(),
+            vec![
+                "configs 1, 2 use 127.0.0.1:8080".to_string(),
+                "configs 1, 2 use alias 'db'".to_string(),
+            ]
+        );
+        create_unique_config_indexes(&mut conn).await.unwrap();
+        assert!(!index_exists(&mut conn, "idx_configs_unique_local_endpoint").await);
+        assert!(!index_exists(&mut conn, "idx_configs_unique_alias").await);
+
+        sqlx::query("UPDATE configs SET local_port = 8081, alias = 'db-2' WHERE id = 2")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        assert!(duplicate_config_keys(&mut conn).await.unwrap().is_empty());
+        create_unique_config_indexes(&mut conn).await.unwrap();
+        assert!(index_exists(&mut conn, "idx_configs_unique_local_endpoint").await);
+        assert!(index_exists(&mut conn, "idx_configs_unique_alias").await);
+
+        let duplicate = sqlx::query("UPDATE configs SET local_address = ' ' WHERE id = 3")
+            .execute(&mut *conn)
+            .await;
+        assert!(duplicate.is_err());
+    }
 }
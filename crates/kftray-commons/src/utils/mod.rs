@@ -1,12 +1,24 @@
 pub mod config;
 pub mod config_dir;
+pub mod config_discovery;
+pub mod config_format;
+pub mod config_group;
+pub mod config_history;
+pub mod config_schema;
 pub mod config_state;
+pub mod config_template;
+pub mod config_validation;
+pub mod connection_drain;
+pub mod context_proxy;
 pub mod db;
 pub mod db_mode;
 pub mod error;
+pub mod git_provider;
+pub mod git_sync;
 pub mod github;
 pub mod hostsfile;
 pub mod http_logs_config;
+pub mod local_port;
 pub mod manifests;
 pub mod migration;
 pub mod settings;
@@ -33,6 +33,10 @@ use crate::expose::{
     models::ExposeResources,
     templates,
 };
+use crate::kube::reconcile::{
+    apply_owner_metadata,
+    apply_owner_metadata_to_deployment,
+};
 
 /// Extracts the first part of a domain name (before the first dot) to use as a
 /// DNS-1035 compliant name For example: "testelocal.ideia.totvs.io" ->
@@ -180,8 +184,9 @@ async fn create_deployment(
     let template = templates::load_deployment_template()?;
     let rendered = templates::render_template(&template, &values);
 
-    let deployment: Deployment = serde_json::from_str(&rendered)
+    let mut deployment: Deployment = serde_json::from_str(&rendered)
         .map_err(|e| format!("Failed to parse deployment: {}", e))?;
+    apply_owner_metadata_to_deployment(&mut deployment);
 
     deployments
         .create(&PostParams::default(), &deployment)
@@ -243,8 +248,9 @@ async fn create_service(
     let template = templates::load_service_template()?;
     let rendered = templates::render_template(&template, &values);
 
-    let service: Service =
+    let mut service: Service =
         serde_json::from_str(&rendered).map_err(|e| format!("Failed to parse service: {}", e))?;
+    apply_owner_metadata(&mut service.metadata);
 
     services
         .create(&PostParams::default(), &service)
@@ -295,8 +301,9 @@ async fn create_ingress(
     let template = templates::load_ingress_template()?;
     let rendered = templates::render_template(&template, &values);
 
-    let ingress: Ingress =
+    let mut ingress: Ingress =
         serde_json::from_str(&rendered).map_err(|e| format!("Failed to parse ingress: {}", e))?;
+    apply_owner_metadata(&mut ingress.metadata);
 
     ingresses
         .create(&PostParams::default(), &ingress)
@@ -15,6 +15,10 @@ use log::{
     info,
 };
 
+use crate::kube::reconcile::{
+    start_heartbeat,
+    stop_heartbeat,
+};
 use crate::kube::shared_client::{
     SHARED_CLIENT_MANAGER,
     ServiceClientKey,
@@ -40,8 +44,6 @@ async fn start_single_expose(config: Config, mode: DatabaseMode) -> Result<Custo
     use self::kubernetes::create_expose_resources;
     use self::websocket_client::WebSocketTunnelClient;
     use crate::kube::models::{
-        NameSpace,
-        Port,
         PortForward,
         Target,
         TargetSelector,
@@ -50,8 +52,7 @@ async fn start_single_expose(config: Config, mode: DatabaseMode) -> Result<Custo
 
     let config_id = config.id.ok_or("Config has no ID")?;
 
-    let client_key =
-        ServiceClientKey::new(config.context.clone(), config.kubeconfig.clone(), config_id);
+    let client_key = ServiceClientKey::for_config(&config);
     let client = SHARED_CLIENT_MANAGER
         .get_client(client_key)
         .await
@@ -66,20 +67,24 @@ async fn start_single_expose(config: Config, mode: DatabaseMode) -> Result<Custo
         resources.deployment_name, resources.service_name, resources.pod_name
     );
 
+    start_heartbeat(
+        client.clone(),
+        config.namespace.clone(),
+        config_id.to_string(),
+    );
+
     let label_selector = format!("app=kftray-expose,config_id={}", config_id);
-    let target = Target {
-        selector: TargetSelector::PodLabel(label_selector),
-        port: Port::Number(9999),
-        namespace: NameSpace(Some(config.namespace.clone())),
-    };
+    let target = Target::new(
+        TargetSelector::PodLabel(label_selector),
+        9999,
+        config.namespace.clone(),
+    );
 
     let port_forward = PortForward::new(
         target,
         Some(0),
         None,
-        config.context.clone(),
-        config.kubeconfig.clone(),
-        config_id,
+        ServiceClientKey::for_config(&config),
         "expose".to_string(),
     )
     .await
@@ -135,6 +140,7 @@ async fn start_single_expose(config: Config, mode: DatabaseMode) -> Result<Custo
         config_id,
         is_running: true,
         process_id: Some(std::process::id()),
+        local_port: None,
     };
     update_config_state_with_mode(&config_state, mode).await?;
 
@@ -164,6 +170,8 @@ pub async fn stop_expose(
 
     info!("Stopping expose for config {}", config_id);
 
+    stop_heartbeat(&config_id.to_string());
+
     let config = get_config_with_mode(config_id, mode).await?;
 
     {
@@ -174,8 +182,7 @@ pub async fn stop_expose(
         }
     }
 
-    let client_key =
-        ServiceClientKey::new(config.context.clone(), config.kubeconfig.clone(), config_id);
+    let client_key = ServiceClientKey::for_config(&config);
     let client = SHARED_CLIENT_MANAGER
         .get_client(client_key)
         .await
@@ -189,6 +196,7 @@ pub async fn stop_expose(
         config_id,
         is_running: false,
         process_id: None,
+        local_port: None,
     };
     update_config_state_with_mode(&config_state, mode).await?;
 
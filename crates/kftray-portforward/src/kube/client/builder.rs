@@ -6,6 +6,7 @@ use kube::config::Kubeconfig;
 use log::info;
 
 use super::config::{
+    Impersonation,
     create_config_with_context,
     get_kubeconfig_paths_from_option,
     merge_kubeconfigs,
@@ -14,6 +15,14 @@ use super::connection::create_client_with_config;
 
 pub async fn create_client_with_specific_context(
     kubeconfig: Option<String>, context_name: Option<&str>,
+) -> Result<(Option<Client>, Option<Kubeconfig>, Vec<String>)> {
+    create_client_with_impersonation(kubeconfig, context_name, &Impersonation::default()).await
+}
+
+/// Same as [`create_client_with_specific_context`], sending every request
+/// as the impersonated user and groups.
+pub async fn create_client_with_impersonation(
+    kubeconfig: Option<String>, context_name: Option<&str>, impersonation: &Impersonation,
 ) -> Result<(Option<Client>, Option<Kubeconfig>, Vec<String>)> {
     {
         unsafe { env::remove_var("PYTHONHOME") };
@@ -25,7 +34,7 @@ pub async fn create_client_with_specific_context(
     let (merged_kubeconfig, all_contexts, mut errors) = merge_kubeconfigs(&kubeconfig_paths)?;
 
     if let Some(context_name) = context_name {
-        match create_config_with_context(&merged_kubeconfig, context_name).await {
+        match create_config_with_context(&merged_kubeconfig, context_name, impersonation).await {
             Ok(config) => match create_client_with_config(&config).await {
                 Some(client) => {
                     info!("Created new client for context: {context_name}");
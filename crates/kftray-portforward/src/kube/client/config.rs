@@ -4,7 +4,11 @@ use anyhow::{
     Context,
     Result,
 };
+use hyper::Uri;
+use keyring::Entry;
 use kftray_commons::config_dir::get_kubeconfig_paths;
+use kftray_commons::models::context_proxy_model::ContextProxy;
+use kftray_commons::utils::context_proxy::get_context_proxy;
 use kube::config::{
     Config,
     KubeConfigOptions,
@@ -13,6 +17,7 @@ use kube::config::{
 use log::{
     error,
     info,
+    warn,
 };
 use openssl::base64::{
     decode_block,
@@ -20,11 +25,18 @@ use openssl::base64::{
 };
 use secrecy::ExposeSecret;
 
+use super::exec_auth::resolve_exec_credential;
+use super::proxy::{
+    redact_proxy_url,
+    with_proxy_credentials,
+};
 use super::utils::{
     convert_pkcs8_to_pkcs1,
     is_pkcs8_key,
 };
 
+const KEYRING_SERVICE: &str = "kftray";
+
 pub trait ConfigExtClone {
     fn clone_with_invalid_certs(&self, accept_invalid_certs: bool) -> Self;
 }
@@ -37,6 +49,85 @@ impl ConfigExtClone for Config {
     }
 }
 
+/// Identity to impersonate on API requests, taken from a config's
+/// `impersonate_user` and `impersonate_groups`.
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct Impersonation {
+    pub user: Option<String>,
+    pub groups: Vec<String>,
+}
+
+impl Impersonation {
+    pub fn of(config: &kftray_commons::models::config_model::Config) -> Self {
+        Self {
+            user: config
+                .impersonate_user
+                .as_deref()
+                .map(str::trim)
+                .filter(|user| !user.is_empty())
+                .map(str::to_string),
+            groups: config
+                .impersonate_groups
+                .iter()
+                .map(|group| group.trim().to_string())
+                .filter(|group| !group.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.user.is_none() && self.groups.is_empty()
+    }
+
+    fn apply(&self, config: &mut Config) {
+        if let Some(user) = &self.user {
+            config.auth_info.impersonate = Some(user.clone());
+        }
+        if !self.groups.is_empty() {
+            config.auth_info.impersonate_groups = Some(self.groups.clone());
+        }
+    }
+}
+
+/// Points `config` at the proxy kftray manages for its context, replacing any
+/// `proxy-url` from the kubeconfig. When the API server matches a `no_proxy`
+/// rule the connection is made directly.
+fn apply_context_proxy(config: &mut Config, proxy: &ContextProxy) -> Result<()> {
+    let host = config.cluster_url.host().unwrap_or_default();
+    if proxy.bypasses(host) {
+        info!(
+            "API server {host} matches no_proxy for context {}",
+            proxy.context
+        );
+        config.proxy_url = None;
+        return Ok(());
+    }
+
+    let mut proxy_url: Uri = proxy
+        .proxy_url
+        .parse()
+        .with_context(|| format!("Invalid proxy URL for context {}", proxy.context))?;
+
+    if let Some(username) = proxy.username.as_deref().filter(|u| !u.is_empty()) {
+        let password = match proxy.credentials_ref.as_deref().filter(|r| !r.is_empty()) {
+            Some(name) => Entry::new(KEYRING_SERVICE, name)
+                .and_then(|entry| entry.get_password())
+                .with_context(|| format!("Failed to read proxy credential '{name}'"))?,
+            None => String::new(),
+        };
+        proxy_url = with_proxy_credentials(&proxy_url, username, &password)
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    info!(
+        "Using proxy {} for context {}",
+        redact_proxy_url(&proxy_url),
+        proxy.context
+    );
+    config.proxy_url = Some(proxy_url);
+    Ok(())
+}
+
 pub fn get_kubeconfig_paths_from_option(kubeconfig: Option<String>) -> Result<Vec<PathBuf>> {
     match kubeconfig {
         Some(path) if path == "default" => {
@@ -88,7 +179,21 @@ pub fn merge_kubeconfigs(paths: &[PathBuf]) -> Result<(Kubeconfig, Vec<String>,
 }
 
 pub async fn create_config_with_context(
-    kubeconfig: &Kubeconfig, context_name: &str,
+    kubeconfig: &Kubeconfig, context_name: &str, impersonation: &Impersonation,
+) -> Result<Config> {
+    let mut config = create_config_without_exec(kubeconfig, context_name, impersonation).await?;
+
+    resolve_exec_credential(&mut config)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    Ok(config)
+}
+
+/// Builds the configuration of a context without running its exec plugin,
+/// so callers can report a failing plugin separately.
+pub(super) async fn create_config_without_exec(
+    kubeconfig: &Kubeconfig, context_name: &str, impersonation: &Impersonation,
 ) -> Result<Config> {
     info!("Creating configuration for context: {context_name}");
     let mut kubeconfig = kubeconfig.clone();
@@ -114,8 +219,9 @@ pub async fn create_config_with_context(
     } else {
         Some(context_name.to_owned())
     };
+    let resolved_context = context_to_use.clone().unwrap_or_default();
 
-    Config::from_custom_kubeconfig(
+    let mut config = Config::from_custom_kubeconfig(
         kubeconfig,
         &KubeConfigOptions {
             context: context_to_use,
@@ -123,7 +229,20 @@ pub async fn create_config_with_context(
         },
     )
     .await
-    .context("Failed to create configuration from kubeconfig")
+    .context("Failed to create configuration from kubeconfig")?;
+
+    match get_context_proxy(&resolved_context).await {
+        Ok(Some(proxy)) if proxy.enabled => apply_context_proxy(&mut config, &proxy)?,
+        Ok(_) => {}
+        Err(e) => warn!("Failed to load proxy settings for context {resolved_context}: {e}"),
+    }
+
+    if !impersonation.is_empty() {
+        info!("Impersonating {impersonation:?} for context: {context_name}");
+        impersonation.apply(&mut config);
+    }
+
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -271,7 +390,8 @@ mod tests {
         };
         kubeconfig.contexts = vec![named_context];
 
-        let result = create_config_with_context(&kubeconfig, context_name).await;
+        let result =
+            create_config_with_context(&kubeconfig, context_name, &Impersonation::default()).await;
         assert!(result.is_err());
     }
 
@@ -325,9 +445,10 @@ users:
         std::fs::write(&kubeconfig_path, kubeconfig_content).unwrap();
 
         let kubeconfig = Kubeconfig::read_from(&kubeconfig_path).unwrap();
-        let config = create_config_with_context(&kubeconfig, "test-context")
-            .await
-            .unwrap();
+        let config =
+            create_config_with_context(&kubeconfig, "test-context", &Impersonation::default())
+                .await
+                .unwrap();
 
         assert!(config.accept_invalid_certs);
     }
@@ -362,9 +483,10 @@ users:
         std::fs::write(&kubeconfig_path, kubeconfig_content).unwrap();
 
         let kubeconfig = Kubeconfig::read_from(&kubeconfig_path).unwrap();
-        let config = create_config_with_context(&kubeconfig, "test-context")
-            .await
-            .unwrap();
+        let config =
+            create_config_with_context(&kubeconfig, "test-context", &Impersonation::default())
+                .await
+                .unwrap();
 
         assert!(!config.accept_invalid_certs);
     }
@@ -398,11 +520,58 @@ users:
         std::fs::write(&kubeconfig_path, kubeconfig_content).unwrap();
 
         let kubeconfig = Kubeconfig::read_from(&kubeconfig_path).unwrap();
-        let config = create_config_with_context(&kubeconfig, "test-context")
+        let config =
+            create_config_with_context(&kubeconfig, "test-context", &Impersonation::default())
+                .await
+                .unwrap();
+
+        assert!(!config.accept_invalid_certs);
+    }
+
+    #[tokio::test]
+    async fn test_create_config_with_impersonation() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let kubeconfig_path = temp_dir.path().join("kubeconfig");
+
+        let kubeconfig_content = r#"
+apiVersion: v1
+kind: Config
+clusters:
+- name: test-cluster
+  cluster:
+    server: https://test-server.com
+contexts:
+- name: test-context
+  context:
+    cluster: test-cluster
+    user: test-user
+current-context: test-context
+users:
+- name: test-user
+  user:
+    token: test-token
+"#;
+
+        std::fs::write(&kubeconfig_path, kubeconfig_content).unwrap();
+
+        let kubeconfig = Kubeconfig::read_from(&kubeconfig_path).unwrap();
+        let impersonation = Impersonation::of(&kftray_commons::models::config_model::Config {
+            impersonate_user: Some(" deployer ".to_string()),
+            impersonate_groups: vec!["developers".to_string(), " ".to_string()],
+            ..Default::default()
+        });
+
+        let config = create_config_with_context(&kubeconfig, "test-context", &impersonation)
             .await
             .unwrap();
 
-        assert!(!config.accept_invalid_certs);
+        assert_eq!(config.auth_info.impersonate.as_deref(), Some("deployer"));
+        assert_eq!(
+            config.auth_info.impersonate_groups,
+            Some(vec!["developers".to_string()])
+        );
     }
 
     #[tokio::test]
@@ -476,4 +645,29 @@ users:
             Some(false)
         );
     }
+
+    #[test]
+    fn test_apply_context_proxy() {
+        let mut config = Config::new("https://api.prod.corp.example.com:6443".parse().unwrap());
+        let mut proxy = ContextProxy {
+            context: "prod".to_string(),
+            proxy_url: "socks5h://127.0.0.1:1080".to_string(),
+            enabled: true,
+            ..ContextProxy::default()
+        };
+
+        apply_context_proxy(&mut config, &proxy).unwrap();
+        assert_eq!(
+            config.proxy_url.as_ref().map(ToString::to_string),
+            Some("socks5h://127.0.0.1:1080/".to_string())
+        );
+
+        proxy.no_proxy = vec![".corp.example.com".to_string()];
+        apply_context_proxy(&mut config, &proxy).unwrap();
+        assert!(config.proxy_url.is_none());
+
+        proxy.no_proxy.clear();
+        proxy.proxy_url = "not a url".to_string();
+        assert!(apply_context_proxy(&mut config, &proxy).is_err());
+    }
 }
@@ -26,6 +26,10 @@ use super::error::{
     KubeClientError,
     KubeResult,
 };
+use super::exec_auth::{
+    ExecAuthLayer,
+    ExecPlugin,
+};
 use super::proxy::{
     create_insecure_with_proxy,
     create_openssl_with_proxy,
@@ -33,7 +37,7 @@ use super::proxy::{
 };
 
 type StrategyFuture<'a> = Pin<Box<dyn Future<Output = KubeResult<Client>> + Send + 'a>>;
-type Strategy<'a> = (&'static str, StrategyFuture<'a>);
+pub(super) type Strategy<'a> = (&'static str, StrategyFuture<'a>);
 
 const POOL_MAX_IDLE_PER_HOST: usize = 5;
 
@@ -45,14 +49,17 @@ static HTTP_CONNECTOR: LazyLock<HttpConnector> = LazyLock::new(|| {
 });
 
 pub async fn create_client_with_config(config: &Config) -> Option<Client> {
-    let strategies = if config.accept_invalid_certs {
+    execute_strategies(strategies_for(config)).await
+}
+
+/// The strategies tried for `config`, in the order they are attempted.
+pub(super) fn strategies_for(config: &Config) -> Vec<Strategy<'_>> {
+    if config.accept_invalid_certs {
         info!("Creating insecure connection strategies for skip-tls-verify=true");
         create_insecure_connection_strategies(config)
     } else {
         create_connection_strategies(config)
-    };
-
-    execute_strategies(strategies).await
+    }
 }
 
 async fn execute_strategies(strategies: Vec<Strategy<'_>>) -> Option<Client> {
@@ -233,29 +240,43 @@ where
         .build(connector)
 }
 
-async fn test_client_connection(client: &Client) -> KubeResult<()> {
-    client.apiserver_version().await.map_err(|e| {
+/// Asks the API server for its version, returning the reported git version.
+pub(super) async fn test_client_connection(client: &Client) -> KubeResult<String> {
+    let info = client.apiserver_version().await.map_err(|e| {
         KubeClientError::connection_error_with_source(
             "Failed to connect to Kubernetes API server",
             e,
         )
     })?;
-    Ok(())
+    Ok(info.git_version)
 }
 
 pub fn build_kube_client<C>(
-    config: Config, hyper_client: hyper_util::client::legacy::Client<C, kube::client::Body>,
+    mut config: Config, hyper_client: hyper_util::client::legacy::Client<C, kube::client::Body>,
 ) -> KubeResult<Client>
 where
     C: hyper_util::client::legacy::connect::Connect + Clone + Send + Sync + 'static,
 {
+    // Exec plugin tokens come from the shared credential cache rather than a
+    // plugin run per client.
+    let exec_layer = ExecPlugin::of(&config).map(ExecAuthLayer::new);
+    if exec_layer.is_some() {
+        config.auth_info.exec = None;
+    }
+
     let auth_layer = config
         .auth_layer()
         .map_err(|e| KubeClientError::auth_error_with_source("Failed to create auth layer", e))?;
 
+    let extra_headers_layer = config.extra_headers_layer().map_err(|e| {
+        KubeClientError::auth_error_with_source("Failed to create impersonation headers", e)
+    })?;
+
     let service = ServiceBuilder::new()
         .layer(config.base_uri_layer())
+        .layer(extra_headers_layer)
         .option_layer(auth_layer)
+        .option_layer(exec_layer)
         .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> { Box::new(e) })
         .service(hyper_client);
 
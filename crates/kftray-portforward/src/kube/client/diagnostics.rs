@@ -0,0 +1,797 @@
+use std::error::Error;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::net::{
+    IpAddr,
+    SocketAddr,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use anyhow::Result;
+use kube::config::{
+    AuthInfo,
+    Config,
+};
+use log::info;
+use openssl::asn1::Asn1Time;
+use openssl::ssl::{
+    SslConnector,
+    SslMethod,
+    SslVerifyMode,
+};
+use openssl::x509::{
+    X509,
+    X509NameRef,
+    X509Ref,
+    X509VerifyResult,
+};
+use serde::Serialize;
+
+use super::config::{
+    Impersonation,
+    create_config_without_exec,
+    get_kubeconfig_paths_from_option,
+    merge_kubeconfigs,
+};
+use super::connection::{
+    strategies_for,
+    test_client_connection,
+};
+use super::exec_auth::{
+    ExecPlugin,
+    resolve_exec_credential,
+};
+use super::proxy::redact_proxy_url;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const STRATEGY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Certificates closer than this to expiry are reported as a warning.
+const EXPIRY_WARNING_DAYS: i32 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+    Skipped,
+}
+
+/// Outcome of one probe, e.g. resolving the API server host.
+#[derive(Clone, Debug, Serialize)]
+pub struct DiagnosticCheck {
+    pub check: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub elapsed_ms: Option<u64>,
+}
+
+/// A certificate presented by the API server.
+#[derive(Clone, Debug, Serialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_after: String,
+    pub days_remaining: i32,
+    pub subject_alt_names: Vec<String>,
+}
+
+/// Client build and version probe for one connection strategy.
+#[derive(Clone, Debug, Serialize)]
+pub struct StrategyDiagnostics {
+    pub strategy: String,
+    pub proxy: Option<String>,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl StrategyDiagnostics {
+    pub fn connected(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|c| c.check == "version" && c.status == CheckStatus::Ok)
+    }
+}
+
+/// Everything found while connecting to the API server of a context. The
+/// network checks run once against the endpoint every strategy talks to;
+/// the strategies then differ in TLS backend and certificate verification.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ConnectionReport {
+    pub context: String,
+    pub server: String,
+    pub auth_method: String,
+    pub proxy: Option<String>,
+    pub checks: Vec<DiagnosticCheck>,
+    pub certificates: Vec<CertificateInfo>,
+    pub strategies: Vec<StrategyDiagnostics>,
+}
+
+impl ConnectionReport {
+    pub fn connected(&self) -> bool {
+        self.strategies.iter().any(StrategyDiagnostics::connected)
+    }
+
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{} ({}) [{}]\n",
+            self.context,
+            self.server,
+            if self.connected() {
+                "reachable"
+            } else {
+                "unreachable"
+            }
+        );
+        let _ = writeln!(out, "  auth:  {}", self.auth_method);
+        let _ = writeln!(out, "  proxy: {}", self.proxy.as_deref().unwrap_or("none"));
+        write_checks(&mut out, &self.checks, "  ");
+
+        if !self.certificates.is_empty() {
+            out.push_str("\n  certificate chain:\n");
+            for cert in &self.certificates {
+                let _ = writeln!(
+                    out,
+                    "    {} issued by {}, expires {} ({} days)",
+                    cert.subject, cert.issuer, cert.not_after, cert.days_remaining
+                );
+            }
+        }
+
+        for strategy in &self.strategies {
+            let _ = writeln!(out, "\n  strategy {}", strategy.strategy);
+            write_checks(&mut out, &strategy.checks, "    ");
+        }
+        out
+    }
+}
+
+fn write_checks(out: &mut String, checks: &[DiagnosticCheck], indent: &str) {
+    for check in checks {
+        let marker = match check.status {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Warning => "warn",
+            CheckStatus::Error => "error",
+            CheckStatus::Skipped => "skip",
+        };
+        let _ = write!(out, "{indent}{marker:<5} {}: {}", check.check, check.detail);
+        if let Some(ms) = check.elapsed_ms {
+            let _ = write!(out, " ({ms} ms)");
+        }
+        out.push('\n');
+    }
+}
+
+/// Probes the API server of `context_name` step by step and tries every
+/// connection strategy, collecting the result of each instead of stopping at
+/// the first failure. A failing exec plugin is reported as the credentials
+/// check; the network checks still run.
+pub async fn diagnose_connection(
+    kubeconfig: Option<String>, context_name: &str,
+) -> Result<ConnectionReport> {
+    let paths = get_kubeconfig_paths_from_option(kubeconfig)?;
+    let (merged_kubeconfig, _, _) = merge_kubeconfigs(&paths)?;
+    let mut config =
+        create_config_without_exec(&merged_kubeconfig, context_name, &Impersonation::default())
+            .await?;
+
+    info!("Running connection diagnostics for context: {context_name}");
+    let auth_method = describe_auth(&config.auth_info);
+    let credentials = check_credentials(&mut config).await;
+    let mut report = diagnose_config(context_name, &config, credentials).await;
+    report.auth_method = auth_method;
+    Ok(report)
+}
+
+/// Runs the exec plugin of `config`, if any, and reports how that went.
+async fn check_credentials(config: &mut Config) -> Option<DiagnosticCheck> {
+    ExecPlugin::of(config)?;
+    let command = config
+        .auth_info
+        .exec
+        .as_ref()
+        .and_then(|exec| exec.command.clone())
+        .unwrap_or_default();
+
+    let started = Instant::now();
+    let result = resolve_exec_credential(config).await;
+    let elapsed = Some(started.elapsed().as_millis() as u64);
+    Some(match result {
+        Ok(()) => check(
+            "credentials",
+            CheckStatus::Ok,
+            format!("exec plugin {command} returned a credential"),
+            elapsed,
+        ),
+        Err(e) => check("credentials", CheckStatus::Error, e, elapsed),
+    })
+}
+
+async fn diagnose_config(
+    context_name: &str, config: &Config, credentials: Option<DiagnosticCheck>,
+) -> ConnectionReport {
+    let mut report = ConnectionReport {
+        context: context_name.to_string(),
+        server: config.cluster_url.to_string(),
+        auth_method: describe_auth(&config.auth_info),
+        proxy: config.proxy_url.as_ref().map(redact_proxy_url),
+        ..Default::default()
+    };
+
+    // Without a credential every strategy would run the failing plugin again.
+    let credentials_failed = credentials
+        .as_ref()
+        .is_some_and(|c| c.status == CheckStatus::Error);
+    report.checks.extend(credentials);
+
+    probe_endpoint(config, &mut report).await;
+
+    for (description, strategy) in strategies_for(config) {
+        let mut checks = Vec::new();
+        if credentials_failed {
+            checks.push(check(
+                "client",
+                CheckStatus::Skipped,
+                "the exec plugin returned no credential",
+                None,
+            ));
+            report.strategies.push(StrategyDiagnostics {
+                strategy: description.to_string(),
+                proxy: report.proxy.clone(),
+                checks,
+            });
+            continue;
+        }
+        let (client, elapsed) = timed(STRATEGY_TIMEOUT, strategy).await;
+        match client {
+            Ok(Ok(client)) => {
+                checks.push(check("client", CheckStatus::Ok, "built", elapsed));
+                let (version, elapsed) =
+                    timed(STRATEGY_TIMEOUT, test_client_connection(&client)).await;
+                checks.push(match version {
+                    Ok(Ok(version)) => check(
+                        "version",
+                        CheckStatus::Ok,
+                        format!("API server {version}"),
+                        elapsed,
+                    ),
+                    Ok(Err(e)) => check("version", CheckStatus::Error, error_chain(&e), elapsed),
+                    Err(_) => check("version", CheckStatus::Error, "timed out", elapsed),
+                });
+            }
+            Ok(Err(e)) => checks.push(check(
+                "client",
+                CheckStatus::Error,
+                error_chain(&e),
+                elapsed,
+            )),
+            Err(_) => checks.push(check("client", CheckStatus::Error, "timed out", elapsed)),
+        }
+
+        report.strategies.push(StrategyDiagnostics {
+            strategy: description.to_string(),
+            proxy: report.proxy.clone(),
+            checks,
+        });
+    }
+
+    report
+}
+
+/// Resolves, connects to and, for https servers, handshakes with the API
+/// server the way a direct connection would.
+async fn probe_endpoint(config: &Config, report: &mut ConnectionReport) {
+    let uri = &config.cluster_url;
+    let https = uri.scheme_str() != Some("http");
+    let Some(host) = uri.host().map(|h| h.trim_matches(['[', ']']).to_string()) else {
+        report.checks.push(check(
+            "dns",
+            CheckStatus::Error,
+            "server URL has no host",
+            None,
+        ));
+        return;
+    };
+    let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+
+    let (resolved, elapsed) = timed(
+        PROBE_TIMEOUT,
+        tokio::net::lookup_host((host.as_str(), port)),
+    )
+    .await;
+    let addrs: Vec<SocketAddr> = match resolved {
+        Ok(Ok(addrs)) => addrs.collect(),
+        Ok(Err(e)) => {
+            report
+                .checks
+                .push(check("dns", CheckStatus::Error, e.to_string(), elapsed));
+            return;
+        }
+        Err(_) => {
+            report
+                .checks
+                .push(check("dns", CheckStatus::Error, "timed out", elapsed));
+            return;
+        }
+    };
+    let listed = addrs
+        .iter()
+        .map(|a| a.ip().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    report.checks.push(check(
+        "dns",
+        if addrs.is_empty() {
+            CheckStatus::Error
+        } else {
+            CheckStatus::Ok
+        },
+        format!("{host} -> [{listed}]"),
+        elapsed,
+    ));
+
+    if let Some(proxy) = &config.proxy_url {
+        let proxy_host = proxy.host().unwrap_or_default().trim_matches(['[', ']']);
+        let proxy_port = proxy.port_u16().unwrap_or(match proxy.scheme_str() {
+            Some("socks5") | Some("socks5h") => 1080,
+            _ => 8080,
+        });
+        let (stream, elapsed) = timed(
+            PROBE_TIMEOUT,
+            tokio::net::TcpStream::connect((proxy_host, proxy_port)),
+        )
+        .await;
+        report.checks.push(match stream {
+            Ok(Ok(_)) => check(
+                "tcp",
+                CheckStatus::Ok,
+                format!("connected to proxy {proxy_host}:{proxy_port}"),
+                elapsed,
+            ),
+            Ok(Err(e)) => check(
+                "tcp",
+                CheckStatus::Error,
+                format!("proxy {proxy_host}:{proxy_port}: {e}"),
+                elapsed,
+            ),
+            Err(_) => check("tcp", CheckStatus::Error, "proxy timed out", elapsed),
+        });
+        report.checks.push(check(
+            "tls",
+            CheckStatus::Skipped,
+            "the handshake is tunnelled through the proxy, see the strategy results",
+            None,
+        ));
+        return;
+    }
+
+    let Some(addr) = addrs.first().copied() else {
+        return;
+    };
+    let (stream, elapsed) = timed(PROBE_TIMEOUT, tokio::net::TcpStream::connect(addr)).await;
+    let stream = match stream {
+        Ok(Ok(stream)) => {
+            report.checks.push(check(
+                "tcp",
+                CheckStatus::Ok,
+                format!("connected to {addr}"),
+                elapsed,
+            ));
+            stream
+        }
+        Ok(Err(e)) => {
+            report.checks.push(check(
+                "tcp",
+                CheckStatus::Error,
+                format!("{addr}: {e}"),
+                elapsed,
+            ));
+            return;
+        }
+        Err(_) => {
+            report
+                .checks
+                .push(check("tcp", CheckStatus::Error, "timed out", elapsed));
+            return;
+        }
+    };
+
+    if !https {
+        report.checks.push(check(
+            "tls",
+            CheckStatus::Skipped,
+            "server uses plain http",
+            None,
+        ));
+        return;
+    }
+
+    let server_name = config.tls_server_name.clone().unwrap_or(host);
+    let root_certs = config.root_cert.clone().unwrap_or_default();
+    let started = Instant::now();
+    let handshake = match stream.into_std() {
+        Ok(stream) => tokio::task::spawn_blocking(move || {
+            handshake_tls(stream, &server_name, &root_certs).map(|tls| (tls, server_name))
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string())),
+        Err(e) => Err(e.to_string()),
+    };
+    let elapsed = Some(started.elapsed().as_millis() as u64);
+
+    let (tls, server_name) = match handshake {
+        Ok(result) => result,
+        Err(e) => {
+            report
+                .checks
+                .push(check("tls", CheckStatus::Error, e, elapsed));
+            return;
+        }
+    };
+
+    let verification = if tls.verify_error.is_none() {
+        "certificate verified".to_string()
+    } else if config.accept_invalid_certs {
+        "verification skipped by insecure-skip-tls-verify".to_string()
+    } else {
+        "certificate not trusted".to_string()
+    };
+    report.checks.push(check(
+        "tls",
+        match (&tls.verify_error, config.accept_invalid_certs) {
+            (None, _) => CheckStatus::Ok,
+            (Some(_), true) => CheckStatus::Warning,
+            (Some(_), false) => CheckStatus::Error,
+        },
+        match &tls.verify_error {
+            Some(e) => format!("{}, {verification}: {e}", tls.protocol),
+            None => format!("{}, {verification}", tls.protocol),
+        },
+        elapsed,
+    ));
+
+    if let Some(leaf) = tls.chain.first() {
+        report
+            .checks
+            .push(if san_matches(&server_name, &leaf.subject_alt_names) {
+                check(
+                    "san",
+                    CheckStatus::Ok,
+                    format!("'{server_name}' is covered by the certificate"),
+                    None,
+                )
+            } else {
+                check(
+                    "san",
+                    CheckStatus::Error,
+                    format!(
+                        "'{server_name}' is not in [{}], set tls-server-name in the kubeconfig",
+                        leaf.subject_alt_names.join(", ")
+                    ),
+                    None,
+                )
+            });
+    }
+
+    if let Some(soonest) = tls.chain.iter().min_by_key(|cert| cert.days_remaining) {
+        report.checks.push(check(
+            "expiry",
+            expiry_status(soonest.days_remaining),
+            format!(
+                "{} expires {} ({} days)",
+                soonest.subject, soonest.not_after, soonest.days_remaining
+            ),
+            None,
+        ));
+    }
+
+    report.certificates = tls.chain;
+}
+
+struct TlsHandshake {
+    protocol: String,
+    verify_error: Option<String>,
+    chain: Vec<CertificateInfo>,
+}
+
+/// Completes a handshake without failing on verification so the chain can be
+/// inspected either way, then reports what verification would have said.
+fn handshake_tls(
+    stream: std::net::TcpStream, server_name: &str, root_certs: &[Vec<u8>],
+) -> Result<TlsHandshake, String> {
+    stream
+        .set_nonblocking(false)
+        .and_then(|_| stream.set_read_timeout(Some(PROBE_TIMEOUT)))
+        .and_then(|_| stream.set_write_timeout(Some(PROBE_TIMEOUT)))
+        .map_err(|e| e.to_string())?;
+
+    let mut builder = SslConnector::builder(SslMethod::tls()).map_err(|e| e.to_string())?;
+    builder.set_verify(SslVerifyMode::NONE);
+    for der in root_certs {
+        let cert = X509::from_der(der).map_err(|e| format!("invalid cluster CA: {e}"))?;
+        builder
+            .cert_store_mut()
+            .add_cert(cert)
+            .map_err(|e| format!("invalid cluster CA: {e}"))?;
+    }
+
+    let tls = builder
+        .build()
+        .configure()
+        .map_err(|e| e.to_string())?
+        .connect(server_name, stream)
+        .map_err(|e| format!("handshake failed: {e}"))?;
+    let ssl = tls.ssl();
+
+    let verify_result = ssl.verify_result();
+    let chain = ssl
+        .peer_cert_chain()
+        .map(|chain| chain.iter().map(certificate_info).collect())
+        .unwrap_or_default();
+
+    Ok(TlsHandshake {
+        protocol: ssl.version_str().to_string(),
+        verify_error: (verify_result != X509VerifyResult::OK)
+            .then(|| verify_result.error_string().to_string()),
+        chain,
+    })
+}
+
+fn certificate_info(cert: &X509Ref) -> CertificateInfo {
+    let days_remaining = Asn1Time::days_from_now(0)
+        .ok()
+        .and_then(|now| now.diff(cert.not_after()).ok())
+        .map(|diff| diff.days)
+        .unwrap_or_default();
+
+    let subject_alt_names = cert
+        .subject_alt_names()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| {
+                    name.dnsname().map(ToString::to_string).or_else(|| {
+                        name.ipaddress().and_then(|ip| match ip.len() {
+                            4 => Some(IpAddr::from(<[u8; 4]>::try_from(ip).ok()?).to_string()),
+                            16 => Some(IpAddr::from(<[u8; 16]>::try_from(ip).ok()?).to_string()),
+                            _ => None,
+                        })
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    CertificateInfo {
+        subject: format_name(cert.subject_name()),
+        issuer: format_name(cert.issuer_name()),
+        not_after: cert.not_after().to_string(),
+        days_remaining,
+        subject_alt_names,
+    }
+}
+
+fn format_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let key = entry.object().nid().short_name().unwrap_or("?");
+            let value = entry
+                .data()
+                .as_utf8()
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            format!("{key}={value}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn expiry_status(days_remaining: i32) -> CheckStatus {
+    if days_remaining < 0 {
+        CheckStatus::Error
+    } else if days_remaining < EXPIRY_WARNING_DAYS {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    }
+}
+
+/// Whether `host` is covered by one of the subject alternative names, with
+/// the single-label wildcard rule TLS clients apply.
+fn san_matches(host: &str, sans: &[String]) -> bool {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return sans.iter().any(|san| san.parse::<IpAddr>() == Ok(ip));
+    }
+
+    let host = host.to_ascii_lowercase();
+    sans.iter().any(|san| {
+        let san = san.to_ascii_lowercase();
+        match san.strip_prefix("*.") {
+            Some(suffix) => host
+                .split_once('.')
+                .is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+            None => san == host,
+        }
+    })
+}
+
+fn describe_auth(auth: &AuthInfo) -> String {
+    let mut methods = Vec::new();
+    if let Some(exec) = &auth.exec {
+        methods.push(format!(
+            "exec plugin ({})",
+            exec.command.as_deref().unwrap_or("no command")
+        ));
+    }
+    if let Some(provider) = &auth.auth_provider {
+        methods.push(format!("auth provider ({})", provider.name));
+    }
+    if auth.token.is_some() || auth.token_file.is_some() {
+        methods.push("bearer token".to_string());
+    }
+    if auth.client_certificate.is_some() || auth.client_certificate_data.is_some() {
+        methods.push("client certificate".to_string());
+    }
+    if auth.username.is_some() {
+        methods.push("basic auth".to_string());
+    }
+
+    if methods.is_empty() {
+        "none".to_string()
+    } else {
+        methods.join(", ")
+    }
+}
+
+fn error_chain(error: &dyn Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let _ = write!(message, ": {cause}");
+        source = cause.source();
+    }
+    message
+}
+
+fn check(
+    name: &str, status: CheckStatus, detail: impl Into<String>, elapsed_ms: Option<u64>,
+) -> DiagnosticCheck {
+    DiagnosticCheck {
+        check: name.to_string(),
+        status,
+        detail: detail.into(),
+        elapsed_ms,
+    }
+}
+
+async fn timed<F: Future>(
+    limit: Duration, future: F,
+) -> (Result<F::Output, tokio::time::error::Elapsed>, Option<u64>) {
+    let started = Instant::now();
+    let result = tokio::time::timeout(limit, future).await;
+    (result, Some(started.elapsed().as_millis() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_san_matches() {
+        let sans = vec![
+            "kubernetes.default".to_string(),
+            "*.eks.amazonaws.com".to_string(),
+            "10.0.0.1".to_string(),
+        ];
+
+        assert!(san_matches("kubernetes.default", &sans));
+        assert!(san_matches("ABC.eks.amazonaws.com", &sans));
+        assert!(san_matches("10.0.0.1", &sans));
+        assert!(!san_matches("a.b.eks.amazonaws.com", &sans));
+        assert!(!san_matches("eks.amazonaws.com", &sans));
+        assert!(!san_matches("10.0.0.2", &sans));
+        assert!(!san_matches("kubernetes", &sans));
+    }
+
+    #[test]
+    fn test_describe_auth() {
+        assert_eq!(describe_auth(&AuthInfo::default()), "none");
+
+        let auth = AuthInfo {
+            token_file: Some("/var/run/token".to_string()),
+            client_certificate: Some("/tmp/cert.pem".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(describe_auth(&auth), "bearer token, client certificate");
+    }
+
+    #[test]
+    fn test_expiry_status() {
+        assert_eq!(expiry_status(-1), CheckStatus::Error);
+        assert_eq!(expiry_status(5), CheckStatus::Warning);
+        assert_eq!(expiry_status(365), CheckStatus::Ok);
+    }
+
+    #[test]
+    fn test_report_summary() {
+        let report = ConnectionReport {
+            context: "dev".to_string(),
+            server: "https://127.0.0.1:6443/".to_string(),
+            auth_method: "bearer token".to_string(),
+            checks: vec![check("dns", CheckStatus::Ok, "127.0.0.1", Some(1))],
+            strategies: vec![StrategyDiagnostics {
+                strategy: "Rustls (original settings)".to_string(),
+                proxy: None,
+                checks: vec![
+                    check("client", CheckStatus::Ok, "built", Some(0)),
+                    check("version", CheckStatus::Ok, "API server v1.31.0", Some(4)),
+                ],
+            }],
+            ..Default::default()
+        };
+
+        assert!(report.connected());
+        let summary = report.summary();
+        assert!(summary.starts_with("dev (https://127.0.0.1:6443/) [reachable]"));
+        assert!(summary.contains("  proxy: none"));
+        assert!(summary.contains("  ok    dns: 127.0.0.1 (1 ms)"));
+        assert!(summary.contains("    ok    version: API server v1.31.0 (4 ms)"));
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_config_unreachable_server() {
+        let config = Config::new("https://127.0.0.1:1".parse().unwrap());
+        let report = diagnose_config("dev", &config, None).await;
+
+        assert!(!report.connected());
+        assert_eq!(report.checks[0].status, CheckStatus::Ok);
+        assert_eq!(report.checks[1].check, "tcp");
+        assert_eq!(report.checks[1].status, CheckStatus::Error);
+        assert!(!report.strategies.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_diagnose_failing_exec_plugin() {
+        let mut config = Config::new("https://127.0.0.1:1".parse().unwrap());
+        config.auth_info.exec = Some(
+            serde_json::from_value(serde_json::json!({
+                "apiVersion": "client.authentication.k8s.io/v1beta1",
+                "command": "sh",
+                "args": ["-c", "echo 'login required' >&2; exit 1"],
+            }))
+            .unwrap(),
+        );
+
+        let credentials = check_credentials(&mut config).await.unwrap();
+        assert_eq!(credentials.check, "credentials");
+        assert_eq!(credentials.status, CheckStatus::Error);
+        assert_eq!(
+            credentials.detail,
+            "Exec plugin 'sh' failed: login required"
+        );
+
+        let report = diagnose_config("dev", &config, Some(credentials)).await;
+        assert!(!report.connected());
+        assert_eq!(report.auth_method, "exec plugin (sh)");
+        assert_eq!(report.checks[0].check, "credentials");
+        assert_eq!(report.checks[1].check, "dns");
+        assert_eq!(report.checks[2].check, "tcp");
+        assert!(!report.strategies.is_empty());
+        assert!(
+            report
+                .strategies
+                .iter()
+                .all(|s| s.checks[0].status == CheckStatus::Skipped)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_credentials_without_exec_plugin() {
+        let mut config = Config::new("https://127.0.0.1:1".parse().unwrap());
+        assert!(check_credentials(&mut config).await.is_none());
+    }
+}
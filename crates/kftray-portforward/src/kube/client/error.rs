@@ -211,6 +211,12 @@ impl KubeClientError {
             source: Some(Box::new(source)),
         }
     }
+
+    /// Whether the API server denied the request, usually because RBAC does
+    /// not grant the verb on that scope.
+    pub fn is_forbidden(&self) -> bool {
+        matches!(self, KubeClientError::KubeError(kube::Error::Api(response)) if response.code == 403)
+    }
 }
 
 pub type KubeResult<T> = std::result::Result<T, KubeClientError>;
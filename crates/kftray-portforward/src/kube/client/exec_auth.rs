@@ -0,0 +1,565 @@
+use std::collections::hash_map::DefaultHasher;
+use std::future::Future;
+use std::hash::{
+    Hash,
+    Hasher,
+};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::{
+    Arc,
+    LazyLock,
+};
+use std::task::{
+    Context,
+    Poll,
+};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use http::header::AUTHORIZATION;
+use http::{
+    HeaderValue,
+    Request,
+    Response,
+    StatusCode,
+};
+use k8s_openapi::chrono::{
+    self,
+    DateTime,
+    Utc,
+};
+use kube::config::Config;
+use log::{
+    info,
+    warn,
+};
+use serde::Serialize;
+use serde_json::{
+    Value,
+    json,
+};
+use tokio::io::{
+    AsyncBufReadExt,
+    AsyncReadExt,
+    BufReader,
+};
+use tokio::sync::{
+    Mutex,
+    broadcast,
+};
+use tokio::time::Instant;
+use tower::{
+    Layer,
+    Service,
+};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// How long a plugin may run before it is assumed to be waiting for input.
+const EXEC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait once a plugin asked the user to log in through a browser.
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Credentials are refreshed this long before they expire.
+const EXPIRY_MARGIN: chrono::TimeDelta = chrono::TimeDelta::seconds(60);
+
+const DEFAULT_API_VERSION: &str = "client.authentication.k8s.io/v1beta1";
+
+/// Credentials per exec plugin configuration, shared by every client built
+/// for the same kubeconfig user. The mutex makes concurrent clients wait for
+/// one plugin run instead of starting their own.
+static CREDENTIALS: LazyLock<DashMap<u64, Arc<Mutex<Option<ExecCredential>>>>> =
+    LazyLock::new(DashMap::new);
+
+static AUTH_PROMPTS: LazyLock<broadcast::Sender<AuthPrompt>> =
+    LazyLock::new(|| broadcast::channel(16).0);
+
+/// An exec plugin that needs the user to do something, like finishing a
+/// browser login or running a login command in a terminal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuthPrompt {
+    pub command: String,
+    pub message: String,
+    pub url: Option<String>,
+}
+
+/// Receives a prompt whenever an exec plugin waits for or requires the user.
+pub fn subscribe_auth_prompts() -> broadcast::Receiver<AuthPrompt> {
+    AUTH_PROMPTS.subscribe()
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct ExecCredential {
+    token: Option<String>,
+    client_certificate_data: Option<String>,
+    client_key_data: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl ExecCredential {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| expires_at - EXPIRY_MARGIN > now)
+    }
+}
+
+/// The exec plugin of a kubeconfig user, with everything needed to run it.
+#[derive(Clone, Debug)]
+pub(super) struct ExecPlugin {
+    key: u64,
+    command: String,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    api_version: String,
+    interactive_mode: Option<String>,
+    cluster: Option<Value>,
+}
+
+impl ExecPlugin {
+    pub(super) fn of(config: &Config) -> Option<Self> {
+        let exec = serde_json::to_value(config.auth_info.exec.as_ref()?).ok()?;
+        let command = exec.get("command")?.as_str()?.to_string();
+
+        let strings = |field: &str| -> Vec<String> {
+            exec.get(field)
+                .and_then(Value::as_array)
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let env = exec
+            .get("env")
+            .and_then(Value::as_array)
+            .map(|vars| {
+                vars.iter()
+                    .filter_map(|var| {
+                        Some((
+                            var.get("name")?.as_str()?.to_string(),
+                            var.get("value")?.as_str()?.to_string(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let provide_cluster_info = exec
+            .get("provideClusterInfo")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let cluster = provide_cluster_info.then(|| {
+            json!({
+                "server": config.cluster_url.to_string(),
+                "tls-server-name": config.tls_server_name,
+                "insecure-skip-tls-verify": config.accept_invalid_certs,
+            })
+        });
+
+        let mut hasher = DefaultHasher::new();
+        exec.to_string().hash(&mut hasher);
+        cluster.as_ref().map(Value::to_string).hash(&mut hasher);
+
+        Some(Self {
+            key: hasher.finish(),
+            command,
+            args: strings("args"),
+            env,
+            api_version: exec
+                .get("apiVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(DEFAULT_API_VERSION)
+                .to_string(),
+            interactive_mode: exec
+                .get("interactiveMode")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            cluster,
+        })
+    }
+
+    /// Returns the cached credential, running the plugin when there is none
+    /// or it is about to expire.
+    pub(super) async fn credential(&self) -> Result<ExecCredential, String> {
+        let slot = CREDENTIALS.entry(self.key).or_default().clone();
+        let mut cached = slot.lock().await;
+
+        if let Some(credential) = cached.as_ref().filter(|c| c.is_fresh(Utc::now())) {
+            return Ok(credential.clone());
+        }
+
+        let credential = self.run().await?;
+        *cached = Some(credential.clone());
+        Ok(credential)
+    }
+
+    /// Drops the cached credential, e.g. after the API server rejected it.
+    fn invalidate(&self) {
+        if let Some(slot) = CREDENTIALS.get(&self.key)
+            && let Ok(mut cached) = slot.try_lock()
+        {
+            *cached = None;
+        }
+    }
+
+    async fn run(&self) -> Result<ExecCredential, String> {
+        if self.interactive_mode.as_deref() == Some("Always") {
+            let message = format!(
+                "'{}' always asks for input in a terminal. Run `{}` in a terminal once, then retry.",
+                self.command,
+                self.command_line()
+            );
+            self.prompt(message.clone(), None);
+            return Err(message);
+        }
+
+        info!("Running exec credential plugin '{}'", self.command);
+        let exec_info = json!({
+            "apiVersion": self.api_version,
+            "kind": "ExecCredential",
+            "spec": {
+                "interactive": false,
+                "cluster": self.cluster,
+            },
+        });
+
+        let mut child = tokio::process::Command::new(&self.command)
+            .args(&self.args)
+            .envs(self.env.iter().cloned())
+            .env("KUBERNETES_EXEC_INFO", exec_info.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to run exec plugin '{}': {e}", self.command))?;
+
+        let stdout = child.stdout.take();
+        let stdout = tokio::spawn(async move {
+            let mut buf = Vec::new();
+            if let Some(mut stdout) = stdout {
+                let _ = stdout.read_to_end(&mut buf).await;
+            }
+            buf
+        });
+
+        let mut stderr_lines = Vec::new();
+        let mut deadline = Instant::now() + EXEC_TIMEOUT;
+        if let Some(stderr) = child.stderr.take() {
+            let mut lines = BufReader::new(stderr).lines();
+            loop {
+                tokio::select! {
+                    line = lines.next_line() => match line {
+                        Ok(Some(line)) => {
+                            if let Some(url) = find_url(&line) {
+                                deadline = Instant::now() + LOGIN_TIMEOUT;
+                                self.prompt(line.clone(), Some(url));
+                            }
+                            stderr_lines.push(line);
+                        }
+                        _ => break,
+                    },
+                    _ = tokio::time::sleep_until(deadline) => {
+                        return Err(self.timed_out());
+                    }
+                }
+            }
+        }
+
+        let status = match tokio::time::timeout_at(deadline, child.wait()).await {
+            Ok(status) => status.map_err(|e| e.to_string())?,
+            Err(_) => return Err(self.timed_out()),
+        };
+        let stdout = stdout.await.unwrap_or_default();
+
+        if !status.success() {
+            let stderr = stderr_lines.join("\n");
+            let reason = stderr_lines
+                .iter()
+                .rev()
+                .find(|line| !line.trim().is_empty())
+                .cloned()
+                .unwrap_or_else(|| status.to_string());
+            if let Some(hint) = login_hint(&stderr) {
+                self.prompt(format!("{reason}. {hint}"), None);
+            }
+            return Err(format!("Exec plugin '{}' failed: {reason}", self.command));
+        }
+
+        parse_exec_credential(&stdout)
+            .map_err(|e| format!("Exec plugin '{}' returned {e}", self.command))
+    }
+
+    fn timed_out(&self) -> String {
+        let message = format!(
+            "'{}' did not return a credential and may be waiting for input. Run `{}` in a terminal, then retry.",
+            self.command,
+            self.command_line()
+        );
+        self.prompt(message.clone(), None);
+        message
+    }
+
+    fn prompt(&self, message: String, url: Option<String>) {
+        warn!("Exec plugin '{}' needs attention: {message}", self.command);
+        let _ = AUTH_PROMPTS.send(AuthPrompt {
+            command: self.command.clone(),
+            message,
+            url,
+        });
+    }
+
+    fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Runs the exec plugin of `config` once so prompts show up while the client
+/// is being built. Certificate credentials are applied to the config, since
+/// they are needed for the TLS handshake; tokens are sent per request by
+/// [`ExecAuthLayer`] so they can be refreshed.
+pub(super) async fn resolve_exec_credential(config: &mut Config) -> Result<(), String> {
+    let Some(plugin) = ExecPlugin::of(config) else {
+        return Ok(());
+    };
+
+    let credential = plugin.credential().await?;
+    if let (Some(certificate), Some(key)) = (
+        credential.client_certificate_data,
+        credential.client_key_data,
+    ) {
+        config.auth_info.client_certificate_data = Some(certificate);
+        config.auth_info.client_key_data = Some(key.into());
+        config.auth_info.exec = None;
+    }
+    Ok(())
+}
+
+fn parse_exec_credential(stdout: &[u8]) -> Result<ExecCredential, String> {
+    let value: Value =
+        serde_json::from_slice(stdout).map_err(|e| format!("invalid ExecCredential: {e}"))?;
+    let status = value
+        .get("status")
+        .ok_or("an ExecCredential without status")?;
+    let field = |name: &str| status.get(name).and_then(Value::as_str).map(str::to_string);
+
+    let credential = ExecCredential {
+        token: field("token"),
+        client_certificate_data: field("clientCertificateData"),
+        client_key_data: field("clientKeyData"),
+        expires_at: field("expirationTimestamp")
+            .map(|timestamp| {
+                DateTime::parse_from_rfc3339(&timestamp)
+                    .map(|t| t.with_timezone(&Utc))
+                    .map_err(|e| format!("an invalid expirationTimestamp '{timestamp}': {e}"))
+            })
+            .transpose()?,
+    };
+
+    if credential.token.is_none() && credential.client_certificate_data.is_none() {
+        return Err("an ExecCredential without token or client certificate".to_string());
+    }
+    Ok(credential)
+}
+
+fn find_url(line: &str) -> Option<String> {
+    let start = line.find("https://").or_else(|| line.find("http://"))?;
+    let url = line[start..]
+        .split_whitespace()
+        .next()?
+        .trim_end_matches(['.', ',', ')', '"', '\'']);
+    Some(url.to_string())
+}
+
+/// Suggests the login command for failures of well-known plugins.
+fn login_hint(stderr: &str) -> Option<&'static str> {
+    let stderr = stderr.to_ascii_lowercase();
+    if stderr.contains("sso") && (stderr.contains("token") || stderr.contains("login")) {
+        Some("Run `aws sso login` and retry.")
+    } else if stderr.contains("gcloud auth login") || stderr.contains("reauthentication") {
+        Some("Run `gcloud auth login` and retry.")
+    } else if stderr.contains("az login") {
+        Some("Run `az login` and retry.")
+    } else {
+        None
+    }
+}
+
+/// Sets the bearer token of an exec plugin on every request, taking it from
+/// the shared credential cache.
+#[derive(Clone)]
+pub(super) struct ExecAuthLayer {
+    plugin: Arc<ExecPlugin>,
+}
+
+impl ExecAuthLayer {
+    pub(super) fn new(plugin: ExecPlugin) -> Self {
+        Self {
+            plugin: Arc::new(plugin),
+        }
+    }
+}
+
+impl<S> Layer<S> for ExecAuthLayer {
+    type Service = ExecAuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ExecAuthService {
+            inner,
+            plugin: self.plugin.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct ExecAuthService<S> {
+    inner: S,
+    plugin: Arc<ExecPlugin>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ExecAuthService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<BoxError>,
+    ReqBody: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let plugin = self.plugin.clone();
+
+        Box::pin(async move {
+            let credential = plugin.credential().await?;
+            if let Some(token) = credential.token {
+                let mut value = HeaderValue::try_from(format!("Bearer {token}"))?;
+                value.set_sensitive(true);
+                request.headers_mut().insert(AUTHORIZATION, value);
+            }
+
+            let response = inner.call(request).await.map_err(Into::into)?;
+            if response.status() == StatusCode::UNAUTHORIZED {
+                plugin.invalidate();
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_exec_credential() {
+        let credential = parse_exec_credential(
+            br#"{"kind":"ExecCredential","apiVersion":"client.authentication.k8s.io/v1beta1",
+                "status":{"token":"abc","expirationTimestamp":"2030-01-01T00:00:00Z"}}"#,
+        )
+        .unwrap();
+        assert_eq!(credential.token.as_deref(), Some("abc"));
+        assert!(credential.is_fresh(Utc::now()));
+
+        let expired = parse_exec_credential(
+            br#"{"status":{"token":"abc","expirationTimestamp":"2020-01-01T00:00:00Z"}}"#,
+        )
+        .unwrap();
+        assert!(!expired.is_fresh(Utc::now()));
+
+        assert!(parse_exec_credential(br#"{"status":{}}"#).is_err());
+        assert!(parse_exec_credential(b"not json").is_err());
+    }
+
+    #[test]
+    fn test_find_url() {
+        assert_eq!(
+            find_url(
+                "To sign in, use a web browser to open the page https://microsoft.com/devicelogin and enter the code ABC123."
+            )
+            .as_deref(),
+            Some("https://microsoft.com/devicelogin")
+        );
+        assert_eq!(
+            find_url("Please visit http://localhost:8000.").as_deref(),
+            Some("http://localhost:8000")
+        );
+        assert_eq!(find_url("error: token expired"), None);
+    }
+
+    #[test]
+    fn test_login_hint() {
+        assert_eq!(
+            login_hint("Error loading SSO Token: Token for my-sso does not exist"),
+            Some("Run `aws sso login` and retry.")
+        );
+        assert_eq!(login_hint("permission denied"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_credential_is_cached_until_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let counter = dir.path().join("runs");
+        let script = format!(
+            "echo run >> {}; echo '{{\"status\":{{\"token\":\"t\",\"expirationTimestamp\":\"2099-01-01T00:00:00Z\"}}}}'",
+            counter.display()
+        );
+
+        let plugin = ExecPlugin {
+            key: rand::random(),
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script],
+            env: Vec::new(),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            interactive_mode: None,
+            cluster: None,
+        };
+
+        for _ in 0..3 {
+            let credential = plugin.credential().await.unwrap();
+            assert_eq!(credential.token.as_deref(), Some("t"));
+        }
+        let runs = std::fs::read_to_string(&counter).unwrap();
+        assert_eq!(runs.lines().count(), 1);
+
+        plugin.invalidate();
+        plugin.credential().await.unwrap();
+        let runs = std::fs::read_to_string(&counter).unwrap();
+        assert_eq!(runs.lines().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_interactive_plugin_prompts_instead_of_running() {
+        let mut prompts = subscribe_auth_prompts();
+        let plugin = ExecPlugin {
+            key: rand::random(),
+            command: "kubectl-login".to_string(),
+            args: vec!["--interactive".to_string()],
+            env: Vec::new(),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            interactive_mode: Some("Always".to_string()),
+            cluster: None,
+        };
+
+        assert!(plugin.credential().await.is_err());
+        let prompt = prompts.recv().await.unwrap();
+        assert_eq!(prompt.command, "kubectl-login");
+        assert!(prompt.message.contains("kubectl-login --interactive"));
+    }
+}
@@ -1,19 +1,33 @@
 pub mod builder;
 pub mod config;
 pub mod connection;
+pub mod diagnostics;
 pub mod error;
+pub mod exec_auth;
 pub mod proxy;
 pub mod utils;
 
-pub use builder::create_client_with_specific_context;
+pub use builder::{
+    create_client_with_impersonation,
+    create_client_with_specific_context,
+};
 pub use config::{
     ConfigExtClone,
+    Impersonation,
     create_config_with_context,
     get_kubeconfig_paths_from_option,
     merge_kubeconfigs,
 };
 pub use connection::create_client_with_config;
+pub use diagnostics::{
+    ConnectionReport,
+    diagnose_connection,
+};
 pub use error::{
     KubeClientError,
     KubeResult,
 };
+pub use exec_auth::{
+    AuthPrompt,
+    subscribe_auth_prompts,
+};
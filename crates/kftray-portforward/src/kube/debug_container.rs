@@ -0,0 +1,639 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use k8s_openapi::api::core::v1::{
+    EnvVar,
+    EphemeralContainer,
+    Pod,
+};
+use k8s_openapi::chrono::Utc;
+use kftray_commons::models::config_model::Config;
+use kube::api::{
+    Api,
+    ListParams,
+    Patch,
+    PatchParams,
+};
+use kube::{
+    Client,
+    ResourceExt,
+};
+use log::{
+    info,
+    warn,
+};
+use serde_json::{
+    Value,
+    json,
+};
+
+use crate::kube::models::Target;
+use crate::kube::pod_selection::{
+    SelectionContext,
+    select_pod,
+};
+use crate::kube::pod_watcher::PodWatcher;
+use crate::kube::shared_client::{
+    SHARED_CLIENT_MANAGER,
+    ServiceClientKey,
+};
+
+/// Image of the injected relay, pinned to the release of this build since it
+/// runs inside pods kftray doesn't own.
+pub const KFTRAY_SERVER_IMAGE: &str = concat!(
+    "ghcr.io/hcavarsan/kftray-server:v",
+    env!("CARGO_PKG_VERSION")
+);
+
+/// First kftray-server release whose relays honour `LISTEN_ADDRESS` and
+/// serve the `mux` proxy type. Older images listen on every pod interface.
+const LOOPBACK_RELAY_VERSION: (u64, u64, u64) = (0, 28, 0);
+
+const RELAY_NAME_PREFIX: &str = "kftray-relay";
+const RELAY_PORT_BASE: u16 = 40000;
+const RELAY_PORT_RANGE: u16 = 20000;
+const RELAY_LISTEN_ADDRESS: &str = "127.0.0.1";
+const RELAY_READY_TIMEOUT: Duration = Duration::from_secs(60);
+/// Relays that exit right away, usually because their port is taken by an
+/// undeclared listener, are retried on the next free port.
+const RELAY_ATTEMPTS: usize = 3;
+
+/// A kftray-server ephemeral container relaying a pod-local port.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugRelay {
+    pub pod_name: String,
+    pub container_name: String,
+    pub port: u16,
+}
+
+/// Whether `config` asks for a debug container and the workload supports
+/// one. UDP services already go through a kftray-server relay pod.
+pub fn wants_debug_container(config: &Config) -> bool {
+    config.debug_container.is_some()
+        && match config.workload_type.as_deref() {
+            Some("pod") => true,
+            Some("service") => config.protocol == "tcp",
+            _ => false,
+        }
+}
+
+/// Release version in the tag of a kftray-server image, `None` for `latest`
+/// and other tags that don't name a release.
+fn image_version(image: &str) -> Option<(u64, u64, u64)> {
+    let name = image.rsplit_once('/').map_or(image, |(_, name)| name);
+    let (_, tag) = name.split_once(':')?;
+    let mut parts = tag.strip_prefix('v').unwrap_or(tag).split('.');
+    let mut next = || parts.next()?.parse::<u64>().ok();
+    let version = (next()?, next()?, next()?);
+    parts.next().is_none().then_some(version)
+}
+
+/// Whether `image` is a kftray-server release that binds relays to the
+/// pod's loopback address.
+pub(crate) fn binds_loopback(image: &str) -> bool {
+    image_version(image).is_some_and(|version| version >= LOOPBACK_RELAY_VERSION)
+}
+
+/// Fails when the relay image of this build predates loopback binding, so
+/// no relay is started that would be reachable from the cluster network.
+pub(crate) fn ensure_loopback_relay_image() -> Result<(), String> {
+    if binds_loopback(KFTRAY_SERVER_IMAGE) {
+        return Ok(());
+    }
+    let (major, minor, patch) = LOOPBACK_RELAY_VERSION;
+    Err(format!(
+        "{KFTRAY_SERVER_IMAGE} can't bind a relay to the pod's loopback address, relays need kftray-server {major}.{minor}.{patch} or newer"
+    ))
+}
+
+fn env_value<'a>(env: Option<&'a [EnvVar]>, name: &str) -> Option<&'a str> {
+    env?.iter()
+        .find(|var| var.name == name)
+        .and_then(|var| var.value.as_deref())
+}
+
+/// Port a relay ephemeral container was started with, when its image binds
+/// to the pod's loopback address and it was asked to.
+fn relay_listen_port(container: &EphemeralContainer) -> Option<u16> {
+    if !container.image.as_deref().is_some_and(binds_loopback) {
+        return None;
+    }
+    let env = container.env.as_deref();
+    if env_value(env, "LISTEN_ADDRESS") != Some(RELAY_LISTEN_ADDRESS) {
+        return None;
+    }
+    env_value(env, "LOCAL_PORT")?.parse().ok()
+}
+
+/// Ports declared by the pod's containers or claimed by any of its ephemeral
+/// containers, stopped ones included.
+fn used_ports(pod: &Pod) -> HashSet<u16> {
+    let Some(spec) = pod.spec.as_ref() else {
+        return HashSet::new();
+    };
+
+    let declared = spec
+        .containers
+        .iter()
+        .chain(spec.init_containers.iter().flatten())
+        .flat_map(|container| container.ports.iter().flatten())
+        .filter_map(|port| u16::try_from(port.container_port).ok());
+    let relays = spec
+        .ephemeral_containers
+        .iter()
+        .flatten()
+        .filter_map(|container| {
+            env_value(container.env.as_deref(), "LOCAL_PORT")?
+                .parse::<u16>()
+                .ok()
+        });
+
+    declared.chain(relays).collect()
+}
+
+/// Port a new relay for `remote_port` listens on inside the pod: the first
+/// port from `40000 + remote_port % 20000` upwards, wrapping within
+/// 40000-59999, that the pod doesn't use yet. The same pod state always
+/// gives the same port.
+pub(crate) fn relay_port(pod: &Pod, remote_port: u16) -> Result<u16, String> {
+    let used = used_ports(pod);
+    let start = remote_port % RELAY_PORT_RANGE;
+
+    (0..RELAY_PORT_RANGE)
+        .map(|offset| RELAY_PORT_BASE + (start + offset) % RELAY_PORT_RANGE)
+        .find(|port| *port != remote_port && !used.contains(port))
+        .ok_or_else(|| format!("Pod '{}' has no free relay port", pod.name_any()))
+}
+
+/// Whether one of the pod's regular containers declares `port`.
+pub(crate) fn declares_port(pod: &Pod, port: u16) -> bool {
+    pod.spec.as_ref().is_some_and(|spec| {
+        spec.containers.iter().any(|container| {
+            container
+                .ports
+                .iter()
+                .flatten()
+                .any(|declared| declared.container_port == i32::from(port))
+        })
+    })
+}
+
+fn relay_base_name(protocol: &str, remote_port: u16) -> String {
+    format!("{RELAY_NAME_PREFIX}-{protocol}-{remote_port}")
+}
+
+/// Name and port of a relay for `base_name` that is already running in the
+/// pod and only listens on its loopback address.
+fn running_relay(pod: &Pod, base_name: &str) -> Option<(String, u16)> {
+    let running: Vec<&str> = pod
+        .status
+        .as_ref()?
+        .ephemeral_container_statuses
+        .as_ref()?
+        .iter()
+        .filter(|status| {
+            status
+                .state
+                .as_ref()
+                .is_some_and(|state| state.running.is_some())
+        })
+        .map(|status| status.name.as_str())
+        .collect();
+
+    pod.spec
+        .as_ref()?
+        .ephemeral_containers
+        .as_ref()?
+        .iter()
+        .filter(|container| {
+            container.name == base_name || container.name.starts_with(&format!("{base_name}-"))
+        })
+        .filter(|container| running.contains(&container.name.as_str()))
+        .find_map(|container| Some((container.name.clone(), relay_listen_port(container)?)))
+}
+
+/// First relay name not used by the pod. Ephemeral containers can't be
+/// removed or restarted, so a stopped relay needs a new name.
+fn unused_relay_name(pod: &Pod, base_name: &str) -> String {
+    let used: Vec<&str> = pod
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.ephemeral_containers.as_ref())
+        .map(|containers| containers.iter().map(|c| c.name.as_str()).collect())
+        .unwrap_or_default();
+
+    std::iter::once(base_name.to_string())
+        .chain((2..).map(|n| format!("{base_name}-{n}")))
+        .find(|name| !used.contains(&name.as_str()))
+        .unwrap_or_else(|| base_name.to_string())
+}
+
+fn relay_container(name: &str, protocol: &str, remote_port: u16, relay_port: u16) -> Value {
+    json!({
+        "name": name,
+        "image": KFTRAY_SERVER_IMAGE,
+        "env": [
+            {"name": "LOCAL_PORT", "value": relay_port.to_string()},
+            {"name": "REMOTE_PORT", "value": remote_port.to_string()},
+            {"name": "REMOTE_ADDRESS", "value": "127.0.0.1"},
+            {"name": "PROXY_TYPE", "value": protocol},
+            {"name": "LISTEN_ADDRESS", "value": RELAY_LISTEN_ADDRESS},
+            {"name": "RUST_LOG", "value": "INFO"}
+        ]
+    })
+}
+
+/// Whether the relay `name` is running, `None` while it is still starting.
+fn relay_state(pod: Option<&Pod>, name: &str) -> Option<bool> {
+    let state = pod?
+        .status
+        .as_ref()?
+        .ephemeral_container_statuses
+        .as_ref()?
+        .iter()
+        .find(|status| status.name == name)?
+        .state
+        .as_ref()?;
+
+    if state.running.is_some() {
+        Some(true)
+    } else if state.terminated.is_some() {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// What starting `config` would do about a debug container in `pod`, for
+/// the start plan.
+pub(crate) fn describe_relay(config: &Config, pod: &Pod) -> Option<String> {
+    if !wants_debug_container(config) {
+        return None;
+    }
+    let remote_port = config.remote_port?;
+    let pod_name = pod.name_any();
+
+    if config.debug_container.as_deref() == Some("auto") && declares_port(pod, remote_port) {
+        return Some(format!(
+            "'{pod_name}' declares port {remote_port}, no debug container needed"
+        ));
+    }
+
+    if let Err(e) = ensure_loopback_relay_image() {
+        return Some(e);
+    }
+
+    let base_name = relay_base_name(&config.protocol.to_lowercase(), remote_port);
+    Some(match running_relay(pod, &base_name) {
+        Some((name, port)) => format!("would reuse '{name}' in '{pod_name}' on port {port}"),
+        None => match relay_port(pod, remote_port) {
+            Ok(port) => format!(
+                "would add '{}' to '{pod_name}' relaying 127.0.0.1:{remote_port} on 127.0.0.1:{port}, needs patch on pods/ephemeralcontainers; it stays in the pod until the pod is recreated",
+                unused_relay_name(pod, &base_name)
+            ),
+            Err(e) => e,
+        },
+    })
+}
+
+/// Injects a kftray-server ephemeral container into the pod `target` resolves
+/// to, relaying `127.0.0.1:remote_port` of the pod. With `auto`, pods whose
+/// containers declare the remote port are forwarded to directly and `None`
+/// is returned.
+pub(crate) async fn prepare_debug_relay(
+    config: &Config, target: &Target,
+) -> Result<Option<DebugRelay>, String> {
+    let Some(mode) = config.debug_container.as_deref() else {
+        return Ok(None);
+    };
+    let remote_port = config
+        .remote_port
+        .ok_or("debug_container needs a remote_port")?;
+
+    let client = SHARED_CLIENT_MANAGER
+        .get_client(ServiceClientKey::for_config(config))
+        .await
+        .map_err(|e| e.to_string())?;
+    let client = Client::clone(&client);
+
+    let label_selector = PodWatcher::resolve_label_selector(&client, &config.namespace, target)
+        .await
+        .map_err(|e| e.to_string())?;
+    let pods: Api<Pod> = Api::namespaced(client, &config.namespace);
+    let list = pods
+        .list(&ListParams::default().labels(&label_selector))
+        .await
+        .map_err(|e| format!("Could not list pods for '{label_selector}': {e}"))?;
+
+    let (pod, _) = select_pod(
+        &list.items,
+        &target.selection,
+        &SelectionContext::default(),
+        Utc::now(),
+    );
+    let pod = pod.ok_or_else(|| format!("No ready pod matches '{label_selector}'"))?;
+    let pod_name = pod.name_any();
+
+    if mode == "auto" && declares_port(pod, remote_port) {
+        info!("Pod {pod_name} declares port {remote_port}, forwarding without a debug container");
+        return Ok(None);
+    }
+    ensure_loopback_relay_image()?;
+
+    let protocol = config.protocol.to_lowercase();
+    let base_name = relay_base_name(&protocol, remote_port);
+
+    if let Some((container_name, port)) = running_relay(pod, &base_name) {
+        info!("Reusing debug container {container_name} in pod {pod_name}");
+        return Ok(Some(DebugRelay {
+            pod_name,
+            container_name,
+            port,
+        }));
+    }
+
+    let mut pod = pod.clone();
+    for attempt in 1..=RELAY_ATTEMPTS {
+        let port = relay_port(&pod, remote_port)?;
+        let container_name = unused_relay_name(&pod, &base_name);
+        let patch = json!({
+            "spec": {
+                "ephemeralContainers": [relay_container(&container_name, &protocol, remote_port, port)]
+            }
+        });
+        pods.patch_ephemeral_containers(&pod_name, &PatchParams::default(), &Patch::Strategic(patch))
+            .await
+            .map_err(|e| {
+                format!(
+                    "Could not add a debug container to pod '{pod_name}', this needs patch on pods/ephemeralcontainers: {e}"
+                )
+            })?;
+
+        let started = tokio::time::timeout(
+            RELAY_READY_TIMEOUT,
+            kube_runtime::wait::await_condition(pods.clone(), &pod_name, |pod: Option<&Pod>| {
+                relay_state(pod, &container_name).is_some()
+            }),
+        )
+        .await;
+        let updated = match started {
+            Ok(Ok(Some(updated))) => updated,
+            Ok(Ok(None)) => return Err(format!("Pod '{pod_name}' was deleted")),
+            Ok(Err(e)) => return Err(format!("Debug container {container_name} failed: {e}")),
+            Err(_) => {
+                return Err(format!(
+                    "Debug container {container_name} in pod '{pod_name}' did not start within {}s",
+                    RELAY_READY_TIMEOUT.as_secs()
+                ));
+            }
+        };
+
+        if relay_state(Some(&updated), &container_name) == Some(true) {
+            info!(
+                "Debug container {container_name} relays 127.0.0.1:{remote_port} of pod {pod_name} on 127.0.0.1:{port}"
+            );
+            return Ok(Some(DebugRelay {
+                pod_name,
+                container_name,
+                port,
+            }));
+        }
+
+        warn!(
+            "Debug container {container_name} in pod {pod_name} exited, port {port} may be in use (attempt {attempt}/{RELAY_ATTEMPTS})"
+        );
+        pod = updated;
+    }
+
+    Err(format!(
+        "Debug containers in pod '{pod_name}' kept exiting, they stay in the pod until it is recreated"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::{
+        Container,
+        ContainerPort,
+        ContainerState,
+        ContainerStateRunning,
+        ContainerStateTerminated,
+        ContainerStatus,
+        PodSpec,
+        PodStatus,
+    };
+
+    use super::*;
+
+    fn relay_status(name: &str, running: bool) -> ContainerStatus {
+        let state = if running {
+            ContainerState {
+                running: Some(ContainerStateRunning::default()),
+                ..Default::default()
+            }
+        } else {
+            ContainerState {
+                terminated: Some(ContainerStateTerminated::default()),
+                ..Default::default()
+            }
+        };
+        ContainerStatus {
+            name: name.to_string(),
+            state: Some(state),
+            ..Default::default()
+        }
+    }
+
+    fn pod(ports: &[i32], relays: &[(&str, u16, bool)]) -> Pod {
+        Pod {
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "app".to_string(),
+                    ports: Some(
+                        ports
+                            .iter()
+                            .map(|port| ContainerPort {
+                                container_port: *port,
+                                ..Default::default()
+                            })
+                            .collect(),
+                    ),
+                    ..Default::default()
+                }],
+                ephemeral_containers: Some(
+                    relays
+                        .iter()
+                        .map(|(name, port, _)| EphemeralContainer {
+                            name: name.to_string(),
+                            image: Some("ghcr.io/hcavarsan/kftray-server:v0.28.0".to_string()),
+                            env: Some(vec![
+                                EnvVar {
+                                    name: "LOCAL_PORT".to_string(),
+                                    value: Some(port.to_string()),
+                                    ..Default::default()
+                                },
+                                EnvVar {
+                                    name: "LISTEN_ADDRESS".to_string(),
+                                    value: Some(RELAY_LISTEN_ADDRESS.to_string()),
+                                    ..Default::default()
+                                },
+                            ]),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                ephemeral_container_statuses: Some(
+                    relays
+                        .iter()
+                        .map(|(name, _, running)| relay_status(name, *running))
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_wants_debug_container() {
+        let mut config = Config {
+            workload_type: Some("service".to_string()),
+            protocol: "tcp".to_string(),
+            ..Config::default()
+        };
+        assert!(!wants_debug_container(&config));
+
+        config.debug_container = Some("auto".to_string());
+        assert!(wants_debug_container(&config));
+
+        config.protocol = "udp".to_string();
+        assert!(!wants_debug_container(&config));
+
+        config.workload_type = Some("pod".to_string());
+        assert!(wants_debug_container(&config));
+
+        config.workload_type = Some("proxy".to_string());
+        assert!(!wants_debug_container(&config));
+    }
+
+    #[test]
+    fn test_declares_port() {
+        let pod = pod(&[8080], &[]);
+        assert!(declares_port(&pod, 8080));
+        assert!(!declares_port(&pod, 6060));
+    }
+
+    #[test]
+    fn test_relay_names() {
+        let base = relay_base_name("tcp", 6060);
+        assert_eq!(base, "kftray-relay-tcp-6060");
+
+        let fresh = pod(&[], &[]);
+        assert_eq!(running_relay(&fresh, &base), None);
+        assert_eq!(unused_relay_name(&fresh, &base), base);
+
+        let stopped = pod(&[], &[(base.as_str(), 46060, false)]);
+        assert_eq!(running_relay(&stopped, &base), None);
+        assert_eq!(
+            unused_relay_name(&stopped, &base),
+            "kftray-relay-tcp-6060-2"
+        );
+
+        let restarted = pod(
+            &[],
+            &[
+                (base.as_str(), 46060, false),
+                ("kftray-relay-tcp-6060-2", 46061, true),
+            ],
+        );
+        assert_eq!(
+            running_relay(&restarted, &base),
+            Some(("kftray-relay-tcp-6060-2".to_string(), 46061))
+        );
+        assert_eq!(
+            relay_state(Some(&restarted), "kftray-relay-tcp-6060-2"),
+            Some(true)
+        );
+        assert_eq!(relay_state(Some(&restarted), &base), Some(false));
+        assert_eq!(relay_state(Some(&restarted), "kftray-relay-tcp-80"), None);
+        assert_eq!(running_relay(&restarted, "kftray-relay-tcp-60"), None);
+    }
+
+    #[test]
+    fn test_binds_loopback() {
+        assert_eq!(
+            image_version("ghcr.io/hcavarsan/kftray-server:v0.27.2"),
+            Some((0, 27, 2))
+        );
+        assert_eq!(
+            image_version("localhost:5000/kftray-server:1.2.3"),
+            Some((1, 2, 3))
+        );
+        assert_eq!(image_version("localhost:5000/kftray-server"), None);
+        assert_eq!(
+            image_version("ghcr.io/hcavarsan/kftray-server:latest"),
+            None
+        );
+        assert_eq!(
+            image_version("ghcr.io/hcavarsan/kftray-server:v0.28.0-rc1"),
+            None
+        );
+
+        assert!(!binds_loopback("ghcr.io/hcavarsan/kftray-server:v0.27.2"));
+        assert!(!binds_loopback("ghcr.io/hcavarsan/kftray-server:latest"));
+        assert!(binds_loopback("ghcr.io/hcavarsan/kftray-server:v0.28.0"));
+        assert!(binds_loopback("ghcr.io/hcavarsan/kftray-server:v1.0.0"));
+        assert_eq!(
+            ensure_loopback_relay_image().is_ok(),
+            binds_loopback(KFTRAY_SERVER_IMAGE)
+        );
+
+        let base = relay_base_name("tcp", 6060);
+        let mut old = pod(&[], &[(base.as_str(), 46060, true)]);
+        old.spec
+            .as_mut()
+            .unwrap()
+            .ephemeral_containers
+            .as_mut()
+            .unwrap()[0]
+            .image = Some("ghcr.io/hcavarsan/kftray-server:v0.27.2".to_string());
+        assert_eq!(running_relay(&old, &base), None);
+    }
+
+    #[test]
+    fn test_relay_port_skips_used_ports() {
+        assert_eq!(relay_port(&pod(&[], &[]), 6060), Ok(46060));
+        assert_eq!(relay_port(&pod(&[], &[]), 25000), Ok(45000));
+
+        let taken = pod(&[48080], &[("kftray-relay-tcp-28080", 48081, false)]);
+        assert_eq!(relay_port(&taken, 8080), Ok(48082));
+        assert_eq!(relay_port(&taken, 28080), Ok(48082));
+
+        let wrapping = pod(&[59999], &[]);
+        assert_eq!(relay_port(&wrapping, 19999), Ok(40000));
+    }
+
+    #[test]
+    fn test_relay_container_env() {
+        let container = relay_container("kftray-relay-tcp-6060", "tcp", 6060, 46060);
+        assert_eq!(container["image"], KFTRAY_SERVER_IMAGE);
+        let env: Vec<(String, String)> = container["env"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|var| {
+                (
+                    var["name"].as_str().unwrap().to_string(),
+                    var["value"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert!(env.contains(&("LOCAL_PORT".to_string(), "46060".to_string())));
+        assert!(env.contains(&("REMOTE_PORT".to_string(), "6060".to_string())));
+        assert!(env.contains(&("REMOTE_ADDRESS".to_string(), "127.0.0.1".to_string())));
+        assert!(env.contains(&("LISTEN_ADDRESS".to_string(), "127.0.0.1".to_string())));
+    }
+}
@@ -0,0 +1,495 @@
+use std::collections::{
+    BTreeMap,
+    HashSet,
+};
+
+use k8s_openapi::api::apps::v1::{
+    Deployment,
+    ReplicaSet,
+    StatefulSet,
+};
+use k8s_openapi::api::core::v1::{
+    Pod,
+    PodSpec,
+    Service,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kftray_commons::models::config_model::Config;
+use kftray_commons::utils::config_discovery::{
+    KubectlPortForward,
+    assign_local_ports_with_mode,
+    parse_kubectl_port_forward,
+};
+use kftray_commons::utils::db_mode::DatabaseMode;
+use kube::Client;
+use kube::api::{
+    Api,
+    ListParams,
+};
+use log::{
+    debug,
+    info,
+    warn,
+};
+use serde::Serialize;
+use sysinfo::{
+    ProcessRefreshKind,
+    ProcessesToUpdate,
+    System,
+    UpdateKind,
+};
+
+use crate::kube::client::{
+    create_client_with_specific_context,
+    get_kubeconfig_paths_from_option,
+    merge_kubeconfigs,
+};
+
+/// Labels that differ between pods of the same workload and must not end up
+/// in a pod selector.
+const POD_INSTANCE_LABELS: &[&str] = &[
+    "pod-template-hash",
+    "controller-revision-hash",
+    "statefulset.kubernetes.io/pod-name",
+    "apps.kubernetes.io/pod-index",
+];
+
+/// Configs found by an import source, plus a note for every forward that
+/// could not be converted.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct DiscoveredConfigs {
+    pub configs: Vec<Config>,
+    pub skipped: Vec<String>,
+}
+
+/// Lists the `kubectl port-forward` processes running for this user.
+pub fn scan_kubectl_port_forwards() -> Vec<KubectlPortForward> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+
+    system
+        .processes()
+        .values()
+        .filter_map(|process| {
+            let args: Vec<String> = process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+            parse_kubectl_port_forward(&args)
+        })
+        .collect()
+}
+
+/// Converts running `kubectl port-forward` processes into configs, resolving
+/// Service ports to target ports and pods to label selectors.
+pub async fn discover_kubectl_port_forwards_with_mode(
+    mode: DatabaseMode,
+) -> Result<DiscoveredConfigs, String> {
+    let forwards = scan_kubectl_port_forwards();
+    info!("Found {} kubectl port-forward process(es)", forwards.len());
+
+    let mut discovered = DiscoveredConfigs::default();
+    let mut seen = HashSet::new();
+
+    for forward in forwards {
+        match kubectl_forward_to_configs(&forward).await {
+            Ok(configs) => {
+                for config in configs {
+                    let key = (
+                        config.context.clone(),
+                        config.namespace.clone(),
+                        config.service.clone(),
+                        config.target.clone(),
+                        config.remote_port,
+                        config.local_port,
+                    );
+                    if seen.insert(key) {
+                        discovered.configs.push(config);
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Skipping kubectl port-forward {}/{}: {e}",
+                    forward.resource_kind, forward.resource_name
+                );
+                discovered.skipped.push(format!(
+                    "{}/{}: {e}",
+                    forward.resource_kind, forward.resource_name
+                ));
+            }
+        }
+    }
+
+    assign_local_ports_with_mode(&mut discovered.configs, mode).await?;
+    Ok(discovered)
+}
+
+pub async fn discover_kubectl_port_forwards() -> Result<DiscoveredConfigs, String> {
+    discover_kubectl_port_forwards_with_mode(DatabaseMode::File).await
+}
+
+/// One candidate config per port of every Service in `namespace` that has a
+/// selector, with local ports that do not clash with stored configs.
+pub async fn discover_service_configs_with_mode(
+    context: &str, kubeconfig: Option<String>, namespace: &str, mode: DatabaseMode,
+) -> Result<Vec<Config>, String> {
+    let (client, context) = client_for(kubeconfig.clone(), Some(context)).await?;
+    let services: Api<Service> = Api::namespaced(client, namespace);
+    let list = services
+        .list(&ListParams::default())
+        .await
+        .map_err(|e| format!("Failed to list services in '{namespace}': {e}"))?;
+
+    let mut configs = Vec::new();
+    for service in &list.items {
+        let Some(name) = service.metadata.name.clone() else {
+            continue;
+        };
+        let Some(spec) = &service.spec else {
+            continue;
+        };
+        if spec.selector.as_ref().is_none_or(BTreeMap::is_empty) {
+            debug!("Skipping service {name} without selector");
+            continue;
+        }
+
+        let ports = spec.ports.clone().unwrap_or_default();
+        for port in &ports {
+            let Some(target) = service_target_port(service, &port.port.to_string()) else {
+                continue;
+            };
+            let alias = match (&port.name, ports.len()) {
+                (Some(port_name), 2..) => format!("{name}-{port_name}"),
+                _ => name.clone(),
+            };
+            let protocol = port.protocol.as_deref().unwrap_or("TCP").to_lowercase();
+
+            configs.push(discovered_config(DiscoveredTarget {
+                context: &context,
+                kubeconfig: kubeconfig.clone(),
+                namespace,
+                workload_type: "service",
+                service: Some(name.clone()),
+                target: None,
+                alias,
+                local_port: None,
+                remote_port: target,
+                protocol,
+                local_address: None,
+            }));
+        }
+    }
+
+    configs.sort_by(|a, b| (&a.alias, a.remote_port).cmp(&(&b.alias, b.remote_port)));
+    assign_local_ports_with_mode(&mut configs, mode).await?;
+    Ok(configs)
+}
+
+pub async fn discover_service_configs(
+    context: &str, kubeconfig: Option<String>, namespace: &str,
+) -> Result<Vec<Config>, String> {
+    discover_service_configs_with_mode(context, kubeconfig, namespace, DatabaseMode::File).await
+}
+
+struct DiscoveredTarget<'a> {
+    context: &'a str,
+    kubeconfig: Option<String>,
+    namespace: &'a str,
+    workload_type: &'a str,
+    service: Option<String>,
+    target: Option<String>,
+    alias: String,
+    local_port: Option<u16>,
+    remote_port: u16,
+    protocol: String,
+    local_address: Option<String>,
+}
+
+fn discovered_config(found: DiscoveredTarget<'_>) -> Config {
+    Config {
+        id: None,
+        context: Some(found.context.to_string()),
+        kubeconfig: found.kubeconfig,
+        namespace: found.namespace.to_string(),
+        service: found.service,
+        alias: Some(found.alias),
+        local_port: found.local_port,
+        remote_port: Some(found.remote_port),
+        protocol: found.protocol,
+        workload_type: Some(found.workload_type.to_string()),
+        target: found.target,
+        local_address: found.local_address,
+        auto_loopback_address: false,
+        remote_address: None,
+        domain_enabled: None,
+        http_logs_enabled: None,
+        http_logs_max_file_size: None,
+        http_logs_retention_days: None,
+        http_logs_auto_cleanup: None,
+        exposure_type: None,
+        cert_manager_enabled: None,
+        cert_issuer: None,
+        cert_issuer_kind: None,
+        ingress_class: None,
+        ingress_annotations: None,
+        groups: None,
+        port_conflict_policy: None,
+        impersonate_user: None,
+        impersonate_groups: Vec::new(),
+        pod_selection: None,
+        drain_policy: None,
+        drain_timeout_secs: None,
+        debug_container: None,
+        proxy_relay: None,
+    }
+}
+
+async fn kubectl_forward_to_configs(forward: &KubectlPortForward) -> Result<Vec<Config>, String> {
+    let (client, context) =
+        client_for(forward.kubeconfig.clone(), forward.context.as_deref()).await?;
+    let namespace = forward
+        .namespace
+        .clone()
+        .unwrap_or_else(|| client.default_namespace().to_string());
+    let local_address = forward
+        .address
+        .clone()
+        .filter(|address| address != "localhost" && address != "127.0.0.1");
+    let name = &forward.resource_name;
+
+    let (workload_type, service, target, pod_spec, service_object) =
+        match forward.resource_kind.as_str() {
+            "service" => {
+                let services: Api<Service> = Api::namespaced(client.clone(), &namespace);
+                let service = services.get(name).await.map_err(|e| e.to_string())?;
+                ("service", Some(name.clone()), None, None, Some(service))
+            }
+            "pod" => {
+                let pods: Api<Pod> = Api::namespaced(client.clone(), &namespace);
+                let pod = pods.get(name).await.map_err(|e| e.to_string())?;
+                let selector: BTreeMap<String, String> = pod
+                    .metadata
+                    .labels
+                    .clone()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|(key, _)| !POD_INSTANCE_LABELS.contains(&key.as_str()))
+                    .collect();
+                (
+                    "pod",
+                    None,
+                    Some(selector_string(&selector)?),
+                    pod.spec,
+                    None,
+                )
+            }
+            "deployment" => {
+                let api: Api<Deployment> = Api::namespaced(client.clone(), &namespace);
+                let spec = api
+                    .get(name)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .spec
+                    .ok_or("deployment has no spec")?;
+                let selector = match_labels(&spec.selector)?;
+                ("pod", None, Some(selector), spec.template.spec, None)
+            }
+            "statefulset" => {
+                let api: Api<StatefulSet> = Api::namespaced(client.clone(), &namespace);
+                let spec = api
+                    .get(name)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .spec
+                    .ok_or("statefulset has no spec")?;
+                let selector = match_labels(&spec.selector)?;
+                ("pod", None, Some(selector), spec.template.spec, None)
+            }
+            "replicaset" => {
+                let api: Api<ReplicaSet> = Api::namespaced(client.clone(), &namespace);
+                let spec = api
+                    .get(name)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .spec
+                    .ok_or("replicaset has no spec")?;
+                let selector = match_labels(&spec.selector)?;
+                let template_spec = spec.template.and_then(|template| template.spec);
+                ("pod", None, Some(selector), template_spec, None)
+            }
+            kind => return Err(format!("unsupported resource kind '{kind}'")),
+        };
+
+    forward
+        .ports
+        .iter()
+        .map(|mapping| {
+            let remote_port = match (&service_object, &pod_spec) {
+                (Some(service), _) => service_target_port(service, &mapping.remote_port),
+                (None, Some(spec)) => container_port(spec, &mapping.remote_port),
+                (None, None) => mapping.remote_port.parse().ok(),
+            }
+            .ok_or_else(|| format!("port '{}' could not be resolved", mapping.remote_port))?;
+
+            Ok(discovered_config(DiscoveredTarget {
+                context: &context,
+                kubeconfig: forward.kubeconfig.clone(),
+                namespace: &namespace,
+                workload_type,
+                service: service.clone(),
+                target: target.clone(),
+                alias: name.clone(),
+                local_port: mapping.local_port,
+                remote_port,
+                protocol: "tcp".to_string(),
+                local_address: local_address.clone(),
+            }))
+        })
+        .collect()
+}
+
+async fn client_for(
+    kubeconfig: Option<String>, context: Option<&str>,
+) -> Result<(Client, String), String> {
+    let context = match context {
+        Some(context) => context.to_string(),
+        None => {
+            let paths =
+                get_kubeconfig_paths_from_option(kubeconfig.clone()).map_err(|e| e.to_string())?;
+            let (merged, _, _) = merge_kubeconfigs(&paths).map_err(|e| e.to_string())?;
+            merged
+                .current_context
+                .ok_or("no --context given and the kubeconfig has no current-context")?
+        }
+    };
+
+    let (client, _, _) = create_client_with_specific_context(kubeconfig, Some(&context))
+        .await
+        .map_err(|e| e.to_string())?;
+    let client =
+        client.ok_or_else(|| format!("Failed to create client for context '{context}'"))?;
+    Ok((client, context))
+}
+
+/// Maps a Service port, given by number or name, to the port it targets on
+/// the pods. Named target ports fall back to the Service port, like
+/// `extract_ports_from_service`.
+fn service_target_port(service: &Service, port: &str) -> Option<u16> {
+    let spec = service.spec.as_ref()?;
+    let service_port = spec
+        .ports
+        .as_ref()?
+        .iter()
+        .find(|p| p.port.to_string() == port || p.name.as_deref() == Some(port))?;
+
+    let target = match service_port.target_port {
+        Some(IntOrString::Int(target)) => target,
+        _ => service_port.port,
+    };
+    u16::try_from(target).ok()
+}
+
+fn container_port(spec: &PodSpec, port: &str) -> Option<u16> {
+    if let Ok(number) = port.parse() {
+        return Some(number);
+    }
+    spec.containers
+        .iter()
+        .flat_map(|container| container.ports.iter().flatten())
+        .find(|p| p.name.as_deref() == Some(port))
+        .and_then(|p| u16::try_from(p.container_port).ok())
+}
+
+fn match_labels(selector: &LabelSelector) -> Result<String, String> {
+    selector_string(&selector.match_labels.clone().unwrap_or_default())
+}
+
+fn selector_string(labels: &BTreeMap<String, String>) -> Result<String, String> {
+    if labels.is_empty() {
+        return Err("no labels to select pods by".to_string());
+    }
+    Ok(labels
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::{
+        Container,
+        ContainerPort,
+        ServicePort,
+        ServiceSpec,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_service_target_port() {
+        let service = Service {
+            spec: Some(ServiceSpec {
+                ports: Some(vec![
+                    ServicePort {
+                        name: Some("http".to_string()),
+                        port: 80,
+                        target_port: Some(IntOrString::Int(8080)),
+                        ..Default::default()
+                    },
+                    ServicePort {
+                        name: Some("grpc".to_string()),
+                        port: 9090,
+                        target_port: Some(IntOrString::String("grpc".to_string())),
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(service_target_port(&service, "80"), Some(8080));
+        assert_eq!(service_target_port(&service, "http"), Some(8080));
+        assert_eq!(service_target_port(&service, "grpc"), Some(9090));
+        assert_eq!(service_target_port(&service, "443"), None);
+    }
+
+    #[test]
+    fn test_container_port_by_name() {
+        let spec = PodSpec {
+            containers: vec![Container {
+                name: "web".to_string(),
+                ports: Some(vec![ContainerPort {
+                    name: Some("http".to_string()),
+                    container_port: 3000,
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(container_port(&spec, "http"), Some(3000));
+        assert_eq!(container_port(&spec, "5000"), Some(5000));
+        assert_eq!(container_port(&spec, "metrics"), None);
+    }
+
+    #[test]
+    fn test_selector_string_rejects_empty() {
+        assert!(selector_string(&BTreeMap::new()).is_err());
+
+        let labels = BTreeMap::from([
+            ("app".to_string(), "web".to_string()),
+            ("tier".to_string(), "frontend".to_string()),
+        ]);
+        assert_eq!(selector_string(&labels).unwrap(), "app=web,tier=frontend");
+    }
+}
@@ -0,0 +1,214 @@
+use std::sync::atomic::{
+    AtomicU64,
+    AtomicUsize,
+    Ordering,
+};
+use std::sync::{
+    Arc,
+    Mutex,
+};
+use std::time::Duration;
+
+use kftray_commons::utils::connection_drain::DrainPolicy;
+use tokio_util::sync::CancellationToken;
+use tracing::{
+    debug,
+    info,
+};
+
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Connections opened while the same pod was selected. They share a token so
+/// they can be closed together once the pod is replaced.
+#[derive(Clone)]
+struct Generation {
+    token: CancellationToken,
+    active: Arc<AtomicUsize>,
+}
+
+impl Generation {
+    fn new(parent: &CancellationToken) -> Self {
+        Self {
+            token: parent.child_token(),
+            active: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+/// Held by a forwarded connection for as long as it is open.
+pub struct ConnectionGuard {
+    active: Arc<AtomicUsize>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Applies the drain policy of a forward to its open connections when the
+/// pod watcher moves to another pod.
+pub struct ConnectionDrainer {
+    policy: DrainPolicy,
+    parent: CancellationToken,
+    current: Mutex<Generation>,
+    migrations: AtomicU64,
+    closed: Arc<AtomicU64>,
+}
+
+impl ConnectionDrainer {
+    pub fn new(policy: DrainPolicy, parent: CancellationToken) -> Self {
+        Self {
+            policy,
+            current: Mutex::new(Generation::new(&parent)),
+            parent,
+            migrations: AtomicU64::new(0),
+            closed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Token and guard for a new connection on the current pod.
+    pub fn register(&self) -> (CancellationToken, ConnectionGuard) {
+        let generation = self.current.lock().unwrap().clone();
+        generation.active.fetch_add(1, Ordering::Relaxed);
+        (
+            generation.token,
+            ConnectionGuard {
+                active: generation.active,
+            },
+        )
+    }
+
+    /// Number of pod changes handled so far.
+    pub fn migrations(&self) -> u64 {
+        self.migrations.load(Ordering::Relaxed)
+    }
+
+    /// Number of connections closed because their pod was replaced.
+    pub fn closed_connections(&self) -> u64 {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// Starts a new generation for `pod` and applies the policy to the
+    /// connections of the previous one.
+    pub fn migrate(&self, pod: &str) {
+        let previous = std::mem::replace(
+            &mut *self.current.lock().unwrap(),
+            Generation::new(&self.parent),
+        );
+        let migration = self.migrations.fetch_add(1, Ordering::Relaxed) + 1;
+        let open = previous.active.load(Ordering::Relaxed);
+
+        if open == 0 {
+            debug!("Pod changed to {pod} (migration {migration}), no open connections");
+            return;
+        }
+
+        match self.policy {
+            DrainPolicy::Keep => info!(
+                "Pod changed to {pod} (migration {migration}), keeping {open} connection(s) on the previous pod"
+            ),
+            DrainPolicy::Close => {
+                previous.token.cancel();
+                self.closed.fetch_add(open as u64, Ordering::Relaxed);
+                info!(
+                    "Pod changed to {pod} (migration {migration}), closed {open} connection(s) on the previous pod"
+                );
+            }
+            DrainPolicy::Drain(timeout) => {
+                info!(
+                    "Pod changed to {pod} (migration {migration}), draining {open} connection(s) for up to {}s",
+                    timeout.as_secs()
+                );
+                tokio::spawn(drain(previous, timeout, self.closed.clone(), migration));
+            }
+        }
+    }
+}
+
+async fn drain(previous: Generation, timeout: Duration, closed: Arc<AtomicU64>, migration: u64) {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    while previous.active.load(Ordering::Relaxed) > 0 && tokio::time::Instant::now() < deadline {
+        tokio::select! {
+            _ = previous.token.cancelled() => return,
+            _ = tokio::time::sleep(DRAIN_POLL_INTERVAL) => {}
+        }
+    }
+
+    let remaining = previous.active.load(Ordering::Relaxed);
+    closed.fetch_add(remaining as u64, Ordering::Relaxed);
+    previous.token.cancel();
+    if remaining > 0 {
+        info!("Drain of migration {migration} timed out, closed {remaining} connection(s)");
+    } else {
+        info!("Drain of migration {migration} finished, all connections closed by their clients");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_close_policy_cancels_previous_connections() {
+        let drainer = ConnectionDrainer::new(DrainPolicy::Close, CancellationToken::new());
+        let (old_token, _old_guard) = drainer.register();
+
+        drainer.migrate("api-2");
+        let (new_token, _new_guard) = drainer.register();
+
+        assert!(old_token.is_cancelled());
+        assert!(!new_token.is_cancelled());
+        assert_eq!(drainer.migrations(), 1);
+        assert_eq!(drainer.closed_connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_keep_policy_leaves_connections_open() {
+        let drainer = ConnectionDrainer::new(DrainPolicy::Keep, CancellationToken::new());
+        let (old_token, _old_guard) = drainer.register();
+
+        drainer.migrate("api-2");
+
+        assert!(!old_token.is_cancelled());
+        assert_eq!(drainer.closed_connections(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drain_policy_closes_after_timeout() {
+        let drainer = ConnectionDrainer::new(
+            DrainPolicy::Drain(Duration::from_millis(300)),
+            CancellationToken::new(),
+        );
+        let (lingering, _lingering_guard) = drainer.register();
+        let (finished, finished_guard) = drainer.register();
+
+        drainer.migrate("api-2");
+        drop(finished_guard);
+        assert!(!lingering.is_cancelled());
+
+        tokio::time::timeout(Duration::from_secs(2), lingering.cancelled())
+            .await
+            .unwrap();
+        assert!(finished.is_cancelled());
+        assert_eq!(drainer.closed_connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_drain_policy_finishes_when_clients_disconnect() {
+        let drainer = ConnectionDrainer::new(
+            DrainPolicy::Drain(Duration::from_secs(60)),
+            CancellationToken::new(),
+        );
+        let (token, guard) = drainer.register();
+
+        drainer.migrate("api-2");
+        drop(guard);
+
+        tokio::time::timeout(Duration::from_secs(2), token.cancelled())
+            .await
+            .unwrap();
+        assert_eq!(drainer.closed_connections(), 0);
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::HashSet;
+use std::sync::{
+    LazyLock,
+    Mutex,
+};
+
+use k8s_openapi::api::core::v1::Service;
+use k8s_openapi::api::discovery::v1::{
+    Endpoint,
+    EndpointSlice,
+};
+use kftray_commons::models::config_model::Config;
+use kube::Client;
+use kube::api::{
+    Api,
+    ListParams,
+};
+
+use crate::kube::shared_client::{
+    SHARED_CLIENT_MANAGER,
+    ServiceClientKey,
+};
+
+const SERVICE_NAME_LABEL: &str = "kubernetes.io/service-name";
+
+/// Configs of selector-less services currently forwarded through a proxy pod,
+/// so stopping them also removes the pod.
+static ENDPOINT_FORWARDS: LazyLock<Mutex<HashSet<i64>>> = LazyLock::new(Default::default);
+
+/// An address and port taken from the EndpointSlices of a Service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ServiceEndpoint {
+    pub address: String,
+    pub port: u16,
+}
+
+pub(crate) fn has_selector(service: &Service) -> bool {
+    service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.selector.as_ref())
+        .is_some_and(|selector| !selector.is_empty())
+}
+
+fn is_usable(endpoint: &Endpoint) -> bool {
+    endpoint.conditions.as_ref().is_none_or(|conditions| {
+        conditions.ready != Some(false) && conditions.terminating != Some(true)
+    })
+}
+
+/// Picks the first ready endpoint serving `remote_port`. Like kube-proxy, the
+/// Service port is mapped to the endpoint port of the same name. The number
+/// is only matched when the Service port has no name or `remote_port` is not
+/// a Service port.
+pub(crate) fn pick_endpoint(
+    service: &Service, slices: &[EndpointSlice], remote_port: u16,
+) -> Result<ServiceEndpoint, String> {
+    let service_name = service.metadata.name.clone().unwrap_or_default();
+    let service_port = service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.ports.as_ref())
+        .and_then(|ports| ports.iter().find(|p| p.port == i32::from(remote_port)));
+    // A single Service port may be unnamed, its endpoint port is then too.
+    let port_name = service_port.map(|p| p.name.clone().unwrap_or_default());
+
+    for slice in slices {
+        let ports = slice.ports.as_deref().unwrap_or_default();
+        let by_name = port_name.as_deref().and_then(|name| {
+            ports
+                .iter()
+                .find(|p| p.name.as_deref().unwrap_or_default() == name)
+        });
+        let port = match port_name.as_deref() {
+            Some(name) if !name.is_empty() => by_name,
+            _ => by_name.or_else(|| {
+                ports
+                    .iter()
+                    .find(|p| p.port == Some(i32::from(remote_port)))
+            }),
+        }
+        .and_then(|p| p.port)
+        .and_then(|p| u16::try_from(p).ok());
+        let Some(port) = port else {
+            continue;
+        };
+
+        let address = slice
+            .endpoints
+            .iter()
+            .filter(|endpoint| is_usable(endpoint))
+            .find_map(|endpoint| endpoint.addresses.first());
+        if let Some(address) = address {
+            return Ok(ServiceEndpoint {
+                address: address.clone(),
+                port,
+            });
+        }
+    }
+
+    Err(format!(
+        "Service '{service_name}' has no selector and no ready endpoint for port {remote_port}"
+    ))
+}
+
+/// Reads the EndpointSlices of a selector-less Service and picks the endpoint
+/// for `remote_port`.
+pub(crate) async fn resolve_service_endpoint(
+    client: &Client, namespace: &str, service: &Service, remote_port: u16,
+) -> Result<ServiceEndpoint, String> {
+    let service_name = service.metadata.name.clone().unwrap_or_default();
+    let slices: Api<EndpointSlice> = Api::namespaced(client.clone(), namespace);
+    let list = slices
+        .list(&ListParams::default().labels(&format!("{SERVICE_NAME_LABEL}={service_name}")))
+        .await
+        .map_err(|e| format!("Failed to list EndpointSlices of '{service_name}': {e}"))?;
+
+    pick_endpoint(service, &list.items, remote_port)
+}
+
+/// The proxy config forwarding `config` to `endpoint` through a kftray-server
+/// pod.
+pub(crate) fn proxy_config(config: &Config, endpoint: &ServiceEndpoint) -> Config {
+    let mut proxy = config.clone();
+    proxy.workload_type = Some("proxy".to_string());
+    proxy.remote_address = Some(endpoint.address.clone());
+    proxy.remote_port = Some(endpoint.port);
+    proxy
+}
+
+/// For a tcp service config whose Service has no selector, the proxy config
+/// to start instead. `None` when the Service selects its pods.
+pub(crate) async fn endpoint_proxy_config(config: &Config) -> Result<Option<Config>, String> {
+    let service_name = config.service.clone().unwrap_or_default();
+    let client = SHARED_CLIENT_MANAGER
+        .get_client(ServiceClientKey::for_config(config))
+        .await
+        .map_err(|e| e.to_string())?;
+    let client = Client::clone(&client);
+
+    let services: Api<Service> = Api::namespaced(client.clone(), &config.namespace);
+    let service = services
+        .get(&service_name)
+        .await
+        .map_err(|e| format!("Service '{service_name}' not found: {e}"))?;
+    if has_selector(&service) {
+        return Ok(None);
+    }
+
+    let remote_port = config.remote_port.unwrap_or_default();
+    let endpoint =
+        resolve_service_endpoint(&client, &config.namespace, &service, remote_port).await?;
+    Ok(Some(proxy_config(config, &endpoint)))
+}
+
+pub(crate) fn mark_endpoint_forward(config_id: i64) {
+    ENDPOINT_FORWARDS.lock().unwrap().insert(config_id);
+}
+
+/// Forgets the config, returning whether it was forwarded through a proxy pod.
+pub(crate) fn take_endpoint_forward(config_id: i64) -> bool {
+    ENDPOINT_FORWARDS.lock().unwrap().remove(&config_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::{
+        ServicePort,
+        ServiceSpec,
+    };
+    use k8s_openapi::api::discovery::v1::{
+        EndpointConditions,
+        EndpointPort,
+    };
+    use kube::api::ObjectMeta;
+
+    use super::*;
+
+    fn service(ports: &[(&str, i32)]) -> Service {
+        Service {
+            metadata: ObjectMeta {
+                name: Some("legacy-db".to_string()),
+                ..Default::default()
+            },
+            spec: Some(ServiceSpec {
+                ports: Some(
+                    ports
+                        .iter()
+                        .map(|(name, port)| ServicePort {
+                            name: Some(name.to_string()),
+                            port: *port,
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn endpoint(address: &str, ready: bool) -> Endpoint {
+        Endpoint {
+            addresses: vec![address.to_string()],
+            conditions: Some(EndpointConditions {
+                ready: Some(ready),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn slice(ports: &[(&str, i32)], endpoints: Vec<Endpoint>) -> EndpointSlice {
+        EndpointSlice {
+            address_type: "IPv4".to_string(),
+            endpoints,
+            ports: Some(
+                ports
+                    .iter()
+                    .map(|(name, port)| EndpointPort {
+                        name: Some(name.to_string()),
+                        port: Some(*port),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pick_endpoint_skips_unready_endpoints() {
+        let slices = vec![slice(
+            &[("pg", 5432)],
+            vec![endpoint("10.0.0.1", false), endpoint("10.0.0.2", true)],
+        )];
+
+        assert_eq!(
+            pick_endpoint(&service(&[("pg", 5432)]), &slices, 5432),
+            Ok(ServiceEndpoint {
+                address: "10.0.0.2".to_string(),
+                port: 5432,
+            })
+        );
+    }
+
+    #[test]
+    fn test_pick_endpoint_maps_service_port_by_name() {
+        let slices = vec![slice(&[("pg", 6432)], vec![endpoint("10.0.0.3", true)])];
+
+        assert_eq!(
+            pick_endpoint(&service(&[("pg", 5432)]), &slices, 5432),
+            Ok(ServiceEndpoint {
+                address: "10.0.0.3".to_string(),
+                port: 6432,
+            })
+        );
+        assert!(pick_endpoint(&service(&[("pg", 5432)]), &slices, 8080).is_err());
+    }
+
+    #[test]
+    fn test_pick_endpoint_prefers_port_name_over_number() {
+        let slices = vec![slice(
+            &[("metrics", 5432), ("pg", 6432)],
+            vec![endpoint("10.0.0.4", true)],
+        )];
+
+        assert_eq!(
+            pick_endpoint(&service(&[("pg", 5432), ("metrics", 9187)]), &slices, 5432),
+            Ok(ServiceEndpoint {
+                address: "10.0.0.4".to_string(),
+                port: 6432,
+            })
+        );
+    }
+
+    #[test]
+    fn test_pick_endpoint_unnamed_service_port() {
+        let slices = vec![slice(&[("", 8080)], vec![endpoint("10.0.0.5", true)])];
+        assert_eq!(
+            pick_endpoint(&service(&[("", 80)]), &slices, 80),
+            Ok(ServiceEndpoint {
+                address: "10.0.0.5".to_string(),
+                port: 8080,
+            })
+        );
+
+        let slices = vec![slice(&[("web", 8080)], vec![endpoint("10.0.0.6", true)])];
+        assert_eq!(
+            pick_endpoint(&service(&[]), &slices, 8080),
+            Ok(ServiceEndpoint {
+                address: "10.0.0.6".to_string(),
+                port: 8080,
+            })
+        );
+    }
+
+    #[test]
+    fn test_endpoint_forward_registry() {
+        mark_endpoint_forward(4901);
+        assert!(take_endpoint_forward(4901));
+        assert!(!take_endpoint_forward(4901));
+    }
+}
@@ -0,0 +1,100 @@
+use kftray_commons::models::response::CustomResponse;
+use kftray_commons::utils::config_group::get_group_configs_with_mode;
+use kftray_commons::utils::config_state::get_configs_state_with_mode;
+use kftray_commons::utils::config_validation::validate_config;
+use kftray_commons::utils::db_mode::DatabaseMode;
+use log::{
+    info,
+    warn,
+};
+
+use crate::kube::restart::{
+    start_config_with_mode,
+    stop_config_with_mode,
+};
+
+async fn running_config_ids(mode: DatabaseMode) -> Result<Vec<i64>, String> {
+    Ok(get_configs_state_with_mode(mode)
+        .await?
+        .into_iter()
+        .filter(|state| state.is_running)
+        .map(|state| state.config_id)
+        .collect())
+}
+
+/// Starts every config of the group that is not already running. Configs that
+/// fail validation or fail to start are reported in the error after the others
+/// were attempted.
+pub async fn start_group_with_mode(
+    group: &str, mode: DatabaseMode,
+) -> Result<Vec<CustomResponse>, String> {
+    let configs = get_group_configs_with_mode(group, mode).await?;
+    let running = running_config_ids(mode).await?;
+
+    let mut responses = Vec::new();
+    let mut errors = Vec::new();
+
+    for config in configs {
+        let config_id = config.id.unwrap_or_default();
+        if running.contains(&config_id) {
+            continue;
+        }
+
+        if let Err(e) = validate_config(&config).into_result() {
+            errors.push(format!("config {config_id}: {e}"));
+            continue;
+        }
+
+        match start_config_with_mode(config, mode).await {
+            Ok(started) => responses.extend(started),
+            Err(e) => errors.push(format!("config {config_id}: {e}")),
+        }
+    }
+
+    info!(
+        "Started {} port forwards of group '{group}'",
+        responses.len()
+    );
+
+    if errors.is_empty() {
+        Ok(responses)
+    } else {
+        Err(format!(
+            "Failed to start some port forwards of group '{group}': {}",
+            errors.join("; ")
+        ))
+    }
+}
+
+/// Stops every running config of the group.
+pub async fn stop_group_with_mode(
+    group: &str, mode: DatabaseMode,
+) -> Result<Vec<CustomResponse>, String> {
+    let configs = get_group_configs_with_mode(group, mode).await?;
+    let running = running_config_ids(mode).await?;
+
+    let mut responses = Vec::new();
+    let mut errors = Vec::new();
+
+    for config in configs
+        .iter()
+        .filter(|config| running.contains(&config.id.unwrap_or_default()))
+    {
+        match stop_config_with_mode(config, mode).await {
+            Ok(response) => responses.push(response),
+            Err(e) => {
+                warn!("Failed to stop config {:?}: {e}", config.id);
+                errors.push(format!("config {}: {e}", config.id.unwrap_or_default()));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(responses)
+    } else {
+        Err(format!(
+            "Failed to stop some port forwards of group '{group}': {}",
+            errors.join("; ")
+        ))
+    }
+}
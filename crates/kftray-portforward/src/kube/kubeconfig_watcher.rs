@@ -0,0 +1,307 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{
+    BTreeSet,
+    HashMap,
+};
+use std::hash::{
+    Hash,
+    Hasher,
+};
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::sync::OnceLock;
+use std::time::{
+    Duration,
+    SystemTime,
+};
+
+use kftray_commons::utils::db_mode::DatabaseMode;
+use kube::config::Kubeconfig;
+use log::{
+    info,
+    warn,
+};
+use tokio::task::JoinHandle;
+
+use crate::kube::client::get_kubeconfig_paths_from_option;
+use crate::kube::restart::restart_running_configs_with_mode;
+use crate::kube::shared_client::SHARED_CLIENT_MANAGER;
+
+/// How often the kubeconfig files behind cached clients are checked.
+pub const KUBECONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Tools like `aws eks update-kubeconfig` rewrite the file in several steps,
+/// so forwards are restarted only after the file had time to settle.
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+static WATCHER: OnceLock<JoinHandle<()>> = OnceLock::new();
+
+/// Modification time and size of a kubeconfig file, `None` when it is missing
+/// or unreadable.
+type FileStamp = Option<(SystemTime, u64)>;
+
+fn file_stamp(path: &Path) -> FileStamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// The kubeconfig files a client was built from and a hash of the context,
+/// cluster and user entries it resolved to at that time. Edits to other
+/// contexts of the same files leave the fingerprint unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KubeconfigFingerprint {
+    paths: Vec<PathBuf>,
+    stamps: Vec<FileStamp>,
+    context: Option<String>,
+    hash: u64,
+}
+
+impl KubeconfigFingerprint {
+    /// Fingerprints `context` in the files `kubeconfig` resolves to, the same
+    /// way clients resolve them. Without a context the current context is
+    /// used.
+    pub fn of(kubeconfig: Option<String>, context: Option<&str>) -> Self {
+        Self::from_paths(
+            get_kubeconfig_paths_from_option(kubeconfig).unwrap_or_default(),
+            context,
+        )
+    }
+
+    pub fn from_paths(paths: Vec<PathBuf>, context: Option<&str>) -> Self {
+        let mut files = KubeconfigFiles::default();
+        let context = context.map(String::from);
+        // Stamped before reading, so a write racing the read is seen later.
+        let stamps = files.stamps(&paths);
+        let hash = hash_context_entries(&files.merged(&paths), context.as_deref());
+        Self {
+            paths,
+            stamps,
+            context,
+            hash,
+        }
+    }
+
+    /// Whether the context, its cluster or its user changed, appeared or
+    /// disappeared since the fingerprint was taken. The files are only parsed
+    /// when their modification time or size moved; `files` shares the reads
+    /// between the fingerprints checked in one poll.
+    pub fn has_changed(&mut self, files: &mut KubeconfigFiles) -> bool {
+        let stamps = files.stamps(&self.paths);
+        if stamps == self.stamps {
+            return false;
+        }
+
+        self.stamps = stamps;
+        hash_context_entries(&files.merged(&self.paths), self.context.as_deref()) != self.hash
+    }
+}
+
+/// The kubeconfig files stat'ed and parsed during one poll, so files shared by
+/// several cached clients are read once.
+#[derive(Default)]
+pub struct KubeconfigFiles {
+    stamps: HashMap<PathBuf, FileStamp>,
+    parsed: HashMap<PathBuf, Option<Kubeconfig>>,
+}
+
+impl KubeconfigFiles {
+    fn stamps(&mut self, paths: &[PathBuf]) -> Vec<FileStamp> {
+        paths
+            .iter()
+            .map(|path| {
+                *self
+                    .stamps
+                    .entry(path.clone())
+                    .or_insert_with(|| file_stamp(path))
+            })
+            .collect()
+    }
+
+    /// Merges `paths` the way clients do. The files are read without the
+    /// logging of the client builder since this runs on every poll.
+    fn merged(&mut self, paths: &[PathBuf]) -> Kubeconfig {
+        paths
+            .iter()
+            .filter_map(|path| {
+                self.parsed
+                    .entry(path.clone())
+                    .or_insert_with(|| Kubeconfig::read_from(path).ok())
+                    .clone()
+            })
+            .try_fold(Kubeconfig::default(), Kubeconfig::merge)
+            .unwrap_or_default()
+    }
+}
+
+/// Hashes the entries `context` resolves to in the merged kubeconfig.
+fn hash_context_entries(merged: &Kubeconfig, context: Option<&str>) -> u64 {
+    let name = context
+        .map(String::from)
+        .or_else(|| merged.current_context.clone());
+    let named_context = merged
+        .contexts
+        .iter()
+        .find(|named| Some(&named.name) == name.as_ref());
+    let context = named_context.and_then(|named| named.context.as_ref());
+    let cluster = context.and_then(|context| {
+        merged
+            .clusters
+            .iter()
+            .find(|named| named.name == context.cluster)
+    });
+    let user = context
+        .and_then(|context| context.user.as_ref())
+        .and_then(|user| merged.auth_infos.iter().find(|named| &named.name == user));
+
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(&(&name, named_context, cluster, user))
+        .ok()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Starts the background task that evicts cached clients whose kubeconfig
+/// changed and restarts the running forwards that used them. Calling it again
+/// is a no-op.
+pub fn start_kubeconfig_watcher(mode: DatabaseMode) {
+    WATCHER.get_or_init(|| tokio::spawn(watch_kubeconfigs(mode)));
+}
+
+async fn watch_kubeconfigs(mode: DatabaseMode) {
+    let mut interval = tokio::time::interval(KUBECONFIG_POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        // Stat'ing and parsing the files blocks, so it stays off the runtime
+        // workers.
+        let evicted =
+            match tokio::task::spawn_blocking(|| SHARED_CLIENT_MANAGER.evict_changed_kubeconfigs())
+                .await
+            {
+                Ok(evicted) => evicted,
+                Err(e) => {
+                    warn!("Kubeconfig check failed: {e}");
+                    continue;
+                }
+            };
+        if evicted.is_empty() {
+            continue;
+        }
+
+        let config_ids: Vec<i64> = evicted
+            .iter()
+            .map(|key| key.config_id)
+            .filter(|config_id| *config_id >= 0)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        info!(
+            "Kubeconfig changed, dropped {} cached client(s), restarting configs {config_ids:?}",
+            evicted.len()
+        );
+
+        tokio::time::sleep(SETTLE_DELAY).await;
+
+        if let Err(e) = restart_running_configs_with_mode(&config_ids, mode).await {
+            warn!("Failed to re-establish forwards after kubeconfig change: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KUBECONFIG: &str = r#"
+apiVersion: v1
+kind: Config
+current-context: dev
+contexts:
+- name: dev
+  context:
+    cluster: dev
+    user: dev
+- name: prod
+  context:
+    cluster: prod
+    user: prod
+clusters:
+- name: dev
+  cluster:
+    server: https://dev.example.com
+- name: prod
+  cluster:
+    server: https://prod.example.com
+users:
+- name: dev
+  user:
+    token: dev-token
+- name: prod
+  user:
+    token: prod-token
+"#;
+
+    #[test]
+    fn test_fingerprint_detects_context_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config");
+        std::fs::write(&path, KUBECONFIG).unwrap();
+
+        let mut dev = KubeconfigFingerprint::from_paths(vec![path.clone()], Some("dev"));
+        let mut prod = KubeconfigFingerprint::from_paths(vec![path.clone()], Some("prod"));
+        let mut current = KubeconfigFingerprint::from_paths(vec![path.clone()], None);
+        assert!(
+            !dev.has_changed(&mut KubeconfigFiles::default())
+                && !prod.has_changed(&mut KubeconfigFiles::default())
+                && !current.has_changed(&mut KubeconfigFiles::default())
+        );
+
+        std::fs::write(&path, KUBECONFIG.replace("prod-token", "rotated")).unwrap();
+        assert!(!dev.has_changed(&mut KubeconfigFiles::default()));
+        assert!(!current.has_changed(&mut KubeconfigFiles::default()));
+        assert!(prod.has_changed(&mut KubeconfigFiles::default()));
+
+        std::fs::write(
+            &path,
+            KUBECONFIG.replace("https://dev.example.com", "https://dev2.example.com"),
+        )
+        .unwrap();
+        assert!(dev.has_changed(&mut KubeconfigFiles::default()));
+        assert!(!prod.has_changed(&mut KubeconfigFiles::default()));
+
+        std::fs::write(
+            &path,
+            KUBECONFIG.replace("current-context: dev", "current-context: prod"),
+        )
+        .unwrap();
+        assert!(!dev.has_changed(&mut KubeconfigFiles::default()));
+        assert!(current.has_changed(&mut KubeconfigFiles::default()));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(dev.has_changed(&mut KubeconfigFiles::default()));
+    }
+
+    #[test]
+    fn test_unchanged_files_are_not_parsed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config");
+        std::fs::write(&path, KUBECONFIG).unwrap();
+        let mut dev = KubeconfigFingerprint::from_paths(vec![path.clone()], Some("dev"));
+
+        let mut files = KubeconfigFiles::default();
+        assert!(!dev.has_changed(&mut files));
+        assert!(files.parsed.is_empty());
+
+        std::fs::write(&path, KUBECONFIG.replace("prod-token", "rotated")).unwrap();
+        let mut files = KubeconfigFiles::default();
+        assert!(!dev.has_changed(&mut files));
+        assert_eq!(files.parsed.len(), 1);
+
+        let mut files = KubeconfigFiles::default();
+        assert!(!dev.has_changed(&mut files));
+        assert!(files.parsed.is_empty());
+    }
+}
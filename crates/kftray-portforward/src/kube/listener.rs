@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use httparse::Request;
 use k8s_openapi::api::core::v1::Pod;
+use kftray_commons::utils::connection_drain::DrainPolicy;
 use kube::Api;
 use tokio::io::{
     AsyncReadExt,
@@ -19,6 +20,7 @@ use tracing::{
     info,
 };
 
+use crate::kube::drain::ConnectionDrainer;
 use crate::kube::http_log_watcher::HttpLogStateWatcher;
 use crate::kube::models::{
     Port,
@@ -78,15 +80,14 @@ pub struct PortForwarder {
     http_log_watcher: HttpLogStateWatcher,
     initialization_lock: Arc<tokio::sync::Mutex<bool>>,
     background_tasks: Arc<tokio::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>>,
+    drain_policy: DrainPolicy,
+    relay_header: Option<Arc<[u8]>>,
 }
 
 impl PortForwarder {
     pub async fn new(
-        namespace: &str, target: Target, context_name: Option<String>, kubeconfig: Option<String>,
-        config_id: i64,
+        namespace: &str, target: Target, client_key: crate::kube::shared_client::ServiceClientKey,
     ) -> anyhow::Result<Self> {
-        let client_key =
-            crate::kube::shared_client::ServiceClientKey::new(context_name, kubeconfig, config_id);
         let client = SHARED_CLIENT_MANAGER.get_client(client_key).await?;
         let pod_watcher = PodWatcher::new((*client).clone(), target.clone()).await?;
 
@@ -100,6 +101,8 @@ impl PortForwarder {
             http_log_watcher: HttpLogStateWatcher::new(),
             initialization_lock: Arc::new(tokio::sync::Mutex::new(false)),
             background_tasks: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            drain_policy: target.drain_policy,
+            relay_header: target.relay_header.map(Arc::from),
         })
     }
 
@@ -174,9 +177,12 @@ impl PortForwarder {
             portforwarder = Some(self.create_portforwarder(target_port).await?);
         }
 
-        let stream = self
+        let mut stream = self
             .get_stream_with_retry(portforwarder.unwrap(), target_port)
             .await?;
+        if let Some(header) = &self.relay_header {
+            stream.write_all(header).await?;
+        }
         self.spawn_next_portforwarder(target_port);
 
         Ok(Box::new(stream))
@@ -350,6 +356,10 @@ impl PortForwarder {
         let forwarder_clone = Arc::clone(&self);
         let cancel_token = cancellation_token.clone();
 
+        let drainer = Arc::new(ConnectionDrainer::new(
+            self.drain_policy,
+            cancellation_token.clone(),
+        ));
         let mut pod_change_rx = self.pod_watcher.subscribe_pod_changes();
         let mut last_pod_change = tokio::time::Instant::now();
         let mut pending_pod: Option<String> = None;
@@ -371,9 +381,10 @@ impl PortForwarder {
                         *next_pf = None;
                         drop(next_pf);
 
+                        drainer.migrate(&new_pod);
                         pending_pod = Some(new_pod.clone());
                         last_pod_change = tokio::time::Instant::now();
-                        debug!("Pod change detected: {}, killed connections, debouncing for 3s", new_pod);
+                        debug!("Pod change detected: {}, debouncing for 3s", new_pod);
                     }
                     continue;
                 }
@@ -401,10 +412,11 @@ impl PortForwarder {
             let forwarder = Arc::clone(&forwarder_clone);
             let mut tcp_forwarder = tcp_forwarder.clone();
             let http_log_watcher_clone = Arc::new(self.http_log_watcher.clone());
-            let cancel_token_clone = cancel_token.clone();
+            let (cancel_token_clone, connection_guard) = drainer.register();
             let tls_acceptor_clone = tls_acceptor.clone();
 
             tokio::spawn(async move {
+                let _connection_guard = connection_guard;
                 let upstream_stream = match forwarder.get_stream().await {
                     Ok(stream) => stream,
                     Err(e) => {
@@ -454,6 +466,13 @@ impl PortForwarder {
             });
         }
 
+        debug!(
+            "Listener on port {} stopped after {} pod change(s), {} connection(s) closed by the drain policy",
+            port,
+            drainer.migrations(),
+            drainer.closed_connections()
+        );
+
         Ok(())
     }
 
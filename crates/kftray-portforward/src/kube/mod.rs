@@ -1,12 +1,23 @@
 pub mod client;
+mod debug_container;
+mod discovery;
+mod drain;
+mod endpoints;
+mod group;
 pub mod http_log_watcher;
+pub mod kubeconfig_watcher;
 pub mod listener;
 pub mod models;
 pub mod operations;
+mod plan;
+pub mod pod_selection;
 pub mod pod_watcher;
 mod proxy;
+pub mod reconcile;
+mod restart;
 mod service;
 pub mod shared_client;
+mod shared_relay;
 mod start;
 mod stop;
 pub mod tcp_forwarder;
@@ -15,21 +26,47 @@ pub mod udp_forwarder;
 #[cfg(test)]
 mod tests;
 
+pub use discovery::{
+    DiscoveredConfigs,
+    discover_kubectl_port_forwards,
+    discover_kubectl_port_forwards_with_mode,
+    discover_service_configs,
+    discover_service_configs_with_mode,
+    scan_kubectl_port_forwards,
+};
+pub use group::{
+    start_group_with_mode,
+    stop_group_with_mode,
+};
 pub use http_log_watcher::{
     HttpLogStateEvent,
     HttpLogStateWatcher,
 };
+pub use kubeconfig_watcher::start_kubeconfig_watcher;
 pub use listener::{
     ListenerConfig,
     PortForwarder,
     Protocol,
 };
+pub use plan::{
+    ForwardPlan,
+    PlanStatus,
+    PlanStep,
+    plan_port_forward,
+    plan_port_forward_with_mode,
+    render_plans,
+};
 pub use proxy::{
     deploy_and_forward_pod,
     deploy_and_forward_pod_with_mode,
     stop_proxy_forward,
     stop_proxy_forward_with_mode,
 };
+pub use restart::{
+    restart_port_forward_with_mode,
+    restart_running_configs_with_mode,
+    stop_running_configs_with_mode,
+};
 pub use service::retrieve_service_configs;
 pub use start::{
     start_port_forward,
@@ -6,6 +6,8 @@ use k8s_openapi::api::core::v1::{
     Service,
 };
 use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kftray_commons::models::config_model::PodSelectionPreferences;
+use kftray_commons::utils::connection_drain::DrainPolicy;
 use kube::api::Api;
 use serde::{
     Deserialize,
@@ -14,6 +16,8 @@ use serde::{
 use tokio::sync::Mutex;
 use tracing::debug;
 
+use crate::kube::client::Impersonation;
+
 impl NameSpace {
     pub fn name_any(&self) -> String {
         self.0.clone().unwrap_or_else(|| "default".to_string())
@@ -64,9 +68,29 @@ impl Target {
             selector,
             port: port.into(),
             namespace: NameSpace(namespace.into().map(Into::into)),
+            selection: PodSelectionPreferences::default(),
+            drain_policy: DrainPolicy::default(),
+            relay_header: None,
         }
     }
 
+    pub fn with_selection(mut self, selection: PodSelectionPreferences) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub fn with_drain_policy(mut self, drain_policy: DrainPolicy) -> Self {
+        self.drain_policy = drain_policy;
+        self
+    }
+
+    /// Header written on every upstream stream before any client data, naming
+    /// the destination for a shared relay pod.
+    pub fn with_relay_header(mut self, relay_header: Vec<u8>) -> Self {
+        self.relay_header = Some(relay_header);
+        self
+    }
+
     pub fn find(&self, pod: &Pod, port: Option<Port>) -> anyhow::Result<TargetPod> {
         let port = port.as_ref().unwrap_or(&self.port);
         let pod_name = pod.metadata.name.as_ref().context("Pod Name is None")?;
@@ -164,6 +188,7 @@ pub struct PortForward {
     pub context_name: Option<String>,
     pub kubeconfig: Option<String>,
     pub config_id: i64,
+    pub impersonation: Impersonation,
     pub workload_type: String,
     pub connection: Arc<Mutex<Option<tokio::net::TcpStream>>>,
 }
@@ -172,6 +197,7 @@ pub struct PortForward {
 pub enum TargetSelector {
     ServiceName(String),
     PodLabel(String),
+    PodName(String),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -185,6 +211,9 @@ pub struct Target {
     pub selector: TargetSelector,
     pub port: Port,
     pub namespace: NameSpace,
+    pub selection: PodSelectionPreferences,
+    pub drain_policy: DrainPolicy,
+    pub relay_header: Option<Vec<u8>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
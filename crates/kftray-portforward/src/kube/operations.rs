@@ -12,7 +12,10 @@ use kube::{
     Api,
     Client,
 };
-use log::info;
+use log::{
+    info,
+    warn,
+};
 
 use super::client::create_client_with_specific_context;
 use super::client::error::{
@@ -35,6 +38,34 @@ pub async fn list_all_namespaces(client: Client) -> KubeResult<Vec<String>> {
     Ok(namespace_names)
 }
 
+/// Namespaces to scan when discovering services. An explicit list skips the
+/// cluster-wide namespace list, which users with namespace-scoped RBAC are not
+/// allowed to read. Without one, a forbidden list falls back to the namespace
+/// of the context.
+pub async fn discovery_namespaces(
+    client: Client, namespaces: Option<Vec<String>>,
+) -> KubeResult<Vec<String>> {
+    let explicit: Vec<String> = namespaces
+        .unwrap_or_default()
+        .into_iter()
+        .map(|namespace| namespace.trim().to_string())
+        .filter(|namespace| !namespace.is_empty())
+        .collect();
+    if !explicit.is_empty() {
+        return Ok(explicit);
+    }
+
+    let default_namespace = client.default_namespace().to_string();
+    match list_all_namespaces(client).await {
+        Ok(namespaces) => Ok(namespaces),
+        Err(e) if e.is_forbidden() => {
+            warn!("Not allowed to list namespaces, using '{default_namespace}' only: {e}");
+            Ok(vec![default_namespace])
+        }
+        Err(e) => Err(e),
+    }
+}
+
 pub async fn get_services_with_annotation(
     client: Client, namespace: &str, _: &str,
 ) -> KubeResult<Vec<ServiceInfo>> {
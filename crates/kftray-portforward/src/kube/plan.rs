@@ -0,0 +1,644 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::time::Duration;
+
+use k8s_openapi::api::core::v1::{
+    Namespace,
+    Pod,
+    Service,
+};
+use k8s_openapi::chrono::Utc;
+use kftray_commons::{
+    models::config_model::Config,
+    utils::{
+        config_dir::{
+            get_pod_manifest_path,
+            get_proxy_deployment_manifest_path,
+        },
+        config_validation::{
+            IssueSeverity,
+            config_label,
+            validate_config_with_mode,
+        },
+        db_mode::DatabaseMode,
+        local_port::PortConflictPolicy,
+        settings::get_app_settings,
+    },
+};
+use kube::{
+    Client,
+    api::{
+        Api,
+        ListParams,
+    },
+};
+use serde::Serialize;
+
+use crate::kube::client::{
+    Impersonation,
+    create_client_with_impersonation,
+    get_kubeconfig_paths_from_option,
+    merge_kubeconfigs,
+};
+use crate::kube::debug_container::describe_relay;
+use crate::kube::endpoints::{
+    proxy_config,
+    resolve_service_endpoint,
+};
+use crate::kube::operations::extract_ports_from_service;
+use crate::kube::pod_selection::{
+    NodeZoneCache,
+    SelectionContext,
+    select_pod,
+};
+use crate::kube::proxy::{
+    proxy_manifest_values,
+    render_json_template,
+    should_use_deployment_manifest,
+};
+use crate::kube::shared_relay::{
+    relay_forward_for,
+    relay_manifest_values,
+    uses_shared_relay,
+};
+use crate::network_utils::is_custom_loopback_address;
+
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// One resolved step of a start, e.g. which context or pod would be used.
+#[derive(Clone, Debug, Serialize)]
+pub struct PlanStep {
+    pub step: String,
+    pub status: PlanStatus,
+    pub detail: String,
+}
+
+/// What starting a config would do, resolved without binding ports, writing
+/// the hosts file or creating anything in the cluster.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ForwardPlan {
+    pub config_id: Option<i64>,
+    pub label: String,
+    pub steps: Vec<PlanStep>,
+    /// Rendered proxy manifest for proxy and UDP configs.
+    pub manifest: Option<String>,
+}
+
+impl ForwardPlan {
+    fn new(config: &Config) -> Self {
+        ForwardPlan {
+            config_id: config.id,
+            label: config_label(config),
+            ..Default::default()
+        }
+    }
+
+    fn ok(&mut self, step: &str, detail: impl Into<String>) {
+        self.push(PlanStatus::Ok, step, detail.into());
+    }
+
+    fn warning(&mut self, step: &str, detail: impl Into<String>) {
+        self.push(PlanStatus::Warning, step, detail.into());
+    }
+
+    fn error(&mut self, step: &str, detail: impl Into<String>) {
+        self.push(PlanStatus::Error, step, detail.into());
+    }
+
+    fn push(&mut self, status: PlanStatus, step: &str, detail: String) {
+        self.steps.push(PlanStep {
+            step: step.to_string(),
+            status,
+            detail,
+        });
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.steps.iter().any(|s| s.status == PlanStatus::Error)
+    }
+
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{} [{}]\n",
+            self.label,
+            if self.has_errors() {
+                "would fail"
+            } else {
+                "ready"
+            }
+        );
+        for step in &self.steps {
+            let marker = match step.status {
+                PlanStatus::Ok => "ok",
+                PlanStatus::Warning => "warn",
+                PlanStatus::Error => "error",
+            };
+            let _ = writeln!(out, "  {marker:<5} {}: {}", step.step, step.detail);
+        }
+        out
+    }
+}
+
+/// Renders a list of plans as one readable report.
+pub fn render_plans(plans: &[ForwardPlan]) -> String {
+    if plans.is_empty() {
+        return "No configs to plan".to_string();
+    }
+    plans
+        .iter()
+        .map(ForwardPlan::summary)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub async fn plan_port_forward(configs: Vec<Config>) -> Vec<ForwardPlan> {
+    plan_port_forward_with_mode(configs, DatabaseMode::File).await
+}
+
+pub async fn plan_port_forward_with_mode(
+    configs: Vec<Config>, mode: DatabaseMode,
+) -> Vec<ForwardPlan> {
+    let ssl_enabled = get_app_settings()
+        .await
+        .map(|s| s.ssl_enabled)
+        .unwrap_or(false);
+
+    let mut plans = Vec::with_capacity(configs.len());
+    for config in &configs {
+        plans.push(plan_config(config, mode, ssl_enabled).await);
+    }
+    plans
+}
+
+async fn plan_config(config: &Config, mode: DatabaseMode, ssl_enabled: bool) -> ForwardPlan {
+    let mut plan = ForwardPlan::new(config);
+
+    match validate_config_with_mode(config, mode).await {
+        Ok(validation) => {
+            for issue in validation.issues {
+                match issue.severity {
+                    IssueSeverity::Error => plan.error("config", issue.to_string()),
+                    IssueSeverity::Warning => plan.warning("config", issue.to_string()),
+                }
+            }
+        }
+        Err(e) => plan.warning("config", format!("could not run validation: {e}")),
+    }
+
+    if let Some(client) = plan_client(config, &mut plan).await {
+        plan_namespace(&client, config, &mut plan).await;
+        plan_target(&client, config, &mut plan).await;
+    }
+
+    if config.workload_type.as_deref() != Some("expose") {
+        plan_local_endpoint(config, &mut plan).await;
+        plan_hosts(config, ssl_enabled, &mut plan);
+    }
+
+    plan
+}
+
+async fn plan_client(config: &Config, plan: &mut ForwardPlan) -> Option<Client> {
+    let paths = match get_kubeconfig_paths_from_option(config.kubeconfig.clone()) {
+        Ok(paths) => paths,
+        Err(e) => {
+            plan.error("kubeconfig", e.to_string());
+            return None;
+        }
+    };
+    plan.ok(
+        "kubeconfig",
+        paths
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+
+    let (kubeconfig, contexts, errors) = match merge_kubeconfigs(&paths) {
+        Ok(merged) => merged,
+        Err(e) => {
+            plan.error("kubeconfig", e.to_string());
+            return None;
+        }
+    };
+    for e in errors {
+        plan.warning("kubeconfig", e);
+    }
+
+    let context = match config.context.as_deref().filter(|c| !c.is_empty()) {
+        None => {
+            plan.error("context", "no context set");
+            return None;
+        }
+        Some("@current") => {
+            let Some(current) = kubeconfig.current_context.clone() else {
+                plan.error("context", "the kubeconfig has no current-context");
+                return None;
+            };
+            current
+        }
+        Some(context) => context.to_string(),
+    };
+    if !contexts.contains(&context) {
+        plan.error(
+            "context",
+            format!("'{context}' is not defined in the kubeconfig"),
+        );
+        return None;
+    }
+    plan.ok("context", context.clone());
+
+    let impersonation = Impersonation::of(config);
+    match create_client_with_impersonation(
+        config.kubeconfig.clone(),
+        Some(&context),
+        &impersonation,
+    )
+    .await
+    {
+        Ok((Some(client), _, _)) => {
+            plan.ok("cluster", "client created");
+            if let Some(user) = &impersonation.user {
+                plan.ok("identity", format!("impersonating '{user}'"));
+            }
+            Some(client)
+        }
+        Ok((None, _, _)) => {
+            plan.error(
+                "cluster",
+                format!("could not create a client for '{context}'"),
+            );
+            None
+        }
+        Err(e) => {
+            plan.error("cluster", e.to_string());
+            None
+        }
+    }
+}
+
+async fn plan_namespace(client: &Client, config: &Config, plan: &mut ForwardPlan) {
+    let namespaces: Api<Namespace> = Api::all(client.clone());
+    match namespaces.get_opt(&config.namespace).await {
+        Ok(Some(_)) => plan.ok("namespace", format!("'{}' exists", config.namespace)),
+        Ok(None) => plan.error(
+            "namespace",
+            format!("'{}' does not exist", config.namespace),
+        ),
+        Err(e) => plan.warning(
+            "namespace",
+            format!("could not check '{}': {e}", config.namespace),
+        ),
+    }
+}
+
+async fn plan_target(client: &Client, config: &Config, plan: &mut ForwardPlan) {
+    match (config.workload_type.as_deref(), config.protocol.as_str()) {
+        (Some("expose"), _) => plan_expose(config, plan),
+        (Some("pod"), _) => {
+            let selector = config.target.clone().unwrap_or_default();
+            plan_pods(client, config, &selector, plan).await;
+        }
+        (Some("service"), "udp") | (Some("proxy"), _) => plan_proxy(config, plan),
+        _ => plan_service(client, config, plan).await,
+    }
+}
+
+async fn plan_service(client: &Client, config: &Config, plan: &mut ForwardPlan) {
+    let name = config.service.clone().unwrap_or_default();
+    let services: Api<Service> = Api::namespaced(client.clone(), &config.namespace);
+    let service = match services.get_opt(&name).await {
+        Ok(Some(service)) => service,
+        Ok(None) => {
+            plan.error(
+                "service",
+                format!("'{name}' not found in '{}'", config.namespace),
+            );
+            return;
+        }
+        Err(e) => {
+            plan.error("service", format!("could not get '{name}': {e}"));
+            return;
+        }
+    };
+
+    let ports = extract_ports_from_service(&service);
+    let mut listed: Vec<String> = ports
+        .iter()
+        .map(|(name, port)| format!("{name}={port}"))
+        .collect();
+    listed.sort();
+    match config.remote_port {
+        Some(remote_port) if ports.values().any(|p| *p == i32::from(remote_port)) => plan.ok(
+            "ports",
+            format!(
+                "remote port {remote_port} matches a target port ({})",
+                listed.join(", ")
+            ),
+        ),
+        Some(remote_port) => plan.warning(
+            "ports",
+            format!(
+                "remote port {remote_port} is not a target port of '{name}' ({})",
+                listed.join(", ")
+            ),
+        ),
+        None => plan.error("ports", "remote port is not set"),
+    }
+
+    let selector = service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.selector.as_ref())
+        .filter(|selector| !selector.is_empty())
+        .map(|selector| {
+            let mut pairs: Vec<String> = selector.iter().map(|(k, v)| format!("{k}={v}")).collect();
+            pairs.sort();
+            pairs.join(",")
+        });
+    match selector {
+        Some(selector) => {
+            plan.ok("selector", selector.clone());
+            plan_pods(client, config, &selector, plan).await;
+        }
+        None => {
+            let remote_port = config.remote_port.unwrap_or_default();
+            match resolve_service_endpoint(client, &config.namespace, &service, remote_port).await {
+                Ok(endpoint) => {
+                    plan.ok(
+                        "selector",
+                        format!("'{name}' has no selector, using its EndpointSlices"),
+                    );
+                    plan_proxy(&proxy_config(config, &endpoint), plan);
+                }
+                Err(e) => plan.error("selector", e),
+            }
+        }
+    }
+}
+
+async fn plan_pods(client: &Client, config: &Config, selector: &str, plan: &mut ForwardPlan) {
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &config.namespace);
+    let list = match pods.list(&ListParams::default().labels(selector)).await {
+        Ok(list) => list,
+        Err(e) => {
+            plan.error("pod", format!("could not list pods for '{selector}': {e}"));
+            return;
+        }
+    };
+
+    if list.items.is_empty() {
+        plan.error("pod", format!("no pods match '{selector}'"));
+        return;
+    }
+
+    let preferences = config.pod_selection.clone().unwrap_or_default();
+    let zones = match preferences.preferred_zone {
+        Some(_) => Some(
+            NodeZoneCache::new(client.clone())
+                .zones_for(&list.items)
+                .await,
+        ),
+        None => None,
+    };
+    let context = SelectionContext {
+        node_zones: zones.as_ref(),
+        current: None,
+    };
+    let (pod, report) = select_pod(&list.items, &preferences, &context, Utc::now());
+
+    if preferences.is_set() {
+        for line in report.explain().lines() {
+            plan.ok("pod candidate", line);
+        }
+    }
+
+    match pod {
+        Some(pod) => {
+            plan.ok(
+                "pod",
+                format!(
+                    "would forward to '{}' ({} pod(s) match)",
+                    pod.metadata.name.clone().unwrap_or_default(),
+                    list.items.len()
+                ),
+            );
+            if let Some(detail) = describe_relay(config, pod) {
+                plan.ok("debug container", detail);
+            }
+        }
+        None if preferences.is_set() => plan.error(
+            "pod",
+            format!(
+                "{} pod(s) match '{selector}' but none fits the pod selection",
+                list.items.len()
+            ),
+        ),
+        None => plan.error(
+            "pod",
+            format!(
+                "{} pod(s) match '{selector}' but none is ready",
+                list.items.len()
+            ),
+        ),
+    }
+}
+
+fn plan_proxy(config: &Config, plan: &mut ForwardPlan) {
+    let prepared = if uses_shared_relay(config) {
+        relay_forward_for(config).map(|forward| {
+            let destination = forward.target();
+            (
+                relay_manifest_values(&forward),
+                destination,
+                Some(forward.name),
+            )
+        })
+    } else {
+        proxy_manifest_values(config, "kftray-forward-<generated>").map(|values| {
+            let destination = format!("{}:{}", values["remote_address"], values["remote_port"]);
+            (values, destination, None)
+        })
+    };
+    let (values, destination, shared_relay) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            plan.error("proxy", e);
+            return;
+        }
+    };
+
+    let use_deployment = should_use_deployment_manifest();
+    let (kind, path) = if use_deployment {
+        ("Deployment", get_proxy_deployment_manifest_path())
+    } else {
+        ("Pod", get_pod_manifest_path())
+    };
+    let contents = match path
+        .map_err(|e| e.to_string())
+        .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()))
+    {
+        Ok(contents) => contents,
+        Err(e) => {
+            plan.error("proxy", format!("could not read the {kind} manifest: {e}"));
+            return;
+        }
+    };
+
+    let rendered = render_json_template(&contents, &values);
+    match serde_json::from_str::<serde_json::Value>(&rendered) {
+        Ok(manifest) => {
+            let detail = match shared_relay {
+                Some(name) => format!(
+                    "would use the shared relay {kind} '{name}' in '{}', creating it if needed, relaying to {destination}; falls back to a dedicated proxy pod if the relay can't start or doesn't allow this target",
+                    config.namespace
+                ),
+                None => format!(
+                    "would deploy a {kind} in '{}' relaying to {destination}",
+                    config.namespace
+                ),
+            };
+            plan.ok("proxy", detail);
+            plan.manifest = serde_json::to_string_pretty(&manifest).ok();
+        }
+        Err(e) => plan.error("proxy", format!("rendered {kind} manifest is invalid: {e}")),
+    }
+}
+
+fn plan_expose(config: &Config, plan: &mut ForwardPlan) {
+    plan.ok(
+        "expose",
+        format!(
+            "would create {} expose resources for '{}' in '{}' reaching local port {}",
+            config.exposure_type.as_deref().unwrap_or("cluster"),
+            config.alias.clone().unwrap_or_default(),
+            config.namespace,
+            config.local_port.unwrap_or_default()
+        ),
+    );
+}
+
+async fn plan_local_endpoint(config: &Config, plan: &mut ForwardPlan) {
+    let address = if config.auto_loopback_address {
+        plan.ok(
+            "local address",
+            "a free loopback address would be allocated",
+        );
+        None
+    } else {
+        let address = config
+            .local_address
+            .clone()
+            .unwrap_or_else(|| "127.0.0.1".to_string());
+        if is_custom_loopback_address(&address) {
+            plan.ok(
+                "local address",
+                format!("{address} (added as a loopback alias if missing)"),
+            );
+        } else {
+            plan.ok("local address", address.clone());
+        }
+        Some(address)
+    };
+
+    match config.local_port {
+        None | Some(0) => plan.ok("local port", "a free port would be picked"),
+        Some(port) => match address {
+            Some(address) if config.protocol == "tcp" => {
+                let probe = tokio::time::timeout(
+                    PORT_PROBE_TIMEOUT,
+                    tokio::net::TcpStream::connect((address.as_str(), port)),
+                )
+                .await;
+                if matches!(probe, Ok(Ok(_))) {
+                    match PortConflictPolicy::of(config) {
+                        PortConflictPolicy::Fail => {
+                            plan.error("local port", format!("{address}:{port} is already in use"))
+                        }
+                        PortConflictPolicy::Next => plan.warning(
+                            "local port",
+                            format!("{address}:{port} is in use, the next free port would be used"),
+                        ),
+                        PortConflictPolicy::Random => plan.warning(
+                            "local port",
+                            format!("{address}:{port} is in use, a random free port would be used"),
+                        ),
+                    }
+                } else {
+                    plan.ok("local port", port.to_string());
+                }
+            }
+            _ => plan.ok("local port", port.to_string()),
+        },
+    }
+}
+
+fn plan_hosts(config: &Config, ssl_enabled: bool, plan: &mut ForwardPlan) {
+    let alias = config.alias.clone().filter(|a| !a.is_empty());
+
+    if config.domain_enabled.unwrap_or_default() && config.service.is_some() {
+        match &alias {
+            Some(alias) => plan.ok(
+                "hosts",
+                format!("would add '{alias}' pointing at the local address"),
+            ),
+            None => plan.warning("hosts", "domain is enabled but no alias is set"),
+        }
+    }
+
+    if ssl_enabled && let Some(alias) = &alias {
+        plan.ok(
+            "ssl",
+            format!("would add HTTPS hosts entries and a certificate for '{alias}'"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_summary_marks_failures() {
+        let mut plan = ForwardPlan {
+            label: "db".to_string(),
+            ..Default::default()
+        };
+        plan.ok("context", "dev");
+        assert!(plan.summary().starts_with("db [ready]"));
+
+        plan.error("namespace", "'missing' does not exist");
+        let summary = plan.summary();
+        assert!(summary.starts_with("db [would fail]"));
+        assert!(summary.contains("error namespace: 'missing' does not exist"));
+    }
+
+    #[test]
+    fn test_plan_hosts_reports_alias_and_ssl() {
+        let config = Config {
+            alias: Some("db.local".to_string()),
+            domain_enabled: Some(true),
+            ..Config::default()
+        };
+        let mut plan = ForwardPlan::new(&config);
+        plan_hosts(&config, true, &mut plan);
+
+        let steps: Vec<&str> = plan.steps.iter().map(|s| s.step.as_str()).collect();
+        assert_eq!(steps, vec!["hosts", "ssl"]);
+        assert!(!plan.has_errors());
+    }
+
+    #[test]
+    fn test_render_plans_empty() {
+        assert_eq!(render_plans(&[]), "No configs to plan");
+    }
+}
@@ -0,0 +1,529 @@
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use k8s_openapi::api::core::v1::{
+    Node,
+    Pod,
+};
+use k8s_openapi::chrono::{
+    DateTime,
+    Utc,
+};
+use kftray_commons::models::config_model::PodSelectionPreferences;
+use kube::{
+    Api,
+    Client,
+    ResourceExt,
+};
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+/// Node label holding the zone, used for `preferred_zone`.
+pub const ZONE_LABEL: &str = "topology.kubernetes.io/zone";
+
+/// Facts about the cluster that are not part of the pod itself.
+#[derive(Default)]
+pub struct SelectionContext<'a> {
+    /// Zone of each node, for the nodes that could be looked up.
+    pub node_zones: Option<&'a HashMap<String, String>>,
+    /// Pod currently in use. It wins ties, so forwards don't move between
+    /// equally good pods.
+    pub current: Option<&'a str>,
+}
+
+/// Zones of the nodes pods run on, looked up once per node. Nodes that can't
+/// be read (e.g. no RBAC on nodes) are retried on the next selection.
+#[derive(Clone)]
+pub struct NodeZoneCache {
+    client: Client,
+    zones: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl NodeZoneCache {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            zones: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn zones_for(&self, pods: &[Pod]) -> HashMap<String, String> {
+        let nodes: HashSet<&str> = pods
+            .iter()
+            .filter_map(|pod| pod.spec.as_ref()?.node_name.as_deref())
+            .collect();
+
+        let missing: Vec<&str> = {
+            let zones = self.zones.read().await;
+            nodes
+                .iter()
+                .copied()
+                .filter(|node| !zones.contains_key(*node))
+                .collect()
+        };
+
+        if !missing.is_empty() {
+            let api: Api<Node> = Api::all(self.client.clone());
+            for node in missing {
+                match api.get(node).await {
+                    Ok(found) => {
+                        let zone = found.labels().get(ZONE_LABEL).cloned().unwrap_or_default();
+                        self.zones.write().await.insert(node.to_string(), zone);
+                    }
+                    Err(e) => debug!("Could not read zone of node {node}: {e}"),
+                }
+            }
+        }
+
+        self.zones
+            .read()
+            .await
+            .iter()
+            .filter(|(node, zone)| nodes.contains(node.as_str()) && !zone.is_empty())
+            .map(|(node, zone)| (node.clone(), zone.clone()))
+            .collect()
+    }
+}
+
+/// One pod considered for a forward and why it was or wasn't chosen.
+#[derive(Debug, Clone, Serialize)]
+pub struct PodCandidate {
+    pub name: String,
+    pub node: Option<String>,
+    pub eligible: bool,
+    pub notes: Vec<String>,
+}
+
+/// Outcome of a pod selection, kept for the plan and logs.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PodSelectionReport {
+    pub chosen: Option<String>,
+    pub candidates: Vec<PodCandidate>,
+}
+
+impl PodSelectionReport {
+    pub fn explain(&self) -> String {
+        let mut out = String::new();
+        for candidate in &self.candidates {
+            let marker = if self.chosen.as_deref() == Some(candidate.name.as_str()) {
+                "chosen"
+            } else if candidate.eligible {
+                "eligible"
+            } else {
+                "skipped"
+            };
+            let _ = write!(out, "{} [{marker}]", candidate.name);
+            if let Some(node) = &candidate.node {
+                let _ = write!(out, " on {node}");
+            }
+            if !candidate.notes.is_empty() {
+                let _ = write!(out, ": {}", candidate.notes.join(", "));
+            }
+            out.push('\n');
+        }
+        out.trim_end().to_string()
+    }
+}
+
+struct Ranked<'p> {
+    pod: &'p Pod,
+    index: usize,
+    recently_restarted: bool,
+    preference: (bool, bool, bool),
+}
+
+/// Whether the pod is running and Ready. kftray's own relay pods only need
+/// to be running.
+pub fn is_pod_ready(pod: &Pod) -> bool {
+    pod.status
+        .as_ref()
+        .map(|status| {
+            let is_running = status.phase.as_deref() == Some("Running");
+
+            if let Some(name) = &pod.metadata.name
+                && name.starts_with("kftray-forward-")
+                && is_running
+            {
+                return true;
+            }
+
+            let is_ready = status
+                .conditions
+                .as_ref()
+                .map(|conditions| {
+                    conditions
+                        .iter()
+                        .any(|c| c.type_ == "Ready" && c.status == "True")
+                })
+                .unwrap_or(false);
+
+            is_running && is_ready
+        })
+        .unwrap_or(false)
+}
+
+/// Most recent container restart of the pod, if any container restarted.
+fn last_restart(pod: &Pod) -> Option<DateTime<Utc>> {
+    pod.status
+        .as_ref()?
+        .container_statuses
+        .as_ref()?
+        .iter()
+        .filter(|status| status.restart_count > 0)
+        .filter_map(|status| {
+            let running = status
+                .state
+                .as_ref()
+                .and_then(|state| state.running.as_ref())
+                .and_then(|running| running.started_at.as_ref());
+            let terminated = status
+                .last_state
+                .as_ref()
+                .and_then(|state| state.terminated.as_ref())
+                .and_then(|terminated| terminated.finished_at.as_ref());
+            running.or(terminated).map(|time| time.0)
+        })
+        .max()
+}
+
+fn container_ready(pod: &Pod, container: &str) -> Option<bool> {
+    pod.status
+        .as_ref()?
+        .container_statuses
+        .as_ref()?
+        .iter()
+        .find(|status| status.name == container)
+        .map(|status| status.ready)
+}
+
+fn has_annotation(pod: &Pod, annotation: &str) -> bool {
+    let (key, value) = match annotation.split_once('=') {
+        Some((key, value)) => (key.trim(), Some(value.trim())),
+        None => (annotation.trim(), None),
+    };
+    pod.annotations()
+        .get(key)
+        .is_some_and(|actual| value.is_none_or(|value| actual == value))
+}
+
+/// Picks the pod to forward to. Pods that are not ready, are terminating (when
+/// excluded) or lack a ready `required_container` are skipped. Recently
+/// restarted pods are only used when nothing else is left. The rest are
+/// ranked by preferred node, zone and annotation, then by age, then the
+/// current pod, then the listing order.
+pub fn select_pod<'p>(
+    pods: &'p [Pod], preferences: &PodSelectionPreferences, context: &SelectionContext,
+    now: DateTime<Utc>,
+) -> (Option<&'p Pod>, PodSelectionReport) {
+    let mut report = PodSelectionReport::default();
+    let mut ranked = Vec::new();
+
+    for (index, pod) in pods.iter().enumerate() {
+        let node = pod.spec.as_ref().and_then(|spec| spec.node_name.clone());
+        let mut candidate = PodCandidate {
+            name: pod.name_any(),
+            node: node.clone(),
+            eligible: false,
+            notes: Vec::new(),
+        };
+
+        if !is_pod_ready(pod) {
+            candidate.notes.push("not ready".to_string());
+        } else if preferences.exclude_terminating && pod.metadata.deletion_timestamp.is_some() {
+            candidate.notes.push("terminating".to_string());
+        } else if let Some(container) = preferences.required_container.as_deref()
+            && container_ready(pod, container) != Some(true)
+        {
+            let state = match container_ready(pod, container) {
+                Some(_) => "is not ready",
+                None => "not found",
+            };
+            candidate
+                .notes
+                .push(format!("container '{container}' {state}"));
+        } else {
+            candidate.eligible = true;
+        }
+
+        let recently_restarted = match (preferences.restart_grace_secs, last_restart(pod)) {
+            (Some(grace), Some(restarted)) => {
+                let age = (now - restarted).num_seconds().max(0);
+                let recent = age < grace as i64;
+                if recent && candidate.eligible {
+                    candidate.notes.push(format!("restarted {age}s ago"));
+                }
+                recent
+            }
+            _ => false,
+        };
+
+        if candidate.eligible {
+            let zone = node
+                .as_deref()
+                .and_then(|node| context.node_zones?.get(node));
+            let on_node = preferences
+                .preferred_node
+                .as_deref()
+                .is_some_and(|preferred| node.as_deref() == Some(preferred));
+            let in_zone = preferences
+                .preferred_zone
+                .as_deref()
+                .is_some_and(|preferred| zone.map(String::as_str) == Some(preferred));
+            let annotated = preferences
+                .preferred_annotation
+                .as_deref()
+                .is_some_and(|annotation| has_annotation(pod, annotation));
+
+            if on_node {
+                candidate.notes.push("on preferred node".to_string());
+            }
+            match (preferences.preferred_zone.is_some(), zone) {
+                (true, _) if in_zone => candidate.notes.push("in preferred zone".to_string()),
+                (true, None) => candidate.notes.push("zone unknown".to_string()),
+                _ => {}
+            }
+            if annotated {
+                candidate.notes.push("has preferred annotation".to_string());
+            }
+
+            ranked.push(Ranked {
+                pod,
+                index,
+                recently_restarted,
+                preference: (on_node, in_zone, annotated),
+            });
+        }
+
+        report.candidates.push(candidate);
+    }
+
+    let created = |pod: &Pod| pod.metadata.creation_timestamp.as_ref().map(|time| time.0);
+    ranked.sort_by(|a, b| {
+        a.recently_restarted
+            .cmp(&b.recently_restarted)
+            .then_with(|| b.preference.cmp(&a.preference))
+            .then_with(|| match preferences.prefer_age.as_deref() {
+                Some("newest") => created(b.pod).cmp(&created(a.pod)),
+                Some("oldest") => created(a.pod).cmp(&created(b.pod)),
+                _ => std::cmp::Ordering::Equal,
+            })
+            .then_with(|| {
+                let is_current = |pod: &Pod| context.current == Some(pod.name_any().as_str());
+                is_current(b.pod).cmp(&is_current(a.pod))
+            })
+            .then_with(|| a.index.cmp(&b.index))
+    });
+
+    let chosen = ranked.first().map(|ranked| ranked.pod);
+    if let Some(first) = ranked.first()
+        && first.recently_restarted
+    {
+        report.candidates[first.index]
+            .notes
+            .push("used because no pod without a recent restart is ready".to_string());
+    }
+    report.chosen = chosen.map(|pod| pod.name_any());
+
+    (chosen, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::{
+        ContainerState,
+        ContainerStateRunning,
+        ContainerStatus,
+        PodCondition,
+        PodSpec,
+        PodStatus,
+    };
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{
+        ObjectMeta,
+        Time,
+    };
+    use k8s_openapi::chrono::Duration;
+
+    use super::*;
+
+    fn pod(name: &str, node: &str, created_secs_ago: i64, now: DateTime<Utc>) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                creation_timestamp: Some(Time(now - Duration::seconds(created_secs_ago))),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                node_name: Some(node.to_string()),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Running".to_string()),
+                conditions: Some(vec![PodCondition {
+                    type_: "Ready".to_string(),
+                    status: "True".to_string(),
+                    ..Default::default()
+                }]),
+                container_statuses: Some(vec![ContainerStatus {
+                    name: "app".to_string(),
+                    ready: true,
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn restarted(mut pod: Pod, secs_ago: i64, now: DateTime<Utc>) -> Pod {
+        let status = &mut pod
+            .status
+            .as_mut()
+            .unwrap()
+            .container_statuses
+            .as_mut()
+            .unwrap()[0];
+        status.restart_count = 1;
+        status.state = Some(ContainerState {
+            running: Some(ContainerStateRunning {
+                started_at: Some(Time(now - Duration::seconds(secs_ago))),
+            }),
+            ..Default::default()
+        });
+        pod
+    }
+
+    fn chosen(pods: &[Pod], preferences: &PodSelectionPreferences, now: DateTime<Utc>) -> String {
+        let (pod, _) = select_pod(pods, preferences, &SelectionContext::default(), now);
+        pod.map(|pod| pod.name_any()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_select_pod_defaults_to_first_ready() {
+        let now = Utc::now();
+        let mut not_ready = pod("a", "node-1", 10, now);
+        not_ready.status.as_mut().unwrap().conditions = None;
+        let pods = vec![
+            not_ready,
+            pod("b", "node-1", 20, now),
+            pod("c", "node-2", 5, now),
+        ];
+
+        let (pod, report) = select_pod(
+            &pods,
+            &PodSelectionPreferences::default(),
+            &SelectionContext::default(),
+            now,
+        );
+        assert_eq!(pod.unwrap().name_any(), "b");
+        assert_eq!(
+            report.explain(),
+            "a [skipped] on node-1: not ready\nb [chosen] on node-1\nc [eligible] on node-2"
+        );
+    }
+
+    #[test]
+    fn test_select_pod_exclusions() {
+        let now = Utc::now();
+        let mut terminating = pod("old", "node-1", 100, now);
+        terminating.metadata.deletion_timestamp = Some(Time(now));
+        let pods = vec![
+            terminating,
+            restarted(pod("flaky", "node-1", 50, now), 30, now),
+        ];
+
+        let preferences = PodSelectionPreferences {
+            exclude_terminating: true,
+            restart_grace_secs: Some(300),
+            ..Default::default()
+        };
+        let (pod, report) = select_pod(&pods, &preferences, &SelectionContext::default(), now);
+        assert_eq!(pod.unwrap().name_any(), "flaky");
+        assert!(
+            report.candidates[0]
+                .notes
+                .contains(&"terminating".to_string())
+        );
+        assert!(
+            report.candidates[1]
+                .notes
+                .iter()
+                .any(|n| n.starts_with("used because"))
+        );
+
+        let mut pods = pods;
+        pods.push(pod("stable", "node-2", 60, now));
+        assert_eq!(chosen(&pods, &preferences, now), "stable");
+
+        let preferences = PodSelectionPreferences {
+            required_container: Some("sidecar".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(chosen(&pods, &preferences, now), "");
+    }
+
+    #[test]
+    fn test_select_pod_preferences() {
+        let now = Utc::now();
+        let mut annotated = pod("annotated", "node-3", 30, now);
+        annotated
+            .metadata
+            .annotations
+            .get_or_insert_default()
+            .insert("tier".to_string(), "canary".to_string());
+        let pods = vec![
+            pod("oldest", "node-1", 300, now),
+            pod("newest", "node-2", 10, now),
+            annotated,
+        ];
+
+        let prefer = |preferences: PodSelectionPreferences| chosen(&pods, &preferences, now);
+        assert_eq!(
+            prefer(PodSelectionPreferences {
+                prefer_age: Some("newest".to_string()),
+                ..Default::default()
+            }),
+            "newest"
+        );
+        assert_eq!(
+            prefer(PodSelectionPreferences {
+                prefer_age: Some("oldest".to_string()),
+                ..Default::default()
+            }),
+            "oldest"
+        );
+        assert_eq!(
+            prefer(PodSelectionPreferences {
+                preferred_node: Some("node-2".to_string()),
+                prefer_age: Some("oldest".to_string()),
+                ..Default::default()
+            }),
+            "newest"
+        );
+        assert_eq!(
+            prefer(PodSelectionPreferences {
+                preferred_annotation: Some("tier=canary".to_string()),
+                ..Default::default()
+            }),
+            "annotated"
+        );
+
+        let zones = HashMap::from([("node-1".to_string(), "eu-west-1b".to_string())]);
+        let context = SelectionContext {
+            node_zones: Some(&zones),
+            current: Some("newest"),
+        };
+        let preferences = PodSelectionPreferences {
+            preferred_zone: Some("eu-west-1b".to_string()),
+            ..Default::default()
+        };
+        let (pod, _) = select_pod(&pods, &preferences, &context, now);
+        assert_eq!(pod.unwrap().name_any(), "oldest");
+
+        let (pod, _) = select_pod(&pods, &PodSelectionPreferences::default(), &context, now);
+        assert_eq!(pod.unwrap().name_any(), "newest");
+    }
+}
@@ -13,6 +13,7 @@ use k8s_openapi::api::core::v1::{
     Pod,
     Service,
 };
+use k8s_openapi::chrono::Utc;
 use kube::{
     Api,
     Client,
@@ -45,6 +46,12 @@ use crate::kube::models::{
     TargetPod,
     TargetSelector,
 };
+use crate::kube::pod_selection::{
+    NodeZoneCache,
+    SelectionContext,
+    is_pod_ready,
+    select_pod,
+};
 
 pub struct PodWatcher {
     store: Store<Pod>,
@@ -55,6 +62,7 @@ pub struct PodWatcher {
     cancellation_token: CancellationToken,
     pod_change_tx: tokio::sync::broadcast::Sender<String>,
     target: Target,
+    node_zones: Option<NodeZoneCache>,
 }
 
 impl Drop for PodWatcher {
@@ -69,12 +77,24 @@ impl Drop for PodWatcher {
 impl PodWatcher {
     pub async fn new(client: Client, target: Target) -> Result<Self> {
         let namespace = target.namespace.name_any();
-        let label_selector = Self::resolve_label_selector(&client, &namespace, &target).await?;
-
-        info!(
-            "Starting pod watcher for namespace '{}' with labels: {}",
-            namespace, label_selector
-        );
+        let watcher_config = match &target.selector {
+            TargetSelector::PodName(pod_name) => {
+                info!(
+                    "Starting pod watcher for namespace '{}' on pod: {}",
+                    namespace, pod_name
+                );
+                WatcherConfig::default().fields(&format!("metadata.name={pod_name}"))
+            }
+            _ => {
+                let label_selector =
+                    Self::resolve_label_selector(&client, &namespace, &target).await?;
+                info!(
+                    "Starting pod watcher for namespace '{}' with labels: {}",
+                    namespace, label_selector
+                );
+                WatcherConfig::default().labels(&label_selector)
+            }
+        };
 
         let (store, writer) = reflector::store_shared(256);
         let subscriber = writer
@@ -90,8 +110,17 @@ impl PodWatcher {
         let (pod_change_tx, _) = tokio::sync::broadcast::channel(16);
         let pod_change_tx_clone = pod_change_tx.clone();
 
+        let node_zones = target
+            .selection
+            .preferred_zone
+            .is_some()
+            .then(|| NodeZoneCache::new(client.clone()));
+        let node_zones_clone = node_zones.clone();
+        let store_clone = store.clone();
+        let keep_annotations = target.selection.needs_annotations();
+        let keep_container_statuses = target.selection.needs_container_statuses();
+
         let pods_api: Api<Pod> = Api::namespaced(client, &namespace);
-        let watcher_config = WatcherConfig::default().labels(&label_selector);
 
         let token_clone = cancellation_token.clone();
         let reflector_task = tokio::spawn(async move {
@@ -99,9 +128,13 @@ impl PodWatcher {
                 .default_backoff()
                 .modify(|pod| {
                     pod.managed_fields_mut().clear();
-                    pod.annotations_mut().clear();
+                    if !keep_annotations {
+                        pod.annotations_mut().clear();
+                    }
                     if let Some(status) = &mut pod.status {
-                        status.container_statuses = None;
+                        if !keep_container_statuses {
+                            status.container_statuses = None;
+                        }
                         status.init_container_statuses = None;
                         status.ephemeral_container_statuses = None;
                     }
@@ -160,7 +193,7 @@ impl PodWatcher {
                     pod = stream.next() => {
                         match pod {
                             Some(pod) => {
-                                Self::update_latest_pod(&latest_pod_clone, &pod, &target_clone, &pod_change_tx_clone).await;
+                                Self::update_latest_pod(&latest_pod_clone, &pod, &target_clone, &pod_change_tx_clone, &store_clone, node_zones_clone.as_ref()).await;
                             }
                             None => {
                                 debug!("Pod subscriber stream ended");
@@ -181,51 +214,114 @@ impl PodWatcher {
             cancellation_token,
             pod_change_tx,
             target,
+            node_zones,
         })
     }
 
     async fn update_latest_pod(
         latest_ready_pod: &Arc<RwLock<Option<TargetPod>>>, pod: &Pod, target: &Target,
-        pod_change_tx: &tokio::sync::broadcast::Sender<String>,
+        pod_change_tx: &tokio::sync::broadcast::Sender<String>, store: &Store<Pod>,
+        node_zones: Option<&NodeZoneCache>,
     ) {
-        if Self::is_pod_ready(pod)
-            && let Ok(port_number) = Self::extract_port_from_pod(pod, &target.port)
-        {
-            let target_pod = TargetPod {
-                pod_name: pod.name_any(),
-                port_number,
-            };
-
-            let mut latest = latest_ready_pod.write().await;
-            let pod_changed = match latest.as_ref() {
-                Some(current) => current.pod_name != target_pod.pod_name,
-                None => true,
-            };
-
-            if pod_changed {
-                debug!(
-                    "Pod changed to: {}, connections should reconnect",
-                    pod.name_any()
-                );
-                let _ = pod_change_tx.send(target_pod.pod_name.clone());
-            }
-
-            *latest = Some(target_pod);
-            debug!("Updated latest ready pod: {}", pod.name_any());
+        let selected = if target.selection.is_set() {
+            let current = latest_ready_pod
+                .read()
+                .await
+                .as_ref()
+                .map(|current| current.pod_name.clone());
+            Self::preferred_pod(store, target, node_zones, current.as_deref()).await
+        } else if is_pod_ready(pod) {
+            Self::extract_port_from_pod(pod, &target.port)
+                .ok()
+                .map(|port_number| TargetPod {
+                    pod_name: pod.name_any(),
+                    port_number,
+                })
+        } else {
+            None
+        };
+
+        let Some(target_pod) = selected else {
+            return;
+        };
+
+        let mut latest = latest_ready_pod.write().await;
+        let pod_changed = match latest.as_ref() {
+            Some(current) => current.pod_name != target_pod.pod_name,
+            None => true,
+        };
+
+        if pod_changed {
+            debug!(
+                "Pod changed to: {}, connections should reconnect",
+                target_pod.pod_name
+            );
+            let _ = pod_change_tx.send(target_pod.pod_name.clone());
         }
+
+        debug!("Updated latest ready pod: {}", target_pod.pod_name);
+        *latest = Some(target_pod);
+    }
+
+    /// Best pod in the store according to the target's selection preferences.
+    async fn preferred_pod(
+        store: &Store<Pod>, target: &Target, node_zones: Option<&NodeZoneCache>,
+        current: Option<&str>,
+    ) -> Option<TargetPod> {
+        let pods: Vec<Pod> = store
+            .state()
+            .iter()
+            .map(|pod| pod.as_ref().clone())
+            .collect();
+        let zones = match node_zones {
+            Some(cache) => Some(cache.zones_for(&pods).await),
+            None => None,
+        };
+        let context = SelectionContext {
+            node_zones: zones.as_ref(),
+            current,
+        };
+
+        let (pod, report) = select_pod(&pods, &target.selection, &context, Utc::now());
+        debug!("Pod selection:\n{}", report.explain());
+
+        let pod = pod?;
+        let port_number = Self::extract_port_from_pod(pod, &target.port).ok()?;
+        Some(TargetPod {
+            pod_name: pod.name_any(),
+            port_number,
+        })
     }
 
     pub async fn get_ready_pod(&self) -> Option<TargetPod> {
+        if self.target.selection.is_set() {
+            let current = self
+                .latest_ready_pod
+                .read()
+                .await
+                .as_ref()
+                .map(|current| current.pod_name.clone());
+            let selected = Self::preferred_pod(
+                &self.store,
+                &self.target,
+                self.node_zones.as_ref(),
+                current.as_deref(),
+            )
+            .await;
+            *self.latest_ready_pod.write().await = selected.clone();
+            return selected;
+        }
+
         if let Some(cached) = self.latest_ready_pod.read().await.clone() {
             for pod in self.store.state() {
-                if pod.name_any() == cached.pod_name && Self::is_pod_ready(&pod) {
+                if pod.name_any() == cached.pod_name && is_pod_ready(&pod) {
                     return Some(cached);
                 }
             }
         }
 
         for pod in self.store.state() {
-            if Self::is_pod_ready(&pod)
+            if is_pod_ready(&pod)
                 && let Ok(port_number) = Self::extract_port_from_pod(&pod, &self.target.port)
             {
                 let target_pod = TargetPod {
@@ -286,11 +382,15 @@ impl PodWatcher {
         self.pod_change_tx.subscribe()
     }
 
-    async fn resolve_label_selector(
+    pub(crate) async fn resolve_label_selector(
         client: &Client, namespace: &str, target: &Target,
     ) -> Result<String> {
         match &target.selector {
             TargetSelector::PodLabel(label_selector) => Ok(label_selector.clone()),
+            TargetSelector::PodName(pod_name) => Err(anyhow!(
+                "Pod '{}' is selected by name, not by labels",
+                pod_name
+            )),
             TargetSelector::ServiceName(service_name) => {
                 let service_api: Api<Service> = Api::namespaced(client.clone(), namespace);
                 let service = service_api
@@ -321,34 +421,6 @@ impl PodWatcher {
         }
     }
 
-    fn is_pod_ready(pod: &Pod) -> bool {
-        pod.status
-            .as_ref()
-            .map(|status| {
-                let is_running = status.phase.as_deref() == Some("Running");
-
-                if let Some(name) = &pod.metadata.name
-                    && name.starts_with("kftray-forward-")
-                    && is_running
-                {
-                    return true;
-                }
-
-                let is_ready = status
-                    .conditions
-                    .as_ref()
-                    .map(|conditions| {
-                        conditions
-                            .iter()
-                            .any(|c| c.type_ == "Ready" && c.status == "True")
-                    })
-                    .unwrap_or(false);
-
-                is_running && is_ready
-            })
-            .unwrap_or(false)
-    }
-
     fn extract_port_from_pod(pod: &Pod, target_port: &Port) -> Result<u16> {
         match target_port {
             Port::Number(num) => (*num)
@@ -44,10 +44,23 @@ use rand::distr::{
     SampleString,
 };
 
+use crate::kube::reconcile::{
+    apply_owner_metadata,
+    apply_owner_metadata_to_deployment,
+    start_heartbeat,
+    stop_heartbeat,
+};
 use crate::kube::shared_client::{
     SHARED_CLIENT_MANAGER,
     ServiceClientKey,
 };
+use crate::kube::shared_relay::{
+    RelayForward,
+    prepare_shared_relay,
+    register_relay_forward,
+    release_shared_relay,
+    uses_shared_relay,
+};
 
 pub async fn deploy_and_forward_pod(configs: Vec<Config>) -> Result<Vec<CustomResponse>, String> {
     deploy_and_forward_pod_with_mode(configs, DatabaseMode::File, false).await
@@ -59,11 +72,7 @@ pub async fn deploy_and_forward_pod_with_mode(
     let mut responses: Vec<CustomResponse> = Vec::new();
 
     for mut config in configs.into_iter() {
-        let client_key = ServiceClientKey::new(
-            config.context.clone(),
-            config.kubeconfig.clone(),
-            config.id.unwrap_or(-1),
-        );
+        let client_key = ServiceClientKey::for_config(&config);
 
         let shared_client = SHARED_CLIENT_MANAGER
             .get_client(client_key)
@@ -74,6 +83,15 @@ pub async fn deploy_and_forward_pod_with_mode(
             })?;
         let client = Client::clone(&shared_client);
 
+        if uses_shared_relay(&config)
+            && let Some(forward) = prepare_shared_relay(&client, &config, mode).await
+        {
+            responses.push(
+                forward_through_shared_relay(&client, &config, forward, mode, ssl_override).await?,
+            );
+            continue;
+        }
+
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map_err(|e| e.to_string())?
@@ -104,21 +122,7 @@ pub async fn deploy_and_forward_pod_with_mode(
             config.remote_address.clone_from(&config.service);
         }
 
-        let mut values: HashMap<&str, String> = HashMap::new();
-        values.insert("hashed_name", hashed_name.clone());
-        values.insert("config_id", config_id_str.clone());
-        values.insert("service_name", config.service.as_ref().unwrap().clone());
-        values.insert(
-            "remote_address",
-            config.remote_address.as_ref().unwrap().clone(),
-        );
-        values.insert("remote_port", config.remote_port.expect("None").to_string());
-        let local_port_value = config
-            .remote_port
-            .unwrap_or(config.local_port.expect("None"))
-            .to_string();
-        values.insert("local_port", local_port_value);
-        values.insert("protocol", protocol.clone());
+        let values = proxy_manifest_values(&config, &hashed_name)?;
 
         let use_deployment = should_use_deployment_manifest();
 
@@ -131,8 +135,9 @@ pub async fn deploy_and_forward_pod_with_mode(
                 .map_err(|e| e.to_string())?;
 
             let rendered_json = render_json_template(&contents, &values);
-            let deployment: Deployment =
+            let mut deployment: Deployment =
                 serde_json::from_str(&rendered_json).map_err(|e| e.to_string())?;
+            apply_owner_metadata_to_deployment(&mut deployment);
 
             let deployments: Api<Deployment> = Api::namespaced(client.clone(), &config.namespace);
 
@@ -203,6 +208,11 @@ pub async fn deploy_and_forward_pod_with_mode(
                             let response = port_forward_responses
                                 .pop()
                                 .ok_or("No response received from port forwarding")?;
+                            start_heartbeat(
+                                client.clone(),
+                                config.namespace.clone(),
+                                config_id_str.clone(),
+                            );
                             responses.push(response);
                         }
                         Err(e) => {
@@ -223,7 +233,8 @@ pub async fn deploy_and_forward_pod_with_mode(
                 .map_err(|e| e.to_string())?;
 
             let rendered_json = render_json_template(&contents, &values);
-            let pod: Pod = serde_json::from_str(&rendered_json).map_err(|e| e.to_string())?;
+            let mut pod: Pod = serde_json::from_str(&rendered_json).map_err(|e| e.to_string())?;
+            apply_owner_metadata(&mut pod.metadata);
 
             let pods: Api<Pod> = Api::namespaced(client.clone(), &config.namespace);
 
@@ -276,6 +287,11 @@ pub async fn deploy_and_forward_pod_with_mode(
                             let response = port_forward_responses
                                 .pop()
                                 .ok_or("No response received from port forwarding")?;
+                            start_heartbeat(
+                                client.clone(),
+                                config.namespace.clone(),
+                                config_id_str.clone(),
+                            );
                             responses.push(response);
                         }
                         Err(e) => {
@@ -292,6 +308,35 @@ pub async fn deploy_and_forward_pod_with_mode(
     Ok(responses)
 }
 
+/// Forwards a tcp proxy config through the prepared shared relay of its
+/// namespace.
+async fn forward_through_shared_relay(
+    client: &Client, config: &Config, forward: RelayForward, mode: DatabaseMode, ssl_override: bool,
+) -> Result<CustomResponse, String> {
+    let config_id = config.id.unwrap_or_default();
+    register_relay_forward(config_id, forward.clone());
+
+    match super::start::start_port_forward_with_mode(
+        vec![config.clone()],
+        "tcp",
+        mode,
+        ssl_override,
+    )
+    .await
+    {
+        Ok(mut port_forward_responses) => {
+            start_heartbeat(client.clone(), forward.namespace, forward.key);
+            port_forward_responses
+                .pop()
+                .ok_or_else(|| "No response received from port forwarding".to_string())
+        }
+        Err(e) => {
+            release_shared_relay(client, config_id).await;
+            Err(format!("Failed to start port forwarding {e}"))
+        }
+    }
+}
+
 pub async fn stop_proxy_forward_with_mode(
     config_id: i64, namespace: &str, service_name: String,
     mode: kftray_commons::utils::db_mode::DatabaseMode,
@@ -305,11 +350,7 @@ pub async fn stop_proxy_forward_with_mode(
             e.to_string()
         })?;
 
-    let client_key = ServiceClientKey::new(
-        config.context.clone(),
-        config.kubeconfig.clone(),
-        config.id.unwrap_or(-1),
-    );
+    let client_key = ServiceClientKey::for_config(&config);
 
     let shared_client = SHARED_CLIENT_MANAGER
         .get_client(client_key)
@@ -320,6 +361,10 @@ pub async fn stop_proxy_forward_with_mode(
         })?;
     let client = Client::clone(&shared_client);
 
+    stop_heartbeat(&config_id.to_string());
+    delete_proxy_deployments(&client, namespace, config_id).await;
+    release_shared_relay(&client, config_id).await;
+
     let pods: Api<Pod> = Api::namespaced(client, namespace);
 
     let lp = ListParams::default().labels(&format!("config_id={config_id}"));
@@ -386,11 +431,7 @@ pub async fn stop_proxy_forward(
             e.to_string()
         })?;
 
-    let client_key = ServiceClientKey::new(
-        config.context.clone(),
-        config.kubeconfig.clone(),
-        config.id.unwrap_or(-1),
-    );
+    let client_key = ServiceClientKey::for_config(&config);
 
     let shared_client = SHARED_CLIENT_MANAGER
         .get_client(client_key)
@@ -401,6 +442,10 @@ pub async fn stop_proxy_forward(
         })?;
     let client = Client::clone(&shared_client);
 
+    stop_heartbeat(&config_id.to_string());
+    delete_proxy_deployments(&client, namespace, config_id).await;
+    release_shared_relay(&client, config_id).await;
+
     let pods: Api<Pod> = Api::namespaced(client, namespace);
 
     let lp = ListParams::default().labels(&format!("config_id={config_id}"));
@@ -458,6 +503,36 @@ pub async fn stop_proxy_forward(
     Ok(stop_result)
 }
 
+/// Deletes the proxy Deployments of a config so their pods are not recreated
+/// once the forward is stopped.
+async fn delete_proxy_deployments(client: &Client, namespace: &str, config_id: i64) {
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+    let lp = ListParams::default().labels(&format!("config_id={config_id}"));
+
+    let deployment_list = match deployments.list(&lp).await {
+        Ok(list) => list,
+        Err(e) => {
+            error!("Error listing proxy deployments: {e}");
+            return;
+        }
+    };
+
+    for deployment in deployment_list.items {
+        if let Some(name) = deployment.metadata.name
+            && name.starts_with("kftray-forward-")
+        {
+            let dp = DeleteParams {
+                propagation_policy: Some(kube::api::PropagationPolicy::Background),
+                ..DeleteParams::default()
+            };
+            match deployments.delete(&name, &dp).await {
+                Ok(_) => info!("Successfully deleted deployment: {name}"),
+                Err(e) => error!("Failed to delete deployment {name}: {e}"),
+            }
+        }
+    }
+}
+
 fn is_custom_pod_manifest() -> bool {
     match get_pod_manifest_path() {
         Ok(path) if path.exists() => {
@@ -484,7 +559,43 @@ fn is_custom_pod_manifest() -> bool {
     }
 }
 
-fn should_use_deployment_manifest() -> bool {
+/// Builds the placeholder values substituted into the proxy manifest
+/// template. The remote address falls back to the service name.
+pub(crate) fn proxy_manifest_values(
+    config: &Config, hashed_name: &str,
+) -> Result<HashMap<&'static str, String>, String> {
+    let service = config
+        .service
+        .clone()
+        .filter(|s| !s.is_empty())
+        .ok_or("Proxy config has no service")?;
+    let remote_address = config
+        .remote_address
+        .clone()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| service.clone());
+    let remote_port = config
+        .remote_port
+        .ok_or("Proxy config has no remote port")?;
+
+    let mut values: HashMap<&'static str, String> = HashMap::new();
+    values.insert("hashed_name", hashed_name.to_string());
+    values.insert(
+        "config_id",
+        config
+            .id
+            .map_or_else(|| "default".into(), |id| id.to_string()),
+    );
+    values.insert("service_name", service);
+    values.insert("remote_address", remote_address);
+    values.insert("remote_port", remote_port.to_string());
+    values.insert("local_port", remote_port.to_string());
+    values.insert("protocol", config.protocol.to_string().to_lowercase());
+
+    Ok(values)
+}
+
+pub(crate) fn should_use_deployment_manifest() -> bool {
     if is_custom_pod_manifest() {
         info!("Using legacy Pod manifest (custom detected)");
         return false;
@@ -499,7 +610,7 @@ fn should_use_deployment_manifest() -> bool {
     false
 }
 
-fn render_json_template(template: &str, values: &HashMap<&str, String>) -> String {
+pub(crate) fn render_json_template(template: &str, values: &HashMap<&str, String>) -> String {
     let mut rendered_template = template.to_string();
 
     for (key, value) in values.iter() {
@@ -612,6 +723,28 @@ mod tests {
         assert!(rendered.contains("\"protocol\": \"TCP\""));
     }
 
+    #[test]
+    fn test_proxy_manifest_values_defaults_remote_address_to_service() {
+        let config = Config {
+            id: Some(7),
+            service: Some("db".to_string()),
+            remote_port: Some(5432),
+            protocol: "tcp".to_string(),
+            ..Config::default()
+        };
+
+        let values = proxy_manifest_values(&config, "kftray-forward-test").unwrap();
+        assert_eq!(values["remote_address"], "db");
+        assert_eq!(values["config_id"], "7");
+        assert_eq!(values["local_port"], "5432");
+
+        let missing_port = Config {
+            remote_port: None,
+            ..config
+        };
+        assert!(proxy_manifest_values(&missing_port, "kftray-forward-test").is_err());
+    }
+
     #[tokio::test]
     async fn test_deploy_and_forward_pod_empty_config() {
         let configs = Vec::new();
@@ -649,6 +782,15 @@ mod tests {
             cert_issuer_kind: None,
             ingress_class: None,
             ingress_annotations: None,
+            groups: None,
+            port_conflict_policy: None,
+            impersonate_user: None,
+            impersonate_groups: Vec::new(),
+            pod_selection: None,
+            drain_policy: None,
+            drain_timeout_secs: None,
+            debug_container: None,
+            proxy_relay: None,
         };
 
         let result = deploy_and_forward_pod(vec![config]).await;
@@ -0,0 +1,615 @@
+use std::collections::{
+    BTreeMap,
+    HashSet,
+};
+use std::time::{
+    Duration,
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+use dashmap::DashMap;
+use k8s_openapi::api::{
+    apps::v1::Deployment,
+    core::v1::{
+        Pod,
+        Service,
+    },
+    networking::v1::Ingress,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kftray_commons::utils::{
+    config::read_configs_with_mode,
+    db_mode::DatabaseMode,
+};
+use kube::api::{
+    Api,
+    DeleteParams,
+    ListParams,
+    Patch,
+    PatchParams,
+};
+use kube::{
+    Client,
+    Resource,
+};
+use log::{
+    debug,
+    error,
+    info,
+    warn,
+};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use tokio::task::JoinHandle;
+
+use crate::kube::shared_client::{
+    SHARED_CLIENT_MANAGER,
+    ServiceClientKey,
+};
+
+pub const MANAGED_BY_LABEL: &str = "app.kubernetes.io/managed-by";
+pub const MANAGED_BY_VALUE: &str = "kftray";
+pub const OWNER_USER_LABEL: &str = "kftray.app/owner-user";
+pub const OWNER_HOST_LABEL: &str = "kftray.app/owner-host";
+pub const HEARTBEAT_ANNOTATION: &str = "kftray.app/heartbeat";
+
+/// How often running forwards refresh the heartbeat annotation on the
+/// resources they created.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Resources whose heartbeat is older than this are considered orphaned.
+pub const DEFAULT_STALE_AFTER: Duration = Duration::from_secs(5 * 60);
+
+static HEARTBEATS: Lazy<DashMap<String, JoinHandle<()>>> = Lazy::new(DashMap::new);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerIdentity {
+    pub user: String,
+    pub host: String,
+}
+
+impl OwnerIdentity {
+    pub fn current() -> Self {
+        let user = whoami::username();
+        let host = whoami::fallible::hostname().unwrap_or_else(|_| "unknown".to_string());
+
+        Self {
+            user: sanitize_label_value(&user),
+            host: sanitize_label_value(&host),
+        }
+    }
+
+    pub fn label_selector(&self) -> String {
+        format!(
+            "{MANAGED_BY_LABEL}={MANAGED_BY_VALUE},{OWNER_USER_LABEL}={},{OWNER_HOST_LABEL}={}",
+            self.user, self.host
+        )
+    }
+
+    pub fn labels(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (MANAGED_BY_LABEL.to_string(), MANAGED_BY_VALUE.to_string()),
+            (OWNER_USER_LABEL.to_string(), self.user.clone()),
+            (OWNER_HOST_LABEL.to_string(), self.host.clone()),
+        ])
+    }
+}
+
+/// Kubernetes label values are limited to 63 alphanumeric characters, `-`,
+/// `_` and `.`, and must start and end with an alphanumeric character.
+fn sanitize_label_value(value: &str) -> String {
+    let cleaned: String = value
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    let trimmed: String = cleaned
+        .trim_matches(|c: char| !c.is_ascii_alphanumeric())
+        .chars()
+        .take(63)
+        .collect();
+    let trimmed = trimmed
+        .trim_end_matches(|c: char| !c.is_ascii_alphanumeric())
+        .to_string();
+
+    if trimmed.is_empty() {
+        "unknown".to_string()
+    } else {
+        trimmed
+    }
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Adds the owner labels and a fresh heartbeat annotation to resource
+/// metadata before it is created in the cluster.
+pub fn apply_owner_metadata(meta: &mut ObjectMeta) {
+    let identity = OwnerIdentity::current();
+
+    meta.labels
+        .get_or_insert_with(BTreeMap::new)
+        .extend(identity.labels());
+    meta.annotations
+        .get_or_insert_with(BTreeMap::new)
+        .insert(HEARTBEAT_ANNOTATION.to_string(), now_secs().to_string());
+}
+
+/// Same as [`apply_owner_metadata`], also labelling the pod template so the
+/// pods spawned by the Deployment can be attributed to their owner.
+pub fn apply_owner_metadata_to_deployment(deployment: &mut Deployment) {
+    apply_owner_metadata(&mut deployment.metadata);
+
+    if let Some(template_meta) = deployment
+        .spec
+        .as_mut()
+        .and_then(|spec| spec.template.metadata.as_mut())
+    {
+        template_meta
+            .labels
+            .get_or_insert_with(BTreeMap::new)
+            .extend(OwnerIdentity::current().labels());
+    }
+}
+
+fn heartbeat_age(meta: &ObjectMeta, now: i64) -> Option<i64> {
+    meta.annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(HEARTBEAT_ANNOTATION))
+        .and_then(|value| value.parse::<i64>().ok())
+        .map(|heartbeat| now - heartbeat)
+}
+
+fn creation_age(meta: &ObjectMeta, now: i64) -> Option<i64> {
+    meta.creation_timestamp
+        .as_ref()
+        .map(|created| now - created.0.timestamp())
+}
+
+fn is_stale(meta: &ObjectMeta, stale_after: Duration, now: i64) -> bool {
+    let threshold = stale_after.as_secs() as i64;
+
+    match heartbeat_age(meta, now).or_else(|| creation_age(meta, now)) {
+        Some(age) => age >= threshold,
+        None => true,
+    }
+}
+
+fn config_id_of(meta: &ObjectMeta) -> Option<String> {
+    meta.labels
+        .as_ref()
+        .and_then(|labels| labels.get("config_id"))
+        .cloned()
+}
+
+/// Starts refreshing the heartbeat annotation of every resource owned by this
+/// user and host that carries the given `config_id` label.
+pub fn start_heartbeat(client: Client, namespace: String, config_id: String) {
+    let key = config_id.clone();
+    let handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = refresh_heartbeat(&client, &namespace, &config_id).await {
+                warn!("Failed to refresh heartbeat for config {config_id}: {e}");
+            }
+        }
+    });
+
+    if let Some(previous) = HEARTBEATS.insert(key, handle) {
+        previous.abort();
+    }
+}
+
+pub fn stop_heartbeat(config_id: &str) {
+    if let Some((_, handle)) = HEARTBEATS.remove(config_id) {
+        debug!("Stopping heartbeat for config {config_id}");
+        handle.abort();
+    }
+}
+
+pub fn stop_all_heartbeats() {
+    let keys: Vec<String> = HEARTBEATS.iter().map(|entry| entry.key().clone()).collect();
+    for key in keys {
+        stop_heartbeat(&key);
+    }
+}
+
+fn active_heartbeats() -> HashSet<String> {
+    HEARTBEATS.iter().map(|entry| entry.key().clone()).collect()
+}
+
+async fn refresh_heartbeat(
+    client: &Client, namespace: &str, config_id: &str,
+) -> Result<(), String> {
+    let selector = format!(
+        "{},config_id={config_id}",
+        OwnerIdentity::current().label_selector()
+    );
+    let lp = ListParams::default().labels(&selector);
+    let patch = Patch::Merge(json!({
+        "metadata": {
+            "annotations": {
+                HEARTBEAT_ANNOTATION: now_secs().to_string()
+            }
+        }
+    }));
+
+    patch_all::<Deployment>(client, namespace, &lp, &patch).await?;
+    patch_all::<Pod>(client, namespace, &lp, &patch).await?;
+    patch_all::<Service>(client, namespace, &lp, &patch).await?;
+    patch_all::<Ingress>(client, namespace, &lp, &patch).await?;
+
+    Ok(())
+}
+
+async fn patch_all<K>(
+    client: &Client, namespace: &str, lp: &ListParams, patch: &Patch<serde_json::Value>,
+) -> Result<(), String>
+where
+    K: Resource<Scope = k8s_openapi::NamespaceResourceScope>
+        + Clone
+        + DeserializeOwned
+        + std::fmt::Debug,
+    <K as Resource>::DynamicType: Default,
+{
+    let api: Api<K> = Api::namespaced(client.clone(), namespace);
+    let list = api.list(lp).await.map_err(|e| e.to_string())?;
+
+    for item in list.items {
+        if let Some(name) = item.meta().name.as_deref() {
+            api.patch(name, &PatchParams::default(), patch)
+                .await
+                .map_err(|e| format!("Failed to patch {name}: {e}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct OrphanedResource {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+    pub context: Option<String>,
+    pub config_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconcileReport {
+    pub deleted: Vec<OrphanedResource>,
+    pub kept_active: usize,
+    pub errors: Vec<String>,
+}
+
+impl ReconcileReport {
+    fn merge(&mut self, other: ReconcileReport) {
+        self.deleted.extend(other.deleted);
+        self.kept_active += other.kept_active;
+        self.errors.extend(other.errors);
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Removed {} orphaned resource(s), kept {} in use",
+            self.deleted.len(),
+            self.kept_active
+        );
+        for resource in &self.deleted {
+            summary.push_str(&format!(
+                "\n- {} {}/{}",
+                resource.kind, resource.namespace, resource.name
+            ));
+        }
+        for error in &self.errors {
+            summary.push_str(&format!("\n! {error}"));
+        }
+        summary
+    }
+}
+
+/// Deletes kftray resources owned by this user and host that are no longer
+/// refreshed by a running forward, across all namespaces of the cluster.
+///
+/// With `include_legacy`, resources created before owner labels existed are
+/// also matched by their `kftray-forward-<user>-` / `kftray-expose-<user>-`
+/// name prefix and judged by creation time instead of heartbeat. Those can't
+/// be told apart from the live proxies of older kftray clients or of another
+/// machine with the same username, so only the manual clean action, after a
+/// confirmation, includes them.
+pub async fn reconcile_orphaned_resources(
+    client: Client, context: Option<String>, stale_after: Duration, include_legacy: bool,
+) -> ReconcileReport {
+    let identity = OwnerIdentity::current();
+    let active = active_heartbeats();
+    let now = now_secs();
+    let mut report = ReconcileReport::default();
+
+    let owned = ListParams::default().labels(&identity.label_selector());
+
+    let candidates = Candidates {
+        client: &client,
+        context: &context,
+        active: &active,
+        stale_after,
+        now,
+    };
+
+    candidates
+        .sweep::<Deployment>("Deployment", &owned, |_| true, &mut report)
+        .await;
+    candidates
+        .sweep::<Pod>(
+            "Pod",
+            &owned,
+            |pod| pod.metadata.owner_references.is_none(),
+            &mut report,
+        )
+        .await;
+    candidates
+        .sweep::<Service>("Service", &owned, |_| true, &mut report)
+        .await;
+    candidates
+        .sweep::<Ingress>("Ingress", &owned, |_| true, &mut report)
+        .await;
+
+    if include_legacy {
+        sweep_legacy(&candidates, &mut report).await;
+    }
+
+    info!(
+        "Reconcile for context {:?}: {} deleted, {} kept active, {} errors",
+        context,
+        report.deleted.len(),
+        report.kept_active,
+        report.errors.len()
+    );
+
+    report
+}
+
+/// Deletes stale unlabelled resources named after this user by kftray
+/// versions that predate owner labels.
+async fn sweep_legacy(candidates: &Candidates<'_>, report: &mut ReconcileReport) {
+    let username = whoami::username().to_lowercase();
+    let clean_username: String = username.chars().filter(|c| c.is_alphanumeric()).collect();
+    let legacy_prefixes = [
+        format!("kftray-forward-{clean_username}-"),
+        format!("kftray-expose-{clean_username}-"),
+    ];
+    let unlabelled = ListParams::default().labels(&format!("!{MANAGED_BY_LABEL},config_id"));
+    let is_legacy = |meta: &ObjectMeta| {
+        meta.name
+            .as_deref()
+            .is_some_and(|name| legacy_prefixes.iter().any(|p| name.starts_with(p)))
+    };
+
+    let legacy_deleted_before = report.deleted.len();
+    candidates
+        .sweep::<Deployment>(
+            "Deployment",
+            &unlabelled,
+            |d| is_legacy(&d.metadata),
+            report,
+        )
+        .await;
+    candidates
+        .sweep::<Pod>(
+            "Pod",
+            &unlabelled,
+            |p| p.metadata.owner_references.is_none() && is_legacy(&p.metadata),
+            report,
+        )
+        .await;
+
+    let legacy_expose: Vec<OrphanedResource> = report.deleted[legacy_deleted_before..]
+        .iter()
+        .filter(|r| r.name.starts_with(&legacy_prefixes[1]))
+        .cloned()
+        .collect();
+    for resource in legacy_expose {
+        let Some(config_id) = resource.config_id else {
+            continue;
+        };
+        if let Err(e) = crate::expose::kubernetes::delete_expose_resources(
+            candidates.client.clone(),
+            &resource.namespace,
+            &config_id,
+        )
+        .await
+        {
+            report.errors.push(e);
+        }
+    }
+}
+
+struct Candidates<'a> {
+    client: &'a Client,
+    context: &'a Option<String>,
+    active: &'a HashSet<String>,
+    stale_after: Duration,
+    now: i64,
+}
+
+impl Candidates<'_> {
+    async fn sweep<K>(
+        &self, kind: &str, lp: &ListParams, filter: impl Fn(&K) -> bool,
+        report: &mut ReconcileReport,
+    ) where
+        K: Resource<Scope = k8s_openapi::NamespaceResourceScope>
+            + Clone
+            + DeserializeOwned
+            + std::fmt::Debug,
+        <K as Resource>::DynamicType: Default,
+    {
+        let api: Api<K> = Api::all(self.client.clone());
+        let items = match api.list(lp).await {
+            Ok(list) => list.items,
+            Err(e) => {
+                report
+                    .errors
+                    .push(format!("Failed to list {kind} resources: {e}"));
+                return;
+            }
+        };
+
+        for item in items.into_iter().filter(|item| filter(item)) {
+            let meta = item.meta();
+            let (Some(name), Some(namespace)) = (meta.name.clone(), meta.namespace.clone()) else {
+                continue;
+            };
+
+            let tracked_locally = config_id_of(meta).is_some_and(|id| self.active.contains(&id));
+            if tracked_locally || !is_stale(meta, self.stale_after, self.now) {
+                report.kept_active += 1;
+                continue;
+            }
+
+            let namespaced: Api<K> = Api::namespaced(self.client.clone(), &namespace);
+            let dp = DeleteParams {
+                grace_period_seconds: Some(0),
+                propagation_policy: Some(kube::api::PropagationPolicy::Background),
+                ..DeleteParams::default()
+            };
+
+            match namespaced.delete(&name, &dp).await {
+                Ok(_) => {
+                    info!("Deleted orphaned {kind} {namespace}/{name}");
+                    report.deleted.push(OrphanedResource {
+                        kind: kind.to_string(),
+                        namespace,
+                        name,
+                        context: self.context.clone(),
+                        config_id: config_id_of(meta),
+                    });
+                }
+                Err(e) => {
+                    error!("Failed to delete orphaned {kind} {namespace}/{name}: {e}");
+                    report
+                        .errors
+                        .push(format!("Failed to delete {kind} {namespace}/{name}: {e}"));
+                }
+            }
+        }
+    }
+}
+
+/// Runs [`reconcile_orphaned_resources`] once for every distinct
+/// context/kubeconfig pair referenced by the stored configs, only touching
+/// resources labelled with this user and host. Safe to run unattended.
+pub async fn reconcile_all_contexts(mode: DatabaseMode, stale_after: Duration) -> ReconcileReport {
+    reconcile_contexts(mode, stale_after, false).await
+}
+
+/// Same as [`reconcile_all_contexts`], also deleting stale unlabelled
+/// resources of older kftray versions named after this user. Only for the
+/// manual clean action, once the user confirmed it.
+pub async fn reconcile_all_contexts_with_legacy(
+    mode: DatabaseMode, stale_after: Duration,
+) -> ReconcileReport {
+    reconcile_contexts(mode, stale_after, true).await
+}
+
+async fn reconcile_contexts(
+    mode: DatabaseMode, stale_after: Duration, include_legacy: bool,
+) -> ReconcileReport {
+    let mut report = ReconcileReport::default();
+
+    let configs = match read_configs_with_mode(mode).await {
+        Ok(configs) => configs,
+        Err(e) => {
+            report.errors.push(format!("Failed to read configs: {e}"));
+            return report;
+        }
+    };
+
+    let mut seen = HashSet::new();
+    for config in configs {
+        let pair = (config.context.clone(), config.kubeconfig.clone());
+        if !seen.insert(pair) {
+            continue;
+        }
+
+        let client_key = ServiceClientKey::for_config(&config);
+
+        match SHARED_CLIENT_MANAGER.get_client(client_key).await {
+            Ok(client) => {
+                let context_report = reconcile_orphaned_resources(
+                    Client::clone(&client),
+                    config.context.clone(),
+                    stale_after,
+                    include_legacy,
+                )
+                .await;
+                report.merge(context_report);
+            }
+            Err(e) => {
+                warn!("Skipping reconcile for context {:?}: {e}", config.context);
+                report.errors.push(format!(
+                    "Failed to connect to context {}: {e}",
+                    config.context.unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_label_value() {
+        assert_eq!(sanitize_label_value("Alice"), "alice");
+        assert_eq!(sanitize_label_value("DOMAIN\\bob"), "domain-bob");
+        assert_eq!(sanitize_label_value("my host.local"), "my-host.local");
+        assert_eq!(sanitize_label_value("--x--"), "x");
+        assert_eq!(sanitize_label_value("@@@"), "unknown");
+        assert_eq!(sanitize_label_value(&"a".repeat(80)).len(), 63);
+    }
+
+    #[test]
+    fn test_apply_owner_metadata() {
+        let mut meta = ObjectMeta::default();
+        apply_owner_metadata(&mut meta);
+
+        let labels = meta.labels.unwrap();
+        assert_eq!(labels.get(MANAGED_BY_LABEL).unwrap(), MANAGED_BY_VALUE);
+        assert!(labels.contains_key(OWNER_USER_LABEL));
+        assert!(labels.contains_key(OWNER_HOST_LABEL));
+        assert!(meta.annotations.unwrap().contains_key(HEARTBEAT_ANNOTATION));
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let now = now_secs();
+        let mut meta = ObjectMeta::default();
+        assert!(is_stale(&meta, DEFAULT_STALE_AFTER, now));
+
+        meta.annotations = Some(BTreeMap::from([(
+            HEARTBEAT_ANNOTATION.to_string(),
+            (now - 10).to_string(),
+        )]));
+        assert!(!is_stale(&meta, DEFAULT_STALE_AFTER, now));
+        assert!(is_stale(&meta, Duration::from_secs(5), now));
+    }
+}
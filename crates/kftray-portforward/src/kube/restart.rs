@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use kftray_commons::models::config_model::Config;
+use kftray_commons::models::config_state_model::ConfigState;
+use kftray_commons::models::response::CustomResponse;
+use kftray_commons::utils::config::get_config_with_mode;
+use kftray_commons::utils::config_state::get_configs_state_with_mode;
+use kftray_commons::utils::db_mode::DatabaseMode;
+use log::{
+    info,
+    warn,
+};
+
+use crate::kube::{
+    deploy_and_forward_pod_with_mode,
+    start_port_forward_with_mode,
+    stop_port_forward_with_mode,
+    stop_proxy_forward_with_mode,
+};
+
+/// Whether the config is forwarded through a kftray-server pod instead of a
+/// direct port forward.
+pub(crate) fn uses_proxy_pod(config: &Config) -> bool {
+    config.workload_type.as_deref() == Some("proxy")
+        || (config.workload_type.as_deref() != Some("expose") && config.protocol == "udp")
+}
+
+/// Starts the forward of a single config with the entry point matching its
+/// workload type.
+pub(crate) async fn start_config_with_mode(
+    config: Config, mode: DatabaseMode,
+) -> Result<Vec<CustomResponse>, String> {
+    if uses_proxy_pod(&config) {
+        deploy_and_forward_pod_with_mode(vec![config], mode, false).await
+    } else {
+        start_port_forward_with_mode(vec![config], "tcp", mode, false).await
+    }
+}
+
+/// Stops the forward of a single config, removing its proxy pod if it has one.
+pub(crate) async fn stop_config_with_mode(
+    config: &Config, mode: DatabaseMode,
+) -> Result<CustomResponse, String> {
+    let config_id = config.id.ok_or_else(|| "Config has no id".to_string())?;
+
+    if config.workload_type.as_deref() == Some("proxy") {
+        stop_proxy_forward_with_mode(
+            config_id,
+            &config.namespace,
+            config.service.clone().unwrap_or_default(),
+            mode,
+        )
+        .await
+    } else {
+        stop_port_forward_with_mode(config_id.to_string(), mode).await
+    }
+}
+
+/// Stops and starts the forward of a config so it picks up the definition
+/// currently stored in the database.
+pub async fn restart_port_forward_with_mode(
+    config: Config, mode: DatabaseMode,
+) -> Result<Vec<CustomResponse>, String> {
+    let config_id = config.id.ok_or_else(|| "Config has no id".to_string())?;
+
+    if let Err(e) = stop_config_with_mode(&config, mode).await {
+        warn!("Failed to stop config {config_id} before restart: {e}");
+    }
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    start_config_with_mode(config, mode).await
+}
+
+/// The ids in `config_ids` whose forward is running.
+fn running_config_ids(states: &[ConfigState], config_ids: &[i64]) -> Vec<i64> {
+    states
+        .iter()
+        .filter(|state| state.is_running && config_ids.contains(&state.config_id))
+        .map(|state| state.config_id)
+        .collect()
+}
+
+/// Stops the given configs that currently have a running forward and returns
+/// the ids that were stopped. Stopping reads the stored config, so this runs
+/// before the configs are deleted.
+pub async fn stop_running_configs_with_mode(
+    config_ids: &[i64], mode: DatabaseMode,
+) -> Result<Vec<i64>, String> {
+    let states = get_configs_state_with_mode(mode).await?;
+
+    let mut stopped = Vec::new();
+    let mut errors = Vec::new();
+
+    for config_id in running_config_ids(&states, config_ids) {
+        let result = match get_config_with_mode(config_id, mode).await {
+            Ok(config) => stop_config_with_mode(&config, mode).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(_) => {
+                info!("Stopped port forward for config {config_id}");
+                stopped.push(config_id);
+            }
+            Err(e) => errors.push(format!("config {config_id}: {e}")),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(stopped)
+    } else {
+        Err(format!(
+            "Failed to stop some port forwards: {}",
+            errors.join("; ")
+        ))
+    }
+}
+
+/// Restarts the given configs that currently have a running forward and
+/// returns the ids that were restarted. Configs that are not running are
+/// left alone.
+pub async fn restart_running_configs_with_mode(
+    config_ids: &[i64], mode: DatabaseMode,
+) -> Result<Vec<i64>, String> {
+    let states = get_configs_state_with_mode(mode).await?;
+    let running = running_config_ids(&states, config_ids);
+
+    let mut restarted = Vec::new();
+    let mut errors = Vec::new();
+
+    for config_id in running {
+        let config = match get_config_with_mode(config_id, mode).await {
+            Ok(config) => config,
+            Err(e) => {
+                errors.push(format!("config {config_id}: {e}"));
+                continue;
+            }
+        };
+
+        match restart_port_forward_with_mode(config, mode).await {
+            Ok(_) => {
+                info!("Restarted port forward for config {config_id}");
+                restarted.push(config_id);
+            }
+            Err(e) => errors.push(format!("config {config_id}: {e}")),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(restarted)
+    } else {
+        Err(format!(
+            "Failed to restart some port forwards: {}",
+            errors.join("; ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_config_ids() {
+        let states = vec![
+            ConfigState::new(1, true),
+            ConfigState::new(2, false),
+            ConfigState::new(3, true),
+            ConfigState::new(4, true),
+        ];
+
+        assert_eq!(running_config_ids(&states, &[1, 2, 3]), vec![1, 3]);
+        assert_eq!(running_config_ids(&states, &[2, 5]), Vec::<i64>::new());
+        assert_eq!(running_config_ids(&states, &[]), Vec::<i64>::new());
+    }
+}
@@ -14,15 +14,18 @@ use log::{
 };
 
 use crate::kube::operations::{
+    discovery_namespaces,
     get_services_with_annotation,
-    list_all_namespaces,
 };
 use crate::kube::shared_client::{
     SHARED_CLIENT_MANAGER,
     ServiceClientKey,
 };
+/// Builds configs from the annotated services of a context. `namespaces`
+/// limits the scan to those namespaces for users who cannot list namespaces
+/// cluster-wide.
 pub async fn retrieve_service_configs(
-    context: &str, kubeconfig: Option<String>,
+    context: &str, kubeconfig: Option<String>, namespaces: Option<Vec<String>>,
 ) -> Result<Vec<Config>, String> {
     let client_key = ServiceClientKey::new(Some(context.to_string()), kubeconfig.clone(), -1);
 
@@ -31,15 +34,16 @@ pub async fn retrieve_service_configs(
         .await
         .map_err(|e| e.to_string())?;
 
-    retrieve_service_configs_direct(context, kubeconfig, &client).await
+    retrieve_service_configs_direct(context, kubeconfig, namespaces, &client).await
 }
 
 async fn retrieve_service_configs_direct(
-    context: &str, kubeconfig: Option<String>, client: &std::sync::Arc<kube::Client>,
+    context: &str, kubeconfig: Option<String>, namespaces: Option<Vec<String>>,
+    client: &std::sync::Arc<kube::Client>,
 ) -> Result<Vec<Config>, String> {
     let annotation = "kftray.app/configs";
 
-    let namespaces = list_all_namespaces(Client::clone(client))
+    let namespaces = discovery_namespaces(Client::clone(client), namespaces)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -164,6 +168,15 @@ fn parse_configs(
                 cert_issuer_kind: None,
                 ingress_class: None,
                 ingress_annotations: None,
+                groups: None,
+                port_conflict_policy: None,
+                impersonate_user: None,
+                impersonate_groups: Vec::new(),
+                pod_selection: None,
+                drain_policy: None,
+                drain_timeout_secs: None,
+                debug_container: None,
+                proxy_relay: None,
             })
         })
         .collect()
@@ -201,6 +214,15 @@ fn create_default_configs(
             cert_issuer_kind: None,
             ingress_class: None,
             ingress_annotations: None,
+            groups: None,
+            port_conflict_policy: None,
+            impersonate_user: None,
+            impersonate_groups: Vec::new(),
+            pod_selection: None,
+            drain_policy: None,
+            drain_timeout_secs: None,
+            debug_container: None,
+            proxy_relay: None,
         })
         .collect()
 }
@@ -5,17 +5,26 @@ use std::time::{
 };
 
 use dashmap::DashMap;
+use kftray_commons::models::config_model::Config;
 use kube::Client;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 
-use crate::kube::client::create_client_with_specific_context;
+use crate::kube::client::{
+    Impersonation,
+    create_client_with_impersonation,
+};
+use crate::kube::kubeconfig_watcher::{
+    KubeconfigFiles,
+    KubeconfigFingerprint,
+};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ServiceClientKey {
     pub context_name: Option<String>,
     pub kubeconfig_path: Option<String>,
     pub config_id: i64,
+    pub impersonation: Impersonation,
 }
 
 impl ServiceClientKey {
@@ -26,6 +35,20 @@ impl ServiceClientKey {
             context_name,
             kubeconfig_path,
             config_id,
+            impersonation: Impersonation::default(),
+        }
+    }
+
+    /// The key of the client a config talks to the cluster with, including
+    /// the identity it impersonates.
+    pub fn for_config(config: &Config) -> Self {
+        Self {
+            impersonation: Impersonation::of(config),
+            ..Self::new(
+                config.context.clone(),
+                config.kubeconfig.clone(),
+                config.id.unwrap_or(-1),
+            )
         }
     }
 }
@@ -33,13 +56,15 @@ impl ServiceClientKey {
 struct CachedClient {
     client: Arc<Client>,
     created_at: Instant,
+    kubeconfig: KubeconfigFingerprint,
 }
 
 impl CachedClient {
-    fn new(client: Client) -> Self {
+    fn new(client: Client, kubeconfig: KubeconfigFingerprint) -> Self {
         Self {
             client: Arc::new(client),
             created_at: Instant::now(),
+            kubeconfig,
         }
     }
 
@@ -73,9 +98,12 @@ impl SharedClientManager {
             self.clients.remove(&key);
         }
 
-        let (client_opt, _, _) = create_client_with_specific_context(
+        let kubeconfig =
+            KubeconfigFingerprint::of(key.kubeconfig_path.clone(), key.context_name.as_deref());
+        let (client_opt, _, _) = create_client_with_impersonation(
             key.kubeconfig_path.clone(),
             key.context_name.as_deref(),
+            &key.impersonation,
         )
         .await?;
 
@@ -86,7 +114,7 @@ impl SharedClientManager {
             )
         })?;
 
-        let cached_client = CachedClient::new(client);
+        let cached_client = CachedClient::new(client, kubeconfig);
         let client_arc = cached_client.client.clone();
         self.clients.insert(key, cached_client);
         Ok(client_arc)
@@ -96,6 +124,44 @@ impl SharedClientManager {
         self.clients.remove(key);
     }
 
+    /// Drops the clients whose context, cluster or user entry changed in
+    /// their kubeconfig files since they were created and returns their keys.
+    /// Reads the files with blocking I/O, each at most once per call.
+    pub fn evict_changed_kubeconfigs(&self) -> Vec<ServiceClientKey> {
+        let mut files = KubeconfigFiles::default();
+        let changed: Vec<ServiceClientKey> = self
+            .clients
+            .iter_mut()
+            .filter_map(|mut entry| {
+                entry
+                    .kubeconfig
+                    .has_changed(&mut files)
+                    .then(|| entry.key().clone())
+            })
+            .collect();
+
+        for key in &changed {
+            self.clients.remove(key);
+        }
+        changed
+    }
+
+    /// Drops the clients created for `context_name`, for example after its
+    /// proxy settings changed, and returns their keys.
+    pub fn evict_context(&self, context_name: &str) -> Vec<ServiceClientKey> {
+        let matching: Vec<ServiceClientKey> = self
+            .clients
+            .iter()
+            .filter(|entry| entry.key().context_name.as_deref() == Some(context_name))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for key in &matching {
+            self.clients.remove(key);
+        }
+        matching
+    }
+
     pub fn cleanup_expired(&self) {
         self.clients
             .retain(|_, cached| !cached.is_expired(self.client_ttl));
@@ -130,4 +196,21 @@ mod tests {
 
         assert_eq!(key1, key2);
     }
+
+    #[test]
+    fn test_service_client_key_for_config_includes_impersonation() {
+        let config = Config {
+            id: Some(7),
+            impersonate_user: Some("deployer".to_string()),
+            ..Config::default()
+        };
+
+        let key = ServiceClientKey::for_config(&config);
+        assert_eq!(key.config_id, 7);
+        assert_eq!(key.impersonation.user.as_deref(), Some("deployer"));
+        assert_ne!(
+            key,
+            ServiceClientKey::new(config.context.clone(), config.kubeconfig.clone(), 7)
+        );
+    }
 }
@@ -0,0 +1,703 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+    HashMap,
+};
+use std::fs;
+use std::sync::{
+    LazyLock,
+    Mutex,
+};
+use std::time::Duration;
+
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{
+    EnvVar,
+    Pod,
+    PodSpec,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kftray_commons::{
+    models::config_model::Config,
+    utils::{
+        config::read_configs_with_mode,
+        config_dir::{
+            get_pod_manifest_path,
+            get_proxy_deployment_manifest_path,
+        },
+        db_mode::DatabaseMode,
+    },
+};
+use kube::Client;
+use kube::api::{
+    Api,
+    DeleteParams,
+    ListParams,
+    PostParams,
+};
+use log::{
+    debug,
+    info,
+    warn,
+};
+use rand::distr::{
+    Alphanumeric,
+    SampleString,
+};
+
+use crate::kube::debug_container::{
+    KFTRAY_SERVER_IMAGE,
+    ensure_loopback_relay_image,
+};
+use crate::kube::pod_selection::is_pod_ready;
+use crate::kube::proxy::{
+    render_json_template,
+    should_use_deployment_manifest,
+};
+use crate::kube::reconcile::{
+    OwnerIdentity,
+    apply_owner_metadata,
+    apply_owner_metadata_to_deployment,
+    stop_heartbeat,
+};
+
+/// Port the shared relay listens on inside its pod.
+pub const SHARED_RELAY_PORT: u16 = 39000;
+
+/// Label naming the kftray process that created a shared relay.
+pub const OWNER_INSTANCE_LABEL: &str = "kftray.app/owner-instance";
+
+/// First word of the header naming the destination of a relayed connection,
+/// as read by the kftray-server `mux` proxy type.
+const RELAY_HEADER_PREFIX: &str = "KFTRAY-RELAY";
+
+const RELAY_READY_TIMEOUT: Duration = Duration::from_secs(60);
+const RELAY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Random id of this kftray process. Shared relays are scoped to it, so
+/// kftui and the desktop app, or two machines with the same username, never
+/// reuse or delete each other's relay.
+static INSTANCE_ID: LazyLock<String> = LazyLock::new(|| {
+    Alphanumeric
+        .sample_string(&mut rand::rng(), 8)
+        .to_lowercase()
+});
+
+/// Token and allowed targets of the relays this process created, by relay
+/// key.
+static RELAYS: LazyLock<Mutex<HashMap<String, RelayAccess>>> = LazyLock::new(Default::default);
+
+/// Proxy configs currently forwarded through a shared relay, by config id.
+static RELAY_FORWARDS: LazyLock<Mutex<HashMap<i64, RelayForward>>> =
+    LazyLock::new(Default::default);
+
+/// What a relay accepts: its token and the `host:port` targets of the
+/// configs it was created for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RelayAccess {
+    pub token: String,
+    pub targets: BTreeSet<String>,
+}
+
+/// A proxy config forwarded through the shared relay of its namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RelayForward {
+    /// Name of the relay Deployment or Pod.
+    pub name: String,
+    /// Value of the relay's `config_id` label, also its heartbeat key.
+    pub key: String,
+    pub namespace: String,
+    pub remote_address: String,
+    pub remote_port: u16,
+    /// Token of the relay, empty until the relay is prepared.
+    pub token: String,
+}
+
+impl RelayForward {
+    pub fn selector(&self) -> String {
+        format!("app={},config_id={}", self.name, self.key)
+    }
+
+    pub fn target(&self) -> String {
+        format!("{}:{}", self.remote_address, self.remote_port)
+    }
+
+    pub fn header(&self) -> Vec<u8> {
+        format!(
+            "{RELAY_HEADER_PREFIX} {} {} {}\n",
+            self.token, self.remote_address, self.remote_port
+        )
+        .into_bytes()
+    }
+}
+
+/// Whether `config` asks to be forwarded through the shared relay instead of
+/// a pod of its own. Only tcp proxies can share a relay.
+pub(crate) fn uses_shared_relay(config: &Config) -> bool {
+    config.protocol == "tcp" && config.proxy_relay.as_deref() == Some("shared")
+}
+
+fn fnv1a(value: &str) -> u32 {
+    value.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Stable hash of this process and the cluster and namespace of `config`, so
+/// every config of a namespace shares one relay and one heartbeat.
+fn relay_hash(config: &Config) -> u32 {
+    let owner = OwnerIdentity::current();
+    fnv1a(&format!(
+        "{}/{}/{}/{}/{}/{}",
+        owner.user,
+        owner.host,
+        *INSTANCE_ID,
+        config.context.as_deref().unwrap_or_default(),
+        config.kubeconfig.as_deref().unwrap_or_default(),
+        config.namespace
+    ))
+}
+
+/// The relay forward for a proxy config. The remote address falls back to
+/// the service name, like dedicated proxy pods.
+pub(crate) fn relay_forward_for(config: &Config) -> Result<RelayForward, String> {
+    let username = whoami::username().to_lowercase();
+    let clean_username: String = username
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .take(24)
+        .collect();
+    let remote_address = config
+        .remote_address
+        .clone()
+        .filter(|s| !s.is_empty())
+        .or_else(|| config.service.clone().filter(|s| !s.is_empty()))
+        .ok_or("Proxy config has no remote address or service")?;
+    let hash = relay_hash(config);
+
+    Ok(RelayForward {
+        name: format!("kftray-shared-relay-{clean_username}-{hash:08x}"),
+        key: format!("relay-{hash:08x}"),
+        namespace: config.namespace.clone(),
+        remote_address,
+        remote_port: config
+            .remote_port
+            .ok_or("Proxy config has no remote port")?,
+        token: String::new(),
+    })
+}
+
+/// Targets of the stored shared-relay proxy configs that map to the relay
+/// `key`.
+fn relay_targets(configs: &[Config], key: &str) -> BTreeSet<String> {
+    configs
+        .iter()
+        .filter(|config| config.workload_type.as_deref() == Some("proxy"))
+        .filter(|config| uses_shared_relay(config))
+        .filter_map(|config| relay_forward_for(config).ok())
+        .filter(|forward| forward.key == key)
+        .map(|forward| forward.target())
+        .collect()
+}
+
+/// Placeholder values of the proxy manifest templates for the shared relay.
+pub(crate) fn relay_manifest_values(forward: &RelayForward) -> HashMap<&'static str, String> {
+    HashMap::from([
+        ("hashed_name", forward.name.clone()),
+        ("config_id", forward.key.clone()),
+        ("service_name", forward.name.clone()),
+        ("remote_address", "127.0.0.1".to_string()),
+        ("remote_port", SHARED_RELAY_PORT.to_string()),
+        ("local_port", SHARED_RELAY_PORT.to_string()),
+        ("protocol", "mux".to_string()),
+    ])
+}
+
+/// Sets the token, allowed targets and loopback listen address on the
+/// kftray-server container of a rendered relay manifest, whatever the
+/// template declares, and pins its image to a release that honours them.
+fn set_relay_env(spec: &mut PodSpec, access: &RelayAccess) {
+    let index = spec
+        .containers
+        .iter()
+        .position(|container| {
+            container
+                .env
+                .iter()
+                .flatten()
+                .any(|var| var.name == "PROXY_TYPE")
+        })
+        .unwrap_or_default();
+    let Some(container) = spec.containers.get_mut(index) else {
+        return;
+    };
+    container.image = Some(KFTRAY_SERVER_IMAGE.to_string());
+
+    let values = [
+        ("RELAY_TOKEN", access.token.clone()),
+        (
+            "RELAY_ALLOWED_TARGETS",
+            access.targets.iter().cloned().collect::<Vec<_>>().join(","),
+        ),
+        ("LISTEN_ADDRESS", "127.0.0.1".to_string()),
+    ];
+    let env = container.env.get_or_insert_with(Vec::new);
+    for (name, value) in values {
+        env.retain(|var| var.name != name);
+        env.push(EnvVar {
+            name: name.to_string(),
+            value: Some(value),
+            ..Default::default()
+        });
+    }
+}
+
+fn apply_instance_label(meta: &mut ObjectMeta) {
+    meta.labels
+        .get_or_insert_with(BTreeMap::new)
+        .insert(OWNER_INSTANCE_LABEL.to_string(), INSTANCE_ID.clone());
+}
+
+/// Whether an existing relay object was created by this process for `key`.
+fn is_own_relay(meta: &ObjectMeta, key: &str) -> bool {
+    meta.labels.as_ref().is_some_and(|labels| {
+        labels.get(OWNER_INSTANCE_LABEL) == Some(&*INSTANCE_ID)
+            && labels.get("config_id").map(String::as_str) == Some(key)
+    })
+}
+
+/// Creates the shared relay of the namespace unless this process already
+/// runs it, then waits until one of its pods is ready.
+async fn ensure_shared_relay(
+    client: &Client, forward: &RelayForward, access: &RelayAccess,
+) -> Result<(), String> {
+    ensure_loopback_relay_image()?;
+    let values = relay_manifest_values(forward);
+
+    let existing = if should_use_deployment_manifest() {
+        let contents = get_proxy_deployment_manifest_path()
+            .map_err(|e| e.to_string())
+            .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()))?;
+        let mut deployment: Deployment =
+            serde_json::from_str(&render_json_template(&contents, &values))
+                .map_err(|e| e.to_string())?;
+        apply_owner_metadata_to_deployment(&mut deployment);
+        apply_instance_label(&mut deployment.metadata);
+        if let Some(spec) = deployment.spec.as_mut() {
+            if let Some(template_meta) = spec.template.metadata.as_mut() {
+                apply_instance_label(template_meta);
+            }
+            if let Some(pod_spec) = spec.template.spec.as_mut() {
+                set_relay_env(pod_spec, access);
+            }
+        }
+
+        let deployments: Api<Deployment> = Api::namespaced(client.clone(), &forward.namespace);
+        match deployments
+            .create(&PostParams::default(), &deployment)
+            .await
+        {
+            Ok(_) => None,
+            Err(kube::Error::Api(response)) if response.code == 409 => Some(
+                deployments
+                    .get(&forward.name)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .metadata,
+            ),
+            Err(e) => return Err(format!("Failed to create shared relay: {e}")),
+        }
+    } else {
+        let contents = get_pod_manifest_path()
+            .map_err(|e| e.to_string())
+            .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()))?;
+        let mut pod: Pod = serde_json::from_str(&render_json_template(&contents, &values))
+            .map_err(|e| e.to_string())?;
+        apply_owner_metadata(&mut pod.metadata);
+        apply_instance_label(&mut pod.metadata);
+        if let Some(pod_spec) = pod.spec.as_mut() {
+            set_relay_env(pod_spec, access);
+        }
+
+        let pods: Api<Pod> = Api::namespaced(client.clone(), &forward.namespace);
+        match pods.create(&PostParams::default(), &pod).await {
+            Ok(_) => None,
+            Err(kube::Error::Api(response)) if response.code == 409 => Some(
+                pods.get(&forward.name)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .metadata,
+            ),
+            Err(e) => return Err(format!("Failed to create shared relay: {e}")),
+        }
+    };
+
+    match existing {
+        None => info!(
+            "Created shared relay {} in {}",
+            forward.name, forward.namespace
+        ),
+        Some(meta) if meta.deletion_timestamp.is_some() => {
+            return Err(format!("Shared relay {} is being deleted", forward.name));
+        }
+        Some(meta) if !is_own_relay(&meta, &forward.key) => {
+            return Err(format!(
+                "'{}' already exists and was not created by this kftray instance",
+                forward.name
+            ));
+        }
+        Some(_) => info!(
+            "Reusing shared relay {} in {}",
+            forward.name, forward.namespace
+        ),
+    }
+
+    wait_for_relay_pod(client, forward).await
+}
+
+/// Why a relay pod can't become ready, for relays that crash or can't pull
+/// their image. Images without the `mux` proxy type exit right away.
+fn relay_pod_failure(pod: &Pod) -> Option<String> {
+    pod.status
+        .as_ref()?
+        .container_statuses
+        .as_ref()?
+        .iter()
+        .find_map(|status| {
+            let state = status.state.as_ref();
+            if let Some(terminated) = state.and_then(|state| state.terminated.as_ref()) {
+                return Some(format!(
+                    "container {} exited with code {}",
+                    status.name, terminated.exit_code
+                ));
+            }
+            if status.restart_count > 0 {
+                return Some(format!(
+                    "container {} restarted {} time(s)",
+                    status.name, status.restart_count
+                ));
+            }
+            state
+                .and_then(|state| state.waiting.as_ref())
+                .and_then(|waiting| waiting.reason.clone())
+                .filter(|reason| {
+                    matches!(
+                        reason.as_str(),
+                        "CrashLoopBackOff"
+                            | "ErrImagePull"
+                            | "ImagePullBackOff"
+                            | "InvalidImageName"
+                            | "CreateContainerConfigError"
+                    )
+                })
+                .map(|reason| format!("container {} is in {reason}", status.name))
+        })
+}
+
+async fn wait_for_relay_pod(client: &Client, forward: &RelayForward) -> Result<(), String> {
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &forward.namespace);
+    let lp = ListParams::default().labels(&forward.selector());
+    let deadline = tokio::time::Instant::now() + RELAY_READY_TIMEOUT;
+
+    loop {
+        let list = pods.list(&lp).await.map_err(|e| e.to_string())?;
+        let live = list
+            .items
+            .iter()
+            .filter(|pod| pod.metadata.deletion_timestamp.is_none());
+        if live.clone().any(is_pod_ready) {
+            return Ok(());
+        }
+        if let Some(failure) = live.clone().find_map(relay_pod_failure) {
+            return Err(format!(
+                "Shared relay {} failed to start, its kftray-server image may not support the mux proxy type: {failure}",
+                forward.name
+            ));
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(format!(
+                "Shared relay {} did not become ready within {}s",
+                forward.name,
+                RELAY_READY_TIMEOUT.as_secs()
+            ));
+        }
+        tokio::time::sleep(RELAY_POLL_INTERVAL).await;
+    }
+}
+
+/// Gets the shared relay of the namespace of `config` ready, creating it with
+/// the targets of every stored shared-relay config of that namespace when it
+/// is not running yet. `None` when the config should use a dedicated proxy
+/// pod instead: the running relay doesn't allow its target, or the relay
+/// can't be started, for example because its image predates the `mux` proxy
+/// type.
+pub(crate) async fn prepare_shared_relay(
+    client: &Client, config: &Config, mode: DatabaseMode,
+) -> Option<RelayForward> {
+    let mut forward = match relay_forward_for(config) {
+        Ok(forward) => forward,
+        Err(e) => {
+            warn!("Config {:?} can't use a shared relay: {e}", config.id);
+            return None;
+        }
+    };
+
+    let known = RELAYS.lock().unwrap().get(&forward.key).cloned();
+    let access = match known {
+        Some(access) => access,
+        None => {
+            let configs = read_configs_with_mode(mode).await.unwrap_or_default();
+            let mut targets = relay_targets(&configs, &forward.key);
+            targets.insert(forward.target());
+            let access = RelayAccess {
+                token: Alphanumeric.sample_string(&mut rand::rng(), 32),
+                targets,
+            };
+            RELAYS
+                .lock()
+                .unwrap()
+                .entry(forward.key.clone())
+                .or_insert(access)
+                .clone()
+        }
+    };
+
+    if !access.targets.contains(&forward.target()) {
+        info!(
+            "{} is not a target of shared relay {}, using a dedicated proxy pod",
+            forward.target(),
+            forward.name
+        );
+        return None;
+    }
+    forward.token = access.token.clone();
+
+    if let Err(e) = ensure_shared_relay(client, &forward, &access).await {
+        warn!("Shared relay unavailable, using a dedicated proxy pod: {e}");
+        if !relay_in_use(&forward.key) {
+            RELAYS.lock().unwrap().remove(&forward.key);
+            delete_relay(client, &forward).await;
+        }
+        return None;
+    }
+
+    Some(forward)
+}
+
+pub(crate) fn register_relay_forward(config_id: i64, forward: RelayForward) {
+    RELAY_FORWARDS.lock().unwrap().insert(config_id, forward);
+}
+
+pub(crate) fn relay_forward(config_id: i64) -> Option<RelayForward> {
+    RELAY_FORWARDS.lock().unwrap().get(&config_id).cloned()
+}
+
+fn relay_in_use(key: &str) -> bool {
+    RELAY_FORWARDS
+        .lock()
+        .unwrap()
+        .values()
+        .any(|forward| forward.key == key)
+}
+
+/// Forgets the config, returning its relay when no other config uses it.
+fn take_relay_forward(config_id: i64) -> Option<RelayForward> {
+    let mut forwards = RELAY_FORWARDS.lock().unwrap();
+    let forward = forwards.remove(&config_id)?;
+    let in_use = forwards.values().any(|other| other.key == forward.key);
+    (!in_use).then_some(forward)
+}
+
+async fn delete_relay(client: &Client, forward: &RelayForward) {
+    let dp = DeleteParams {
+        grace_period_seconds: Some(0),
+        propagation_policy: Some(kube::api::PropagationPolicy::Background),
+        ..DeleteParams::default()
+    };
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), &forward.namespace);
+    if let Err(e) = deployments.delete(&forward.name, &dp).await {
+        debug!("No shared relay Deployment {} to delete: {e}", forward.name);
+    }
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &forward.namespace);
+    if let Err(e) = pods.delete(&forward.name, &dp).await {
+        debug!("No shared relay Pod {} to delete: {e}", forward.name);
+    }
+}
+
+/// Releases the relay of a stopped config, deleting it once the last config
+/// of this process using it is gone.
+pub(crate) async fn release_shared_relay(client: &Client, config_id: i64) {
+    let Some(forward) = take_relay_forward(config_id) else {
+        return;
+    };
+
+    stop_heartbeat(&forward.key);
+    RELAYS.lock().unwrap().remove(&forward.key);
+    delete_relay(client, &forward).await;
+
+    info!(
+        "Deleted shared relay {} in {}",
+        forward.name, forward.namespace
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::{
+        Container,
+        ContainerState,
+        ContainerStateWaiting,
+        ContainerStatus,
+        PodStatus,
+    };
+
+    use super::*;
+
+    fn proxy_config(id: i64, namespace: &str) -> Config {
+        Config {
+            id: Some(id),
+            namespace: namespace.to_string(),
+            service: Some("db".to_string()),
+            remote_port: Some(5432),
+            protocol: "tcp".to_string(),
+            workload_type: Some("proxy".to_string()),
+            proxy_relay: Some("shared".to_string()),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_relay_forward_for_config() {
+        let mut forward = relay_forward_for(&proxy_config(1, "data")).unwrap();
+
+        assert!(forward.name.starts_with("kftray-shared-relay-"));
+        assert!(forward.name.len() <= 63);
+        assert_eq!(forward.remote_address, "db");
+        assert_eq!(forward.target(), "db:5432");
+        forward.token = "s3cret".to_string();
+        assert_eq!(forward.header(), b"KFTRAY-RELAY s3cret db 5432\n".to_vec());
+        assert_eq!(
+            forward.key,
+            relay_forward_for(&proxy_config(2, "data")).unwrap().key
+        );
+        assert_ne!(
+            forward.key,
+            relay_forward_for(&proxy_config(1, "other")).unwrap().key
+        );
+    }
+
+    #[test]
+    fn test_uses_shared_relay() {
+        let mut config = proxy_config(1, "data");
+        assert!(uses_shared_relay(&config));
+
+        config.proxy_relay = None;
+        assert!(!uses_shared_relay(&config));
+
+        config.proxy_relay = Some("dedicated".to_string());
+        assert!(!uses_shared_relay(&config));
+
+        config.proxy_relay = Some("shared".to_string());
+        config.protocol = "udp".to_string();
+        assert!(!uses_shared_relay(&config));
+    }
+
+    #[test]
+    fn test_relay_targets() {
+        let key = relay_forward_for(&proxy_config(1, "data")).unwrap().key;
+        let mut cache = proxy_config(2, "data");
+        cache.remote_address = Some("10.0.0.7".to_string());
+        cache.remote_port = Some(6379);
+        let mut dedicated = proxy_config(3, "data");
+        dedicated.remote_address = Some("mq".to_string());
+        dedicated.proxy_relay = None;
+
+        let configs = vec![
+            proxy_config(1, "data"),
+            cache,
+            dedicated,
+            proxy_config(4, "other"),
+        ];
+        assert_eq!(
+            relay_targets(&configs, &key),
+            BTreeSet::from(["10.0.0.7:6379".to_string(), "db:5432".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_set_relay_env() {
+        let env = |name: &str, value: &str| EnvVar {
+            name: name.to_string(),
+            value: Some(value.to_string()),
+            ..Default::default()
+        };
+        let mut spec = PodSpec {
+            containers: vec![Container {
+                name: "kftray-server".to_string(),
+                env: Some(vec![env("PROXY_TYPE", "mux"), env("RELAY_TOKEN", "old")]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let access = RelayAccess {
+            token: "s3cret".to_string(),
+            targets: BTreeSet::from(["db:5432".to_string(), "mq:5672".to_string()]),
+        };
+
+        set_relay_env(&mut spec, &access);
+
+        let vars = spec.containers[0].env.clone().unwrap();
+        assert!(vars.contains(&env("RELAY_TOKEN", "s3cret")));
+        assert!(vars.contains(&env("RELAY_ALLOWED_TARGETS", "db:5432,mq:5672")));
+        assert!(vars.contains(&env("LISTEN_ADDRESS", "127.0.0.1")));
+        assert_eq!(vars.iter().filter(|v| v.name == "RELAY_TOKEN").count(), 1);
+        assert_eq!(
+            spec.containers[0].image.as_deref(),
+            Some(KFTRAY_SERVER_IMAGE)
+        );
+    }
+
+    #[test]
+    fn test_relay_pod_failure() {
+        let pod = |reason: &str, restarts: i32| Pod {
+            status: Some(PodStatus {
+                container_statuses: Some(vec![ContainerStatus {
+                    name: "kftray-server".to_string(),
+                    restart_count: restarts,
+                    state: Some(ContainerState {
+                        waiting: Some(ContainerStateWaiting {
+                            reason: Some(reason.to_string()),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(relay_pod_failure(&pod("ContainerCreating", 0)), None);
+        assert!(relay_pod_failure(&pod("ImagePullBackOff", 0)).is_some());
+        assert!(relay_pod_failure(&pod("ContainerCreating", 2)).is_some());
+    }
+
+    #[test]
+    fn test_relay_is_released_by_last_config() {
+        let first = relay_forward_for(&proxy_config(5001, "relay-test")).unwrap();
+        let second = relay_forward_for(&proxy_config(5002, "relay-test")).unwrap();
+        register_relay_forward(5001, first.clone());
+        register_relay_forward(5002, second);
+
+        assert_eq!(relay_forward(5001), Some(first.clone()));
+        assert!(relay_in_use(&first.key));
+        assert_eq!(take_relay_forward(5001), None);
+        assert_eq!(
+            take_relay_forward(5002).map(|f| f.key),
+            Some(first.key.clone())
+        );
+        assert_eq!(take_relay_forward(5002), None);
+        assert!(!relay_in_use(&first.key));
+    }
+}
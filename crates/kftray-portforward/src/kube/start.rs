@@ -9,8 +9,14 @@ use kftray_commons::{
         response::CustomResponse,
     },
     utils::{
+        config::get_config_with_mode,
         config_state::update_config_state_with_mode,
+        connection_drain::DrainPolicy,
         db_mode::DatabaseMode,
+        local_port::{
+            reserved_local_ports_with_mode,
+            resolve_local_port,
+        },
         settings::get_app_settings,
         timeout_manager::start_timeout_for_forward,
     },
@@ -29,12 +35,25 @@ use crate::{
         add_host_entry,
         add_ssl_host_entry,
     },
+    kube::debug_container::{
+        prepare_debug_relay,
+        wants_debug_container,
+    },
+    kube::endpoints::{
+        endpoint_proxy_config,
+        mark_endpoint_forward,
+    },
     kube::models::{
         Port,
         PortForward,
         Target,
         TargetSelector,
     },
+    kube::shared_client::ServiceClientKey,
+    kube::shared_relay::{
+        SHARED_RELAY_PORT,
+        relay_forward,
+    },
     port_forward::{
         CHILD_PROCESSES,
         PROCESS_MANAGEMENT_LOCK,
@@ -85,6 +104,30 @@ fn workload_type_description(workload_type: Option<&str>) -> &'static str {
 
 static FALLBACK_ALLOCATION_MUTEX: Lazy<TokioMutex<()>> = Lazy::new(|| TokioMutex::new(()));
 
+/// Moves `config` to another local port when its port is taken and its
+/// `port_conflict_policy` allows it. Only this start uses the new port; the
+/// stored config keeps its own. Returns the port that was in use.
+async fn resolve_local_port_conflict(
+    config: &mut Config, local_address: &str, mode: DatabaseMode,
+) -> Result<Option<u16>, String> {
+    let reserved = reserved_local_ports_with_mode(local_address, config.id, mode)
+        .await
+        .unwrap_or_default();
+    let Some(port) = resolve_local_port(config, local_address, &reserved)? else {
+        return Ok(None);
+    };
+
+    let previous = config.local_port;
+    info!(
+        "Local port {local_address}:{} is in use, using {port} for config {}",
+        previous.unwrap_or_default(),
+        config.id.unwrap_or_default()
+    );
+    config.local_port = Some(port);
+
+    Ok(previous)
+}
+
 async fn allocate_local_address_for_config(config: &mut Config) -> Result<String, String> {
     if !config.auto_loopback_address {
         let address = config
@@ -305,10 +348,25 @@ async fn get_allocated_loopback_addresses() -> std::collections::HashSet<String>
     allocated
 }
 
+/// The stored config with the local address of `config`. Proxy forwards
+/// start from a modified copy of the config, which must not replace the
+/// stored definition.
+async fn stored_with_local_address(config: &Config, mode: DatabaseMode) -> Config {
+    let mut stored = match config.id {
+        Some(id) => get_config_with_mode(id, mode)
+            .await
+            .unwrap_or_else(|_| config.clone()),
+        None => config.clone(),
+    };
+    stored.local_address.clone_from(&config.local_address);
+    stored
+}
+
 async fn save_allocated_address_to_db(config: &Config) -> Result<(), String> {
     use kftray_commons::utils::config::update_config;
 
-    match update_config(config.clone()).await {
+    let stored = stored_with_local_address(config, DatabaseMode::File).await;
+    match update_config(stored).await {
         Ok(_) => {
             info!(
                 "Successfully saved allocated address to database for config {}",
@@ -353,6 +411,46 @@ pub async fn start_port_forward_with_mode(
             continue;
         }
 
+        if config.workload_type.as_deref() == Some("service") && config.protocol == "tcp" {
+            match endpoint_proxy_config(config).await {
+                Ok(Some(proxy)) => {
+                    info!(
+                        "Service {:?} has no selector, forwarding to {}:{} through a proxy pod",
+                        config.service,
+                        proxy.remote_address.clone().unwrap_or_default(),
+                        proxy.remote_port.unwrap_or_default()
+                    );
+                    match Box::pin(super::proxy::deploy_and_forward_pod_with_mode(
+                        vec![proxy],
+                        mode,
+                        ssl_override,
+                    ))
+                    .await
+                    {
+                        Ok(response) => {
+                            mark_endpoint_forward(config.id.unwrap_or_default());
+                            responses.extend(response);
+                        }
+                        Err(e) => {
+                            let error_message = format!(
+                                "Failed to forward selector-less service {:?}: {e}",
+                                config.service
+                            );
+                            error!("{}", &error_message);
+                            errors.push(error_message);
+                        }
+                    }
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    error!("{e}");
+                    errors.push(e);
+                    continue;
+                }
+            }
+        }
+
         let selector = match (config.workload_type.as_deref(), config.protocol.as_str()) {
             (Some("pod"), "tcp") => {
                 TargetSelector::PodLabel(config.target.clone().unwrap_or_default())
@@ -382,10 +480,37 @@ pub async fn start_port_forward_with_mode(
         };
 
         let remote_port = Port::from(config.remote_port.unwrap_or_default() as i32);
-        let context_name = Some(config.context.clone());
-        let kubeconfig = Some(config.kubeconfig.clone());
         let namespace = config.namespace.clone();
-        let target = Target::new(selector, remote_port, namespace.clone());
+        let mut target = Target::new(selector, remote_port, namespace.clone())
+            .with_selection(config.pod_selection.clone().unwrap_or_default())
+            .with_drain_policy(DrainPolicy::of(config));
+
+        if config.workload_type.as_deref() == Some("proxy")
+            && let Some(relay) = relay_forward(config.id.unwrap_or_default())
+        {
+            target.selector = TargetSelector::PodLabel(relay.selector());
+            target.port = Port::Number(i32::from(SHARED_RELAY_PORT));
+            target = target.with_relay_header(relay.header());
+        }
+
+        if wants_debug_container(config) {
+            match prepare_debug_relay(config, &target).await {
+                Ok(Some(relay)) => {
+                    info!(
+                        "Forwarding through debug container {} in pod {}",
+                        relay.container_name, relay.pod_name
+                    );
+                    target.selector = TargetSelector::PodName(relay.pod_name);
+                    target.port = Port::Number(i32::from(relay.port));
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    error!("{e}");
+                    errors.push(e);
+                    continue;
+                }
+            }
+        }
 
         debug!("Remote Port: {:?}", config.remote_port);
         debug!("Local Port: {:?}", config.local_port);
@@ -404,6 +529,16 @@ pub async fn start_port_forward_with_mode(
             }
         };
 
+        let replaced_port =
+            match resolve_local_port_conflict(config, &final_local_address, mode).await {
+                Ok(replaced_port) => replaced_port,
+                Err(e) => {
+                    error!("{e}");
+                    errors.push(e);
+                    continue;
+                }
+            };
+
         if config.domain_enabled.unwrap_or_default()
             && let Some(service_name) = &config.service
         {
@@ -449,9 +584,7 @@ pub async fn start_port_forward_with_mode(
             target,
             actual_config.local_port,
             local_address_clone,
-            context_name.clone().flatten(),
-            kubeconfig.flatten(),
-            actual_config.id.unwrap_or_default(),
+            ServiceClientKey::for_config(&actual_config),
             actual_config.workload_type.clone().unwrap_or_default(),
         )
         .await;
@@ -514,7 +647,10 @@ pub async fn start_port_forward_with_mode(
                             .await
                             .insert(handle_key.clone(), handle);
 
-                        let config_state = ConfigState::new(config.id.unwrap(), true);
+                        let config_state = ConfigState {
+                            local_port: replaced_port.map(|_| actual_local_port),
+                            ..ConfigState::new(config.id.unwrap(), true)
+                        };
                         if let Err(e) = update_config_state_with_mode(&config_state, mode).await {
                             error!("Failed to update config state: {e}");
                         }
@@ -664,7 +800,7 @@ pub async fn start_port_forward_with_mode(
                                     protocol.to_uppercase()
                                 };
                                 format!(
-                                    "{} forwarding from 127.0.0.1:{} -> {:?}:{}{}",
+                                    "{} forwarding from 127.0.0.1:{} -> {:?}:{}{}{}",
                                     protocol_display,
                                     actual_local_port,
                                     config.remote_port.unwrap_or_default(),
@@ -673,7 +809,10 @@ pub async fn start_port_forward_with_mode(
                                         " (HTTP redirects to HTTPS)"
                                     } else {
                                         ""
-                                    }
+                                    },
+                                    replaced_port
+                                        .map(|port| format!(" (local port {port} was in use)"))
+                                        .unwrap_or_default()
                                 )
                             },
                             stderr: String::new(),
@@ -797,6 +936,15 @@ mod tests {
             cert_issuer_kind: None,
             ingress_class: None,
             ingress_annotations: None,
+            groups: None,
+            port_conflict_policy: None,
+            impersonate_user: None,
+            impersonate_groups: Vec::new(),
+            pod_selection: None,
+            drain_policy: None,
+            drain_timeout_secs: None,
+            debug_container: None,
+            proxy_relay: None,
         }
     }
 
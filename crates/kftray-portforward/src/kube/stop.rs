@@ -38,10 +38,12 @@ use crate::hostsfile::{
     remove_host_entry,
     remove_ssl_host_entry,
 };
+use crate::kube::endpoints::take_endpoint_forward;
 use crate::kube::shared_client::{
     SHARED_CLIENT_MANAGER,
     ServiceClientKey,
 };
+use crate::kube::shared_relay::release_shared_relay;
 #[cfg(test)]
 use crate::port_forward::PortForwardProcess;
 use crate::port_forward::{
@@ -125,6 +127,8 @@ pub async fn stop_all_port_forward_with_mode(
     crate::ssl::ensure_crypto_provider_installed();
     info!("Attempting to stop all port forwards in mode: {mode:?}");
 
+    crate::kube::reconcile::stop_all_heartbeats();
+
     let mut responses = Vec::with_capacity(1024);
 
     let handle_keys: Vec<String> = {
@@ -282,26 +286,20 @@ pub async fn stop_all_port_forward_with_mode(
         .iter()
         .filter(|config| running_configs_state.contains(&config.id.unwrap_or_default()))
         .filter(|config| {
-            config.protocol == "udp" || matches!(config.workload_type.as_deref(), Some("proxy"))
-        })
-        .filter_map(|config| {
-            config
-                .kubeconfig
-                .as_ref()
-                .map(|kubeconfig| (config, kubeconfig))
+            config.protocol == "udp"
+                || matches!(config.workload_type.as_deref(), Some("proxy"))
+                || take_endpoint_forward(config.id.unwrap_or_default())
         })
-        .map(|(config, kubeconfig)| {
+        .filter(|config| config.kubeconfig.is_some())
+        .map(|config| {
             let config_id_str = config.id.unwrap_or_default();
             async move {
-                let client_key = ServiceClientKey::new(
-                    config.context.clone(),
-                    Some(kubeconfig.clone()),
-                    config_id_str,
-                );
+                let client_key = ServiceClientKey::for_config(config);
 
                 match SHARED_CLIENT_MANAGER.get_client(client_key).await {
                     Ok(shared_client) => {
                         let client = Client::clone(&shared_client);
+                        release_shared_relay(&client, config_id_str).await;
                         let pods: Api<Pod> = Api::all(client.clone());
                         let lp =
                             ListParams::default().labels(&format!("config_id={config_id_str}"));
@@ -425,6 +423,8 @@ pub async fn stop_port_forward_with_mode(
         .parse::<i64>()
         .map_err(|_| "Invalid config ID".to_string())?;
 
+    crate::kube::reconcile::stop_heartbeat(&config_id);
+
     let configs = match mode {
         DatabaseMode::File => get_configs().await.unwrap_or_default(),
         DatabaseMode::Memory => read_configs_with_mode(mode).await.unwrap_or_default(),
@@ -436,6 +436,18 @@ pub async fn stop_port_forward_with_mode(
         return crate::expose::stop_expose(config_id_parsed, &config.namespace, mode).await;
     }
 
+    if let Some(config) = configs.iter().find(|c| c.id == Some(config_id_parsed))
+        && take_endpoint_forward(config_id_parsed)
+    {
+        return Box::pin(crate::kube::proxy::stop_proxy_forward_with_mode(
+            config_id_parsed,
+            &config.namespace,
+            config.service.clone().unwrap_or_default(),
+            mode,
+        ))
+        .await;
+    }
+
     let composite_key = {
         let _global_lock = PROCESS_MANAGEMENT_LOCK.lock().await;
         let child_processes = CHILD_PROCESSES.lock().await;
@@ -514,11 +526,7 @@ pub async fn stop_port_forward_with_mode(
         }
 
         if let Some(config) = configs.iter().find(|c| c.id == Some(config_id_parsed)) {
-            let client_key = ServiceClientKey::new(
-                config.context.clone(),
-                config.kubeconfig.clone(),
-                config_id_parsed,
-            );
+            let client_key = ServiceClientKey::for_config(config);
             SHARED_CLIENT_MANAGER.invalidate_client(&client_key);
             debug!("Invalidated client for config {}", config_id);
         }
@@ -615,6 +623,15 @@ mod tests {
             cert_issuer_kind: None,
             ingress_class: None,
             ingress_annotations: None,
+            groups: None,
+            port_conflict_policy: None,
+            impersonate_user: None,
+            impersonate_groups: Vec::new(),
+            pod_selection: None,
+            drain_policy: None,
+            drain_timeout_secs: None,
+            debug_container: None,
+            proxy_relay: None,
         }
     }
 
@@ -20,6 +20,7 @@ use tokio::net::TcpStream;
 use tokio::time::timeout;
 use tower_test::mock;
 
+use crate::kube::client::Impersonation;
 use crate::kube::models::{
     PortForward,
     Target,
@@ -235,6 +236,15 @@ fn setup_test_config() -> Config {
         cert_issuer_kind: None,
         ingress_class: None,
         ingress_annotations: None,
+        groups: None,
+        port_conflict_policy: None,
+        impersonate_user: None,
+        impersonate_groups: Vec::new(),
+        pod_selection: None,
+        drain_policy: None,
+        drain_timeout_secs: None,
+        debug_container: None,
+        proxy_relay: None,
     }
 }
 
@@ -300,6 +310,7 @@ async fn test_port_forward_tcp_success() -> Result<()> {
         context_name: Some("test-context".to_string()),
         kubeconfig: None,
         config_id: 1,
+        impersonation: Impersonation::default(),
         workload_type: "service".to_string(),
         connection: Arc::new(tokio::sync::Mutex::new(None)),
     };
@@ -404,6 +415,7 @@ async fn test_port_forward_udp_success() -> Result<()> {
         context_name: Some("test-context".to_string()),
         kubeconfig: None,
         config_id: 1,
+        impersonation: Impersonation::default(),
         workload_type: "service".to_string(),
         connection: Arc::new(tokio::sync::Mutex::new(None)),
     };
@@ -555,6 +567,7 @@ async fn test_start_port_forward_mock_components() -> Result<()> {
         context_name: test_config.context.clone(),
         kubeconfig: None,
         config_id: test_config.id.unwrap_or_default(),
+        impersonation: Impersonation::default(),
         workload_type: test_config.workload_type.clone().unwrap_or_default(),
         connection: Arc::new(tokio::sync::Mutex::new(None)),
     };
@@ -667,6 +680,7 @@ async fn test_start_port_forward_mock_components_udp() -> Result<()> {
         context_name: test_config.context.clone(),
         kubeconfig: None,
         config_id: test_config.id.unwrap_or_default(),
+        impersonation: Impersonation::default(),
         workload_type: test_config.workload_type.clone().unwrap_or_default(),
         connection: Arc::new(tokio::sync::Mutex::new(None)),
     };
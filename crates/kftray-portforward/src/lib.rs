@@ -23,7 +23,13 @@ pub use kube::models::{
 };
 pub use kube::operations::*;
 pub use kube::{
+    ForwardPlan,
+    PlanStatus,
+    PlanStep,
     deploy_and_forward_pod,
+    plan_port_forward,
+    plan_port_forward_with_mode,
+    render_plans,
     retrieve_service_configs,
     start_port_forward,
     stop_all_port_forward,
@@ -138,13 +138,12 @@ lazy_static! {
 impl PortForward {
     pub async fn new(
         target: Target, local_port: impl Into<Option<u16>>,
-        local_address: impl Into<Option<String>>, context_name: Option<String>,
-        kubeconfig: Option<String>, config_id: i64, workload_type: String,
+        local_address: impl Into<Option<String>>, client_key: ServiceClientKey,
+        workload_type: String,
     ) -> anyhow::Result<Self> {
         let namespace = target.namespace.name_any();
 
-        let client_key = ServiceClientKey::new(context_name.clone(), kubeconfig.clone(), config_id);
-        let client = SHARED_CLIENT_MANAGER.get_client(client_key).await?;
+        let client = SHARED_CLIENT_MANAGER.get_client(client_key.clone()).await?;
 
         let shared_client = Client::clone(&client);
         let pod_api = Api::namespaced(shared_client.clone(), &namespace);
@@ -157,14 +156,26 @@ impl PortForward {
             pod_api,
             svc_api,
             client: shared_client,
-            context_name: context_name.clone(),
-            kubeconfig: kubeconfig.clone(),
-            config_id,
+            context_name: client_key.context_name,
+            kubeconfig: client_key.kubeconfig_path,
+            config_id: client_key.config_id,
+            impersonation: client_key.impersonation,
             workload_type,
             connection: Arc::new(Mutex::new(None)),
         })
     }
 
+    fn client_key(&self) -> ServiceClientKey {
+        ServiceClientKey {
+            impersonation: self.impersonation.clone(),
+            ..ServiceClientKey::new(
+                self.context_name.clone(),
+                self.kubeconfig.clone(),
+                self.config_id,
+            )
+        }
+    }
+
     pub fn local_port(&self) -> u16 {
         self.local_port.unwrap_or(0)
     }
@@ -195,14 +206,8 @@ impl PortForward {
 
         let namespace = self.target.namespace.name_any();
 
-        let mut direct_forwarder = PortForwarder::new(
-            &namespace,
-            self.target.clone(),
-            self.context_name.clone(),
-            self.kubeconfig.clone(),
-            self.config_id,
-        )
-        .await?;
+        let mut direct_forwarder =
+            PortForwarder::new(&namespace, self.target.clone(), self.client_key()).await?;
 
         if let Err(e) = direct_forwarder.initialize(&self.target).await {
             direct_forwarder.shutdown().await;
@@ -253,14 +258,8 @@ impl PortForward {
 
         let namespace = self.target.namespace.name_any();
 
-        let mut direct_forwarder = PortForwarder::new(
-            &namespace,
-            self.target.clone(),
-            self.context_name.clone(),
-            self.kubeconfig.clone(),
-            self.config_id,
-        )
-        .await?;
+        let mut direct_forwarder =
+            PortForwarder::new(&namespace, self.target.clone(), self.client_key()).await?;
 
         if let Err(e) = direct_forwarder.initialize(&self.target).await {
             direct_forwarder.shutdown().await;
@@ -332,11 +331,8 @@ mod tests {
     use tracing_subscriber;
 
     use super::*;
-    use crate::kube::models::{
-        NameSpace,
-        Port,
-        TargetSelector,
-    };
+    use crate::kube::client::Impersonation;
+    use crate::kube::models::TargetSelector;
 
     fn mock_pod(
         name: &str, namespace: &str, labels: Option<BTreeMap<String, String>>, ready: bool,
@@ -459,11 +455,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_local_port_and_address() {
-        let target = Target {
-            selector: TargetSelector::ServiceName("test-service".to_string()),
-            port: Port::Number(8080),
-            namespace: NameSpace(Some("default".to_string())),
-        };
+        let target = Target::new(
+            TargetSelector::ServiceName("test-service".to_string()),
+            8080,
+            "default",
+        );
 
         let (mock_service, _handle) = mock::pair::<Request<Body>, Response<Body>>();
         let client = Client::new(mock_service, "default");
@@ -478,6 +474,7 @@ mod tests {
             context_name: None,
             kubeconfig: None,
             config_id: 1,
+            impersonation: Impersonation::default(),
             workload_type: "pod".to_string(),
             connection: Arc::new(Mutex::new(None)),
         };
@@ -495,6 +492,7 @@ mod tests {
             context_name: None,
             kubeconfig: None,
             config_id: 2,
+            impersonation: Impersonation::default(),
             workload_type: "pod".to_string(),
             connection: Arc::new(Mutex::new(None)),
         };
@@ -505,11 +503,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_port_forward_new_context_propagation() {
-        let target = Target {
-            selector: TargetSelector::ServiceName("test-service".to_string()),
-            port: Port::Number(8080),
-            namespace: NameSpace(Some("default".to_string())),
-        };
+        let target = Target::new(
+            TargetSelector::ServiceName("test-service".to_string()),
+            8080,
+            "default",
+        );
         let context_name = Some("my-kube-context".to_string());
         let _kubeconfig = Some("/path/to/config".to_string());
 
@@ -526,6 +524,7 @@ mod tests {
             context_name: context_name.clone(),
             kubeconfig: None,
             config_id: 1,
+            impersonation: Impersonation::default(),
             workload_type: "service".to_string(),
             connection: Arc::new(Mutex::new(None)),
         };
@@ -538,11 +537,11 @@ mod tests {
         Client,
         mock::Handle<Request<Body>, Response<Body>>,
     ) {
-        let target = Target {
-            selector: TargetSelector::ServiceName("test-svc".to_string()),
-            port: Port::Number(80),
-            namespace: NameSpace(Some("test-ns".to_string())),
-        };
+        let target = Target::new(
+            TargetSelector::ServiceName("test-svc".to_string()),
+            80,
+            "test-ns",
+        );
 
         let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
         let client = Client::new(mock_service, "test-ns");
@@ -557,6 +556,7 @@ mod tests {
             context_name: None,
             kubeconfig: None,
             config_id: 1,
+            impersonation: Impersonation::default(),
             workload_type: "service".to_string(),
             connection: Arc::new(Mutex::new(None)),
         };
@@ -694,6 +694,7 @@ mod tests {
             context_name: None,
             kubeconfig: None,
             config_id: 1,
+            impersonation: Impersonation::default(),
             workload_type: "service".to_string(),
             connection: Arc::new(Mutex::new(None)),
         };
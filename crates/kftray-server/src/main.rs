@@ -16,6 +16,7 @@ use url::Url;
 
 use crate::proxy::{
     config::{
+        DEFAULT_LISTEN_ADDRESS,
         ProxyConfig,
         ProxyType,
     },
@@ -32,8 +33,17 @@ use crate::proxy::{
 /// * `REMOTE_ADDRESS` - Target server hostname/IP
 /// * `REMOTE_PORT` - Target server port
 /// * `LOCAL_PORT` - Local proxy listening port
-/// * `PROXY_TYPE` - Protocol type ("tcp" or "udp")
+/// * `PROXY_TYPE` - Protocol type ("tcp", "udp", "reverse_http" or "mux")
+/// * `LISTEN_ADDRESS` - IP address to listen on, `0.0.0.0` when unset
+///
+/// The `mux` relay only needs `LOCAL_PORT`, `RELAY_TOKEN` and
+/// `RELAY_ALLOWED_TARGETS`, each connection names its target. It listens on
+/// `127.0.0.1` unless `LISTEN_ADDRESS` is set.
 fn load_config() -> Result<ProxyConfig, ProxyError> {
+    if env::var("PROXY_TYPE").is_ok_and(|value| value.eq_ignore_ascii_case("mux")) {
+        return load_mux_config();
+    }
+
     let target_host = env::var("REMOTE_ADDRESS")
         .map_err(|_| ProxyError::Configuration("REMOTE_ADDRESS not set".into()))?;
 
@@ -114,6 +124,7 @@ fn load_config() -> Result<ProxyConfig, ProxyError> {
         .resolved_ip(resolved_ip)
         .target_port(target_port)
         .proxy_port(proxy_port)
+        .listen_address(load_listen_address(DEFAULT_LISTEN_ADDRESS)?)
         .proxy_type(proxy_type)
         .http_port(http_port)
         .websocket_port(websocket_port)
@@ -123,6 +134,58 @@ fn load_config() -> Result<ProxyConfig, ProxyError> {
     Ok(config)
 }
 
+/// Reads the IP address to listen on from `LISTEN_ADDRESS`
+///
+/// # Parameters
+/// * `default` - Address used when the variable is not set
+fn load_listen_address(default: &str) -> Result<String, ProxyError> {
+    match env::var("LISTEN_ADDRESS") {
+        Ok(address) => address
+            .parse::<std::net::IpAddr>()
+            .map(|ip| ip.to_string())
+            .map_err(|_| ProxyError::Configuration(format!("Invalid LISTEN_ADDRESS: {address}"))),
+        Err(_) => Ok(default.to_string()),
+    }
+}
+
+/// Loads the configuration of the multiplexed relay
+///
+/// # Environment Variables
+/// * `RELAY_TOKEN` - Token every connection must present
+/// * `RELAY_ALLOWED_TARGETS` - Comma-separated `host:port` targets
+fn load_mux_config() -> Result<ProxyConfig, ProxyError> {
+    let proxy_port = env::var("LOCAL_PORT")
+        .map_err(|_| ProxyError::Configuration("LOCAL_PORT not set".into()))?
+        .parse()
+        .map_err(|_| ProxyError::Configuration("Invalid LOCAL_PORT".into()))?;
+
+    let relay_token = env::var("RELAY_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| ProxyError::Configuration("RELAY_TOKEN not set".into()))?;
+
+    let relay_targets = env::var("RELAY_ALLOWED_TARGETS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|target| !target.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let config = ProxyConfig::builder()
+        .target_host(String::new())
+        .target_port(0)
+        .proxy_port(proxy_port)
+        .listen_address(load_listen_address("127.0.0.1")?)
+        .proxy_type(ProxyType::Mux)
+        .relay_token(Some(relay_token))
+        .relay_targets(relay_targets)
+        .build()?;
+
+    println!("Final config proxy type: {:?}", config.proxy_type);
+    Ok(config)
+}
+
 /// Main entry point for the proxy server application
 ///
 /// Sets up logging, loads configuration, starts the proxy server,
@@ -242,6 +305,7 @@ mod tests {
             ProxyType::Tcp => {}
             ProxyType::Udp => panic!("Expected TCP proxy type, got UDP"),
             ProxyType::ReverseHttp => panic!("Expected TCP proxy type, got ReverseHttp"),
+            ProxyType::Mux => panic!("Expected TCP proxy type, got Mux"),
         }
     }
 
@@ -266,6 +330,7 @@ mod tests {
             ProxyType::Udp => {}
             ProxyType::Tcp => panic!("Expected UDP proxy type, got TCP"),
             ProxyType::ReverseHttp => panic!("Expected UDP proxy type, got ReverseHttp"),
+            ProxyType::Mux => panic!("Expected UDP proxy type, got Mux"),
         }
     }
 
@@ -290,6 +355,7 @@ mod tests {
             ProxyType::Tcp => {}
             ProxyType::Udp => panic!("Expected TCP proxy type, got UDP"),
             ProxyType::ReverseHttp => panic!("Expected TCP proxy type, got ReverseHttp"),
+            ProxyType::Mux => panic!("Expected TCP proxy type, got Mux"),
         }
     }
 
@@ -306,6 +372,54 @@ mod tests {
         assert!(load_config().is_err());
     }
 
+    #[test]
+    fn test_load_config_listen_address() {
+        let _lock = ENV_TEST_MUTEX.lock().unwrap();
+        let _guard_addr = EnvVarGuard::set("REMOTE_ADDRESS", "127.0.0.1");
+        let _guard_rport = EnvVarGuard::set("REMOTE_PORT", "6060");
+        let _guard_lport = EnvVarGuard::set("LOCAL_PORT", "46060");
+        let _guard_type = EnvVarGuard::set("PROXY_TYPE", "tcp");
+
+        {
+            let _guard_listen = EnvVarGuard::remove("LISTEN_ADDRESS");
+            assert_eq!(load_config().unwrap().listen_address, "0.0.0.0");
+        }
+
+        {
+            let _guard_listen = EnvVarGuard::set("LISTEN_ADDRESS", "127.0.0.1");
+            assert_eq!(load_config().unwrap().listen_address, "127.0.0.1");
+        }
+
+        {
+            let _guard_listen = EnvVarGuard::set("LISTEN_ADDRESS", "localhost:80");
+            assert!(load_config().is_err());
+        }
+    }
+
+    #[test]
+    fn test_load_config_mux_without_remote() {
+        let _lock = ENV_TEST_MUTEX.lock().unwrap();
+        let _guard_addr = EnvVarGuard::remove("REMOTE_ADDRESS");
+        let _guard_rport = EnvVarGuard::remove("REMOTE_PORT");
+        let _guard_lport = EnvVarGuard::set("LOCAL_PORT", "39000");
+        let _guard_type = EnvVarGuard::set("PROXY_TYPE", "mux");
+        let _guard_listen = EnvVarGuard::remove("LISTEN_ADDRESS");
+        let _guard_targets = EnvVarGuard::set("RELAY_ALLOWED_TARGETS", "db:5432, 10.0.0.7:80");
+
+        {
+            let _guard_token = EnvVarGuard::remove("RELAY_TOKEN");
+            assert!(load_config().is_err());
+        }
+
+        let _guard_token = EnvVarGuard::set("RELAY_TOKEN", "s3cret");
+        let config = load_config().unwrap();
+        assert_eq!(config.proxy_port, 39000);
+        assert_eq!(config.listen_address, "127.0.0.1");
+        assert_eq!(config.relay_token.as_deref(), Some("s3cret"));
+        assert_eq!(config.relay_targets, vec!["db:5432", "10.0.0.7:80"]);
+        assert!(matches!(config.proxy_type, ProxyType::Mux));
+    }
+
     #[test]
     fn test_load_config_invalid_proxy_type() {
         let _lock = ENV_TEST_MUTEX.lock().unwrap();
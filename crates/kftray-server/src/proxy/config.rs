@@ -1,3 +1,6 @@
+/// Address proxies listen on unless `LISTEN_ADDRESS` is set
+pub const DEFAULT_LISTEN_ADDRESS: &str = "0.0.0.0";
+
 /// Configuration settings for a proxy instance
 #[derive(Debug, Clone)]
 pub struct ProxyConfig {
@@ -10,12 +13,18 @@ pub struct ProxyConfig {
     pub target_port: u16,
     /// Local port number the proxy listens on
     pub proxy_port: u16,
+    /// IP address the proxy listens on
+    pub listen_address: String,
     /// Type of proxy protocol (TCP or UDP)
     pub proxy_type: ProxyType,
     /// Port for HTTP server (used in ReverseHttp mode)
     pub http_port: Option<u16>,
     /// Port for WebSocket server (used in ReverseHttp mode)
     pub websocket_port: Option<u16>,
+    /// Token every connection must present (used in Mux mode)
+    pub relay_token: Option<String>,
+    /// `host:port` targets connections may ask for (used in Mux mode)
+    pub relay_targets: Vec<String>,
 }
 
 /// Builder pattern implementation for creating ProxyConfig instances
@@ -25,9 +34,12 @@ pub struct ProxyConfigBuilder {
     resolved_ip: Option<String>,
     target_port: Option<u16>,
     proxy_port: Option<u16>,
+    listen_address: Option<String>,
     proxy_type: Option<ProxyType>,
     http_port: Option<u16>,
     websocket_port: Option<u16>,
+    relay_token: Option<String>,
+    relay_targets: Vec<String>,
 }
 
 impl ProxyConfigBuilder {
@@ -55,6 +67,11 @@ impl ProxyConfigBuilder {
         self
     }
 
+    pub fn listen_address(mut self, address: String) -> Self {
+        self.listen_address = Some(address);
+        self
+    }
+
     pub fn proxy_type(mut self, proxy_type: ProxyType) -> Self {
         self.proxy_type = Some(proxy_type);
         self
@@ -70,6 +87,16 @@ impl ProxyConfigBuilder {
         self
     }
 
+    pub fn relay_token(mut self, token: Option<String>) -> Self {
+        self.relay_token = token;
+        self
+    }
+
+    pub fn relay_targets(mut self, targets: Vec<String>) -> Self {
+        self.relay_targets = targets;
+        self
+    }
+
     pub fn build(self) -> Result<ProxyConfig, String> {
         let target_host = self
             .target_host
@@ -89,9 +116,14 @@ impl ProxyConfigBuilder {
             resolved_ip: self.resolved_ip,
             target_port,
             proxy_port,
+            listen_address: self
+                .listen_address
+                .unwrap_or_else(|| DEFAULT_LISTEN_ADDRESS.to_string()),
             proxy_type,
             http_port: self.http_port,
             websocket_port: self.websocket_port,
+            relay_token: self.relay_token,
+            relay_targets: self.relay_targets,
         })
     }
 }
@@ -111,6 +143,8 @@ pub enum ProxyType {
     Udp,
     /// Reverse HTTP proxy mode (WebSocket tunnel)
     ReverseHttp,
+    /// Multiplexed TCP relay, each connection names its own target
+    Mux,
 }
 
 #[cfg(test)]
@@ -131,9 +165,24 @@ mod tests {
         assert_eq!(config.resolved_ip, None);
         assert_eq!(config.target_port, 8080);
         assert_eq!(config.proxy_port, 9090);
+        assert_eq!(config.listen_address, DEFAULT_LISTEN_ADDRESS);
         assert!(matches!(config.proxy_type, ProxyType::Tcp));
     }
 
+    #[test]
+    fn test_config_builder_listen_address() {
+        let config = ProxyConfig::builder()
+            .target_host("127.0.0.1".to_string())
+            .target_port(6060)
+            .proxy_port(46060)
+            .listen_address("127.0.0.1".to_string())
+            .proxy_type(ProxyType::Tcp)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.listen_address, "127.0.0.1");
+    }
+
     #[test]
     fn test_config_builder_missing_fields() {
         let result = ProxyConfig::builder().build();
@@ -1,5 +1,6 @@
 pub mod config;
 pub mod error;
+pub mod mux;
 pub mod reverse;
 pub mod reverse_http;
 pub mod server;
@@ -0,0 +1,368 @@
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use log::{
+    error,
+    info,
+};
+use tokio::{
+    io::{
+        AsyncReadExt,
+        copy_bidirectional,
+    },
+    net::{
+        TcpListener,
+        TcpStream,
+    },
+    sync::Notify,
+    time::timeout,
+};
+
+use crate::proxy::{
+    config::ProxyConfig,
+    error::ProxyError,
+    tcp::TcpProxy,
+    traits::ProxyHandler,
+};
+
+/// First word of the header line sent at the start of every relayed
+/// connection: `KFTRAY-RELAY <token> <host> <port>\n`
+pub const HEADER_PREFIX: &str = "KFTRAY-RELAY";
+
+const MAX_HEADER_LEN: usize = 512;
+const HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// TCP relay shared by several forwards. Each connection names its own
+/// target in a header line, so one pod can serve every proxy config of a
+/// user in a namespace. Connections must present the relay token and may
+/// only ask for the targets the relay was started with.
+#[derive(Clone)]
+pub struct MuxProxy;
+
+impl MuxProxy {
+    /// Creates a new multiplexed relay instance
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parses a header line into the token, target host and port
+    ///
+    /// # Parameters
+    /// * `line` - Header line without the trailing newline
+    ///
+    /// # Returns
+    /// * `Result<RelayHeader, ProxyError>` - Parsed header or error
+    pub fn parse_header(line: &str) -> Result<RelayHeader, ProxyError> {
+        let mut parts = line.trim_end_matches('\r').split(' ');
+
+        match (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) {
+            (Some(HEADER_PREFIX), Some(token), Some(host), Some(port), None)
+                if !token.is_empty() && !host.is_empty() =>
+            {
+                let port = port
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|port| *port != 0)
+                    .ok_or_else(|| ProxyError::InvalidData(format!("Invalid port: {port}")))?;
+                Ok(RelayHeader {
+                    token: token.to_string(),
+                    host: host.to_string(),
+                    port,
+                })
+            }
+            _ => Err(ProxyError::InvalidData("Invalid relay header".into())),
+        }
+    }
+
+    /// Checks the token and target of a header against the relay
+    /// configuration
+    ///
+    /// # Parameters
+    /// * `header` - Parsed header of the connection
+    /// * `config` - Relay configuration with the token and allowed targets
+    pub fn authorize(header: &RelayHeader, config: &ProxyConfig) -> Result<(), ProxyError> {
+        let token_matches = config
+            .relay_token
+            .as_deref()
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), header.token.as_bytes()));
+        if !token_matches {
+            return Err(ProxyError::Connection("Invalid relay token".into()));
+        }
+
+        let target = format!("{}:{}", header.host, header.port);
+        if !config.relay_targets.contains(&target) {
+            return Err(ProxyError::Connection(format!(
+                "Target {target} is not allowed by this relay"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the header line byte by byte, so no payload after it is consumed
+    async fn read_header(stream: &mut TcpStream) -> Result<RelayHeader, ProxyError> {
+        let mut line = Vec::with_capacity(64);
+
+        loop {
+            let byte = stream.read_u8().await?;
+            if byte == b'\n' {
+                break;
+            }
+            if line.len() == MAX_HEADER_LEN {
+                return Err(ProxyError::InvalidData("Relay header too long".into()));
+            }
+            line.push(byte);
+        }
+
+        let line = String::from_utf8(line)
+            .map_err(|_| ProxyError::InvalidData("Relay header is not UTF-8".into()))?;
+        Self::parse_header(&line)
+    }
+
+    /// Reads the target of a connection, connects to it and copies data
+    /// bidirectionally until either side closes
+    ///
+    /// # Parameters
+    /// * `inbound` - Client connection stream
+    /// * `config` - Relay configuration with the token and allowed targets
+    async fn handle_mux_connection(
+        &self, mut inbound: TcpStream, config: &ProxyConfig,
+    ) -> Result<(), ProxyError> {
+        let header = timeout(HEADER_TIMEOUT, Self::read_header(&mut inbound))
+            .await
+            .map_err(|_| ProxyError::Connection("Timed out waiting for relay header".into()))??;
+        Self::authorize(&header, config)?;
+        let RelayHeader { host, port, .. } = header;
+
+        info!("Relaying connection to {host}:{port}");
+        let mut outbound = match timeout(
+            CONNECTION_TIMEOUT,
+            TcpStream::connect(format!("{host}:{port}")),
+        )
+        .await
+        {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => {
+                return Err(ProxyError::Connection(format!(
+                    "Failed to connect to {host}:{port}: {e}"
+                )));
+            }
+            Err(_) => {
+                return Err(ProxyError::Connection(format!(
+                    "Connection timeout to {host}:{port}"
+                )));
+            }
+        };
+
+        match copy_bidirectional(&mut inbound, &mut outbound).await {
+            Ok((from_client, from_server)) => {
+                info!(
+                    "Connection to {host}:{port} closed. Bytes from client: {from_client}, from server: {from_server}"
+                );
+                Ok(())
+            }
+            Err(e) if TcpProxy::is_connection_reset(&e) => {
+                info!("Connection to {host}:{port} closed by peer");
+                Ok(())
+            }
+            Err(e) => {
+                error!("Connection error: {e}");
+                Err(ProxyError::Io(e))
+            }
+        }
+    }
+}
+
+/// Header sent at the start of a relayed connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayHeader {
+    /// Token of the relay
+    pub token: String,
+    /// Host to connect to
+    pub host: String,
+    /// Port to connect to
+    pub port: u16,
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[async_trait]
+impl ProxyHandler for MuxProxy {
+    /// Starts the relay and handles connections until shutdown is signalled
+    ///
+    /// # Parameters
+    /// * `config` - Configuration containing the port to listen on
+    /// * `shutdown` - Notification mechanism to signal when the relay should
+    ///   stop
+    ///
+    /// # Returns
+    /// * `Result<(), ProxyError>` - Success if relay runs and shuts down
+    ///   cleanly, or error details
+    async fn start(&self, config: ProxyConfig, shutdown: Arc<Notify>) -> Result<(), ProxyError> {
+        if config.relay_token.as_deref().is_none_or(str::is_empty) {
+            return Err(ProxyError::Configuration(
+                "The multiplexed relay needs a token".into(),
+            ));
+        }
+
+        let addr = SocketAddr::new(config.listen_address.parse()?, config.proxy_port);
+        let listener = TcpListener::bind(addr).await?;
+
+        info!(
+            "Multiplexed relay started on {addr} for {} target(s)",
+            config.relay_targets.len()
+        );
+
+        loop {
+            tokio::select! {
+                accept_result = listener.accept() => {
+                    match accept_result {
+                        Ok((stream, addr)) => {
+                            info!("Accepted connection from {addr}");
+                            let config = config.clone();
+                            let proxy = self.clone();
+
+                            tokio::spawn(async move {
+                                if let Err(e) = proxy.handle_mux_connection(stream, &config).await {
+                                    error!("Connection error for {addr}: {e}");
+                                }
+                            });
+                        }
+                        Err(e) => error!("Failed to accept connection: {e}"),
+                    }
+                }
+                _ = shutdown.notified() => {
+                    info!("Shutdown signal received, stopping multiplexed relay");
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+    use crate::proxy::{
+        config::ProxyType,
+        test_utils,
+    };
+
+    const TEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn relay_config(port: u16, targets: Vec<String>) -> ProxyConfig {
+        ProxyConfig::builder()
+            .target_host(String::new())
+            .target_port(0)
+            .proxy_port(port)
+            .listen_address("127.0.0.1".to_string())
+            .proxy_type(ProxyType::Mux)
+            .relay_token(Some("s3cret".to_string()))
+            .relay_targets(targets)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            MuxProxy::parse_header("KFTRAY-RELAY s3cret db.default.svc 5432").unwrap(),
+            RelayHeader {
+                token: "s3cret".to_string(),
+                host: "db.default.svc".to_string(),
+                port: 5432,
+            }
+        );
+        assert_eq!(
+            MuxProxy::parse_header("KFTRAY-RELAY s3cret 10.0.0.7 80\r")
+                .unwrap()
+                .port,
+            80
+        );
+        assert!(MuxProxy::parse_header("KFTRAY-RELAY s3cret db 0").is_err());
+        assert!(MuxProxy::parse_header("KFTRAY-RELAY db 5432").is_err());
+        assert!(MuxProxy::parse_header("GET / HTTP/1.1").is_err());
+    }
+
+    #[test]
+    fn test_authorize() {
+        let config = relay_config(39000, vec!["db:5432".to_string()]);
+        let header = |token: &str, host: &str, port: u16| RelayHeader {
+            token: token.to_string(),
+            host: host.to_string(),
+            port,
+        };
+
+        assert!(MuxProxy::authorize(&header("s3cret", "db", 5432), &config).is_ok());
+        assert!(MuxProxy::authorize(&header("wrong", "db", 5432), &config).is_err());
+        assert!(MuxProxy::authorize(&header("s3cre", "db", 5432), &config).is_err());
+        assert!(MuxProxy::authorize(&header("s3cret", "db", 5433), &config).is_err());
+        assert!(MuxProxy::authorize(&header("s3cret", "10.0.0.1", 22), &config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mux_relays_to_header_target() {
+        let echo_server = test_utils::setup_test_tcp_echo_server().await;
+        let proxy = MuxProxy::new();
+        let shutdown = Arc::new(Notify::new());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let target = format!("{}:{}", echo_server.addr().ip(), echo_server.addr().port());
+        let config = relay_config(addr.port(), vec![target.clone()]);
+
+        let relay_shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let _ = proxy.start(config, relay_shutdown).await;
+        });
+        assert!(
+            test_utils::wait_for_port(addr).await,
+            "Relay failed to start"
+        );
+
+        let mut denied = TcpStream::connect(addr).await.unwrap();
+        denied
+            .write_all(format!("{HEADER_PREFIX} wrong {}\n", target.replace(':', " ")).as_bytes())
+            .await
+            .unwrap();
+        let mut buf = [0; 1];
+        let read = tokio::time::timeout(TEST_TIMEOUT, denied.read(&mut buf))
+            .await
+            .unwrap();
+        assert!(matches!(read, Ok(0) | Err(_)));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let header = format!("{HEADER_PREFIX} s3cret {}\n", target.replace(':', " "));
+        stream.write_all(header.as_bytes()).await.unwrap();
+        stream.write_all(b"Hello, relay!").await.unwrap();
+
+        let mut response = vec![0; 13];
+        tokio::time::timeout(TEST_TIMEOUT, stream.read_exact(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&response, b"Hello, relay!");
+
+        shutdown.notify_one();
+        echo_server.shutdown();
+    }
+}
@@ -9,6 +9,7 @@ use crate::proxy::{
         ProxyType,
     },
     error::ProxyError,
+    mux::MuxProxy,
     reverse::ReverseProxy,
     tcp::TcpProxy,
     traits::ProxyHandler,
@@ -35,6 +36,7 @@ impl ProxyServer {
             ProxyType::Tcp => Box::new(TcpProxy::new()),
             ProxyType::Udp => Box::new(UdpProxy::new()),
             ProxyType::ReverseHttp => Box::new(ReverseProxy::new()),
+            ProxyType::Mux => Box::new(MuxProxy::new()),
         };
 
         Self {
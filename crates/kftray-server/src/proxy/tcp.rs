@@ -138,7 +138,7 @@ impl TcpProxy {
         }
     }
 
-    fn is_connection_reset(error: &std::io::Error) -> bool {
+    pub(crate) fn is_connection_reset(error: &std::io::Error) -> bool {
         matches!(
             error.kind(),
             std::io::ErrorKind::BrokenPipe
@@ -163,10 +163,10 @@ impl ProxyHandler for TcpProxy {
     /// * `Result<(), ProxyError>` - Success if proxy runs and shuts down
     ///   cleanly, or error details
     async fn start(&self, config: ProxyConfig, shutdown: Arc<Notify>) -> Result<(), ProxyError> {
-        let addr: SocketAddr = format!("0.0.0.0:{}", config.proxy_port).parse()?;
+        let addr = SocketAddr::new(config.listen_address.parse()?, config.proxy_port);
         let listener = TcpListener::bind(addr).await?;
 
-        info!("TCP Proxy started on port {}", config.proxy_port);
+        info!("TCP Proxy started on {addr}");
 
         loop {
             tokio::select! {
@@ -1,4 +1,5 @@
 use std::{
+    net::SocketAddr,
     sync::Arc,
     time::Duration,
 };
@@ -154,8 +155,9 @@ impl UdpProxy {
 #[async_trait]
 impl ProxyHandler for UdpProxy {
     async fn start(&self, config: ProxyConfig, shutdown: Arc<Notify>) -> Result<(), ProxyError> {
-        let listener = TcpListener::bind(format!("0.0.0.0:{}", config.proxy_port)).await?;
-        info!("UDP-over-TCP Proxy started on port {}", config.proxy_port);
+        let addr = SocketAddr::new(config.listen_address.parse()?, config.proxy_port);
+        let listener = TcpListener::bind(addr).await?;
+        info!("UDP-over-TCP Proxy started on {addr}");
 
         loop {
             tokio::select! {
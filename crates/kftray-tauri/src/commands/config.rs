@@ -3,13 +3,27 @@ use kftray_commons::config::{
     delete_config,
     delete_configs,
     export_configs,
+    export_configs_in_format_with_mode,
     get_config,
     get_configs,
     import_configs,
     insert_config,
     update_config,
 };
+use kftray_commons::models::config_history_model::ConfigRevision;
 use kftray_commons::models::config_model::Config;
+use kftray_commons::utils::config_format::ConfigFormat;
+use kftray_commons::utils::config_history::{
+    list_config_history,
+    list_deleted_configs,
+    restore_config_revision,
+};
+use kftray_commons::utils::config_validation::{
+    ConfigValidation,
+    validate_config_with_mode,
+    validate_stored_configs_with_mode,
+};
+use kftray_commons::utils::db_mode::DatabaseMode;
 use kftray_commons::utils::settings::get_ssl_enabled;
 use kftray_portforward::ssl::cert_manager::CertificateManager;
 use log::{
@@ -18,16 +32,6 @@ use log::{
     warn,
 };
 
-fn validate_config(config: &Config) -> Result<(), String> {
-    if config.auto_loopback_address && config.local_address.is_some() {
-        warn!(
-            "Config has auto_loopback_address enabled but local_address is also set. \
-             The auto-allocated address will override the manual local_address."
-        );
-    }
-    Ok(())
-}
-
 async fn regenerate_ssl_certificate_if_needed() -> Result<(), String> {
     // Check if SSL is enabled
     match get_ssl_enabled().await {
@@ -45,7 +49,8 @@ async fn regenerate_ssl_certificate_if_needed() -> Result<(), String> {
                     } else {
                         info!("Successfully regenerated global SSL certificate");
 
-                        // Restart SSL proxies for all running configs to pick up new certificates
+                        // Restart SSL proxies for all running configs to pick
+                        // up new certificates
                         info!(
                             "Certificate regeneration successful, attempting to restart SSL proxies"
                         );
@@ -174,7 +179,8 @@ async fn restart_ssl_proxies_if_running() -> Result<(), String> {
 }
 
 async fn restart_ssl_proxies_with_retry() {
-    // Try multiple times with increasing delays to catch configs as they start up
+    // Try multiple times with increasing delays to catch configs as they start
+    // up
     let delays = [100, 500, 1000]; // milliseconds
 
     for (attempt, delay) in delays.iter().enumerate() {
@@ -234,7 +240,6 @@ pub async fn delete_all_configs_cmd() -> Result<(), String> {
 
 #[tauri::command]
 pub async fn insert_config_cmd(config: Config) -> Result<(), String> {
-    validate_config(&config)?;
     let result = insert_config(config).await;
     if result.is_ok() {
         let _ = regenerate_ssl_certificate_if_needed().await;
@@ -261,7 +266,6 @@ pub async fn update_config_cmd(config: Config) -> Result<(), String> {
         "=== UPDATE_CONFIG_CMD CALLED with id={:?}, alias={:?} ===",
         config.id, config.alias
     );
-    validate_config(&config)?;
     let result = update_config(config).await;
     if result.is_ok() {
         let _ = regenerate_ssl_certificate_if_needed().await;
@@ -269,11 +273,27 @@ pub async fn update_config_cmd(config: Config) -> Result<(), String> {
     result
 }
 
+#[tauri::command]
+pub async fn validate_config_cmd(config: Config) -> Result<ConfigValidation, String> {
+    validate_config_with_mode(&config, DatabaseMode::File).await
+}
+
+#[tauri::command]
+pub async fn validate_configs_cmd() -> Result<Vec<ConfigValidation>, String> {
+    validate_stored_configs_with_mode(DatabaseMode::File).await
+}
+
 #[tauri::command]
 pub async fn export_configs_cmd() -> Result<String, String> {
     export_configs().await
 }
 
+#[tauri::command]
+pub async fn export_configs_as_cmd(format: String) -> Result<String, String> {
+    let format: ConfigFormat = format.parse()?;
+    export_configs_in_format_with_mode(format, DatabaseMode::File).await
+}
+
 #[tauri::command]
 pub async fn import_configs_cmd(json: String) -> Result<(), String> {
     let result = import_configs(json).await;
@@ -291,6 +311,28 @@ pub async fn import_configs_cmd(json: String) -> Result<(), String> {
     result
 }
 
+#[tauri::command]
+pub async fn list_config_history_cmd(
+    config_id: Option<i64>,
+) -> Result<Vec<ConfigRevision>, String> {
+    list_config_history(config_id).await
+}
+
+#[tauri::command]
+pub async fn list_deleted_configs_cmd() -> Result<Vec<ConfigRevision>, String> {
+    list_deleted_configs().await
+}
+
+#[tauri::command]
+pub async fn restore_config_revision_cmd(revision_id: i64) -> Result<Config, String> {
+    info!("Restoring config revision {revision_id}");
+    let result = restore_config_revision(revision_id).await;
+    if result.is_ok() {
+        let _ = regenerate_ssl_certificate_if_needed().await;
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -305,6 +347,16 @@ mod tests {
         static ref TEST_MUTEX: Mutex<()> = Mutex::new(());
     }
 
+    fn valid_config() -> Config {
+        Config {
+            workload_type: Some("service".to_string()),
+            protocol: "tcp".to_string(),
+            remote_port: Some(80),
+            alias: None,
+            ..Config::default()
+        }
+    }
+
     async fn setup_isolated_test_db() -> Arc<SqlitePool> {
         let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
         kftray_commons::utils::db::create_db_table(&pool)
@@ -327,7 +379,7 @@ mod tests {
         let _guard = TEST_MUTEX.lock().await;
         let _pool = setup_isolated_test_db().await;
 
-        let config = Config::default();
+        let config = valid_config();
         insert_config_cmd(config)
             .await
             .expect("Failed to insert test config");
@@ -354,11 +406,11 @@ mod tests {
 
         let config1 = Config {
             service: Some("test-service-1".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
         let config2 = Config {
             service: Some("test-service-2".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_cmd(config1)
@@ -388,8 +440,8 @@ mod tests {
         let _guard = TEST_MUTEX.lock().await;
         let _pool = setup_isolated_test_db().await;
 
-        let config1 = Config::default();
-        let config2 = Config::default();
+        let config1 = valid_config();
+        let config2 = valid_config();
 
         insert_config_cmd(config1)
             .await
@@ -424,7 +476,7 @@ mod tests {
         let test_config = Config {
             service: Some("insert-test-service".to_string()),
             namespace: "insert-test-namespace".to_string(),
-            ..Config::default()
+            ..valid_config()
         };
 
         let result = insert_config_cmd(test_config.clone()).await;
@@ -449,7 +501,7 @@ mod tests {
 
         let config = Config {
             service: Some("get-configs-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_cmd(config)
@@ -476,7 +528,7 @@ mod tests {
 
         let config = Config {
             service: Some("get-config-test".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_cmd(config)
@@ -513,7 +565,7 @@ mod tests {
 
         let config = Config {
             service: Some("update-test-original".to_string()),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_cmd(config)
@@ -550,7 +602,7 @@ mod tests {
         let config = Config {
             service: Some("export-test-service".to_string()),
             namespace: "export-test-namespace".to_string(),
-            ..Config::default()
+            ..valid_config()
         };
 
         insert_config_cmd(config)
@@ -617,34 +669,62 @@ mod tests {
         assert!(result.is_err(), "Import with invalid JSON should fail");
     }
 
-    #[test]
-    fn test_validate_config_auto_loopback_only() {
+    #[tokio::test]
+    async fn test_validate_config_cmd_reports_loopback_override() {
+        let _guard = TEST_MUTEX.lock().await;
+        let _pool = setup_isolated_test_db().await;
+
         let config = Config {
             auto_loopback_address: true,
-            local_address: None,
-            ..Config::default()
+            local_address: Some("127.0.0.1".to_string()),
+            ..valid_config()
         };
-        assert!(validate_config(&config).is_ok());
+        let validation = validate_config_cmd(config).await.unwrap();
+        assert!(!validation.has_errors());
+        assert!(
+            validation
+                .warnings()
+                .any(|issue| issue.field == "local_address")
+        );
     }
 
-    #[test]
-    fn test_validate_config_manual_address_only() {
+    #[tokio::test]
+    async fn test_insert_config_cmd_rejects_invalid_config() {
+        let _guard = TEST_MUTEX.lock().await;
+        let _pool = setup_isolated_test_db().await;
+
         let config = Config {
-            auto_loopback_address: false,
-            local_address: Some("127.0.0.1".to_string()),
-            ..Config::default()
+            service: None,
+            ..valid_config()
         };
-        assert!(validate_config(&config).is_ok());
+        let validation = validate_config_cmd(config.clone()).await.unwrap();
+        assert_eq!(validation.errors().count(), 1);
+
+        let error = insert_config_cmd(config).await.unwrap_err();
+        assert!(error.contains("service: is required"), "{error}");
     }
 
-    #[test]
-    fn test_validate_config_both_set_warning() {
-        let config = Config {
-            auto_loopback_address: true,
-            local_address: Some("127.0.0.1".to_string()),
-            ..Config::default()
-        };
-        assert!(validate_config(&config).is_ok());
+    #[tokio::test]
+    async fn test_restore_deleted_config_cmd() {
+        let _guard = TEST_MUTEX.lock().await;
+        let _pool = setup_isolated_test_db().await;
+        let _ = delete_all_configs_cmd().await;
+
+        insert_config_cmd(valid_config()).await.unwrap();
+        let id = get_configs_cmd().await.unwrap()[0].id.unwrap();
+        delete_config_cmd(id).await.unwrap();
+
+        let deleted = list_deleted_configs_cmd().await.unwrap();
+        let revision = deleted
+            .iter()
+            .find(|revision| revision.config_id == id)
+            .unwrap();
+        let restored = restore_config_revision_cmd(revision.id).await.unwrap();
+        assert_eq!(restored.id, Some(id));
+        assert!(get_config_cmd(id).await.is_ok());
+
+        let history = list_config_history_cmd(Some(id)).await.unwrap();
+        assert!(history.len() >= 3);
     }
 
     #[tokio::test]
@@ -0,0 +1,52 @@
+use kftray_commons::models::config_group_model::ConfigGroup;
+use kftray_commons::models::response::CustomResponse;
+use kftray_commons::utils::config_group::{
+    delete_group_with_mode,
+    list_groups_with_mode,
+    save_group_with_mode,
+};
+use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_portforward::kube::{
+    start_group_with_mode,
+    stop_group_with_mode,
+};
+use log::info;
+use tauri::{
+    AppHandle,
+    Wry,
+};
+
+use crate::tray::refresh_tray_groups;
+
+#[tauri::command]
+pub async fn list_config_groups_cmd() -> Result<Vec<ConfigGroup>, String> {
+    list_groups_with_mode(DatabaseMode::File).await
+}
+
+#[tauri::command]
+pub async fn save_config_group_cmd(app: AppHandle<Wry>, group: ConfigGroup) -> Result<i64, String> {
+    info!("Saving config group '{}'", group.name);
+    let id = save_group_with_mode(&group, DatabaseMode::File).await?;
+    refresh_tray_groups(&app).await;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn delete_config_group_cmd(app: AppHandle<Wry>, id: i64) -> Result<(), String> {
+    info!("Deleting config group with id: {id}");
+    delete_group_with_mode(id, DatabaseMode::File).await?;
+    refresh_tray_groups(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn start_config_group_cmd(name: String) -> Result<Vec<CustomResponse>, String> {
+    info!("Starting config group '{name}'");
+    start_group_with_mode(&name, DatabaseMode::File).await
+}
+
+#[tauri::command]
+pub async fn stop_config_group_cmd(name: String) -> Result<Vec<CustomResponse>, String> {
+    info!("Stopping config group '{name}'");
+    stop_group_with_mode(&name, DatabaseMode::File).await
+}
@@ -31,12 +31,12 @@ mod tests {
 
         let config1 = Config {
             service: Some("config-state-test-1".to_string()),
-            ..Config::default()
+            ..kftray_commons::test_utils::valid_config()
         };
 
         let config2 = Config {
             service: Some("config-state-test-2".to_string()),
-            ..Config::default()
+            ..kftray_commons::test_utils::valid_config()
         };
 
         kftray_commons::config::insert_config_with_mode(config1, DatabaseMode::Memory)
@@ -77,7 +77,7 @@ mod tests {
 
         let config = Config {
             service: Some("config-state-running-test".to_string()),
-            ..Config::default()
+            ..kftray_commons::test_utils::valid_config()
         };
 
         if kftray_commons::config::insert_config_with_mode(config, DatabaseMode::Memory)
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use kftray_commons::models::git_sync_model::{
+    GitSyncSubscription,
+    SyncApplyResult,
+    SyncPreview,
+};
+use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_commons::utils::git_sync::{
+    apply_sync_preview_with_mode,
+    check_subscription_with_mode,
+    delete_subscription_with_mode,
+    due_subscriptions_with_mode,
+    get_subscription_with_mode,
+    list_subscriptions_with_mode,
+    save_subscription_with_mode,
+};
+use kftray_portforward::kube::{
+    restart_running_configs_with_mode,
+    stop_running_configs_with_mode,
+};
+use log::{
+    error,
+    info,
+    warn,
+};
+use tauri::{
+    AppHandle,
+    Emitter,
+    Wry,
+};
+use tokio::time::{
+    Duration,
+    interval,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+async fn check_subscription(id: i64) -> Result<SyncPreview, String> {
+    check_subscription_with_mode(id, DatabaseMode::File).await
+}
+
+#[tauri::command]
+pub async fn list_git_sync_subscriptions_cmd() -> Result<Vec<GitSyncSubscription>, String> {
+    list_subscriptions_with_mode(DatabaseMode::File).await
+}
+
+#[tauri::command]
+pub async fn save_git_sync_subscription_cmd(
+    mut subscription: GitSyncSubscription,
+) -> Result<i64, String> {
+    // The sync state is owned by the backend, editing the settings keeps it.
+    if let Some(id) = subscription.id
+        && let Ok(existing) = get_subscription_with_mode(id, DatabaseMode::File).await
+    {
+        subscription.last_commit_sha = existing.last_commit_sha;
+        subscription.applied_keys = existing.applied_keys;
+        subscription.last_checked_at = existing.last_checked_at;
+        subscription.last_synced_at = existing.last_synced_at;
+    }
+
+    save_subscription_with_mode(&subscription, DatabaseMode::File).await
+}
+
+#[tauri::command]
+pub async fn delete_git_sync_subscription_cmd(id: i64) -> Result<(), String> {
+    delete_subscription_with_mode(id, DatabaseMode::File).await
+}
+
+#[tauri::command]
+pub async fn check_git_sync_cmd(id: i64) -> Result<SyncPreview, String> {
+    check_subscription(id).await
+}
+
+#[tauri::command]
+pub async fn apply_git_sync_cmd(preview: SyncPreview) -> Result<SyncApplyResult, String> {
+    let removed_ids = preview.diff.removed_config_ids();
+    if !removed_ids.is_empty() {
+        match stop_running_configs_with_mode(&removed_ids, DatabaseMode::File).await {
+            Ok(stopped) if !stopped.is_empty() => {
+                info!(
+                    "Stopped {} running forwards removed by git sync",
+                    stopped.len()
+                )
+            }
+            Ok(_) => {}
+            Err(e) => error!("{e}"),
+        }
+    }
+
+    let result = apply_sync_preview_with_mode(&preview, DatabaseMode::File).await?;
+
+    if !result.changed_config_ids.is_empty() {
+        match restart_running_configs_with_mode(&result.changed_config_ids, DatabaseMode::File)
+            .await
+        {
+            Ok(restarted) if !restarted.is_empty() => {
+                info!(
+                    "Restarted {} running forwards after git sync",
+                    restarted.len()
+                )
+            }
+            Ok(_) => {}
+            Err(e) => error!("{e}"),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Polls due subscriptions and emits a `git-sync-preview` event when a source
+/// has changes waiting for approval. Each commit is only announced once.
+pub async fn run_git_sync_poller(app_handle: AppHandle<Wry>) {
+    let mut interval = interval(POLL_INTERVAL);
+    let mut notified: HashMap<i64, String> = HashMap::new();
+
+    loop {
+        interval.tick().await;
+
+        let subscriptions = match due_subscriptions_with_mode(DatabaseMode::File).await {
+            Ok(subscriptions) => subscriptions,
+            Err(e) => {
+                error!("Failed to load git sync subscriptions: {e}");
+                continue;
+            }
+        };
+
+        for subscription in subscriptions {
+            let Some(id) = subscription.id else {
+                continue;
+            };
+
+            match check_subscription(id).await {
+                Ok(preview) => {
+                    if preview.diff.is_empty() || notified.get(&id) == Some(&preview.commit_sha) {
+                        continue;
+                    }
+
+                    notified.insert(id, preview.commit_sha.clone());
+                    if let Err(e) = app_handle.emit("git-sync-preview", &preview) {
+                        error!("Failed to emit git sync preview: {e}");
+                    }
+                }
+                Err(e) => warn!("Git sync check for '{}' failed: {e}", subscription.name),
+            }
+        }
+    }
+}
@@ -3,9 +3,12 @@ use keyring::{
     Error as KeyringError,
 };
 use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_commons::utils::git_provider::GitProvider;
 use kftray_commons::utils::github::{
     GitHubConfig,
     GitHubRepository,
+    GitImportRecord,
+    get_config_git_source_with_mode,
 };
 use tauri::{
     Error as TauriError,
@@ -75,19 +78,36 @@ pub fn delete_key(service: &str, name: &str) -> std::result::Result<(), CustomEr
 #[tauri::command]
 pub async fn import_configs_from_github(
     repo_url: String, config_path: String, use_system_credentials: bool, flush: bool,
-    github_token: Option<String>,
-) -> Result<(), String> {
+    github_token: Option<String>, git_ref: Option<String>, insecure_skip_verify: Option<bool>,
+    ca_bundle_path: Option<String>, provider: Option<String>,
+) -> Result<GitImportRecord, String> {
+    let provider = provider
+        .filter(|provider| !provider.trim().is_empty())
+        .map(|provider| provider.parse::<GitProvider>())
+        .transpose()?;
+
     let config = GitHubConfig {
         repo_url,
         config_path,
         use_system_credentials,
         github_token,
         flush_existing: flush,
+        git_ref,
+        insecure_skip_verify: insecure_skip_verify.unwrap_or(false),
+        ca_bundle_path: ca_bundle_path.filter(|path| !path.trim().is_empty()),
+        provider,
     };
 
     GitHubRepository::import_configs(config, DatabaseMode::File).await
 }
 
+/// Returns the repository, ref and commit a config was last imported or synced
+/// from.
+#[tauri::command]
+pub async fn get_config_git_source_cmd(config_id: i64) -> Result<Option<GitImportRecord>, String> {
+    get_config_git_source_with_mode(config_id, DatabaseMode::File).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,6 +194,10 @@ mod tests {
             false,
             false,
             None,
+            None,
+            None,
+            None,
+            None,
         )
         .await;
 
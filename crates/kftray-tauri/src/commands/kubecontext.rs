@@ -1,14 +1,28 @@
-use std::collections::HashSet;
+use std::collections::{
+    BTreeSet,
+    HashSet,
+};
 
 use anyhow::Result;
 use k8s_openapi::api::core::v1::{
-    Namespace,
     Pod,
     Service,
 };
 use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
 use kftray_commons::config_model::Config;
-use kftray_portforward::kube::client::create_client_with_specific_context;
+use kftray_commons::models::context_proxy_model::ContextProxy;
+use kftray_commons::utils::context_proxy::{
+    delete_context_proxy_with_mode,
+    list_context_proxies_with_mode,
+    save_context_proxy_with_mode,
+};
+use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_portforward::kube::client::{
+    ConnectionReport,
+    create_client_with_specific_context,
+    diagnose_connection,
+    subscribe_auth_prompts,
+};
 use kftray_portforward::kube::models::{
     KubeContextInfo,
     KubeNamespaceInfo,
@@ -16,7 +30,15 @@ use kftray_portforward::kube::models::{
     KubeServicePortInfo,
     PodInfo,
 };
-use kftray_portforward::kube::retrieve_service_configs;
+use kftray_portforward::kube::operations::discovery_namespaces;
+use kftray_portforward::kube::shared_client::SHARED_CLIENT_MANAGER;
+use kftray_portforward::kube::{
+    DiscoveredConfigs,
+    discover_kubectl_port_forwards,
+    discover_service_configs,
+    restart_running_configs_with_mode,
+    retrieve_service_configs,
+};
 use kube::Resource;
 use kube::{
     ResourceExt,
@@ -25,7 +47,17 @@ use kube::{
         ListParams,
     },
 };
-use log::info;
+use log::{
+    error,
+    info,
+    warn,
+};
+use tauri::{
+    AppHandle,
+    Emitter,
+    Wry,
+};
+use tokio::sync::broadcast::error::RecvError;
 
 #[tauri::command]
 pub async fn list_kube_contexts(
@@ -104,20 +136,16 @@ pub async fn list_namespaces(
 
     let client =
         client.ok_or_else(|| format!("Client not created for context '{context_name}'"))?;
-    let api: Api<Namespace> = Api::all(client);
 
-    let ns_list = api
-        .list(&ListParams::default())
+    let ns_list = discovery_namespaces(client, None)
         .await
         .map_err(|e| {
             let error_message = format!("Failed to list namespaces: {e}");
             log::error!("{error_message}");
             error_message
         })?
-        .iter()
-        .map(|ns| KubeNamespaceInfo {
-            name: ns.name_any(),
-        })
+        .into_iter()
+        .map(|name| KubeNamespaceInfo { name })
         .collect();
 
     Ok(ns_list)
@@ -252,13 +280,103 @@ pub async fn list_ports(
 
 #[tauri::command]
 pub async fn get_services_with_annotations(
-    context_name: String, kubeconfig_path: Option<String>,
+    context_name: String, kubeconfig_path: Option<String>, namespaces: Option<Vec<String>>,
 ) -> Result<Vec<Config>, String> {
     info!(
-        "get_services_with_annotations called with context: '{context_name}' and kubeconfig: {kubeconfig_path:?}"
+        "get_services_with_annotations called with context: '{context_name}', kubeconfig: {kubeconfig_path:?} and namespaces: {namespaces:?}"
     );
 
-    retrieve_service_configs(&context_name, kubeconfig_path).await
+    retrieve_service_configs(&context_name, kubeconfig_path, namespaces).await
+}
+
+#[tauri::command]
+pub async fn discover_service_configs_cmd(
+    context_name: String, namespace: String, kubeconfig_path: Option<String>,
+) -> Result<Vec<Config>, String> {
+    info!(
+        "discover_service_configs_cmd called for context '{context_name}' namespace '{namespace}'"
+    );
+
+    discover_service_configs(&context_name, kubeconfig_path, &namespace).await
+}
+
+#[tauri::command]
+pub async fn discover_kubectl_port_forwards_cmd() -> Result<DiscoveredConfigs, String> {
+    info!("Scanning for running kubectl port-forward processes");
+
+    discover_kubectl_port_forwards().await
+}
+
+#[tauri::command]
+pub async fn diagnose_connection_cmd(
+    context_name: String, kubeconfig: Option<String>,
+) -> Result<ConnectionReport, String> {
+    info!("diagnose_connection_cmd called for context '{context_name}'");
+
+    diagnose_connection(kubeconfig, &context_name)
+        .await
+        .map_err(|err| format!("Failed to load context '{context_name}': {err}"))
+}
+
+#[tauri::command]
+pub async fn list_context_proxies_cmd() -> Result<Vec<ContextProxy>, String> {
+    list_context_proxies_with_mode(DatabaseMode::File).await
+}
+
+#[tauri::command]
+pub async fn save_context_proxy_cmd(proxy: ContextProxy) -> Result<(), String> {
+    info!(
+        "save_context_proxy_cmd called for context '{}'",
+        proxy.context
+    );
+
+    save_context_proxy_with_mode(&proxy, DatabaseMode::File).await?;
+    reconnect_context(&proxy.context).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_context_proxy_cmd(context_name: String) -> Result<(), String> {
+    info!("delete_context_proxy_cmd called for context '{context_name}'");
+
+    delete_context_proxy_with_mode(&context_name, DatabaseMode::File).await?;
+    reconnect_context(&context_name).await;
+    Ok(())
+}
+
+/// Drops the cached clients of a context and restarts its running forwards so
+/// they pick up the new proxy settings.
+async fn reconnect_context(context_name: &str) {
+    let config_ids: Vec<i64> = SHARED_CLIENT_MANAGER
+        .evict_context(context_name)
+        .iter()
+        .map(|key| key.config_id)
+        .filter(|config_id| *config_id >= 0)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    if let Err(e) = restart_running_configs_with_mode(&config_ids, DatabaseMode::File).await {
+        warn!("Failed to restart forwards for context '{context_name}': {e}");
+    }
+}
+
+/// Emits an `auth-prompt` event whenever an exec credential plugin needs the
+/// user, e.g. to finish a browser login.
+pub async fn run_auth_prompt_forwarder(app_handle: AppHandle<Wry>) {
+    let mut prompts = subscribe_auth_prompts();
+
+    loop {
+        match prompts.recv().await {
+            Ok(prompt) => {
+                if let Err(e) = app_handle.emit("auth-prompt", &prompt) {
+                    error!("Failed to emit auth prompt: {e}");
+                }
+            }
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,7 @@
 pub mod config;
+pub mod config_group;
 pub mod config_state;
+pub mod git_sync;
 pub mod github;
 pub mod helper;
 pub mod httplogs;
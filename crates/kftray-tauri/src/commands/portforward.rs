@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{
+    BTreeSet,
+    HashMap,
+};
 use std::sync::Arc;
 
 use kftray_commons::config::get_configs;
@@ -8,8 +11,17 @@ use kftray_commons::utils::config_state::{
     cleanup_current_process_config_states,
     get_configs_state,
 };
+use kftray_commons::utils::config_validation::validate_config;
+use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_portforward::kube::reconcile::{
+    DEFAULT_STALE_AFTER,
+    ReconcileReport,
+    reconcile_all_contexts_with_legacy,
+};
 use kftray_portforward::kube::{
+    ForwardPlan,
     deploy_and_forward_pod,
+    plan_port_forward,
     start_port_forward,
     stop_all_port_forward,
     stop_port_forward,
@@ -34,6 +46,8 @@ use tokio::time::{
     interval,
 };
 
+use crate::tray::refresh_tray_groups;
+
 pub async fn check_and_emit_changes(app_handle: AppHandle<Wry>) {
     let mut interval = interval(Duration::from_millis(500));
     let previous_config_states = Arc::new(Mutex::new(Vec::new()));
@@ -108,12 +122,23 @@ pub async fn check_and_emit_changes(app_handle: AppHandle<Wry>) {
 
             log::info!("Configs changed event emitted");
 
+            if group_names(&prev_configs) != group_names(&current_configs) {
+                refresh_tray_groups(&app_handle).await;
+            }
+
             *prev_states = current_config_states;
             *prev_configs = current_configs;
         }
     }
 }
 
+fn group_names(configs: &[Config]) -> BTreeSet<&str> {
+    configs
+        .iter()
+        .flat_map(|config| config.groups.iter().flatten().map(String::as_str))
+        .collect()
+}
+
 fn config_compare_changes<T: PartialEq>(prev: &[T], current: &[T]) -> bool {
     if prev.len() != current.len() {
         return false;
@@ -128,10 +153,17 @@ fn config_compare_changes<T: PartialEq>(prev: &[T], current: &[T]) -> bool {
     true
 }
 
+fn validate_before_start(configs: &[Config]) -> Result<(), String> {
+    configs
+        .iter()
+        .try_for_each(|config| validate_config(config).into_result().map(|_| ()))
+}
+
 #[tauri::command]
 pub async fn start_port_forward_udp_cmd(
     configs: Vec<Config>, _app_handle: tauri::AppHandle<Wry>,
 ) -> Result<Vec<CustomResponse>, String> {
+    validate_before_start(&configs)?;
     start_port_forward(configs.clone(), "udp").await
 }
 
@@ -139,6 +171,7 @@ pub async fn start_port_forward_udp_cmd(
 pub async fn start_port_forward_tcp_cmd(
     configs: Vec<Config>, _app_handle: tauri::AppHandle<Wry>,
 ) -> Result<Vec<CustomResponse>, String> {
+    validate_before_start(&configs)?;
     start_port_forward(configs.clone(), "tcp").await
 }
 
@@ -160,6 +193,7 @@ pub async fn stop_port_forward_cmd(
 pub async fn deploy_and_forward_pod_cmd(
     configs: Vec<Config>, _app_handle: tauri::AppHandle<Wry>,
 ) -> Result<Vec<CustomResponse>, String> {
+    validate_before_start(&configs)?;
     deploy_and_forward_pod(configs.clone()).await
 }
 
@@ -174,6 +208,25 @@ pub async fn stop_proxy_forward_cmd(
     stop_proxy_forward(config_id, namespace, service_name).await
 }
 
+/// Manual clean action, run once the user confirmed it. Unlike the startup
+/// reconcile, it also removes unlabelled resources of older kftray versions.
+#[tauri::command]
+pub async fn clean_cluster_cmd() -> Result<ReconcileReport, String> {
+    info!("Cleaning orphaned kftray resources from all contexts");
+
+    let report = reconcile_all_contexts_with_legacy(DatabaseMode::File, DEFAULT_STALE_AFTER).await;
+    info!("{}", report.summary());
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn plan_port_forward_cmd(configs: Vec<Config>) -> Result<Vec<ForwardPlan>, String> {
+    info!("Planning port forward for {} config(s)", configs.len());
+
+    Ok(plan_port_forward(configs).await)
+}
+
 #[tauri::command]
 pub async fn get_active_pod_cmd(config_id: String) -> Result<Option<String>, String> {
     use kftray_portforward::port_forward::CHILD_PROCESSES;
@@ -296,12 +349,14 @@ mod tests {
                 config_id: 1,
                 is_running: true,
                 process_id: Some(1234),
+                local_port: None,
             },
             ConfigState {
                 id: Some(2),
                 config_id: 2,
                 is_running: false,
                 process_id: None,
+                local_port: None,
             },
         ]
     }
@@ -362,6 +417,7 @@ mod tests {
                 config_id: config.id.unwrap_or_default(),
                 is_running: false,
                 process_id: None,
+                local_port: None,
             })
             .collect();
 
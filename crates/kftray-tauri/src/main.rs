@@ -37,6 +37,7 @@ use crate::tray::{
     create_tray_icon,
     handle_run_event,
     handle_window_event,
+    refresh_tray_groups,
 };
 
 fn main() {
@@ -44,6 +45,10 @@ fn main() {
 
     let _ = fix_path_env::fix();
 
+    kftray_commons::utils::config_history::set_default_change_source(
+        kftray_commons::models::config_history_model::ConfigChangeSource::Gui,
+    );
+
     kftray_portforward::ssl::ensure_crypto_provider_installed();
 
     let positioning_active = Arc::new(AtomicBool::new(false));
@@ -101,6 +106,17 @@ fn main() {
                 if let Err(e) = kftray_commons::utils::migration::migrate_configs(None).await {
                     error!("Database migration failed during setup: {e}");
                 }
+
+                let report = kftray_portforward::kube::reconcile::reconcile_all_contexts(
+                    kftray_commons::utils::db_mode::DatabaseMode::File,
+                    kftray_portforward::kube::reconcile::DEFAULT_STALE_AFTER,
+                )
+                .await;
+                info!("Startup cluster reconcile: {}", report.summary());
+
+                kftray_portforward::kube::start_kubeconfig_watcher(
+                    kftray_commons::utils::db_mode::DatabaseMode::File,
+                );
             });
 
             tauri::async_runtime::spawn(async move {
@@ -122,6 +138,16 @@ fn main() {
                 check_and_emit_changes(app_handle_clone).await;
             });
 
+            let app_handle_clone = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                crate::commands::git_sync::run_git_sync_poller(app_handle_clone).await;
+            });
+
+            let app_handle_clone = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                crate::commands::kubecontext::run_auth_prompt_forwarder(app_handle_clone).await;
+            });
+
             tauri::async_runtime::spawn(async move {
                 if let Ok(enabled) = kftray_commons::utils::settings::get_network_monitor().await
                     && enabled
@@ -214,6 +240,11 @@ fn main() {
 
             if let Err(e) = create_tray_icon(app) {
                 error!("Failed to create tray icon: {e}");
+            } else {
+                let app_handle = app.app_handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    refresh_tray_groups(&app_handle).await;
+                });
             }
 
             Ok(())
@@ -257,9 +288,17 @@ fn main() {
             commands::kubecontext::list_pods,
             commands::kubecontext::list_ports,
             commands::kubecontext::get_services_with_annotations,
+            commands::kubecontext::discover_service_configs_cmd,
+            commands::kubecontext::discover_kubectl_port_forwards_cmd,
+            commands::kubecontext::diagnose_connection_cmd,
+            commands::kubecontext::list_context_proxies_cmd,
+            commands::kubecontext::save_context_proxy_cmd,
+            commands::kubecontext::delete_context_proxy_cmd,
             commands::portforward::deploy_and_forward_pod_cmd,
             commands::portforward::stop_proxy_forward_cmd,
             commands::portforward::get_active_pod_cmd,
+            commands::portforward::clean_cluster_cmd,
+            commands::portforward::plan_port_forward_cmd,
             commands::httplogs::set_http_logs_cmd,
             commands::httplogs::get_http_logs_cmd,
             commands::httplogs::get_http_logs_config_cmd,
@@ -270,12 +309,29 @@ fn main() {
             commands::config::get_config_cmd,
             commands::config::update_config_cmd,
             commands::config::export_configs_cmd,
+            commands::config::export_configs_as_cmd,
+            commands::config::validate_config_cmd,
+            commands::config::validate_configs_cmd,
             commands::config::import_configs_cmd,
             commands::config::delete_configs_cmd,
             commands::config::delete_all_configs_cmd,
+            commands::config::list_config_history_cmd,
+            commands::config::list_deleted_configs_cmd,
+            commands::config::restore_config_revision_cmd,
+            commands::config_group::list_config_groups_cmd,
+            commands::config_group::save_config_group_cmd,
+            commands::config_group::delete_config_group_cmd,
+            commands::config_group::start_config_group_cmd,
+            commands::config_group::stop_config_group_cmd,
             commands::window_state::open_save_dialog,
             commands::window_state::close_save_dialog,
             commands::github::import_configs_from_github,
+            commands::github::get_config_git_source_cmd,
+            commands::git_sync::list_git_sync_subscriptions_cmd,
+            commands::git_sync::save_git_sync_subscription_cmd,
+            commands::git_sync::delete_git_sync_subscription_cmd,
+            commands::git_sync::check_git_sync_cmd,
+            commands::git_sync::apply_git_sync_cmd,
             commands::httplogs::open_log_file,
             commands::httplogs::clear_http_logs,
             commands::httplogs::get_http_log_size,
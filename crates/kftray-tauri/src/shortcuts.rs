@@ -25,6 +25,10 @@ use tokio::sync::{
 
 use crate::commands::{
     config::get_configs_cmd,
+    config_group::{
+        start_config_group_cmd,
+        stop_config_group_cmd,
+    },
     config_state::get_config_states,
     portforward::{
         deploy_and_forward_pod_cmd,
@@ -64,6 +68,12 @@ pub async fn setup_shortcut_integration(
     let toggle_port_forward_action = Arc::new(TogglePortForwardAction::new(app.clone()));
     registry.register_handler(toggle_port_forward_action);
 
+    let start_group_action = Arc::new(GroupPortForwardAction::new(app.clone(), true));
+    registry.register_handler(start_group_action);
+
+    let stop_group_action = Arc::new(GroupPortForwardAction::new(app.clone(), false));
+    registry.register_handler(stop_group_action);
+
     let mut manager =
         kftray_shortcuts::create_manager_with_registry(pool.as_ref().clone(), registry)
             .await
@@ -791,3 +801,79 @@ impl ActionHandler for TogglePortForwardAction {
         "Toggle specific port forwards"
     }
 }
+
+struct GroupPortForwardAction {
+    app_handle: AppHandle,
+    start: bool,
+}
+
+impl GroupPortForwardAction {
+    fn new(app_handle: AppHandle, start: bool) -> Self {
+        Self { app_handle, start }
+    }
+}
+
+#[async_trait]
+impl ActionHandler for GroupPortForwardAction {
+    async fn execute(&self, context: &ActionContext) -> kftray_shortcuts::ShortcutResult<()> {
+        info!("Executing {} action", self.action_type());
+
+        let group = context
+            .action_data
+            .as_deref()
+            .and_then(|data| serde_json::from_str::<serde_json::Value>(data).ok())
+            .and_then(|data| data.get("group")?.as_str().map(String::from))
+            .ok_or_else(|| {
+                kftray_shortcuts::ShortcutError::ActionExecutionFailed(
+                    "No group found in action data".to_string(),
+                )
+            })?;
+
+        let result = if self.start {
+            start_config_group_cmd(group.clone()).await
+        } else {
+            stop_config_group_cmd(group.clone()).await
+        };
+
+        match result {
+            Ok(responses) => {
+                let verb = if self.start { "Started" } else { "Stopped" };
+                let _ = self
+                    .app_handle
+                    .notification()
+                    .builder()
+                    .title("Port Forward")
+                    .body(format!(
+                        "{verb} {} port forward(s) in group '{group}'",
+                        responses.len()
+                    ))
+                    .show();
+
+                let _ = self.app_handle.emit("port-forward-status-changed", ());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to run {} for '{group}': {e}", self.action_type());
+                Err(kftray_shortcuts::ShortcutError::ActionExecutionFailed(
+                    format!("Failed to update group '{group}': {e}"),
+                ))
+            }
+        }
+    }
+
+    fn action_type(&self) -> &str {
+        if self.start {
+            "start_group"
+        } else {
+            "stop_group"
+        }
+    }
+
+    fn description(&self) -> &str {
+        if self.start {
+            "Start all port forwards in a group"
+        } else {
+            "Stop all port forwards in a group"
+        }
+    }
+}
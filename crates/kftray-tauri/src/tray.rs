@@ -5,8 +5,15 @@ use std::sync::{
 };
 use std::time::Duration;
 
+use kftray_commons::models::config_group_model::ConfigGroup;
 use kftray_commons::models::window::AppState;
 use kftray_commons::models::window::SaveDialogState;
+use kftray_commons::utils::config_group::list_groups_with_mode;
+use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_portforward::kube::{
+    start_group_with_mode,
+    stop_group_with_mode,
+};
 use log::{
     error,
     info,
@@ -15,11 +22,14 @@ use log::{
 use tauri::PhysicalPosition;
 use tauri::PhysicalSize;
 use tauri::{
+    AppHandle,
+    Emitter,
     Manager,
     RunEvent,
     WindowEvent,
     Wry,
     menu::{
+        Menu,
         MenuBuilder,
         MenuItemBuilder,
         PredefinedMenuItem,
@@ -51,7 +61,13 @@ use crate::window::{
     toggle_window_visibility,
 };
 
-pub fn create_tray_icon(app: &tauri::App<Wry>) -> Result<tauri::tray::TrayIcon<Wry>, tauri::Error> {
+const TRAY_ID: &str = "main";
+const GROUP_START_PREFIX: &str = "group_start:";
+const GROUP_STOP_PREFIX: &str = "group_stop:";
+
+fn build_tray_menu<M: Manager<Wry>>(
+    app: &M, groups: &[ConfigGroup],
+) -> Result<Menu<Wry>, tauri::Error> {
     let quit = MenuItemBuilder::with_id("quit", "Quit")
         .accelerator("CmdOrCtrl+Shift+Q")
         .build(app)?;
@@ -108,18 +124,94 @@ pub fn create_tray_icon(app: &tauri::App<Wry>) -> Result<tauri::tray::TrayIcon<W
         .item(&reset_position)
         .build()?;
 
+    let mut groups_submenu_builder = SubmenuBuilder::new(app, "Groups");
+    if groups.is_empty() {
+        let no_groups = MenuItemBuilder::with_id("no_groups", "No Groups")
+            .enabled(false)
+            .build(app)?;
+        groups_submenu_builder = groups_submenu_builder.item(&no_groups);
+    }
+    for group in groups {
+        let start = MenuItemBuilder::with_id(
+            format!("{GROUP_START_PREFIX}{}", group.name),
+            format!("Start {}", group.name),
+        )
+        .build(app)?;
+        let stop = MenuItemBuilder::with_id(
+            format!("{GROUP_STOP_PREFIX}{}", group.name),
+            format!("Stop {}", group.name),
+        )
+        .build(app)?;
+        groups_submenu_builder = groups_submenu_builder.item(&start).item(&stop);
+    }
+    let groups_submenu = groups_submenu_builder.build()?;
+
     let main_separator = PredefinedMenuItem::separator(app)?;
-    let menu = MenuBuilder::new(app)
+    MenuBuilder::new(app)
         .item(&open)
         .item(&main_separator)
         .item(&pin)
         .item(&set_window_position_submenu)
+        .item(&groups_submenu)
         .item(&quit)
-        .build()?;
+        .build()
+}
+
+/// Rebuilds the tray menu so the Groups submenu reflects the saved groups.
+pub async fn refresh_tray_groups(app: &AppHandle<Wry>) {
+    let groups = match list_groups_with_mode(DatabaseMode::File).await {
+        Ok(groups) => groups,
+        Err(e) => {
+            warn!("Failed to list config groups for tray menu: {e}");
+            return;
+        }
+    };
+
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_tray_menu(app, &groups) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                error!("Failed to update tray menu: {e}");
+            }
+        }
+        Err(e) => error!("Failed to build tray menu: {e}"),
+    }
+}
+
+fn handle_group_menu_event(app: &AppHandle<Wry>, id: &str) {
+    let (name, start) = if let Some(name) = id.strip_prefix(GROUP_START_PREFIX) {
+        (name.to_string(), true)
+    } else if let Some(name) = id.strip_prefix(GROUP_STOP_PREFIX) {
+        (name.to_string(), false)
+    } else {
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = if start {
+            start_group_with_mode(&name, DatabaseMode::File).await
+        } else {
+            stop_group_with_mode(&name, DatabaseMode::File).await
+        };
+        match result {
+            Ok(responses) => {
+                info!("Group '{name}': {} config(s) updated", responses.len());
+                let _ = app.emit("port-forward-status-changed", ());
+            }
+            Err(e) => error!("Failed to update group '{name}': {e}"),
+        }
+    });
+}
+
+pub fn create_tray_icon(app: &tauri::App<Wry>) -> Result<tauri::tray::TrayIcon<Wry>, tauri::Error> {
+    let menu = build_tray_menu(app, &[])?;
     let icon_bytes = include_bytes!("../icons/tray.ico");
     let icon = tauri::image::Image::from_bytes(icon_bytes)?;
 
-    let tray = TrayIconBuilder::new()
+    let tray = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .icon_as_template(true)
         .show_menu_on_left_click(false)
@@ -177,7 +269,7 @@ pub fn create_tray_icon(app: &tauri::App<Wry>) -> Result<tauri::tray::TrayIcon<W
                     toggle_pin_state(app.state::<AppState>(), window);
                 }
             }
-            _ => {}
+            id => handle_group_menu_event(app, id),
         })
         .on_tray_icon_event(|tray, event| {
             match &event {
@@ -1,4 +1,5 @@
 use clap::Parser;
+use kftray_commons::utils::git_provider::GitProvider;
 
 #[derive(Parser)]
 #[command(name = "kftui")]
@@ -8,7 +9,7 @@ pub struct Cli {
     #[arg(
         short = 'c',
         long,
-        help = "Path to config file (local JSON file or path within GitHub repo)",
+        help = "Path to config file (local JSON, YAML or TOML file or path within GitHub repo)",
         value_name = "PATH"
     )]
     pub configs_path: Option<String>,
@@ -21,6 +22,40 @@ pub struct Cli {
     )]
     pub github_url: Option<String>,
 
+    #[arg(
+        long = "git-ref",
+        help = "Branch, tag or full commit SHA to import from (requires --github-url)",
+        value_name = "REF"
+    )]
+    pub git_ref: Option<String>,
+
+    #[arg(
+        long = "git-ca-bundle",
+        help = "PEM bundle of extra CAs to trust when fetching from --github-url",
+        value_name = "PATH"
+    )]
+    pub git_ca_bundle: Option<String>,
+
+    #[arg(
+        long = "git-insecure-skip-verify",
+        help = "Disable TLS certificate and SSH host key verification for --github-url (insecure)"
+    )]
+    pub git_insecure_skip_verify: bool,
+
+    #[arg(
+        long = "git-provider",
+        help = "Hosting provider of --github-url: github, gitlab, bitbucket, gitea, or http for a plain file URL (detected from the host when omitted)",
+        value_name = "PROVIDER"
+    )]
+    pub git_provider: Option<String>,
+
+    #[arg(
+        long = "git-sync-interval",
+        help = "Subscribe to --github-url and check it for changes every SECS seconds (requires --save)",
+        value_name = "SECS"
+    )]
+    pub git_sync_interval: Option<u64>,
+
     #[arg(
         short = 's',
         long,
@@ -37,12 +72,12 @@ pub struct Cli {
     #[arg(
         short = 'j',
         long,
-        help = "Inline JSON configuration string",
+        help = "Inline configuration string (JSON, YAML or TOML)",
         value_name = "JSON"
     )]
     pub json: Option<String>,
 
-    #[arg(long, help = "Read JSON configuration from stdin")]
+    #[arg(long, help = "Read configuration from stdin (JSON, YAML or TOML)")]
     pub stdin: bool,
 
     #[arg(long, help = "Run in non-interactive mode (don't show TUI)")]
@@ -89,10 +124,19 @@ impl Cli {
         self.json.as_deref()
     }
 
+    pub fn get_git_provider(&self) -> Result<Option<GitProvider>, String> {
+        self.git_provider.as_deref().map(str::parse).transpose()
+    }
+
     pub fn get_configs_path_with_default(&self) -> String {
+        // A plain HTTP source is addressed by its URL alone.
+        let default = match self.get_git_provider() {
+            Ok(Some(GitProvider::Http)) => "",
+            _ => "config.json",
+        };
         self.configs_path
             .clone()
-            .unwrap_or_else(|| "config.json".to_string())
+            .unwrap_or_else(|| default.to_string())
     }
 
     pub fn validate(&self) -> Result<(), String> {
@@ -138,7 +182,26 @@ impl Cli {
             .filter_map(|(active, name)| if *active { Some(*name) } else { None })
             .collect();
 
-        if self.github_url.is_some() && self.configs_path.is_none() {
+        if self.github_url.is_none() {
+            let git_flags = [
+                (self.git_ref.is_some(), "--git-ref"),
+                (self.git_ca_bundle.is_some(), "--git-ca-bundle"),
+                (self.git_insecure_skip_verify, "--git-insecure-skip-verify"),
+                (self.git_sync_interval.is_some(), "--git-sync-interval"),
+                (self.git_provider.is_some(), "--git-provider"),
+            ];
+            if let Some((_, flag)) = git_flags.iter().find(|(set, _)| *set) {
+                return Err(format!("{flag} requires --github-url"));
+            }
+        }
+
+        if self.git_sync_interval.is_some() && !self.save {
+            return Err("--git-sync-interval requires --save".to_string());
+        }
+
+        let is_http_source = self.get_git_provider()? == Some(GitProvider::Http);
+
+        if self.github_url.is_some() && self.configs_path.is_none() && !is_http_source {
             return Err("--github-url requires --configs-path to specify the config file path within the repository".to_string());
         }
 
@@ -1,7 +1,20 @@
 use std::fs;
 
-use kftray_commons::utils::config::import_configs_with_mode;
+use kftray_commons::models::config_history_model::ConfigChangeSource;
+use kftray_commons::models::git_sync_model::GitSyncSubscription;
+use kftray_commons::utils::config::{
+    import_configs_in_format_with_mode,
+    import_configs_with_mode,
+};
+use kftray_commons::utils::config_format::ConfigFormat;
+use kftray_commons::utils::config_history::with_change_source;
 use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_commons::utils::git_sync::{
+    apply_sync_preview_with_mode,
+    check_subscription_with_mode,
+    list_subscriptions_with_mode,
+    save_subscription_with_mode,
+};
 use kftray_commons::utils::github::{
     GitHubConfig,
     GitHubRepository,
@@ -15,7 +28,11 @@ pub struct ConfigImporter;
 
 impl ConfigImporter {
     pub async fn import_configs(cli: &Cli, mode: DatabaseMode) -> Result<(), String> {
-        Self::handle_flush_if_needed(cli, mode).await?;
+        with_change_source(
+            ConfigChangeSource::Import,
+            Self::handle_flush_if_needed(cli, mode),
+        )
+        .await?;
         Self::print_import_start_message(cli, mode);
 
         let result = Self::import_from_source(cli, mode).await;
@@ -137,20 +154,77 @@ impl ConfigImporter {
             use_system_credentials: true,
             github_token,
             flush_existing: false,
+            git_ref: cli.git_ref.clone(),
+            insecure_skip_verify: cli.git_insecure_skip_verify,
+            ca_bundle_path: cli.git_ca_bundle.clone(),
+            provider: cli.get_git_provider()?,
         };
 
-        GitHubRepository::import_configs(github_config, mode)
+        let record = GitHubRepository::import_configs(github_config, mode)
             .await
             .map_err(|e| {
                 format!("Failed to import configs from GitHub repository '{github_url}': {e}")
-            })
+            })?;
+
+        log::info!(
+            "Imported configs from {} at commit {}",
+            record.repo_url,
+            record.commit_sha
+        );
+
+        if let Some(interval_secs) = cli.git_sync_interval {
+            Self::save_git_sync_subscription(cli, interval_secs, mode).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Subscribes to the imported source and records the current state as the
+    /// baseline, so later checks only report upstream changes.
+    async fn save_git_sync_subscription(
+        cli: &Cli, interval_secs: u64, mode: DatabaseMode,
+    ) -> Result<(), String> {
+        let repo_url = cli.get_github_url().unwrap().to_string();
+        let config_path = cli.get_configs_path_with_default();
+
+        let existing = list_subscriptions_with_mode(mode)
+            .await?
+            .into_iter()
+            .find(|s| {
+                s.repo_url == repo_url && s.config_path == config_path && s.git_ref == cli.git_ref
+            });
+
+        let subscription = GitSyncSubscription {
+            name: existing
+                .as_ref()
+                .map(|s| s.name.clone())
+                .unwrap_or_else(|| format!("{repo_url}:{config_path}")),
+            repo_url,
+            config_path,
+            git_ref: cli.git_ref.clone(),
+            use_system_credentials: true,
+            insecure_skip_verify: cli.git_insecure_skip_verify,
+            ca_bundle_path: cli.git_ca_bundle.clone(),
+            provider: cli.get_git_provider()?,
+            interval_secs,
+            enabled: true,
+            ..existing.unwrap_or_default()
+        };
+
+        let id = save_subscription_with_mode(&subscription, mode).await?;
+        let baseline = check_subscription_with_mode(id, mode).await?;
+        apply_sync_preview_with_mode(&baseline, mode).await?;
+
+        log::info!("Subscribed to {} every {interval_secs}s", subscription.name);
+        Ok(())
     }
 
     async fn import_from_file(config_path: &str, mode: DatabaseMode) -> Result<(), String> {
-        let json_content = fs::read_to_string(config_path)
+        let content = fs::read_to_string(config_path)
             .map_err(|e| format!("Failed to read config file '{config_path}': {e}"))?;
 
-        import_configs_with_mode(json_content, mode)
+        let format = ConfigFormat::from_path(config_path);
+        import_configs_in_format_with_mode(content, format, mode)
             .await
             .map_err(|e| format!("Failed to import configs from file '{config_path}': {e}"))
     }
@@ -84,6 +84,16 @@ impl CliHandler {
             return Err(e.into());
         }
 
+        let mode = self.mode;
+        tokio::spawn(async move {
+            let report = kftray_portforward::kube::reconcile::reconcile_all_contexts(
+                mode,
+                kftray_portforward::kube::reconcile::DEFAULT_STALE_AFTER,
+            )
+            .await;
+            log::info!("Startup cluster reconcile: {}", report.summary());
+        });
+
         if self.cli.non_interactive {
             println!("Database initialized");
         }
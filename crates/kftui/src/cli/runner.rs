@@ -18,6 +18,7 @@ use kftray_commons::utils::settings::{
     set_ssl_enabled,
 };
 use kftray_portforward::kube::{
+    start_kubeconfig_watcher,
     stop_port_forward_with_mode,
     stop_proxy_forward_with_mode,
 };
@@ -142,6 +143,7 @@ impl PortForwardRunner {
         }
 
         Self::print_active_configurations(&started_configs);
+        start_kubeconfig_watcher(mode);
         Self::wait_for_shutdown_signal(&started_configs, mode).await;
         Ok(())
     }
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use kftray_commons::models::git_sync_model::{
+    SyncApplyResult,
+    SyncPreview,
+};
+use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_commons::utils::git_sync::{
+    apply_sync_preview_with_mode,
+    check_subscription_with_mode,
+    due_subscriptions_with_mode,
+    list_subscriptions_with_mode,
+};
+use kftray_portforward::kube::{
+    restart_running_configs_with_mode,
+    stop_running_configs_with_mode,
+};
+use log::{
+    error,
+    info,
+    warn,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Checks every enabled subscription and returns the previews that contain
+/// changes, along with the errors of the subscriptions that failed.
+pub async fn check_all_subscriptions(mode: DatabaseMode) -> (Vec<SyncPreview>, Vec<String>) {
+    let subscriptions = match list_subscriptions_with_mode(mode).await {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => return (Vec::new(), vec![e]),
+    };
+
+    let mut previews = Vec::new();
+    let mut errors = Vec::new();
+
+    for subscription in subscriptions.into_iter().filter(|s| s.enabled) {
+        let Some(id) = subscription.id else {
+            continue;
+        };
+
+        match check_subscription_with_mode(id, mode).await {
+            Ok(preview) if !preview.diff.is_empty() => previews.push(preview),
+            Ok(_) => {}
+            Err(e) => errors.push(format!("{}: {e}", subscription.name)),
+        }
+    }
+
+    (previews, errors)
+}
+
+/// Applies an approved preview, stopping the running forwards of the configs
+/// it removes first, and restarts the running forwards whose config changed.
+pub async fn apply_preview(
+    preview: &SyncPreview, mode: DatabaseMode,
+) -> Result<SyncApplyResult, String> {
+    let removed_ids = preview.diff.removed_config_ids();
+    if !removed_ids.is_empty() {
+        match stop_running_configs_with_mode(&removed_ids, mode).await {
+            Ok(stopped) => info!(
+                "Stopped {} running forwards removed by git sync",
+                stopped.len()
+            ),
+            Err(e) => error!("{e}"),
+        }
+    }
+
+    let result = apply_sync_preview_with_mode(preview, mode).await?;
+
+    if !result.changed_config_ids.is_empty() {
+        match restart_running_configs_with_mode(&result.changed_config_ids, mode).await {
+            Ok(restarted) => info!(
+                "Restarted {} running forwards after git sync",
+                restarted.len()
+            ),
+            Err(e) => error!("{e}"),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Polls due subscriptions in the background and sends previews with
+/// changes to the UI. Each commit is only sent once.
+pub fn spawn_git_sync_poller(mode: DatabaseMode, sender: UnboundedSender<SyncPreview>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        let mut notified: HashMap<i64, String> = HashMap::new();
+
+        loop {
+            interval.tick().await;
+
+            let subscriptions = match due_subscriptions_with_mode(mode).await {
+                Ok(subscriptions) => subscriptions,
+                Err(e) => {
+                    error!("Failed to load git sync subscriptions: {e}");
+                    continue;
+                }
+            };
+
+            for subscription in subscriptions {
+                let Some(id) = subscription.id else {
+                    continue;
+                };
+
+                match check_subscription_with_mode(id, mode).await {
+                    Ok(preview) => {
+                        if preview.diff.is_empty() || notified.get(&id) == Some(&preview.commit_sha)
+                        {
+                            continue;
+                        }
+
+                        notified.insert(id, preview.commit_sha.clone());
+                        if sender.send(preview).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => warn!("Git sync check for '{}' failed: {e}", subscription.name),
+                }
+            }
+        }
+    });
+}
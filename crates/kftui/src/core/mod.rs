@@ -1,3 +1,4 @@
+pub mod git_sync;
 pub mod port_forward;
 
 pub mod built_info {
@@ -11,6 +11,7 @@ use crossterm::{
 use kftray_commons::models::config_model::Config;
 use kftray_commons::utils::config::get_config_with_mode;
 use kftray_commons::utils::config_state::cleanup_current_process_config_states_with_mode;
+use kftray_commons::utils::config_validation::validate_config;
 use kftray_commons::utils::db_mode::DatabaseMode;
 use kftray_portforward::kube::{
     deploy_and_forward_pod_with_mode,
@@ -35,6 +36,13 @@ pub async fn start_port_forwarding_with_ssl(
 ) {
     let _config_id = config.id.unwrap_or_default();
 
+    if let Err(e) = validate_config(&config).into_result() {
+        error!("{e}");
+        app.error_message = Some(e);
+        app.state = AppState::ShowErrorPopup;
+        return;
+    }
+
     let result = match config.workload_type.as_deref() {
         Some("proxy") => {
             deploy_and_forward_pod_with_mode(vec![config.clone()], mode, ssl_override).await
@@ -123,6 +131,7 @@ pub async fn start_port_forward(
     config_id: i64, mode: DatabaseMode, ssl_override: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let config = get_config_with_mode(config_id, mode).await?;
+    validate_config(&config).into_result()?;
 
     match config.workload_type.as_deref() {
         Some("proxy") => {
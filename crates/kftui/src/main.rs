@@ -25,6 +25,9 @@ use crate::logging::{
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     kftray_portforward::ssl::ensure_crypto_provider_installed();
+    kftray_commons::utils::config_history::set_default_change_source(
+        kftray_commons::models::config_history_model::ConfigChangeSource::Tui,
+    );
 
     let cli = Cli::parse();
 
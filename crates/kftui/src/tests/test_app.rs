@@ -43,6 +43,15 @@ mod tests {
             cert_issuer_kind: None,
             ingress_class: None,
             ingress_annotations: None,
+            groups: None,
+            port_conflict_policy: None,
+            impersonate_user: None,
+            impersonate_groups: Vec::new(),
+            pod_selection: None,
+            drain_policy: None,
+            drain_timeout_secs: None,
+            debug_container: None,
+            proxy_relay: None,
         }
     }
 
@@ -62,6 +71,7 @@ mod tests {
                 config_id: i as i64 + 1,
                 is_running,
                 process_id: if is_running { Some(1234) } else { None },
+                local_port: None,
             });
         }
 
@@ -50,6 +50,15 @@ mod tests {
             cert_issuer_kind: None,
             ingress_class: None,
             ingress_annotations: None,
+            groups: None,
+            port_conflict_policy: None,
+            impersonate_user: None,
+            impersonate_groups: Vec::new(),
+            pod_selection: None,
+            drain_policy: None,
+            drain_timeout_secs: None,
+            debug_container: None,
+            proxy_relay: None,
         }
     }
 
@@ -59,6 +68,7 @@ mod tests {
             config_id: 1,
             is_running: true,
             process_id: Some(1234),
+            local_port: None,
         }
     }
 
@@ -1,6 +1,13 @@
 use crossterm::event::KeyCode;
+use kftray_commons::models::config_group_model::ConfigGroup;
+use kftray_commons::models::config_history_model::{
+    ConfigChangeAction,
+    ConfigChangeSource,
+    ConfigRevision,
+};
 use kftray_commons::models::config_model::Config;
 use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_portforward::kube::client::AuthPrompt;
 
 use crate::tests::test_logger_state;
 use crate::tui::input::{
@@ -11,15 +18,21 @@ use crate::tui::input::{
     DeleteButton,
     clear_selection,
     handle_about_input,
+    handle_auth_prompt_input,
+    handle_clean_cluster_confirmation_input,
+    handle_config_history_input,
     handle_confirmation_popup_input,
+    handle_connection_report_input,
     handle_context_selection_input,
     handle_delete_confirmation_input,
     handle_details_input,
     handle_error_popup_input,
+    handle_group_selection_input,
     handle_help_input,
     handle_logs_input,
     handle_menu_input,
     handle_normal_input,
+    handle_plan_input,
     handle_running_table_input,
     handle_stopped_table_input,
     select_first_row,
@@ -60,6 +73,15 @@ mod tests {
                 cert_issuer_kind: None,
                 ingress_class: None,
                 ingress_annotations: None,
+                groups: None,
+                port_conflict_policy: None,
+                impersonate_user: None,
+                impersonate_groups: Vec::new(),
+                pod_selection: None,
+                drain_policy: None,
+                drain_timeout_secs: None,
+                debug_container: None,
+                proxy_relay: None,
             })
             .collect()
     }
@@ -343,6 +365,134 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_handle_group_selection_input() {
+        let mut app = setup_app();
+        app.state = AppState::ShowGroupSelection;
+        app.groups = vec![
+            ConfigGroup {
+                id: Some(1),
+                name: "backend".to_string(),
+                config_ids: vec![1, 2],
+            },
+            ConfigGroup {
+                id: Some(2),
+                name: "payments".to_string(),
+                config_ids: vec![3],
+            },
+        ];
+        app.group_list_state.select(Some(0));
+
+        handle_group_selection_input(&mut app, KeyCode::Down, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        handle_group_selection_input(&mut app, KeyCode::Down, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        assert_eq!(app.selected_group_index, 1);
+        assert_eq!(app.group_list_state.selected(), Some(1));
+
+        handle_group_selection_input(&mut app, KeyCode::Up, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        assert_eq!(app.selected_group_index, 0);
+
+        handle_group_selection_input(&mut app, KeyCode::Esc, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        assert_eq!(app.state, AppState::Normal);
+    }
+
+    #[tokio::test]
+    async fn test_handle_config_history_input() {
+        let mut app = setup_app();
+        app.state = AppState::ShowConfigHistory;
+        app.history_revisions = (1..=2)
+            .map(|id| ConfigRevision {
+                id,
+                config_id: 1,
+                action: ConfigChangeAction::Update,
+                source: ConfigChangeSource::Tui,
+                label: "db".to_string(),
+                before: None,
+                after: None,
+                changed_at: 0,
+            })
+            .collect();
+        app.history_list_state.select(Some(0));
+
+        handle_config_history_input(&mut app, KeyCode::Down, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        handle_config_history_input(&mut app, KeyCode::Down, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        assert_eq!(app.selected_history_index, 1);
+        assert_eq!(app.history_list_state.selected(), Some(1));
+
+        handle_config_history_input(&mut app, KeyCode::Up, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        assert_eq!(app.selected_history_index, 0);
+
+        handle_config_history_input(&mut app, KeyCode::Esc, DatabaseMode::Memory)
+            .await
+            .unwrap();
+        assert_eq!(app.state, AppState::Normal);
+        assert!(app.history_revisions.is_empty());
+    }
+
+    #[test]
+    fn test_handle_plan_input() {
+        let mut app = setup_app();
+        app.state = AppState::ShowPlan;
+        app.plan_report = "db [ready]\n  ok    context: dev".to_string();
+
+        handle_plan_input(&mut app, KeyCode::Down).unwrap();
+        handle_plan_input(&mut app, KeyCode::Down).unwrap();
+        handle_plan_input(&mut app, KeyCode::Up).unwrap();
+        assert_eq!(app.plan_scroll, 1);
+
+        handle_plan_input(&mut app, KeyCode::Esc).unwrap();
+        assert_eq!(app.state, AppState::Normal);
+        assert_eq!(app.plan_scroll, 0);
+        assert!(app.plan_report.is_empty());
+    }
+
+    #[test]
+    fn test_handle_auth_prompt_input() {
+        let mut app = setup_app();
+        app.state = AppState::ShowAuthPrompt;
+        app.auth_prompt = Some(AuthPrompt {
+            command: "kubelogin".to_string(),
+            message: "Open https://microsoft.com/devicelogin".to_string(),
+            url: Some("https://microsoft.com/devicelogin".to_string()),
+        });
+
+        handle_auth_prompt_input(&mut app, KeyCode::Down).unwrap();
+        assert_eq!(app.state, AppState::ShowAuthPrompt);
+
+        handle_auth_prompt_input(&mut app, KeyCode::Enter).unwrap();
+        assert_eq!(app.state, AppState::Normal);
+        assert!(app.auth_prompt.is_none());
+    }
+
+    #[test]
+    fn test_handle_connection_report_input() {
+        let mut app = setup_app();
+        app.state = AppState::ShowConnectionReport;
+        app.connection_report = "dev (https://127.0.0.1:6443/) [reachable]".to_string();
+
+        handle_connection_report_input(&mut app, KeyCode::PageDown).unwrap();
+        handle_connection_report_input(&mut app, KeyCode::Up).unwrap();
+        assert_eq!(app.connection_report_scroll, 9);
+
+        handle_connection_report_input(&mut app, KeyCode::Char('q')).unwrap();
+        assert_eq!(app.state, AppState::Normal);
+        assert_eq!(app.connection_report_scroll, 0);
+        assert!(app.connection_report.is_empty());
+    }
+
     #[tokio::test]
     async fn test_handle_normal_input() {
         let mut app = setup_app();
@@ -370,6 +520,27 @@ mod tests {
         assert_eq!(app.active_component, ActiveComponent::Menu);
     }
 
+    #[tokio::test]
+    async fn test_clean_cluster_needs_confirmation() {
+        let mut app = setup_app();
+        app.state = AppState::Normal;
+        app.active_component = ActiveComponent::StoppedTable;
+
+        handle_normal_input(&mut app, KeyCode::Char('K'), DatabaseMode::File)
+            .await
+            .unwrap();
+        assert_eq!(app.state, AppState::ShowCleanClusterConfirmation);
+        assert_eq!(app.selected_delete_button, DeleteButton::Close);
+
+        handle_clean_cluster_confirmation_input(&mut app, KeyCode::Right, DatabaseMode::File).await;
+        assert_eq!(app.selected_delete_button, DeleteButton::Confirm);
+
+        handle_clean_cluster_confirmation_input(&mut app, KeyCode::Left, DatabaseMode::File).await;
+        handle_clean_cluster_confirmation_input(&mut app, KeyCode::Enter, DatabaseMode::File).await;
+        assert_eq!(app.state, AppState::Normal);
+        assert!(app.import_export_message.is_none());
+    }
+
     #[tokio::test]
     async fn test_handle_menu_input() {
         let mut app = setup_app();
@@ -68,6 +68,15 @@ fn create_test_config() -> Config {
         cert_issuer_kind: None,
         ingress_class: None,
         ingress_annotations: None,
+        groups: None,
+        port_conflict_policy: None,
+        impersonate_user: None,
+        impersonate_groups: Vec::new(),
+        pod_selection: None,
+        drain_policy: None,
+        drain_timeout_secs: None,
+        debug_container: None,
+        proxy_relay: None,
     }
 }
 
@@ -77,6 +86,7 @@ fn create_test_config_state() -> ConfigState {
         config_id: 1,
         is_running: true,
         process_id: Some(1234),
+        local_port: None,
     }
 }
 
@@ -102,7 +112,8 @@ mod tests {
 
     // Helper to create a standard terminal for testing
     fn setup_terminal() -> Terminal<TestBackend> {
-        // Create a standard terminal size that's large enough to render all UI elements
+        // Create a standard terminal size that's large enough to render all UI
+        // elements
         let backend = TestBackend::new(100, 30);
         Terminal::new(backend).unwrap()
     }
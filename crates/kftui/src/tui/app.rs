@@ -63,6 +63,12 @@ pub async fn run_tui(
         error!("Failed to start network monitor: {e}");
     }
 
+    kftray_portforward::kube::start_kubeconfig_watcher(mode);
+
+    if let Some(sender) = app.git_sync_sender.clone() {
+        crate::core::git_sync::spawn_git_sync_poller(mode, sender);
+    }
+
     let res = run_app(&mut terminal, &mut app, mode).await;
 
     disable_raw_mode()?;
@@ -207,12 +213,14 @@ mod tests {
                 config_id: 1,
                 is_running: false,
                 process_id: None,
+                local_port: None,
             },
             ConfigState {
                 id: Some(2),
                 config_id: 2,
                 is_running: true,
                 process_id: Some(1234),
+                local_port: None,
             },
         ];
 
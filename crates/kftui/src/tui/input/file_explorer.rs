@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use kftray_commons::utils::config_format::ConfigFormat;
 use kftray_commons::utils::db_mode::DatabaseMode;
 use ratatui::crossterm::event::{
     Event,
@@ -21,7 +22,7 @@ pub async fn handle_file_selection(
     app: &mut App, selected_path: &Path,
 ) -> Result<(), std::io::Error> {
     if selected_path.is_file() {
-        if selected_path.extension().and_then(|s| s.to_str()) == Some("json") {
+        if ConfigFormat::from_path(selected_path).is_some() {
             match get_file_content(selected_path) {
                 Ok(content) => app.file_content = Some(content),
                 Err(e) => handle_file_error(app, e),
@@ -52,7 +53,10 @@ pub async fn handle_import(
             Err(e) => show_error_popup(app, format!("Import failed: {e}")),
         }
     } else {
-        show_error_popup(app, "Selected file is not a JSON file".to_string());
+        show_error_popup(
+            app,
+            "Selected file is not a JSON, YAML or TOML file".to_string(),
+        );
     }
     Ok(())
 }
@@ -123,10 +127,13 @@ pub async fn handle_import_enter_key(
             app.import_file_explorer
                 .set_cwd(selected_path.clone())
                 .unwrap();
-        } else if selected_path.extension().and_then(|s| s.to_str()) == Some("json") {
+        } else if ConfigFormat::from_path(&selected_path).is_some() {
             handle_import(app, &selected_path, mode).await?;
         } else {
-            show_error_popup(app, "Selected file is not a JSON file".to_string());
+            show_error_popup(
+                app,
+                "Selected file is not a JSON, YAML or TOML file".to_string(),
+            );
         }
     }
     Ok(())
@@ -19,10 +19,17 @@ use crossterm::event::{
 use crossterm::terminal::size;
 pub use file_explorer::*;
 use kftray_commons::models::{
+    config_group_model::ConfigGroup,
+    config_history_model::ConfigRevision,
     config_model::Config,
     config_state_model::ConfigState,
+    git_sync_model::SyncPreview,
 };
 use kftray_commons::utils::db_mode::DatabaseMode;
+use kftray_portforward::kube::client::{
+    AuthPrompt,
+    subscribe_auth_prompts,
+};
 pub use popup::*;
 use ratatui::widgets::ListState;
 use ratatui::widgets::TableState;
@@ -217,6 +224,13 @@ pub enum AppState {
     ShowSettings,
     ShowHttpLogsConfig,
     ShowHttpLogsViewer,
+    ShowGitSyncPreview,
+    ShowGroupSelection,
+    ShowPlan,
+    ShowConnectionReport,
+    ShowAuthPrompt,
+    ShowConfigHistory,
+    ShowCleanClusterConfirmation,
     #[cfg_attr(debug_assertions, allow(dead_code))]
     ShowUpdateConfirmation,
     #[cfg_attr(debug_assertions, allow(dead_code))]
@@ -288,6 +302,22 @@ pub struct App {
         std::collections::HashMap<i64, (Arc<AtomicBool>, std::time::Instant)>,
     pub error_receiver: Option<tokio::sync::mpsc::UnboundedReceiver<String>>,
     pub error_sender: Option<tokio::sync::mpsc::UnboundedSender<String>>,
+    pub git_sync_previews: Vec<SyncPreview>,
+    pub groups: Vec<ConfigGroup>,
+    pub selected_group_index: usize,
+    pub group_list_state: ListState,
+    pub plan_report: String,
+    pub plan_scroll: u16,
+    pub connection_report: String,
+    pub connection_report_scroll: u16,
+    pub auth_prompt: Option<AuthPrompt>,
+    pub auth_prompt_receiver: Option<tokio::sync::broadcast::Receiver<AuthPrompt>>,
+    pub history_title: String,
+    pub history_revisions: Vec<ConfigRevision>,
+    pub selected_history_index: usize,
+    pub history_list_state: ListState,
+    pub git_sync_receiver: Option<tokio::sync::mpsc::UnboundedReceiver<SyncPreview>>,
+    pub git_sync_sender: Option<tokio::sync::mpsc::UnboundedSender<SyncPreview>>,
     pub search_query: String,
     pub search_focused: bool,
     pub filtered_stopped_configs: Vec<Config>,
@@ -310,6 +340,7 @@ impl App {
         let export_file_explorer = FileExplorer::with_theme(theme).unwrap();
         let tui_logger_state = TuiWidgetState::new();
         let (error_sender, error_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let (git_sync_sender, git_sync_receiver) = tokio::sync::mpsc::unbounded_channel();
 
         let mut app = Self {
             details_scroll_offset: 0,
@@ -373,6 +404,22 @@ impl App {
             configs_being_processed: std::collections::HashMap::new(),
             error_receiver: Some(error_receiver),
             error_sender: Some(error_sender),
+            git_sync_previews: Vec::new(),
+            groups: Vec::new(),
+            selected_group_index: 0,
+            group_list_state: ListState::default(),
+            plan_report: String::new(),
+            plan_scroll: 0,
+            connection_report: String::new(),
+            connection_report_scroll: 0,
+            auth_prompt: None,
+            auth_prompt_receiver: Some(subscribe_auth_prompts()),
+            history_title: String::new(),
+            history_revisions: Vec::new(),
+            selected_history_index: 0,
+            history_list_state: ListState::default(),
+            git_sync_receiver: Some(git_sync_receiver),
+            git_sync_sender: Some(git_sync_sender),
             search_query: String::new(),
             search_focused: false,
             filtered_stopped_configs: Vec::new(),
@@ -697,6 +744,28 @@ impl App {
             self.error_message = Some(error_msg);
             self.state = AppState::ShowErrorPopup;
         }
+
+        if let Some(ref mut receiver) = self.git_sync_receiver {
+            while let Ok(preview) = receiver.try_recv() {
+                self.git_sync_previews
+                    .retain(|p| p.subscription_id != preview.subscription_id);
+                self.git_sync_previews.push(preview);
+            }
+        }
+
+        if let Some(ref mut receiver) = self.auth_prompt_receiver {
+            while let Ok(prompt) = receiver.try_recv() {
+                self.auth_prompt = Some(prompt);
+            }
+        }
+
+        if self.state == AppState::Normal && !self.git_sync_previews.is_empty() {
+            self.state = AppState::ShowGitSyncPreview;
+        }
+
+        if self.state == AppState::Normal && self.auth_prompt.is_some() {
+            self.state = AppState::ShowAuthPrompt;
+        }
     }
 
     pub fn scroll_up(&mut self) {
@@ -848,6 +917,34 @@ pub async fn handle_input(app: &mut App, mode: DatabaseMode) -> io::Result<bool>
                     log::debug!("Handling ShowHttpLogsViewer state");
                     handle_http_logs_viewer_input(app, key.code).await?;
                 }
+                AppState::ShowGitSyncPreview => {
+                    log::debug!("Handling ShowGitSyncPreview state");
+                    handle_git_sync_preview_input(app, key.code, mode).await?;
+                }
+                AppState::ShowGroupSelection => {
+                    log::debug!("Handling ShowGroupSelection state");
+                    handle_group_selection_input(app, key.code, mode).await?;
+                }
+                AppState::ShowPlan => {
+                    log::debug!("Handling ShowPlan state");
+                    handle_plan_input(app, key.code)?;
+                }
+                AppState::ShowConnectionReport => {
+                    log::debug!("Handling ShowConnectionReport state");
+                    handle_connection_report_input(app, key.code)?;
+                }
+                AppState::ShowAuthPrompt => {
+                    log::debug!("Handling ShowAuthPrompt state");
+                    handle_auth_prompt_input(app, key.code)?;
+                }
+                AppState::ShowConfigHistory => {
+                    log::debug!("Handling ShowConfigHistory state");
+                    handle_config_history_input(app, key.code, mode).await?;
+                }
+                AppState::ShowCleanClusterConfirmation => {
+                    log::debug!("Handling ShowCleanClusterConfirmation state");
+                    handle_clean_cluster_confirmation_input(app, key.code, mode).await;
+                }
                 AppState::ShowUpdateConfirmation => {
                     log::debug!("Handling ShowUpdateConfirmation state");
                     handle_update_confirmation_input(app, key.code, mode).await?;
@@ -1159,6 +1256,9 @@ pub async fn handle_stopped_table_input(
         KeyCode::Char('L') => handle_http_logs_config(app, mode).await?,
         KeyCode::Char('o') => handle_open_http_logs(app, mode).await?,
         KeyCode::Char('V') => handle_view_http_logs(app, mode).await?,
+        KeyCode::Char('p') => handle_plan(app, mode).await,
+        KeyCode::Char('t') => handle_connection_test(app).await,
+        KeyCode::Char('u') => open_config_history(app, mode).await,
         _ => {}
     }
     Ok(())
@@ -1214,6 +1314,9 @@ pub async fn handle_running_table_input(
         KeyCode::Char('L') => handle_http_logs_config(app, mode).await?,
         KeyCode::Char('o') => handle_open_http_logs(app, mode).await?,
         KeyCode::Char('V') => handle_view_http_logs(app, mode).await?,
+        KeyCode::Char('p') => handle_plan(app, mode).await,
+        KeyCode::Char('t') => handle_connection_test(app).await,
+        KeyCode::Char('u') => open_config_history(app, mode).await,
         _ => {}
     }
     Ok(())
@@ -1290,6 +1393,10 @@ pub async fn handle_common_hotkeys(
             open_import_file_explorer(app);
             Ok(true)
         }
+        KeyCode::Char('I') => {
+            handle_import_kubectl_forwards(app, mode).await;
+            Ok(true)
+        }
         KeyCode::Char('e') => {
             open_export_file_explorer(app);
             Ok(true)
@@ -1332,6 +1439,23 @@ pub async fn handle_common_hotkeys(
             handle_view_http_logs(app, mode).await?;
             Ok(true)
         }
+        KeyCode::Char('K') => {
+            app.selected_delete_button = DeleteButton::Close;
+            app.state = AppState::ShowCleanClusterConfirmation;
+            Ok(true)
+        }
+        KeyCode::Char('G') => {
+            handle_check_git_sync(app, mode).await;
+            Ok(true)
+        }
+        KeyCode::Char('g') => {
+            open_group_selection(app, mode).await;
+            Ok(true)
+        }
+        KeyCode::Char('U') => {
+            open_deleted_configs(app, mode).await;
+            Ok(true)
+        }
         KeyCode::Char('/') => {
             app.active_component = ActiveComponent::SearchBar;
             app.search_focused = true;
@@ -1345,6 +1469,462 @@ pub async fn handle_common_hotkeys(
     }
 }
 
+/// Message of the clean cluster confirmation popup.
+pub const CLEAN_CLUSTER_CONFIRMATION: &str = "Delete kftray resources of this machine\nthat no running forward refreshes?\n\nThis also deletes unlabelled\nkftray-forward-<user>-* and kftray-expose-<user>-*\nresources older than 5 minutes in every namespace.\nOlder kftray versions created them, and another\nmachine with the same username may still use them.";
+
+pub async fn handle_clean_cluster_confirmation_input(
+    app: &mut App, key: KeyCode, mode: DatabaseMode,
+) {
+    use kftray_portforward::kube::reconcile::{
+        DEFAULT_STALE_AFTER,
+        reconcile_all_contexts_with_legacy,
+    };
+
+    match key {
+        KeyCode::Left | KeyCode::Right => {
+            app.selected_delete_button = match app.selected_delete_button {
+                DeleteButton::Confirm => DeleteButton::Close,
+                DeleteButton::Close => DeleteButton::Confirm,
+            };
+        }
+        KeyCode::Enter if app.selected_delete_button == DeleteButton::Confirm => {
+            let report = reconcile_all_contexts_with_legacy(mode, DEFAULT_STALE_AFTER).await;
+            app.import_export_message = Some(report.summary());
+            app.state = AppState::ShowConfirmationPopup;
+        }
+        KeyCode::Enter | KeyCode::Esc => app.state = AppState::Normal,
+        _ => {}
+    }
+}
+
+async fn handle_import_kubectl_forwards(app: &mut App, mode: DatabaseMode) {
+    let discovered =
+        match kftray_portforward::kube::discover_kubectl_port_forwards_with_mode(mode).await {
+            Ok(discovered) => discovered,
+            Err(e) => {
+                app.error_message = Some(format!("Failed to scan kubectl port-forwards: {e}"));
+                app.state = AppState::ShowErrorPopup;
+                return;
+            }
+        };
+
+    let mut message = if discovered.configs.is_empty() {
+        "No kubectl port-forward processes to import.".to_string()
+    } else {
+        let json = match serde_json::to_string(&discovered.configs) {
+            Ok(json) => json,
+            Err(e) => {
+                app.error_message = Some(format!("Failed to serialize configs: {e}"));
+                app.state = AppState::ShowErrorPopup;
+                return;
+            }
+        };
+        if let Err(e) = kftray_commons::utils::config::import_configs_with_mode(json, mode).await {
+            app.error_message = Some(format!("Import failed: {e}"));
+            app.state = AppState::ShowErrorPopup;
+            return;
+        }
+        format!(
+            "Imported {} config(s) from kubectl port-forward processes.",
+            discovered.configs.len()
+        )
+    };
+
+    for skipped in &discovered.skipped {
+        message.push_str(&format!("\nSkipped {skipped}"));
+    }
+
+    app.import_export_message = Some(message);
+    app.state = AppState::ShowConfirmationPopup;
+}
+
+async fn handle_check_git_sync(app: &mut App, mode: DatabaseMode) {
+    let (previews, errors) = crate::core::git_sync::check_all_subscriptions(mode).await;
+
+    if !errors.is_empty() {
+        app.error_message = Some(format!("Git sync check failed:\n{}", errors.join("\n")));
+        app.state = AppState::ShowErrorPopup;
+    } else if previews.is_empty() {
+        app.import_export_message = Some("Git sync: all subscriptions are up to date.".into());
+        app.state = AppState::ShowConfirmationPopup;
+    }
+
+    app.git_sync_previews = previews;
+    if app.state == AppState::Normal && !app.git_sync_previews.is_empty() {
+        app.state = AppState::ShowGitSyncPreview;
+    }
+}
+
+async fn open_group_selection(app: &mut App, mode: DatabaseMode) {
+    match kftray_commons::utils::config_group::list_groups_with_mode(mode).await {
+        Ok(groups) if groups.is_empty() => {
+            app.import_export_message =
+                Some("No config groups yet. Add groups to a config to start them together.".into());
+            app.state = AppState::ShowConfirmationPopup;
+        }
+        Ok(groups) => {
+            app.groups = groups;
+            app.selected_group_index = 0;
+            app.group_list_state.select(Some(0));
+            app.state = AppState::ShowGroupSelection;
+        }
+        Err(e) => {
+            app.error_message = Some(format!("Failed to load config groups: {e}"));
+            app.state = AppState::ShowErrorPopup;
+        }
+    }
+}
+
+pub async fn handle_group_selection_input(
+    app: &mut App, key: KeyCode, mode: DatabaseMode,
+) -> io::Result<()> {
+    let start = match key {
+        KeyCode::Up => {
+            if app.selected_group_index > 0 {
+                app.selected_group_index -= 1;
+                app.group_list_state.select(Some(app.selected_group_index));
+            }
+            return Ok(());
+        }
+        KeyCode::Down => {
+            if app.selected_group_index + 1 < app.groups.len() {
+                app.selected_group_index += 1;
+                app.group_list_state.select(Some(app.selected_group_index));
+            }
+            return Ok(());
+        }
+        KeyCode::Esc => {
+            app.state = AppState::Normal;
+            return Ok(());
+        }
+        KeyCode::Enter | KeyCode::Char('f') => true,
+        KeyCode::Char('x') => false,
+        _ => return Ok(()),
+    };
+
+    let Some(group) = app.groups.get(app.selected_group_index).cloned() else {
+        return Ok(());
+    };
+
+    let result = if start {
+        kftray_portforward::kube::start_group_with_mode(&group.name, mode).await
+    } else {
+        kftray_portforward::kube::stop_group_with_mode(&group.name, mode).await
+    };
+
+    match result {
+        Ok(responses) => {
+            app.import_export_message = Some(format!(
+                "{} {} port forward(s) in group '{}'.",
+                if start { "Started" } else { "Stopped" },
+                responses.len(),
+                group.name
+            ));
+            app.state = AppState::ShowConfirmationPopup;
+        }
+        Err(e) => {
+            app.error_message = Some(format!("Group '{}': {e}", group.name));
+            app.state = AppState::ShowErrorPopup;
+        }
+    }
+
+    Ok(())
+}
+
+fn show_config_history(app: &mut App, title: String, revisions: Vec<ConfigRevision>) {
+    app.history_title = title;
+    app.history_revisions = revisions;
+    app.selected_history_index = 0;
+    app.history_list_state.select(Some(0));
+    app.state = AppState::ShowConfigHistory;
+}
+
+async fn open_config_history(app: &mut App, mode: DatabaseMode) {
+    let (configs, selected_row) = match app.active_table {
+        ActiveTable::Stopped => (
+            if app.search_query.is_empty() {
+                &app.stopped_configs
+            } else {
+                &app.filtered_stopped_configs
+            },
+            app.selected_row_stopped,
+        ),
+        ActiveTable::Running => (
+            if app.search_query.is_empty() {
+                &app.running_configs
+            } else {
+                &app.filtered_running_configs
+            },
+            app.selected_row_running,
+        ),
+    };
+
+    let Some(config) = configs.get(selected_row).cloned() else {
+        return;
+    };
+    let Some(config_id) = config.id else {
+        return;
+    };
+
+    match kftray_commons::utils::config_history::list_config_history_with_mode(
+        Some(config_id),
+        mode,
+    )
+    .await
+    {
+        Ok(revisions) if revisions.is_empty() => {
+            app.import_export_message = Some(format!(
+                "No recorded changes for '{}' yet.",
+                config.alias.unwrap_or_default()
+            ));
+            app.state = AppState::ShowConfirmationPopup;
+        }
+        Ok(revisions) => {
+            let title = format!("History of '{}'", config.alias.unwrap_or_default());
+            show_config_history(app, title, revisions);
+        }
+        Err(e) => {
+            app.error_message = Some(format!("Failed to load config history: {e}"));
+            app.state = AppState::ShowErrorPopup;
+        }
+    }
+}
+
+async fn open_deleted_configs(app: &mut App, mode: DatabaseMode) {
+    match kftray_commons::utils::config_history::list_deleted_configs_with_mode(mode).await {
+        Ok(revisions) if revisions.is_empty() => {
+            app.import_export_message = Some("No deleted configs to restore.".into());
+            app.state = AppState::ShowConfirmationPopup;
+        }
+        Ok(revisions) => show_config_history(app, "Deleted Configs".into(), revisions),
+        Err(e) => {
+            app.error_message = Some(format!("Failed to load deleted configs: {e}"));
+            app.state = AppState::ShowErrorPopup;
+        }
+    }
+}
+
+pub async fn handle_config_history_input(
+    app: &mut App, key: KeyCode, mode: DatabaseMode,
+) -> io::Result<()> {
+    match key {
+        KeyCode::Up => {
+            if app.selected_history_index > 0 {
+                app.selected_history_index -= 1;
+                app.history_list_state
+                    .select(Some(app.selected_history_index));
+            }
+            return Ok(());
+        }
+        KeyCode::Down => {
+            if app.selected_history_index + 1 < app.history_revisions.len() {
+                app.selected_history_index += 1;
+                app.history_list_state
+                    .select(Some(app.selected_history_index));
+            }
+            return Ok(());
+        }
+        KeyCode::Esc => {
+            app.history_revisions.clear();
+            app.state = AppState::Normal;
+            return Ok(());
+        }
+        KeyCode::Enter | KeyCode::Char('r') => {}
+        _ => return Ok(()),
+    }
+
+    let Some(revision) = app
+        .history_revisions
+        .get(app.selected_history_index)
+        .cloned()
+    else {
+        return Ok(());
+    };
+
+    match kftray_commons::utils::config_history::restore_config_revision_with_mode(
+        revision.id,
+        mode,
+    )
+    .await
+    {
+        Ok(config) => {
+            app.import_export_message = Some(format!(
+                "Restored '{}' to revision {}.",
+                config.alias.unwrap_or_default(),
+                revision.id
+            ));
+            app.state = AppState::ShowConfirmationPopup;
+        }
+        Err(e) => {
+            app.error_message = Some(format!("Failed to restore revision {}: {e}", revision.id));
+            app.state = AppState::ShowErrorPopup;
+        }
+    }
+    app.history_revisions.clear();
+
+    Ok(())
+}
+
+async fn handle_plan(app: &mut App, mode: DatabaseMode) {
+    let (selected_rows, configs, selected_row) = match app.active_table {
+        ActiveTable::Stopped => (
+            &app.selected_rows_stopped,
+            if app.search_query.is_empty() {
+                &app.stopped_configs
+            } else {
+                &app.filtered_stopped_configs
+            },
+            app.selected_row_stopped,
+        ),
+        ActiveTable::Running => (
+            &app.selected_rows_running,
+            if app.search_query.is_empty() {
+                &app.running_configs
+            } else {
+                &app.filtered_running_configs
+            },
+            app.selected_row_running,
+        ),
+    };
+
+    let selected_configs: Vec<Config> = if selected_rows.is_empty() {
+        configs.get(selected_row).cloned().into_iter().collect()
+    } else {
+        selected_rows
+            .iter()
+            .filter_map(|&row| configs.get(row).cloned())
+            .collect()
+    };
+
+    if selected_configs.is_empty() {
+        return;
+    }
+
+    let plans = kftray_portforward::kube::plan_port_forward_with_mode(selected_configs, mode).await;
+    app.plan_report = kftray_portforward::kube::render_plans(&plans);
+    app.plan_scroll = 0;
+    app.state = AppState::ShowPlan;
+}
+
+pub fn handle_plan_input(app: &mut App, key: KeyCode) -> io::Result<()> {
+    match key {
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+            app.plan_report.clear();
+            app.plan_scroll = 0;
+            app.state = AppState::Normal;
+        }
+        KeyCode::Up => app.plan_scroll = app.plan_scroll.saturating_sub(1),
+        KeyCode::Down => app.plan_scroll = app.plan_scroll.saturating_add(1),
+        KeyCode::PageUp => app.plan_scroll = app.plan_scroll.saturating_sub(10),
+        KeyCode::PageDown => app.plan_scroll = app.plan_scroll.saturating_add(10),
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn handle_connection_test(app: &mut App) {
+    let configs = match (app.active_table, app.search_query.is_empty()) {
+        (ActiveTable::Stopped, true) => &app.stopped_configs,
+        (ActiveTable::Stopped, false) => &app.filtered_stopped_configs,
+        (ActiveTable::Running, true) => &app.running_configs,
+        (ActiveTable::Running, false) => &app.filtered_running_configs,
+    };
+    let selected_row = match app.active_table {
+        ActiveTable::Stopped => app.selected_row_stopped,
+        ActiveTable::Running => app.selected_row_running,
+    };
+    let Some(config) = configs.get(selected_row).cloned() else {
+        return;
+    };
+
+    let context = config.context.unwrap_or_else(|| "@current".to_string());
+    app.connection_report =
+        match kftray_portforward::kube::client::diagnose_connection(config.kubeconfig, &context)
+            .await
+        {
+            Ok(report) => report.summary(),
+            Err(e) => format!("{context} [unreachable]\n  error context: {e}"),
+        };
+    app.connection_report_scroll = 0;
+    app.state = AppState::ShowConnectionReport;
+}
+
+pub fn handle_auth_prompt_input(app: &mut App, key: KeyCode) -> io::Result<()> {
+    if matches!(key, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+        app.auth_prompt = None;
+        app.state = AppState::Normal;
+    }
+    Ok(())
+}
+
+pub fn handle_connection_report_input(app: &mut App, key: KeyCode) -> io::Result<()> {
+    match key {
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+            app.connection_report.clear();
+            app.connection_report_scroll = 0;
+            app.state = AppState::Normal;
+        }
+        KeyCode::Up => {
+            app.connection_report_scroll = app.connection_report_scroll.saturating_sub(1)
+        }
+        KeyCode::Down => {
+            app.connection_report_scroll = app.connection_report_scroll.saturating_add(1)
+        }
+        KeyCode::PageUp => {
+            app.connection_report_scroll = app.connection_report_scroll.saturating_sub(10)
+        }
+        KeyCode::PageDown => {
+            app.connection_report_scroll = app.connection_report_scroll.saturating_add(10)
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+pub async fn handle_git_sync_preview_input(
+    app: &mut App, key: KeyCode, mode: DatabaseMode,
+) -> io::Result<()> {
+    let approved = match key {
+        KeyCode::Char('y') | KeyCode::Enter => true,
+        KeyCode::Char('n') | KeyCode::Esc => false,
+        _ => return Ok(()),
+    };
+
+    if app.git_sync_previews.is_empty() {
+        app.state = AppState::Normal;
+        return Ok(());
+    }
+
+    let preview = app.git_sync_previews.remove(0);
+    if approved {
+        match crate::core::git_sync::apply_preview(&preview, mode).await {
+            Ok(result) => {
+                app.import_export_message = Some(format!(
+                    "Git sync applied: {} added, {} changed, {} removed.",
+                    result.added,
+                    result.changed_config_ids.len(),
+                    result.removed_config_ids.len()
+                ));
+            }
+            Err(e) => {
+                app.error_message = Some(format!("Failed to apply git sync: {e}"));
+                app.state = AppState::ShowErrorPopup;
+                return Ok(());
+            }
+        }
+    }
+
+    app.state = if !app.git_sync_previews.is_empty() {
+        AppState::ShowGitSyncPreview
+    } else if approved {
+        AppState::ShowConfirmationPopup
+    } else {
+        AppState::Normal
+    };
+
+    Ok(())
+}
+
 pub fn handle_search_input(app: &mut App, key: KeyCode) -> io::Result<()> {
     match key {
         KeyCode::Esc => {
@@ -32,13 +32,21 @@ use crate::tui::input::{
     ActiveComponent,
     App,
     AppState,
+    CLEAN_CLUSTER_CONFIRMATION,
 };
 use crate::tui::ui::MAUVE;
+use crate::tui::ui::render_auth_prompt_popup;
+use crate::tui::ui::render_config_history_popup;
+use crate::tui::ui::render_confirm_popup;
+use crate::tui::ui::render_connection_report_popup;
 use crate::tui::ui::render_context_selection_popup;
 use crate::tui::ui::render_delete_confirmation_popup;
 use crate::tui::ui::render_details;
+use crate::tui::ui::render_git_sync_preview_popup;
+use crate::tui::ui::render_group_selection_popup;
 use crate::tui::ui::render_http_logs_config_popup;
 use crate::tui::ui::render_http_logs_viewer_popup;
+use crate::tui::ui::render_plan_popup;
 use crate::tui::ui::render_restart_notification_popup;
 use crate::tui::ui::render_settings_popup;
 use crate::tui::ui::render_update_confirmation_popup;
@@ -237,6 +245,61 @@ pub fn draw_ui(f: &mut Frame, app: &mut App, config_states: &[ConfigState]) {
             render_background_overlay(f, size);
             render_update_progress_popup(f, &app.update_progress_message, progress_area);
         }
+        AppState::ShowGitSyncPreview => {
+            if let Some(preview) = app.git_sync_previews.first() {
+                let preview_area = centered_rect(60, 60, size);
+                render_background_overlay(f, size);
+                render_git_sync_preview_popup(
+                    f,
+                    preview,
+                    app.git_sync_previews.len(),
+                    preview_area,
+                );
+            }
+        }
+        AppState::ShowGroupSelection => {
+            let group_area = centered_rect(50, 50, size);
+            render_background_overlay(f, size);
+            render_group_selection_popup(f, app, group_area);
+        }
+        AppState::ShowPlan => {
+            let plan_area = centered_rect(70, 70, size);
+            render_background_overlay(f, size);
+            render_plan_popup(f, &app.plan_report, app.plan_scroll, plan_area);
+        }
+        AppState::ShowAuthPrompt => {
+            if let Some(prompt) = &app.auth_prompt {
+                let prompt_area = centered_rect(60, 40, size);
+                render_background_overlay(f, size);
+                render_auth_prompt_popup(f, prompt, prompt_area);
+            }
+        }
+        AppState::ShowConnectionReport => {
+            let report_area = centered_rect(70, 70, size);
+            render_background_overlay(f, size);
+            render_connection_report_popup(
+                f,
+                &app.connection_report,
+                app.connection_report_scroll,
+                report_area,
+            );
+        }
+        AppState::ShowConfigHistory => {
+            let history_area = centered_rect(70, 60, size);
+            render_background_overlay(f, size);
+            render_config_history_popup(f, app, history_area);
+        }
+        AppState::ShowCleanClusterConfirmation => {
+            let clean_area = centered_rect(60, 40, size);
+            render_background_overlay(f, size);
+            render_confirm_popup(
+                f,
+                "Clean Cluster",
+                CLEAN_CLUSTER_CONFIRMATION,
+                clean_area,
+                app.selected_delete_button,
+            );
+        }
         AppState::ShowRestartNotification => {
             let restart_area = centered_rect(50, 20, size);
             render_background_overlay(f, size);
@@ -1,5 +1,8 @@
 use std::borrow::Cow;
 
+use kftray_commons::models::config_model::Config;
+use kftray_commons::models::git_sync_model::SyncPreview;
+use kftray_portforward::kube::client::AuthPrompt;
 use ratatui::prelude::*;
 use ratatui::style::Modifier;
 use ratatui::text::{
@@ -396,6 +399,38 @@ pub fn render_help_popup(f: &mut Frame, area: Rect) {
             "o: Open HTTP Log File",
             Style::default().fg(YELLOW),
         )),
+        Line::from(Span::styled(
+            "K: Clean Orphaned Cluster Resources",
+            Style::default().fg(YELLOW),
+        )),
+        Line::from(Span::styled(
+            "G: Check Git Sync Subscriptions",
+            Style::default().fg(YELLOW),
+        )),
+        Line::from(Span::styled(
+            "g: Start/Stop Config Groups",
+            Style::default().fg(YELLOW),
+        )),
+        Line::from(Span::styled(
+            "p: Plan Start (Dry Run)",
+            Style::default().fg(YELLOW),
+        )),
+        Line::from(Span::styled(
+            "t: Test Connection",
+            Style::default().fg(YELLOW),
+        )),
+        Line::from(Span::styled(
+            "u: Config History / Undo",
+            Style::default().fg(YELLOW),
+        )),
+        Line::from(Span::styled(
+            "U: Restore Deleted Configs",
+            Style::default().fg(YELLOW),
+        )),
+        Line::from(Span::styled(
+            "I: Import Running kubectl Port-Forwards",
+            Style::default().fg(YELLOW),
+        )),
         Line::from(Span::styled(
             "PageUp/PageDown: Scroll Page Up/Down",
             Style::default().fg(YELLOW),
@@ -729,16 +764,20 @@ fn create_close_button() -> Paragraph<'static> {
 pub fn render_delete_confirmation_popup(
     f: &mut Frame, message: &Option<String>, area: Rect, selected_button: DeleteButton,
 ) {
-    let message_text = message.as_deref().unwrap_or("");
-    let message_paragraph = Text::raw(message_text);
-    render_popup(
+    render_confirm_popup(
         f,
-        area,
         "Delete Confirmation",
-        RED,
-        message_paragraph,
-        Alignment::Center,
+        message.as_deref().unwrap_or(""),
+        area,
+        selected_button,
     );
+}
+
+pub fn render_confirm_popup(
+    f: &mut Frame, title: &str, message: &str, area: Rect, selected_button: DeleteButton,
+) {
+    let message_paragraph = Text::raw(message);
+    render_popup(f, area, title, RED, message_paragraph, Alignment::Center);
 
     let confirm_button = create_button("<Confirm>", selected_button == DeleteButton::Confirm);
     let close_button = create_button("<Close>", selected_button == DeleteButton::Close);
@@ -1797,6 +1836,297 @@ pub fn render_update_progress_popup(f: &mut Frame, message: &Option<String>, are
     );
 }
 
+fn describe_sync_config(config: &Config) -> String {
+    config
+        .alias
+        .clone()
+        .filter(|a| !a.is_empty())
+        .unwrap_or_else(|| {
+            format!(
+                "{}/{}/{}",
+                config.context.as_deref().unwrap_or_default(),
+                config.namespace,
+                config
+                    .service
+                    .as_deref()
+                    .or(config.target.as_deref())
+                    .or(config.remote_address.as_deref())
+                    .unwrap_or_default()
+            )
+        })
+}
+
+pub fn render_git_sync_preview_popup(
+    f: &mut Frame, preview: &SyncPreview, pending: usize, area: Rect,
+) {
+    let diff = &preview.diff;
+    let sha = preview.commit_sha.get(..12).unwrap_or(&preview.commit_sha);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Commit ", Style::default().fg(SUBTEXT0)),
+            Span::styled(sha.to_string(), Style::default().fg(BLUE)),
+            Span::styled(
+                format!(" · {} unchanged", diff.unchanged),
+                Style::default().fg(SUBTEXT0),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    for config in &diff.added {
+        lines.push(Line::from(Span::styled(
+            format!("+ {}", describe_sync_config(config)),
+            Style::default().fg(GREEN),
+        )));
+    }
+    for change in &diff.changed {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "~ {} ({})",
+                describe_sync_config(&change.after),
+                change.fields.join(", ")
+            ),
+            Style::default().fg(YELLOW),
+        )));
+    }
+    for config in &diff.removed {
+        lines.push(Line::from(Span::styled(
+            format!("- {}", describe_sync_config(config)),
+            Style::default().fg(RED),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    if !diff.changed.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Running forwards of changed configs will be restarted.",
+            Style::default().fg(SUBTEXT0),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "y/Enter: Apply   n/Esc: Skip",
+        Style::default().fg(LAVENDER),
+    )));
+
+    let title = if pending > 1 {
+        format!("Git Sync Changes (1 of {pending})")
+    } else {
+        "Git Sync Changes".to_string()
+    };
+
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(create_common_popup_style(&title, YELLOW))
+        .style(Style::default().fg(TEXT).bg(BASE))
+        .wrap(ratatui::widgets::Wrap { trim: false });
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub fn render_auth_prompt_popup(f: &mut Frame, prompt: &AuthPrompt, area: Rect) {
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Plugin: ", Style::default().fg(LAVENDER)),
+            Span::styled(prompt.command.clone(), Style::default().fg(TEXT)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {}", prompt.message),
+            Style::default().fg(YELLOW),
+        )),
+    ];
+
+    if let Some(url) = &prompt.url {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Open: ", Style::default().fg(LAVENDER)),
+            Span::styled(url.clone(), Style::default().fg(BLUE)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  Esc/Enter: Close",
+        Style::default().fg(LAVENDER),
+    )));
+
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(create_common_popup_style("Authentication Needed", YELLOW))
+        .style(Style::default().fg(TEXT).bg(BASE))
+        .wrap(ratatui::widgets::Wrap { trim: false });
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub fn render_plan_popup(f: &mut Frame, report: &str, scroll: u16, area: Rect) {
+    render_report_popup(f, "Start Plan", report, scroll, area);
+}
+
+pub fn render_connection_report_popup(f: &mut Frame, report: &str, scroll: u16, area: Rect) {
+    render_report_popup(f, "Connection Test", report, scroll, area);
+}
+
+fn render_report_popup(f: &mut Frame, title: &str, report: &str, scroll: u16, area: Rect) {
+    let mut lines: Vec<Line> = report
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let color = if trimmed.starts_with("error")
+                || line.ends_with("[would fail]")
+                || line.ends_with("[unreachable]")
+            {
+                RED
+            } else if trimmed.starts_with("warn") {
+                YELLOW
+            } else if line.ends_with("[ready]") || line.ends_with("[reachable]") {
+                GREEN
+            } else {
+                TEXT
+            };
+            Line::from(Span::styled(line.to_string(), Style::default().fg(color)))
+        })
+        .collect();
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "↑/↓: Scroll   Esc/Enter: Close",
+        Style::default().fg(LAVENDER),
+    )));
+
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(create_common_popup_style(title, BLUE))
+        .style(Style::default().fg(TEXT).bg(BASE))
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .scroll((scroll, 0));
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub fn render_group_selection_popup(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(3)])
+        .split(area);
+
+    let running: std::collections::HashSet<i64> = app
+        .running_configs
+        .iter()
+        .filter_map(|config| config.id)
+        .collect();
+
+    let groups: Vec<ListItem> = app
+        .groups
+        .iter()
+        .map(|group| {
+            let active = group
+                .config_ids
+                .iter()
+                .filter(|id| running.contains(id))
+                .count();
+            ListItem::new(Line::from(vec![
+                Span::raw(group.name.clone()),
+                Span::styled(
+                    format!("  {active}/{} running", group.config_ids.len()),
+                    Style::default().fg(SUBTEXT0),
+                ),
+            ]))
+        })
+        .collect();
+
+    let group_list = List::new(groups)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled("Config Groups", Style::default().fg(MAUVE)))
+                .style(Style::default().bg(BASE).fg(TEXT)),
+        )
+        .highlight_style(Style::default().fg(YELLOW).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let legend = Paragraph::new(Line::from(Span::styled(
+        "Enter/f: Start Group   x: Stop Group   Esc: Close",
+        Style::default().fg(LAVENDER),
+    )))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().bg(BASE).fg(TEXT)),
+    )
+    .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(group_list, chunks[0], &mut app.group_list_state);
+    f.render_widget(legend, chunks[1]);
+}
+
+pub fn render_config_history_popup(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(3)])
+        .split(area);
+
+    let revisions: Vec<ListItem> = app
+        .history_revisions
+        .iter()
+        .map(|revision| {
+            let changed_at = chrono::DateTime::from_timestamp(revision.changed_at, 0)
+                .map(|time| {
+                    time.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("#{:<5}", revision.id),
+                    Style::default().fg(SUBTEXT0),
+                ),
+                Span::styled(
+                    format!("{:<8}", revision.action.as_str()),
+                    Style::default().fg(TEAL),
+                ),
+                Span::raw(revision.label.clone()),
+                Span::styled(
+                    format!("  {} via {}", changed_at, revision.source.as_str()),
+                    Style::default().fg(SUBTEXT0),
+                ),
+            ]))
+        })
+        .collect();
+
+    let history_list = List::new(revisions)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(
+                    app.history_title.clone(),
+                    Style::default().fg(MAUVE),
+                ))
+                .style(Style::default().bg(BASE).fg(TEXT)),
+        )
+        .highlight_style(Style::default().fg(YELLOW).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+
+    let legend = Paragraph::new(Line::from(Span::styled(
+        "Enter/r: Restore This Version   Esc: Close",
+        Style::default().fg(LAVENDER),
+    )))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().bg(BASE).fg(TEXT)),
+    )
+    .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(history_list, chunks[0], &mut app.history_list_state);
+    f.render_widget(legend, chunks[1]);
+}
+
 pub fn render_restart_notification_popup(f: &mut Frame, area: Rect) {
     let message_text = "Update completed successfully!\n\nPlease restart the application to apply the new version.";
     let message_paragraph = Text::raw(message_text);
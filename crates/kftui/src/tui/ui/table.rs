@@ -61,11 +61,13 @@ pub fn draw_configs_table(
         .iter()
         .enumerate()
         .map(|(i, config)| {
-            let state = config_states
+            let config_state = config_states
                 .iter()
-                .find(|s| s.config_id == config.id.unwrap_or_default())
-                .map(|s| s.is_running)
-                .unwrap_or(false);
+                .find(|s| s.config_id == config.id.unwrap_or_default());
+            let state = config_state.is_some_and(|s| s.is_running);
+            let local_port = config_state
+                .and_then(|s| s.local_port)
+                .or(config.local_port);
 
             let is_processing = config.id.is_some_and(|id| {
                 configs_being_processed
@@ -107,11 +109,7 @@ pub fn draw_configs_table(
             Row::new(vec![
                 Cell::from(alias_text),
                 Cell::from(config.workload_type.clone().unwrap_or_default()),
-                Cell::from(
-                    config
-                        .local_port
-                        .map_or_else(|| "".to_string(), |port| port.to_string()),
-                ),
+                Cell::from(local_port.map_or_else(|| "".to_string(), |port| port.to_string())),
                 Cell::from(config.context.clone().unwrap_or_default()),
             ])
             .style(row_style)
@@ -190,11 +188,11 @@ pub fn render_details(
     f: &mut Frame, app: &mut App, config: &Config, config_states: &[ConfigState], area: Rect,
     has_focus: bool,
 ) {
-    let state = config_states
+    let config_state = config_states
         .iter()
-        .find(|s| s.config_id == config.id.unwrap_or_default())
-        .map(|s| s.is_running)
-        .unwrap_or(false);
+        .find(|s| s.config_id == config.id.unwrap_or_default());
+    let state = config_state.is_some_and(|s| s.is_running);
+    let running_port = config_state.and_then(|s| s.local_port);
 
     let http_logs_enabled = if let Some(config_id) = config.id {
         *app.http_logs_enabled.get(&config_id).unwrap_or(&false)
@@ -287,11 +285,14 @@ pub fn render_details(
                 "Local Port: ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw(
-                config
-                    .local_port
+            Span::raw(match (running_port, config.local_port) {
+                (Some(running), Some(configured)) => {
+                    format!("{running} (configured {configured} was in use)")
+                }
+                (running, configured) => running
+                    .or(configured)
                     .map_or_else(|| "".to_string(), |port| port.to_string()),
-            ),
+            }),
         ]),
         Line::from(vec![
             Span::styled(
@@ -24,6 +24,7 @@ import { Tooltip } from '@/components/ui/tooltip'
 import {
   Config,
   CustomConfigProps,
+  PodSelectionPreferences,
   PortOption,
   ServiceData,
   StringOption,
@@ -59,6 +60,12 @@ const AddConfigModal: React.FC<CustomConfigProps> = ({
     { value: 'udp', label: 'UDP' },
   ]
 
+  const portConflictOptions: StringOption[] = [
+    { value: 'fail', label: 'Fail' },
+    { value: 'next', label: 'Next free port' },
+    { value: 'random', label: 'Random free port' },
+  ]
+
   const [formState, setFormState] = useState({
     selectedContext: null as StringOption | null,
     selectedNamespace: null as StringOption | null,
@@ -147,6 +154,47 @@ const AddConfigModal: React.FC<CustomConfigProps> = ({
     }
   }
 
+  const updatePodSelection = (preferences: PodSelectionPreferences) => {
+    setNewConfig(prev => ({
+      ...prev,
+      pod_selection: { ...prev.pod_selection, ...preferences },
+    }))
+  }
+
+  const podSelectionInput = (
+    name: keyof PodSelectionPreferences,
+    value: string,
+  ) => {
+    if (name === 'restart_grace_secs') {
+      updatePodSelection({ [name]: value ? Number(value) : undefined })
+    } else {
+      updatePodSelection({ [name]: value.trim() ? value : undefined })
+    }
+  }
+
+  const drainPolicyOptions: StringOption[] = [
+    { value: 'keep', label: 'Keep on previous pod' },
+    { value: 'drain', label: 'Drain, then close' },
+    { value: 'close', label: 'Close immediately' },
+  ]
+
+  const debugContainerOptions: StringOption[] = [
+    { value: '', label: 'Off' },
+    { value: 'auto', label: 'When the port is not declared' },
+    { value: 'always', label: 'Always' },
+  ]
+
+  const proxyRelayOptions: StringOption[] = [
+    { value: 'dedicated', label: 'Dedicated pod' },
+    { value: 'shared', label: 'Shared relay pod' },
+  ]
+
+  const preferAgeOptions: StringOption[] = [
+    { value: '', label: 'No preference' },
+    { value: 'newest', label: 'Newest' },
+    { value: 'oldest', label: 'Oldest' },
+  ]
+
   const handleSetKubeConfig = async () => {
     try {
       await invoke('open_save_dialog')
@@ -656,6 +704,92 @@ const AddConfigModal: React.FC<CustomConfigProps> = ({
                   </Stack>
                 </Grid>
 
+                <Stack gap={1.5}>
+                  <Flex align='center' gap={1}>
+                    <Text fontSize='xs' color='gray.400'>
+                      Groups (Optional)
+                    </Text>
+                    <Tooltip
+                      content='Comma-separated group names. Configs sharing a group can be started and stopped together.'
+                      portalled
+                    >
+                      <span
+                        style={{
+                          display: 'inline-flex',
+                          alignItems: 'center',
+                        }}
+                      >
+                        <Info size={10} color='#6B7280' />
+                      </span>
+                    </Tooltip>
+                  </Flex>
+                  <Input
+                    value={(newConfig.groups ?? []).join(',')}
+                    name='groups'
+                    onChange={handleInputChange}
+                    placeholder='payments, backend'
+                    bg='#161616'
+                    border='1px solid rgba(255, 255, 255, 0.08)'
+                    _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                    _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                    height='28px'
+                    fontSize='13px'
+                  />
+                </Stack>
+
+                <Grid templateColumns='repeat(2, 1fr)' gap={3}>
+                  <Stack gap={1.5}>
+                    <Flex align='center' gap={1}>
+                      <Text fontSize='xs' color='gray.400'>
+                        Impersonate User (Optional)
+                      </Text>
+                      <Tooltip
+                        content='Send every API request for this config as this user, like kubectl --as.'
+                        portalled
+                      >
+                        <span
+                          style={{
+                            display: 'inline-flex',
+                            alignItems: 'center',
+                          }}
+                        >
+                          <Info size={10} color='#6B7280' />
+                        </span>
+                      </Tooltip>
+                    </Flex>
+                    <Input
+                      value={newConfig.impersonate_user || ''}
+                      name='impersonate_user'
+                      onChange={handleInputChange}
+                      placeholder='deployer'
+                      bg='#161616'
+                      border='1px solid rgba(255, 255, 255, 0.08)'
+                      _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                      _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                      height='28px'
+                      fontSize='13px'
+                    />
+                  </Stack>
+
+                  <Stack gap={1.5}>
+                    <Text fontSize='xs' color='gray.400'>
+                      Impersonate Groups (Optional)
+                    </Text>
+                    <Input
+                      value={(newConfig.impersonate_groups ?? []).join(',')}
+                      name='impersonate_groups'
+                      onChange={handleInputChange}
+                      placeholder='developers'
+                      bg='#161616'
+                      border='1px solid rgba(255, 255, 255, 0.08)'
+                      _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                      _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                      height='28px'
+                      fontSize='13px'
+                    />
+                  </Stack>
+                </Grid>
+
                 <Grid templateColumns='repeat(2, 1fr)' gap={3}>
                   <Stack gap={1.5}>
                     <Text fontSize='xs' color='gray.400'>
@@ -693,6 +827,238 @@ const AddConfigModal: React.FC<CustomConfigProps> = ({
                   </Stack>
                 </Grid>
 
+                {(newConfig.workload_type === 'service' ||
+                  newConfig.workload_type === 'pod') && (
+                  <Stack gap={3}>
+                    <Flex align='center' gap={1}>
+                      <Text fontSize='xs' color='gray.400'>
+                        Pod Selection (Optional)
+                      </Text>
+                      <Tooltip
+                        content='Choose which ready pod receives the forward. Plan Selected explains the choice.'
+                        portalled
+                      >
+                        <span
+                          style={{
+                            display: 'inline-flex',
+                            alignItems: 'center',
+                          }}
+                        >
+                          <Info size={10} color='#6B7280' />
+                        </span>
+                      </Tooltip>
+                    </Flex>
+
+                    <Grid templateColumns='repeat(2, 1fr)' gap={3}>
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Preferred Node
+                        </Text>
+                        <Input
+                          value={newConfig.pod_selection?.preferred_node ?? ''}
+                          onChange={e =>
+                            podSelectionInput('preferred_node', e.target.value)
+                          }
+                          placeholder='ip-10-0-1-12'
+                          bg='#161616'
+                          border='1px solid rgba(255, 255, 255, 0.08)'
+                          _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                          _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                          height='28px'
+                          fontSize='13px'
+                        />
+                      </Stack>
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Preferred Zone
+                        </Text>
+                        <Input
+                          value={newConfig.pod_selection?.preferred_zone ?? ''}
+                          onChange={e =>
+                            podSelectionInput('preferred_zone', e.target.value)
+                          }
+                          placeholder='eu-west-1a'
+                          bg='#161616'
+                          border='1px solid rgba(255, 255, 255, 0.08)'
+                          _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                          _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                          height='28px'
+                          fontSize='13px'
+                        />
+                      </Stack>
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Preferred Annotation
+                        </Text>
+                        <Input
+                          value={newConfig.pod_selection?.preferred_annotation ?? ''}
+                          onChange={e =>
+                            podSelectionInput('preferred_annotation', e.target.value)
+                          }
+                          placeholder='tier=canary'
+                          bg='#161616'
+                          border='1px solid rgba(255, 255, 255, 0.08)'
+                          _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                          _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                          height='28px'
+                          fontSize='13px'
+                        />
+                      </Stack>
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Required Ready Container
+                        </Text>
+                        <Input
+                          value={newConfig.pod_selection?.required_container ?? ''}
+                          onChange={e =>
+                            podSelectionInput('required_container', e.target.value)
+                          }
+                          placeholder='app'
+                          bg='#161616'
+                          border='1px solid rgba(255, 255, 255, 0.08)'
+                          _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                          _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                          height='28px'
+                          fontSize='13px'
+                        />
+                      </Stack>
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Avoid Restarts Within (secs)
+                        </Text>
+                        <Input
+                          type='number'
+                          value={newConfig.pod_selection?.restart_grace_secs ?? ''}
+                          onChange={e =>
+                            podSelectionInput('restart_grace_secs', e.target.value)
+                          }
+                          placeholder='300'
+                          bg='#161616'
+                          border='1px solid rgba(255, 255, 255, 0.08)'
+                          _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                          _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                          height='28px'
+                          fontSize='13px'
+                        />
+                      </Stack>
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Prefer Pod Age
+                        </Text>
+                        <Select
+                          value={
+                            preferAgeOptions.find(
+                              option =>
+                                option.value ===
+                                (newConfig.pod_selection?.prefer_age ?? ''),
+                            ) ?? preferAgeOptions[0]
+                          }
+                          onChange={option =>
+                            podSelectionInput(
+                              'prefer_age',
+                              (option as StringOption | null)?.value ?? '',
+                            )
+                          }
+                          options={preferAgeOptions}
+                          styles={selectStyles}
+                        />
+                      </Stack>
+                    </Grid>
+
+                    <Grid templateColumns='repeat(2, 1fr)' gap={3}>
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Open Connections on Pod Change
+                        </Text>
+                        <Select
+                          value={
+                            drainPolicyOptions.find(
+                              option =>
+                                option.value ===
+                                (newConfig.drain_policy ?? 'keep'),
+                            ) ?? drainPolicyOptions[0]
+                          }
+                          onChange={option =>
+                            setNewConfig(prev => ({
+                              ...prev,
+                              drain_policy: (option as StringOption | null)
+                                ?.value as Config['drain_policy'],
+                            }))
+                          }
+                          options={drainPolicyOptions}
+                          styles={selectStyles}
+                        />
+                      </Stack>
+
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Drain Timeout (secs)
+                        </Text>
+                        <Input
+                          type='number'
+                          value={newConfig.drain_timeout_secs ?? ''}
+                          onChange={e =>
+                            setNewConfig(prev => ({
+                              ...prev,
+                              drain_timeout_secs: e.target.value
+                                ? Number(e.target.value)
+                                : undefined,
+                            }))
+                          }
+                          placeholder='30'
+                          disabled={newConfig.drain_policy !== 'drain'}
+                          bg='#161616'
+                          border='1px solid rgba(255, 255, 255, 0.08)'
+                          _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                          _focus={{ borderColor: 'blue.400', boxShadow: 'none' }}
+                          height='28px'
+                          fontSize='13px'
+                        />
+                      </Stack>
+                    </Grid>
+
+                    <Stack gap={1.5}>
+                      <Text fontSize='xs' color='gray.400'>
+                        Debug Container Relay
+                      </Text>
+                      <Select
+                        value={
+                          debugContainerOptions.find(
+                            option =>
+                              option.value === (newConfig.debug_container ?? ''),
+                          ) ?? debugContainerOptions[0]
+                        }
+                        onChange={option =>
+                          setNewConfig(prev => ({
+                            ...prev,
+                            debug_container:
+                              ((option as StringOption | null)?.value ||
+                                undefined) as Config['debug_container'],
+                          }))
+                        }
+                        options={debugContainerOptions}
+                        styles={selectStyles}
+                      />
+                    </Stack>
+
+                    <Checkbox
+                      checked={
+                        newConfig.pod_selection?.exclude_terminating ?? false
+                      }
+                      onCheckedChange={e =>
+                        updatePodSelection({
+                          exclude_terminating: e.checked === true,
+                        })
+                      }
+                      size='xs'
+                    >
+                      <Text fontSize='xs' color='gray.400'>
+                        Skip terminating pods
+                      </Text>
+                    </Checkbox>
+                  </Stack>
+                )}
+
                 {/* Expose-specific options */}
                 {newConfig.workload_type === 'expose' && (
                   <Stack gap={3}>
@@ -1131,7 +1497,55 @@ const AddConfigModal: React.FC<CustomConfigProps> = ({
                           </Text>
                         </Checkbox>
                       </Stack>
+
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          If Local Port Is Taken
+                        </Text>
+                        <Select
+                          name='port_conflict_policy'
+                          value={
+                            portConflictOptions.find(
+                              option =>
+                                option.value ===
+                                (newConfig.port_conflict_policy || 'fail'),
+                            ) ?? portConflictOptions[0]
+                          }
+                          onChange={handleSelectChange}
+                          options={portConflictOptions}
+                          styles={selectStyles}
+                        />
+                      </Stack>
                     </Grid>
+
+                    {newConfig.protocol !== 'udp' && (
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          Proxy Relay
+                        </Text>
+                        <Select
+                          value={
+                            proxyRelayOptions.find(
+                              option =>
+                                option.value ===
+                                (newConfig.proxy_relay ?? 'dedicated'),
+                            ) ?? proxyRelayOptions[0]
+                          }
+                          onChange={option =>
+                            setNewConfig(prev => ({
+                              ...prev,
+                              proxy_relay:
+                                (option as StringOption | null)?.value ===
+                                'shared'
+                                  ? 'shared'
+                                  : undefined,
+                            }))
+                          }
+                          options={proxyRelayOptions}
+                          styles={selectStyles}
+                        />
+                      </Stack>
+                    )}
                   </>
                 ) : newConfig.workload_type !== 'expose' ? (
                   <>
@@ -1323,6 +1737,25 @@ const AddConfigModal: React.FC<CustomConfigProps> = ({
                           </Text>
                         </Checkbox>
                       </Stack>
+
+                      <Stack gap={1.5}>
+                        <Text fontSize='xs' color='gray.400'>
+                          If Local Port Is Taken
+                        </Text>
+                        <Select
+                          name='port_conflict_policy'
+                          value={
+                            portConflictOptions.find(
+                              option =>
+                                option.value ===
+                                (newConfig.port_conflict_policy || 'fail'),
+                            ) ?? portConflictOptions[0]
+                          }
+                          onChange={handleSelectChange}
+                          options={portConflictOptions}
+                          styles={selectStyles}
+                        />
+                      </Stack>
                     </Grid>
                   </>
                 ) : null}
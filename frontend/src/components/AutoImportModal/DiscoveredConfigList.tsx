@@ -0,0 +1,106 @@
+import React from 'react'
+
+import { Box, Flex, Input, Stack, Text } from '@chakra-ui/react'
+
+import { Checkbox } from '@/components/ui/checkbox'
+import { Config } from '@/types'
+
+export interface DiscoveredCandidate {
+  config: Config
+  selected: boolean
+}
+
+interface DiscoveredConfigListProps {
+  candidates: DiscoveredCandidate[]
+  skipped: string[]
+  onChange: (candidates: DiscoveredCandidate[]) => void
+}
+
+const describeTarget = (config: Config) =>
+  config.workload_type === 'pod'
+    ? `${config.namespace} · ${config.target}:${config.remote_port}`
+    : `${config.namespace} · ${config.service}:${config.remote_port}`
+
+const DiscoveredConfigList: React.FC<DiscoveredConfigListProps> = ({
+  candidates,
+  skipped,
+  onChange,
+}) => {
+  const update = (index: number, patch: Partial<DiscoveredCandidate>) => {
+    onChange(
+      candidates.map((candidate, i) =>
+        i === index ? { ...candidate, ...patch } : candidate,
+      ),
+    )
+  }
+
+  const updateLocalPort = (index: number, value: string) => {
+    const port = Number.parseInt(value, 10)
+
+    update(index, {
+      config: {
+        ...candidates[index].config,
+        local_port: Number.isNaN(port) ? 0 : Math.min(port, 65535),
+      },
+    })
+  }
+
+  return (
+    <Stack gap={1.5}>
+      <Text fontSize='xs' color='gray.400'>
+        Found {candidates.length} forward(s)
+      </Text>
+      <Box
+        maxHeight='180px'
+        overflowY='auto'
+        bg='#161616'
+        border='1px solid rgba(255, 255, 255, 0.08)'
+        borderRadius='md'
+        p={1.5}
+      >
+        {candidates.map((candidate, index) => (
+          <Flex
+            key={`${candidate.config.alias}-${candidate.config.remote_port}-${index}`}
+            align='center'
+            justify='space-between'
+            gap={2}
+            py={0.5}
+          >
+            <Checkbox
+              size='xs'
+              checked={candidate.selected}
+              onCheckedChange={e => update(index, { selected: !!e.checked })}
+            >
+              <Stack gap={0}>
+                <Text fontSize='xs' color='gray.200'>
+                  {candidate.config.alias}
+                </Text>
+                <Text fontSize='2xs' color='gray.500'>
+                  {describeTarget(candidate.config)}
+                </Text>
+              </Stack>
+            </Checkbox>
+            <Input
+              size='xs'
+              width='70px'
+              height='22px'
+              fontSize='xs'
+              bg='#111111'
+              borderColor='rgba(255, 255, 255, 0.08)'
+              value={candidate.config.local_port || ''}
+              onChange={e => updateLocalPort(index, e.target.value)}
+              title='Local port'
+            />
+          </Flex>
+        ))}
+      </Box>
+      {skipped.map(reason => (
+        <Text key={reason} fontSize='2xs' color='orange.300'>
+          Skipped {reason}
+        </Text>
+      ))}
+    </Stack>
+  )
+}
+
+export default DiscoveredConfigList
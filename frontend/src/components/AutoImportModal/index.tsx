@@ -6,6 +6,7 @@ import {
   Dialog,
   Flex,
   HStack,
+  Input,
   Spinner,
   Stack,
   Text,
@@ -25,18 +26,44 @@ import {
   StringOption,
 } from '@/types'
 
+import DiscoveredConfigList, {
+  DiscoveredCandidate,
+} from './DiscoveredConfigList'
 import { autoImportSelectStyles } from './styles'
 
+type ImportSource = 'annotations' | 'services' | 'kubectl'
+
+const importSources: { value: ImportSource; label: string }[] = [
+  { value: 'annotations', label: 'Annotations' },
+  { value: 'services', label: 'Namespace' },
+  { value: 'kubectl', label: 'kubectl' },
+]
+
+const parseNamespaces = (value: string) => {
+  const namespaces = value
+    .split(',')
+    .map(namespace => namespace.trim())
+    .filter(Boolean)
+
+  return namespaces.length > 0 ? namespaces : null
+}
+
 const AutoImportModal: React.FC<AutoImportModalProps> = ({
   isOpen,
   onClose,
 }) => {
   const [state, setState] = useState({
+    source: 'annotations' as ImportSource,
     selectedContext: null as SingleValue<StringOption>,
+    selectedNamespace: null as SingleValue<StringOption>,
+    namespaces: '',
     kubeConfig: 'default',
     aliasAsDomain: false,
     enableAutoLoopback: false,
     isImporting: false,
+    isScanning: false,
+    candidates: [] as DiscoveredCandidate[],
+    skipped: [] as string[],
   })
 
   const contextQuery = useQuery<KubeContext[]>({
@@ -45,6 +72,20 @@ const AutoImportModal: React.FC<AutoImportModalProps> = ({
     enabled: isOpen,
   })
 
+  const namespaceQuery = useQuery<{ name: string }[]>({
+    queryKey: [
+      'kube-namespaces',
+      state.selectedContext?.value,
+      state.kubeConfig,
+    ],
+    queryFn: () =>
+      invoke<{ name: string }[]>('list_namespaces', {
+        contextName: state.selectedContext?.value,
+        kubeconfig: state.kubeConfig,
+      }),
+    enabled: isOpen && state.source === 'services' && !!state.selectedContext,
+  })
+
   const handleCheckboxChange = (
     checkbox: string,
     e: { checked: boolean | 'indeterminate' },
@@ -86,6 +127,92 @@ const AutoImportModal: React.FC<AutoImportModalProps> = ({
     }
   }
 
+  const setSource = (source: ImportSource) => {
+    setState(prev => ({ ...prev, source, candidates: [], skipped: [] }))
+  }
+
+  const handleScan = async () => {
+    setState(prev => ({ ...prev, isScanning: true }))
+    try {
+      let configs: Config[] = []
+      let skipped: string[] = []
+
+      if (state.source === 'kubectl') {
+        const discovered = await invoke<{
+          configs: Config[]
+          skipped: string[]
+        }>('discover_kubectl_port_forwards_cmd')
+
+        configs = discovered.configs
+        skipped = discovered.skipped
+      } else {
+        configs = await invoke<Config[]>('discover_service_configs_cmd', {
+          contextName: state.selectedContext?.value,
+          namespace: state.selectedNamespace?.value,
+          kubeconfigPath: state.kubeConfig,
+        })
+      }
+
+      setState(prev => ({
+        ...prev,
+        candidates: configs.map(config => ({ config, selected: true })),
+        skipped,
+      }))
+
+      if (configs.length === 0 && skipped.length === 0) {
+        toaster.warning({
+          title: 'Nothing found',
+          description:
+            state.source === 'kubectl'
+              ? 'No running kubectl port-forward processes were found.'
+              : 'No services with a selector were found in this namespace.',
+          duration: 2000,
+        })
+      }
+    } catch (error) {
+      console.error('Failed to discover configs:', error)
+      toaster.error({
+        title: 'Error',
+        description: String(error),
+        duration: 2000,
+      })
+    } finally {
+      setState(prev => ({ ...prev, isScanning: false }))
+    }
+  }
+
+  const handleImportDiscovered = async () => {
+    const configs = state.candidates
+      .filter(candidate => candidate.selected)
+      .map(candidate => ({
+        ...candidate.config,
+        domain_enabled: state.aliasAsDomain || candidate.config.domain_enabled,
+        auto_loopback_address:
+          state.enableAutoLoopback || candidate.config.auto_loopback_address,
+      }))
+
+    setState(prev => ({ ...prev, isImporting: true }))
+    try {
+      await invoke('import_configs_cmd', { json: JSON.stringify(configs) })
+
+      toaster.success({
+        title: 'Success',
+        description: `Imported ${configs.length} config(s).`,
+        duration: 1000,
+      })
+      onClose()
+    } catch (error) {
+      console.error('Failed to import configs:', error)
+      toaster.error({
+        title: 'Error',
+        description: String(error),
+        duration: 2000,
+      })
+    } finally {
+      setState(prev => ({ ...prev, isImporting: false }))
+    }
+  }
+
   const handleImport = async () => {
     if (!state.selectedContext) {
       toaster.error({
@@ -102,6 +229,7 @@ const AutoImportModal: React.FC<AutoImportModalProps> = ({
       const configs = await invoke<Config[]>('get_services_with_annotations', {
         contextName: state.selectedContext.value,
         kubeconfigPath: state.kubeConfig,
+        namespaces: parseNamespaces(state.namespaces),
       })
 
       for (const config of configs) {
@@ -142,6 +270,8 @@ const AutoImportModal: React.FC<AutoImportModalProps> = ({
     setState(prev => ({
       ...prev,
       selectedContext: newValue,
+      selectedNamespace: null,
+      candidates: [],
     }))
   }
 
@@ -149,8 +279,13 @@ const AutoImportModal: React.FC<AutoImportModalProps> = ({
     if (!isOpen) {
       setState(prev => ({
         ...prev,
+        source: 'annotations',
         selectedContext: null,
+        selectedNamespace: null,
+        namespaces: '',
         kubeConfig: 'default',
+        candidates: [],
+        skipped: [],
       }))
     }
   }, [isOpen])
@@ -200,124 +335,246 @@ const AutoImportModal: React.FC<AutoImportModalProps> = ({
 
           <Dialog.Body p={3}>
             <Stack gap={4}>
-              <Stack gap={1.5}>
-                <Flex align='center' justify='space-between'>
-                  <Text fontSize='xs' color='gray.400'>
-                    Kubeconfig *
-                  </Text>
-                  <Flex gap={2}>
-                    <Button
-                      size='xs'
-                      variant={
-                        state.kubeConfig === 'default' ? 'solid' : 'ghost'
-                      }
-                      onClick={() =>
-                        setState(prev => ({ ...prev, kubeConfig: 'default' }))
-                      }
-                      bg={
-                        state.kubeConfig === 'default'
-                          ? 'whiteAlpha.100'
-                          : 'transparent'
-                      }
-                      _hover={{
-                        bg:
-                          state.kubeConfig === 'default'
-                            ? 'whiteAlpha.200'
-                            : 'whiteAlpha.50',
-                      }}
-                      height='22px'
-                    >
-                      <Text fontSize='xs'>Default</Text>
-                    </Button>
+              <Flex align='center' justify='space-between'>
+                <Text fontSize='xs' color='gray.400'>
+                  Source
+                </Text>
+                <Flex gap={2}>
+                  {importSources.map(source => (
                     <Button
+                      key={source.value}
                       size='xs'
                       variant={
-                        state.kubeConfig !== 'default' ? 'solid' : 'ghost'
+                        state.source === source.value ? 'solid' : 'ghost'
                       }
-                      onClick={handleSetKubeConfig}
+                      onClick={() => setSource(source.value)}
                       bg={
-                        state.kubeConfig !== 'default'
+                        state.source === source.value
                           ? 'whiteAlpha.100'
                           : 'transparent'
                       }
                       _hover={{
                         bg:
-                          state.kubeConfig !== 'default'
+                          state.source === source.value
                             ? 'whiteAlpha.200'
                             : 'whiteAlpha.50',
                       }}
                       height='22px'
                     >
-                      <Text fontSize='xs'>Set Custom Kubeconfig</Text>
+                      <Text fontSize='xs'>{source.label}</Text>
                     </Button>
-                  </Flex>
+                  ))}
                 </Flex>
+              </Flex>
 
-                {state.kubeConfig !== 'default' && (
-                  <Flex
-                    bg='#161616'
-                    border='1px solid rgba(255, 255, 255, 0.08)'
-                    borderRadius='md'
-                    height='35px'
-                    align='center'
-                    justify='space-between'
-                    px={2}
-                    _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
-                  >
-                    <Text
-                      fontSize='xs'
-                      color='gray.300'
-                      truncate
-                      maxW='250px'
-                      title={state.kubeConfig}
-                    >
-                      {state.kubeConfig}
+              {state.source !== 'kubectl' && (
+                <>
+                  <Stack gap={1.5}>
+                    <Flex align='center' justify='space-between'>
+                      <Text fontSize='xs' color='gray.400'>
+                        Kubeconfig *
+                      </Text>
+                      <Flex gap={2}>
+                        <Button
+                          size='xs'
+                          variant={
+                            state.kubeConfig === 'default' ? 'solid' : 'ghost'
+                          }
+                          onClick={() =>
+                            setState(prev => ({
+                              ...prev,
+                              kubeConfig: 'default',
+                            }))
+                          }
+                          bg={
+                            state.kubeConfig === 'default'
+                              ? 'whiteAlpha.100'
+                              : 'transparent'
+                          }
+                          _hover={{
+                            bg:
+                              state.kubeConfig === 'default'
+                                ? 'whiteAlpha.200'
+                                : 'whiteAlpha.50',
+                          }}
+                          height='22px'
+                        >
+                          <Text fontSize='xs'>Default</Text>
+                        </Button>
+                        <Button
+                          size='xs'
+                          variant={
+                            state.kubeConfig !== 'default' ? 'solid' : 'ghost'
+                          }
+                          onClick={handleSetKubeConfig}
+                          bg={
+                            state.kubeConfig !== 'default'
+                              ? 'whiteAlpha.100'
+                              : 'transparent'
+                          }
+                          _hover={{
+                            bg:
+                              state.kubeConfig !== 'default'
+                                ? 'whiteAlpha.200'
+                                : 'whiteAlpha.50',
+                          }}
+                          height='22px'
+                        >
+                          <Text fontSize='xs'>Set Custom Kubeconfig</Text>
+                        </Button>
+                      </Flex>
+                    </Flex>
+
+                    {state.kubeConfig !== 'default' && (
+                      <Flex
+                        bg='#161616'
+                        border='1px solid rgba(255, 255, 255, 0.08)'
+                        borderRadius='md'
+                        height='35px'
+                        align='center'
+                        justify='space-between'
+                        px={2}
+                        _hover={{ borderColor: 'rgba(255, 255, 255, 0.15)' }}
+                      >
+                        <Text
+                          fontSize='xs'
+                          color='gray.300'
+                          truncate
+                          maxW='250px'
+                          title={state.kubeConfig}
+                        >
+                          {state.kubeConfig}
+                        </Text>
+                        <Button
+                          size='xs'
+                          variant='ghost'
+                          onClick={handleSetKubeConfig}
+                          bg='whiteAlpha.50'
+                          _hover={{ bg: 'whiteAlpha.100' }}
+                          height='22px'
+                          minW='70px'
+                        >
+                          Browse
+                        </Button>
+                      </Flex>
+                    )}
+                    {contextQuery.isError && (
+                      <Text color='red.300' fontSize='xs'>
+                        Please select a valid kubeconfig file
+                      </Text>
+                    )}
+                  </Stack>
+
+                  <Stack gap={1.5}>
+                    <Text fontSize='xs' color='gray.400'>
+                      Context *
                     </Text>
-                    <Button
-                      size='xs'
-                      variant='ghost'
-                      onClick={handleSetKubeConfig}
-                      bg='whiteAlpha.50'
-                      _hover={{ bg: 'whiteAlpha.100' }}
-                      height='22px'
-                      minW='70px'
-                    >
-                      Browse
-                    </Button>
-                  </Flex>
-                )}
-                {contextQuery.isError && (
-                  <Text color='red.300' fontSize='xs'>
-                    Please select a valid kubeconfig file
-                  </Text>
-                )}
-              </Stack>
+                    {contextQuery.isLoading ? (
+                      <Flex justify='center' py={2}>
+                        <Spinner size='sm' color='blue.400' />
+                      </Flex>
+                    ) : (
+                      <ReactSelect<StringOption>
+                        options={contextQuery.data?.map(context => ({
+                          label: context.name,
+                          value: context.name,
+                        }))}
+                        value={state.selectedContext}
+                        onChange={handleSelectChange}
+                        styles={autoImportSelectStyles}
+                      />
+                    )}
+                    {contextQuery.isError && (
+                      <Text color='red.300' fontSize='xs'>
+                        Please select a valid kubeconfig file
+                      </Text>
+                    )}
+                  </Stack>
+                </>
+              )}
 
-              <Stack gap={1.5}>
-                <Text fontSize='xs' color='gray.400'>
-                  Context *
-                </Text>
-                {contextQuery.isLoading ? (
-                  <Flex justify='center' py={2}>
-                    <Spinner size='sm' color='blue.400' />
-                  </Flex>
-                ) : (
+              {state.source === 'services' && (
+                <Stack gap={1.5}>
+                  <Text fontSize='xs' color='gray.400'>
+                    Namespace *
+                  </Text>
                   <ReactSelect<StringOption>
-                    options={contextQuery.data?.map(context => ({
-                      label: context.name,
-                      value: context.name,
+                    options={namespaceQuery.data?.map(namespace => ({
+                      label: namespace.name,
+                      value: namespace.name,
                     }))}
-                    value={state.selectedContext}
-                    onChange={handleSelectChange}
+                    value={state.selectedNamespace}
+                    onChange={newValue =>
+                      setState(prev => ({
+                        ...prev,
+                        selectedNamespace: newValue,
+                        candidates: [],
+                      }))
+                    }
+                    isLoading={namespaceQuery.isLoading}
+                    isDisabled={!state.selectedContext}
                     styles={autoImportSelectStyles}
                   />
-                )}
-                {contextQuery.isError && (
-                  <Text color='red.300' fontSize='xs'>
-                    Please select a valid kubeconfig file
+                </Stack>
+              )}
+
+              {state.source === 'annotations' && (
+                <Stack gap={1.5}>
+                  <Text fontSize='xs' color='gray.400'>
+                    Namespaces
                   </Text>
-                )}
-              </Stack>
+                  <Input
+                    size='xs'
+                    height='35px'
+                    fontSize='xs'
+                    bg='#161616'
+                    borderColor='rgba(255, 255, 255, 0.08)'
+                    placeholder='All namespaces, or e.g. team-a, team-b'
+                    value={state.namespaces}
+                    onChange={e =>
+                      setState(prev => ({
+                        ...prev,
+                        namespaces: e.target.value,
+                      }))
+                    }
+                  />
+                  <Text fontSize='2xs' color='gray.500'>
+                    Set this when your role cannot list namespaces
+                    cluster-wide.
+                  </Text>
+                </Stack>
+              )}
+
+              {state.source !== 'annotations' && (
+                <Flex justify='flex-end'>
+                  <Button
+                    size='xs'
+                    variant='ghost'
+                    bg='whiteAlpha.50'
+                    _hover={{ bg: 'whiteAlpha.100' }}
+                    onClick={handleScan}
+                    disabled={
+                      state.isScanning ||
+                      (state.source === 'services' &&
+                        (!state.selectedContext || !state.selectedNamespace))
+                    }
+                    height='22px'
+                  >
+                    {state.isScanning ? <Spinner size='xs' /> : 'Scan'}
+                  </Button>
+                </Flex>
+              )}
+
+              {(state.candidates.length > 0 || state.skipped.length > 0) && (
+                <DiscoveredConfigList
+                  candidates={state.candidates}
+                  skipped={state.skipped}
+                  onChange={candidates =>
+                    setState(prev => ({ ...prev, candidates }))
+                  }
+                />
+              )}
+
               <Stack>
                 <Checkbox
                   size='xs'
@@ -344,25 +601,27 @@ const AutoImportModal: React.FC<AutoImportModalProps> = ({
                 </Checkbox>
               </Stack>
 
-              <VStack align='start' gap={2.5} mt={2}>
-                <Text fontSize='xs' color='gray.300'>
-                  Services must have:
-                </Text>
-                <Stack gap={1.5}>
-                  <Text fontSize='xs' color='gray.400'>
-                    • Annotation{' '}
-                    <Text as='span' color='blue.300' fontFamily='mono'>
-                      kftray.app/enabled: true
-                    </Text>
+              {state.source === 'annotations' && (
+                <VStack align='start' gap={2.5} mt={2}>
+                  <Text fontSize='xs' color='gray.300'>
+                    Services must have:
                   </Text>
-                  <Text fontSize='xs' color='gray.400'>
-                    • Config format:{' '}
-                    <Text as='span' color='blue.300' fontFamily='mono'>
-                      alias-localPort-targetPort
+                  <Stack gap={1.5}>
+                    <Text fontSize='xs' color='gray.400'>
+                      • Annotation{' '}
+                      <Text as='span' color='blue.300' fontFamily='mono'>
+                        kftray.app/enabled: true
+                      </Text>
                     </Text>
-                  </Text>
-                </Stack>
-              </VStack>
+                    <Text fontSize='xs' color='gray.400'>
+                      • Config format:{' '}
+                      <Text as='span' color='blue.300' fontFamily='mono'>
+                        alias-localPort-targetPort
+                      </Text>
+                    </Text>
+                  </Stack>
+                </VStack>
+              )}
 
               <HStack justify='flex-end' gap={2} mt={2}>
                 <Button
@@ -378,8 +637,17 @@ const AutoImportModal: React.FC<AutoImportModalProps> = ({
                   size='xs'
                   bg='blue.500'
                   _hover={{ bg: 'blue.600' }}
-                  onClick={handleImport}
-                  disabled={!state.selectedContext || state.isImporting}
+                  onClick={
+                    state.source === 'annotations'
+                      ? handleImport
+                      : handleImportDiscovered
+                  }
+                  disabled={
+                    state.isImporting ||
+                    (state.source === 'annotations'
+                      ? !state.selectedContext
+                      : !state.candidates.some(candidate => candidate.selected))
+                  }
                   height='28px'
                 >
                   Import
@@ -0,0 +1,206 @@
+import React, { useEffect, useState } from 'react'
+
+import {
+  Box,
+  Button,
+  Dialog,
+  Flex,
+  HStack,
+  Spinner,
+  Stack,
+  Text,
+} from '@chakra-ui/react'
+import { invoke } from '@tauri-apps/api/core'
+
+import { toaster } from '@/components/ui/toaster'
+import { ConfigHistoryModalProps, ConfigRevision } from '@/types'
+
+const describeTarget = (revision: ConfigRevision) => {
+  const config = revision.after ?? revision.before
+
+  if (!config) {
+    return ''
+  }
+
+  return `${config.namespace} · ${config.service || config.target}:${
+    config.remote_port
+  } → ${config.local_port}`
+}
+
+const formatChangedAt = (changedAt: number) =>
+  new Date(changedAt * 1000).toLocaleString()
+
+const ConfigHistoryModal: React.FC<ConfigHistoryModalProps> = ({
+  isOpen,
+  onClose,
+  configId,
+}) => {
+  const [revisions, setRevisions] = useState<ConfigRevision[]>([])
+  const [isLoading, setIsLoading] = useState(false)
+  const [restoringId, setRestoringId] = useState<number | null>(null)
+
+  useEffect(() => {
+    if (!isOpen) {
+      return
+    }
+
+    const loadRevisions = async () => {
+      setIsLoading(true)
+      try {
+        const command =
+          configId === undefined
+            ? 'list_deleted_configs_cmd'
+            : 'list_config_history_cmd'
+
+        setRevisions(await invoke<ConfigRevision[]>(command, { configId }))
+      } catch (error) {
+        console.error('Failed to load config history:', error)
+        toaster.error({
+          title: 'Error',
+          description: String(error),
+          duration: 2000,
+        })
+      } finally {
+        setIsLoading(false)
+      }
+    }
+
+    loadRevisions()
+  }, [isOpen, configId])
+
+  const handleRestore = async (revision: ConfigRevision) => {
+    setRestoringId(revision.id)
+    try {
+      await invoke('restore_config_revision_cmd', { revisionId: revision.id })
+
+      toaster.success({
+        title: 'Restored',
+        description: `Restored '${revision.label}' to revision ${revision.id}.`,
+        duration: 1000,
+      })
+      onClose()
+    } catch (error) {
+      console.error('Failed to restore config:', error)
+      toaster.error({
+        title: 'Error',
+        description: String(error),
+        duration: 2000,
+      })
+    } finally {
+      setRestoringId(null)
+    }
+  }
+
+  return (
+    <Dialog.Root open={isOpen} onOpenChange={onClose}>
+      <Dialog.Backdrop
+        bg='transparent'
+        backdropFilter='blur(4px)'
+        borderRadius='lg'
+        height='100vh'
+      />
+      <Dialog.Positioner overflow='hidden'>
+        <Dialog.Content
+          onClick={e => e.stopPropagation()}
+          maxWidth='460px'
+          width='90vw'
+          bg='#111111'
+          borderRadius='lg'
+          border='1px solid rgba(255, 255, 255, 0.08)'
+          overflow='hidden'
+          mt={70}
+        >
+          <Dialog.Header
+            p={1.5}
+            bg='#161616'
+            borderBottom='1px solid rgba(255, 255, 255, 0.05)'
+          >
+            <Text fontSize='sm' fontWeight='medium' color='gray.100'>
+              {configId === undefined ? 'Deleted Configs' : 'Config History'}
+            </Text>
+          </Dialog.Header>
+
+          <Dialog.Body p={3}>
+            <Stack gap={3}>
+              {isLoading ? (
+                <Flex justify='center' py={2}>
+                  <Spinner size='sm' color='blue.400' />
+                </Flex>
+              ) : revisions.length === 0 ? (
+                <Text fontSize='xs' color='gray.400'>
+                  {configId === undefined
+                    ? 'No deleted configs to restore.'
+                    : 'No recorded changes for this config yet.'}
+                </Text>
+              ) : (
+                <Box
+                  maxHeight='260px'
+                  overflowY='auto'
+                  bg='#161616'
+                  border='1px solid rgba(255, 255, 255, 0.08)'
+                  borderRadius='md'
+                  p={1.5}
+                >
+                  {revisions.map(revision => (
+                    <Flex
+                      key={revision.id}
+                      align='center'
+                      justify='space-between'
+                      gap={2}
+                      py={1}
+                    >
+                      <Stack gap={0}>
+                        <Text fontSize='xs' color='gray.200'>
+                          {revision.label}{' '}
+                          <Text as='span' color='blue.300'>
+                            {revision.action}
+                          </Text>
+                        </Text>
+                        <Text fontSize='2xs' color='gray.500'>
+                          {formatChangedAt(revision.changed_at)} via{' '}
+                          {revision.source}
+                        </Text>
+                        <Text fontSize='2xs' color='gray.500'>
+                          {describeTarget(revision)}
+                        </Text>
+                      </Stack>
+                      <Button
+                        size='xs'
+                        variant='ghost'
+                        bg='whiteAlpha.50'
+                        _hover={{ bg: 'whiteAlpha.100' }}
+                        onClick={() => handleRestore(revision)}
+                        disabled={restoringId !== null}
+                        height='22px'
+                      >
+                        {restoringId === revision.id ? (
+                          <Spinner size='xs' />
+                        ) : (
+                          'Restore'
+                        )}
+                      </Button>
+                    </Flex>
+                  ))}
+                </Box>
+              )}
+
+              <HStack justify='flex-end' gap={2}>
+                <Button
+                  size='xs'
+                  variant='ghost'
+                  onClick={onClose}
+                  _hover={{ bg: 'whiteAlpha.50' }}
+                  height='28px'
+                >
+                  Close
+                </Button>
+              </HStack>
+            </Stack>
+          </Dialog.Body>
+        </Dialog.Content>
+      </Dialog.Positioner>
+    </Dialog.Root>
+  )
+}
+
+export default ConfigHistoryModal
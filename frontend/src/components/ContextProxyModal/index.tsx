@@ -0,0 +1,387 @@
+import React, { useEffect, useState } from 'react'
+
+import {
+  Box,
+  Button,
+  Dialog,
+  Flex,
+  HStack,
+  Input,
+  Spinner,
+  Stack,
+  Text,
+} from '@chakra-ui/react'
+import { invoke } from '@tauri-apps/api/core'
+
+import { Checkbox } from '@/components/ui/checkbox'
+import { toaster } from '@/components/ui/toaster'
+import { ContextProxy, ContextProxyModalProps } from '@/types'
+
+const KEYRING_SERVICE = 'kftray'
+
+interface ProxyFormState {
+  context: string
+  proxyUrl: string
+  username: string
+  password: string
+  noProxy: string
+  enabled: boolean
+  credentialsRef?: string
+}
+
+const emptyForm: ProxyFormState = {
+  context: '',
+  proxyUrl: '',
+  username: '',
+  password: '',
+  noProxy: '',
+  enabled: true,
+}
+
+const inputProps = {
+  bg: '#161616',
+  borderColor: 'rgba(255, 255, 255, 0.08)',
+  _hover: { borderColor: 'rgba(255, 255, 255, 0.20)', bg: '#161616' },
+  height: '30px',
+  fontSize: '12px',
+}
+
+const ContextProxyModal: React.FC<ContextProxyModalProps> = ({
+  isOpen,
+  onClose,
+}) => {
+  const [proxies, setProxies] = useState<ContextProxy[]>([])
+  const [contexts, setContexts] = useState<string[]>([])
+  const [form, setForm] = useState<ProxyFormState>(emptyForm)
+  const [isLoading, setIsLoading] = useState(false)
+  const [isSaving, setIsSaving] = useState(false)
+
+  const showError = (error: unknown) => {
+    toaster.error({
+      title: 'Error',
+      description: String(error),
+      duration: 2000,
+    })
+  }
+
+  const loadProxies = async () => {
+    setIsLoading(true)
+    try {
+      setProxies(await invoke<ContextProxy[]>('list_context_proxies_cmd'))
+    } catch (error) {
+      console.error('Failed to load context proxies:', error)
+      showError(error)
+    } finally {
+      setIsLoading(false)
+    }
+  }
+
+  useEffect(() => {
+    if (!isOpen) {
+      return
+    }
+
+    loadProxies()
+    invoke<{ name: string }[]>('list_kube_contexts', { kubeconfig: null })
+      .then(list => setContexts(list.map(context => context.name)))
+      .catch(error => console.error('Failed to list contexts:', error))
+  }, [isOpen])
+
+  const editProxy = (proxy: ContextProxy) => {
+    setForm({
+      context: proxy.context,
+      proxyUrl: proxy.proxy_url,
+      username: proxy.username ?? '',
+      password: '',
+      noProxy: proxy.no_proxy.join(', '),
+      enabled: proxy.enabled,
+      credentialsRef: proxy.credentials_ref,
+    })
+  }
+
+  const handleSave = async (e: React.FormEvent) => {
+    e.preventDefault()
+    setIsSaving(true)
+    try {
+      const context = form.context.trim()
+      let credentialsRef = form.username ? form.credentialsRef : undefined
+
+      if (form.username && form.password) {
+        credentialsRef = `proxy-${context}`
+        await invoke('store_key', {
+          service: KEYRING_SERVICE,
+          name: credentialsRef,
+          password: form.password,
+        })
+      }
+
+      await invoke('save_context_proxy_cmd', {
+        proxy: {
+          context,
+          proxy_url: form.proxyUrl.trim(),
+          username: form.username.trim() || undefined,
+          credentials_ref: credentialsRef,
+          no_proxy: form.noProxy
+            .split(',')
+            .map(rule => rule.trim())
+            .filter(Boolean),
+          enabled: form.enabled,
+        },
+      })
+
+      toaster.success({
+        title: 'Saved',
+        description: `Proxy for '${context}' saved.`,
+        duration: 1000,
+      })
+      setForm(emptyForm)
+      await loadProxies()
+    } catch (error) {
+      console.error('Failed to save context proxy:', error)
+      showError(error)
+    } finally {
+      setIsSaving(false)
+    }
+  }
+
+  const handleDelete = async (proxy: ContextProxy) => {
+    try {
+      await invoke('delete_context_proxy_cmd', { contextName: proxy.context })
+      await loadProxies()
+    } catch (error) {
+      console.error('Failed to delete context proxy:', error)
+      showError(error)
+    }
+  }
+
+  return (
+    <Dialog.Root open={isOpen} onOpenChange={onClose}>
+      <Dialog.Backdrop
+        bg='transparent'
+        backdropFilter='blur(4px)'
+        borderRadius='lg'
+        height='100vh'
+      />
+      <Dialog.Positioner overflow='hidden'>
+        <Dialog.Content
+          onClick={e => e.stopPropagation()}
+          maxWidth='460px'
+          width='90vw'
+          bg='#111111'
+          borderRadius='lg'
+          border='1px solid rgba(255, 255, 255, 0.08)'
+          overflow='hidden'
+          mt={70}
+        >
+          <Dialog.Header
+            p={1.5}
+            bg='#161616'
+            borderBottom='1px solid rgba(255, 255, 255, 0.05)'
+          >
+            <Text fontSize='sm' fontWeight='medium' color='gray.100'>
+              Context Proxies
+            </Text>
+          </Dialog.Header>
+
+          <Dialog.Body p={3}>
+            <Stack gap={3}>
+              {isLoading ? (
+                <Flex justify='center' py={2}>
+                  <Spinner size='sm' color='blue.400' />
+                </Flex>
+              ) : proxies.length === 0 ? (
+                <Text fontSize='xs' color='gray.400'>
+                  No context uses a kftray managed proxy yet.
+                </Text>
+              ) : (
+                <Box
+                  maxHeight='160px'
+                  overflowY='auto'
+                  bg='#161616'
+                  border='1px solid rgba(255, 255, 255, 0.08)'
+                  borderRadius='md'
+                  p={1.5}
+                >
+                  {proxies.map(proxy => (
+                    <Flex
+                      key={proxy.context}
+                      align='center'
+                      justify='space-between'
+                      gap={2}
+                      py={1}
+                    >
+                      <Stack gap={0}>
+                        <Text fontSize='xs' color='gray.200'>
+                          {proxy.context}
+                          {!proxy.enabled && (
+                            <Text as='span' color='gray.500'>
+                              {' '}
+                              (disabled)
+                            </Text>
+                          )}
+                        </Text>
+                        <Text fontSize='2xs' color='gray.500'>
+                          {proxy.username ? `${proxy.username} @ ` : ''}
+                          {proxy.proxy_url}
+                        </Text>
+                      </Stack>
+                      <HStack gap={1}>
+                        <Button
+                          size='xs'
+                          variant='ghost'
+                          bg='whiteAlpha.50'
+                          _hover={{ bg: 'whiteAlpha.100' }}
+                          onClick={() => editProxy(proxy)}
+                          height='22px'
+                        >
+                          Edit
+                        </Button>
+                        <Button
+                          size='xs'
+                          variant='ghost'
+                          bg='whiteAlpha.50'
+                          _hover={{ bg: 'whiteAlpha.100' }}
+                          onClick={() => handleDelete(proxy)}
+                          height='22px'
+                        >
+                          Delete
+                        </Button>
+                      </HStack>
+                    </Flex>
+                  ))}
+                </Box>
+              )}
+
+              <form onSubmit={handleSave} id='context-proxy-form'>
+                <Stack gap={2}>
+                  <Text fontSize='xs' color='gray.400'>
+                    Context
+                  </Text>
+                  <Input
+                    value={form.context}
+                    onChange={e =>
+                      setForm(prev => ({ ...prev, context: e.target.value }))
+                    }
+                    placeholder='kubeconfig context name'
+                    list='context-proxy-contexts'
+                    {...inputProps}
+                  />
+                  <datalist id='context-proxy-contexts'>
+                    {contexts.map(context => (
+                      <option key={context} value={context} />
+                    ))}
+                  </datalist>
+
+                  <Text fontSize='xs' color='gray.400'>
+                    Proxy URL
+                  </Text>
+                  <Input
+                    value={form.proxyUrl}
+                    onChange={e =>
+                      setForm(prev => ({ ...prev, proxyUrl: e.target.value }))
+                    }
+                    placeholder='http://proxy.corp:3128 or socks5://host:1080'
+                    {...inputProps}
+                  />
+
+                  <HStack gap={2}>
+                    <Stack gap={2} flex={1}>
+                      <Text fontSize='xs' color='gray.400'>
+                        Username (optional)
+                      </Text>
+                      <Input
+                        value={form.username}
+                        onChange={e =>
+                          setForm(prev => ({
+                            ...prev,
+                            username: e.target.value,
+                          }))
+                        }
+                        {...inputProps}
+                      />
+                    </Stack>
+                    <Stack gap={2} flex={1}>
+                      <Text fontSize='xs' color='gray.400'>
+                        Password
+                      </Text>
+                      <Input
+                        type='password'
+                        value={form.password}
+                        onChange={e =>
+                          setForm(prev => ({
+                            ...prev,
+                            password: e.target.value,
+                          }))
+                        }
+                        placeholder={
+                          form.credentialsRef
+                            ? 'unchanged'
+                            : 'stored in keyring'
+                        }
+                        disabled={!form.username}
+                        {...inputProps}
+                      />
+                    </Stack>
+                  </HStack>
+
+                  <Text fontSize='xs' color='gray.400'>
+                    No Proxy (optional)
+                  </Text>
+                  <Input
+                    value={form.noProxy}
+                    onChange={e =>
+                      setForm(prev => ({ ...prev, noProxy: e.target.value }))
+                    }
+                    placeholder='.corp.example.com, 10.0.0.0/8'
+                    {...inputProps}
+                  />
+
+                  <Checkbox
+                    checked={form.enabled}
+                    onCheckedChange={e =>
+                      setForm(prev => ({
+                        ...prev,
+                        enabled: e.checked === true,
+                      }))
+                    }
+                    size='xs'
+                  >
+                    <Text fontSize='xs' color='gray.400'>
+                      Use this proxy
+                    </Text>
+                  </Checkbox>
+                </Stack>
+              </form>
+
+              <HStack justify='flex-end' gap={2}>
+                <Button
+                  size='xs'
+                  variant='ghost'
+                  onClick={onClose}
+                  _hover={{ bg: 'whiteAlpha.50' }}
+                  height='28px'
+                >
+                  Close
+                </Button>
+                <Button
+                  type='submit'
+                  form='context-proxy-form'
+                  size='xs'
+                  bg='blue.500'
+                  _hover={{ bg: 'blue.600' }}
+                  disabled={!form.context.trim() || !form.proxyUrl.trim()}
+                  loading={isSaving}
+                  height='28px'
+                >
+                  Save
+                </Button>
+              </HStack>
+            </Stack>
+          </Dialog.Body>
+        </Dialog.Content>
+      </Dialog.Positioner>
+    </Dialog.Root>
+  )
+}
+
+export default ContextProxyModal
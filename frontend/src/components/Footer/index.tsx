@@ -2,10 +2,18 @@ import React, { useCallback, useState } from 'react'
 import {
   Download,
   Github,
+  History,
   Keyboard,
+  Layers,
+  ListChecks,
   Menu as MenuIcon,
+  Network,
+  Play,
+  Plug,
   Plus,
   Settings,
+  Square,
+  Trash2,
   Upload,
   Wrench,
 } from 'lucide-react'
@@ -32,7 +40,37 @@ import {
   MenuTriggerItem,
 } from '@/components/ui/menu'
 import { Tooltip } from '@/components/ui/tooltip'
-import { FooterProps } from '@/types'
+import {
+  ConfigGroup,
+  ConnectionReport,
+  DiagnosticCheck,
+  FooterProps,
+} from '@/types'
+
+const formatChecks = (checks: DiagnosticCheck[], indent: string) =>
+  checks.map(
+    check =>
+      `${indent}${check.status}: ${check.check}: ${check.detail}` +
+      (check.elapsed_ms == null ? '' : ` (${check.elapsed_ms} ms)`),
+  )
+
+const formatConnectionReport = (report: ConnectionReport) =>
+  [
+    `${report.context} (${report.server})`,
+    `  auth: ${report.auth_method}`,
+    `  proxy: ${report.proxy ?? 'none'}`,
+    ...formatChecks(report.checks, '  '),
+    ...report.certificates.map(
+      cert =>
+        `  cert: ${cert.subject} issued by ${cert.issuer}, expires ` +
+        `${cert.not_after} (${cert.days_remaining} days)`,
+    ),
+    ...report.strategies.flatMap(strategy => [
+      '',
+      `  strategy ${strategy.strategy}`,
+      ...formatChecks(strategy.checks, '    '),
+    ]),
+  ].join('\n')
 
 const Footer: React.FC<FooterProps> = ({
   openModal,
@@ -51,12 +89,16 @@ const Footer: React.FC<FooterProps> = ({
   onSyncComplete,
   openShortcutModal,
   setIsAutoImportModalOpen,
+  openConfigHistory,
+  openContextProxies,
 }) => {
   const [logState, setLogState] = useState({
     size: 0,
     fetchError: false,
   })
 
+  const [groups, setGroups] = useState<ConfigGroup[]>([])
+
   const handleSyncFailure = useCallback((error: Error) => {
     console.error('Sync failed:', error)
   }, [])
@@ -72,6 +114,34 @@ const Footer: React.FC<FooterProps> = ({
     }
   }
 
+  const fetchGroups = async () => {
+    try {
+      setGroups(await invoke<ConfigGroup[]>('list_config_groups_cmd'))
+    } catch (error) {
+      console.error('Failed to fetch config groups:', error)
+    }
+  }
+
+  const openMenu = () => {
+    fetchLogSize()
+    fetchGroups()
+  }
+
+  const handleGroupAction = async (name: string, start: boolean) => {
+    try {
+      await invoke(start ? 'start_config_group_cmd' : 'stop_config_group_cmd', {
+        name,
+      })
+    } catch (error) {
+      console.error(`Failed to ${start ? 'start' : 'stop'} group:`, error)
+      setHelperActionResult({
+        success: false,
+        message: String(error),
+        action: 'group',
+      })
+    }
+  }
+
   const handleClearLogs = async () => {
     try {
       await invoke('clear_http_logs')
@@ -84,7 +154,7 @@ const Footer: React.FC<FooterProps> = ({
   const [helperActionResult, setHelperActionResult] = useState<{
     success: boolean
     message: string
-    action: 'install' | 'uninstall'
+    action: 'install' | 'uninstall' | 'clean' | 'group' | 'plan' | 'connection'
   } | null>(null)
 
   const handleInstallHelper = async () => {
@@ -131,6 +201,120 @@ const Footer: React.FC<FooterProps> = ({
     }
   }
 
+  const [confirmClean, setConfirmClean] = useState(false)
+
+  const handleCleanCluster = async () => {
+    setConfirmClean(false)
+    try {
+      const report = await invoke<{
+        deleted: unknown[]
+        kept_active: number
+        errors: string[]
+      }>('clean_cluster_cmd')
+
+      setHelperActionResult({
+        success: report.errors.length === 0,
+        message:
+          report.errors.length === 0
+            ? `Removed ${report.deleted.length} orphaned resource(s), kept ${report.kept_active} active`
+            : report.errors.join('\n'),
+        action: 'clean',
+      })
+    } catch (error) {
+      console.error('Failed to clean cluster:', error)
+      setHelperActionResult({
+        success: false,
+        message: String(error),
+        action: 'clean',
+      })
+    }
+  }
+
+  const handlePlanSelected = async () => {
+    try {
+      const plans = await invoke<
+        {
+          label: string
+          steps: {
+            step: string
+            status: 'ok' | 'warning' | 'error'
+            detail: string
+          }[]
+        }[]
+      >('plan_port_forward_cmd', { configs: selectedConfigs })
+
+      const failed = plans.some(plan =>
+        plan.steps.some(step => step.status === 'error'),
+      )
+
+      setHelperActionResult({
+        success: !failed,
+        message: plans
+          .map(plan =>
+            [
+              plan.label,
+              ...plan.steps.map(
+                step => `  ${step.status}: ${step.step}: ${step.detail}`,
+              ),
+            ].join('\n'),
+          )
+          .join('\n\n'),
+        action: 'plan',
+      })
+    } catch (error) {
+      console.error('Failed to plan port forward:', error)
+      setHelperActionResult({
+        success: false,
+        message: String(error),
+        action: 'plan',
+      })
+    }
+  }
+
+  const handleTestConnection = async () => {
+    const config = selectedConfigs[0]
+
+    if (!config) {
+      return
+    }
+
+    try {
+      const report = await invoke<ConnectionReport>(
+        'diagnose_connection_cmd',
+        {
+          contextName: config.context || '@current',
+          kubeconfig: config.kubeconfig,
+        },
+      )
+
+      setHelperActionResult({
+        success: report.strategies.some(strategy =>
+          strategy.checks.some(
+            check => check.check === 'version' && check.status === 'ok',
+          ),
+        ),
+        message: formatConnectionReport(report),
+        action: 'connection',
+      })
+    } catch (error) {
+      console.error('Failed to test connection:', error)
+      setHelperActionResult({
+        success: false,
+        message: String(error),
+        action: 'connection',
+      })
+    }
+  }
+
+  const actionTitles = {
+    install: ['Installation Successful', 'Installation Failed'],
+    uninstall: ['Uninstallation Successful', 'Uninstallation Failed'],
+    clean: ['Cluster Cleaned', 'Cluster Cleanup Failed'],
+    group: ['Group Updated', 'Group Action Failed'],
+    plan: ['Start Plan', 'Start Plan Has Errors'],
+    connection: ['Connection Succeeded', 'Connection Failed'],
+  }
+
   const closeHelperActionDialog = () => {
     setHelperActionResult(null)
   }
@@ -170,6 +354,75 @@ const Footer: React.FC<FooterProps> = ({
         <Box fontSize='11px'>Auto Import</Box>
       </MenuItem>
 
+      <MenuItem
+        value='plan-selected'
+        onClick={handlePlanSelected}
+        disabled={selectedConfigs.length === 0}
+      >
+        <Box as={ListChecks} width='12px' height='12px' />
+        <Box fontSize='11px'>Plan Selected</Box>
+      </MenuItem>
+
+      <MenuItem
+        value='test-connection'
+        onClick={handleTestConnection}
+        disabled={selectedConfigs.length !== 1}
+      >
+        <Box as={Plug} width='12px' height='12px' />
+        <Box fontSize='11px'>Test Connection</Box>
+      </MenuItem>
+
+      <MenuItem value='context-proxies' onClick={openContextProxies}>
+        <Box as={Network} width='12px' height='12px' />
+        <Box fontSize='11px'>Context Proxies</Box>
+      </MenuItem>
+
+      <MenuItem
+        value='config-history'
+        onClick={() => openConfigHistory(selectedConfigs[0]?.id)}
+        disabled={selectedConfigs.length !== 1}
+      >
+        <Box as={History} width='12px' height='12px' />
+        <Box fontSize='11px'>Config History</Box>
+      </MenuItem>
+
+      <MenuItem value='restore-deleted' onClick={() => openConfigHistory()}>
+        <Box as={History} width='12px' height='12px' />
+        <Box fontSize='11px'>Restore Deleted</Box>
+      </MenuItem>
+
+      <MenuItem value='clean-cluster' onClick={() => setConfirmClean(true)}>
+        <Box as={Trash2} width='12px' height='12px' />
+        <Box fontSize='11px'>Clean Cluster</Box>
+      </MenuItem>
+
+      <MenuRoot>
+        <MenuTriggerItem disabled={groups.length === 0}>
+          <Box as={Layers} width='12px' height='12px' />
+          <Box fontSize='11px'>Groups</Box>
+        </MenuTriggerItem>
+        <MenuContent>
+          {groups.map(group => (
+            <React.Fragment key={group.name}>
+              <MenuItem
+                value={`group-start-${group.name}`}
+                onClick={() => handleGroupAction(group.name, true)}
+              >
+                <Box as={Play} width='12px' height='12px' />
+                <Box fontSize='11px'>Start {group.name}</Box>
+              </MenuItem>
+              <MenuItem
+                value={`group-stop-${group.name}`}
+                onClick={() => handleGroupAction(group.name, false)}
+              >
+                <Box as={Square} width='12px' height='12px' />
+                <Box fontSize='11px'>Stop {group.name}</Box>
+              </MenuItem>
+            </React.Fragment>
+          ))}
+        </MenuContent>
+      </MenuRoot>
+
       <MenuRoot>
         <MenuTriggerItem>
           <Box as={Wrench} width='12px' height='12px' />
@@ -192,6 +445,62 @@ const Footer: React.FC<FooterProps> = ({
 
   return (
     <>
+      <DialogRoot
+        open={confirmClean}
+        onOpenChange={open => !open && setConfirmClean(false)}
+      >
+        <DialogContent
+          maxWidth='400px'
+          width='350px'
+          bg='#111111'
+          borderRadius='lg'
+          border='1px solid rgba(255, 255, 255, 0.08)'
+          overflow='hidden'
+        >
+          <DialogHeader
+            p={1.5}
+            bg='#161616'
+            borderBottom='1px solid rgba(255, 255, 255, 0.05)'
+          >
+            <DialogTitle fontSize='sm' fontWeight='medium' color='gray.100'>
+              Clean Cluster
+            </DialogTitle>
+            <DialogCloseTrigger onClick={() => setConfirmClean(false)} />
+          </DialogHeader>
+
+          <DialogBody p={3}>
+            <Box fontSize='xs' color='gray.300' whiteSpace='pre-wrap'>
+              Delete kftray resources of this machine that no running
+              forward refreshes? This also deletes unlabelled
+              kftray-forward-&lt;user&gt;-* and kftray-expose-&lt;user&gt;-*
+              resources older than 5 minutes in every namespace. Older kftray
+              versions created them, and another machine with the same
+              username may still use them.
+            </Box>
+
+            <Box display='flex' justifyContent='flex-end' gap={2} mt={4}>
+              <Button
+                onClick={() => setConfirmClean(false)}
+                size='xs'
+                variant='ghost'
+                height='28px'
+              >
+                Cancel
+              </Button>
+              <Button
+                onClick={handleCleanCluster}
+                size='xs'
+                bg='red.600'
+                _hover={{ bg: 'red.700' }}
+                height='28px'
+              >
+                Clean
+              </Button>
+            </Box>
+          </DialogBody>
+        </DialogContent>
+      </DialogRoot>
+
       <DialogRoot
         open={!!helperActionResult}
         onOpenChange={open => !open && closeHelperActionDialog()}
@@ -210,13 +519,10 @@ const Footer: React.FC<FooterProps> = ({
             borderBottom='1px solid rgba(255, 255, 255, 0.05)'
           >
             <DialogTitle fontSize='sm' fontWeight='medium' color='gray.100'>
-              {helperActionResult?.success
-                ? helperActionResult.action === 'install'
-                  ? 'Installation Successful'
-                  : 'Uninstallation Successful'
-                : helperActionResult?.action === 'install'
-                  ? 'Installation Failed'
-                  : 'Uninstallation Failed'}
+              {helperActionResult &&
+                actionTitles[helperActionResult.action][
+                  helperActionResult.success ? 0 : 1
+                ]}
             </DialogTitle>
             <DialogCloseTrigger onClick={closeHelperActionDialog} />
           </DialogHeader>
@@ -235,6 +541,9 @@ const Footer: React.FC<FooterProps> = ({
               {helperActionResult && (
                 <Box
                   fontSize='xs'
+                  whiteSpace='pre-wrap'
+                  maxHeight='300px'
+                  overflowY='auto'
                   color={helperActionResult.success ? 'green.300' : 'red.300'}
                 >
                   {helperActionResult.message}
@@ -280,7 +589,7 @@ const Footer: React.FC<FooterProps> = ({
               <Button
                 size='sm'
                 variant='ghost'
-                onClick={fetchLogSize}
+                onClick={openMenu}
                 height='32px'
                 minWidth='32px'
                 bg='whiteAlpha.50'
@@ -37,6 +37,10 @@ const GitSyncModal: React.FC<GitSyncModalProps> = ({
   const [formState, setFormState] = useState(() => ({
     repoUrl: credentials?.repoUrl || '',
     configPath: credentials?.configPath || '',
+    gitRef: credentials?.gitRef || '',
+    provider: credentials?.provider || '',
+    caBundlePath: credentials?.caBundlePath || '',
+    insecureSkipVerify: credentials?.insecureSkipVerify ?? false,
     authMethod: (credentials?.authMethod || 'none') as AuthMethod,
     gitToken: credentials?.token || '',
     pollingInterval: syncStatus.pollingInterval || 60,
@@ -49,6 +53,10 @@ const GitSyncModal: React.FC<GitSyncModalProps> = ({
         ...prev,
         repoUrl: credentials.repoUrl,
         configPath: credentials.configPath,
+        gitRef: credentials.gitRef || '',
+        provider: credentials.provider || '',
+        caBundlePath: credentials.caBundlePath || '',
+        insecureSkipVerify: credentials.insecureSkipVerify ?? false,
         authMethod: credentials.authMethod,
         gitToken: credentials.token || '',
         flushBeforeSync: credentials.flush ?? false,
@@ -67,6 +75,10 @@ const GitSyncModal: React.FC<GitSyncModalProps> = ({
       const newCredentials = {
         repoUrl: formState.repoUrl,
         configPath: formState.configPath,
+        gitRef: formState.gitRef.trim(),
+        provider: formState.provider.trim().toLowerCase(),
+        caBundlePath: formState.caBundlePath.trim(),
+        insecureSkipVerify: formState.insecureSkipVerify,
         authMethod: formState.authMethod,
         token: formState.authMethod === 'token' ? formState.gitToken : '',
         pollingInterval: formState.pollingInterval,
@@ -229,6 +241,56 @@ const GitSyncModal: React.FC<GitSyncModalProps> = ({
                     />
                   </Stack>
 
+                  {/* Git Ref */}
+                  <Stack gap={2}>
+                    <Text fontSize='xs' color='gray.400'>
+                      Branch, Tag or Commit (optional)
+                    </Text>
+                    <Input
+                      value={formState.gitRef}
+                      onChange={e =>
+                        setFormState(prev => ({
+                          ...prev,
+                          gitRef: e.target.value,
+                        }))
+                      }
+                      placeholder='main, v1.2.0 or full commit SHA'
+                      bg='#161616'
+                      borderColor='rgba(255, 255, 255, 0.08)'
+                      _hover={{
+                        borderColor: 'rgba(255, 255, 255, 0.20)',
+                        bg: '#161616',
+                      }}
+                      height='30px'
+                      fontSize='12px'
+                    />
+                  </Stack>
+
+                  {/* Provider */}
+                  <Stack gap={2}>
+                    <Text fontSize='xs' color='gray.400'>
+                      Provider (optional)
+                    </Text>
+                    <Input
+                      value={formState.provider}
+                      onChange={e =>
+                        setFormState(prev => ({
+                          ...prev,
+                          provider: e.target.value,
+                        }))
+                      }
+                      placeholder='github, gitlab, bitbucket, gitea or http'
+                      bg='#161616'
+                      borderColor='rgba(255, 255, 255, 0.08)'
+                      _hover={{
+                        borderColor: 'rgba(255, 255, 255, 0.20)',
+                        bg: '#161616',
+                      }}
+                      height='30px'
+                      fontSize='12px'
+                    />
+                  </Stack>
+
                   {/* Authentication Method */}
                   <Stack gap={2}>
                     <Text fontSize='xs' color='gray.400'>
@@ -291,6 +353,57 @@ const GitSyncModal: React.FC<GitSyncModalProps> = ({
                     )}
                   </Stack>
 
+                  {/* CA Bundle */}
+                  <Stack gap={2}>
+                    <Text fontSize='xs' color='gray.400'>
+                      Custom CA Bundle (optional)
+                    </Text>
+                    <Input
+                      value={formState.caBundlePath}
+                      onChange={e =>
+                        setFormState(prev => ({
+                          ...prev,
+                          caBundlePath: e.target.value,
+                        }))
+                      }
+                      placeholder='/path/to/internal-ca.pem'
+                      bg='#161616'
+                      borderColor='rgba(255, 255, 255, 0.08)'
+                      _hover={{
+                        borderColor: 'rgba(255, 255, 255, 0.20)',
+                        bg: '#161616',
+                      }}
+                      height='30px'
+                      fontSize='12px'
+                    />
+                  </Stack>
+
+                  <Stack gap={1}>
+                    <Checkbox
+                      checked={formState.insecureSkipVerify}
+                      onCheckedChange={e =>
+                        setFormState(prev => ({
+                          ...prev,
+                          insecureSkipVerify: e.checked === true,
+                        }))
+                      }
+                      size='xs'
+                    >
+                      <Text fontSize='xs' color='gray.400'>
+                        Skip TLS and SSH host key verification
+                      </Text>
+                    </Checkbox>
+                    <Text
+                      fontSize='10px'
+                      color='gray.500'
+                      ml={5}
+                      lineHeight='1.3'
+                    >
+                      Insecure: accepts any certificate or host key for this
+                      repository
+                    </Text>
+                  </Stack>
+
                   <Stack gap={1}>
                     <Checkbox
                       checked={formState.flushBeforeSync}
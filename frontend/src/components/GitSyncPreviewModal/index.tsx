@@ -0,0 +1,168 @@
+import React, { useState } from 'react'
+
+import { Box, Button, Dialog, HStack, Stack, Text } from '@chakra-ui/react'
+
+import { toaster } from '@/components/ui/toaster'
+import { useGitSync } from '@/contexts/GitSyncContext'
+import { Config } from '@/types'
+
+const describeConfig = (config: Config) =>
+  config.alias ||
+  `${config.context}/${config.namespace}/${config.service || config.target || config.remote_address}`
+
+const DiffSection: React.FC<{
+  title: string
+  color: string
+  items: { label: string; detail?: string }[]
+}> = ({ title, color, items }) => {
+  if (items.length === 0) {
+    return null
+  }
+
+  return (
+    <Stack gap={1}>
+      <Text fontSize='xs' color='gray.400'>
+        {title} ({items.length})
+      </Text>
+      {items.map(item => (
+        <Box key={`${title}-${item.label}`} pl={2}>
+          <Text fontSize='xs' color={color} fontFamily='mono' truncate>
+            {item.label}
+          </Text>
+          {item.detail && (
+            <Text fontSize='10px' color='gray.500' truncate>
+              {item.detail}
+            </Text>
+          )}
+        </Box>
+      ))}
+    </Stack>
+  )
+}
+
+const GitSyncPreviewModal: React.FC = () => {
+  const { pendingPreview, approvePreview, dismissPreview } = useGitSync()
+  const [isApplying, setIsApplying] = useState(false)
+
+  if (!pendingPreview) {
+    return null
+  }
+
+  const { diff, commit_sha } = pendingPreview
+
+  const handleApprove = async () => {
+    setIsApplying(true)
+    try {
+      await approvePreview()
+    } catch (error) {
+      console.error('Failed to apply git sync:', error)
+      toaster.error({
+        title: 'Error applying Git sync',
+        description:
+          error instanceof Error ? error.message : String(error ?? 'Unknown'),
+        duration: 2000,
+      })
+    } finally {
+      setIsApplying(false)
+    }
+  }
+
+  return (
+    <Dialog.Root open onOpenChange={dismissPreview}>
+      <Dialog.Backdrop
+        bg='transparent'
+        backdropFilter='blur(4px)'
+        borderRadius='lg'
+        height='100vh'
+      />
+      <Dialog.Positioner overflow='hidden'>
+        <Dialog.Content
+          onClick={e => e.stopPropagation()}
+          maxWidth='400px'
+          width='90vw'
+          maxHeight='80vh'
+          bg='#111111'
+          borderRadius='lg'
+          border='1px solid rgba(255, 255, 255, 0.08)'
+          overflow='hidden'
+          mt={70}
+        >
+          <Dialog.Header
+            p={1.5}
+            bg='#161616'
+            borderBottom='1px solid rgba(255, 255, 255, 0.05)'
+          >
+            <Text fontSize='sm' fontWeight='medium' color='gray.100'>
+              Git Sync Changes
+            </Text>
+          </Dialog.Header>
+
+          <Dialog.Body p={3} overflowY='auto'>
+            <Stack gap={3}>
+              <Text fontSize='xs' color='gray.400'>
+                Commit{' '}
+                <Text as='span' color='blue.300' fontFamily='mono'>
+                  {commit_sha.slice(0, 12)}
+                </Text>{' '}
+                · {diff.unchanged} unchanged
+              </Text>
+
+              <DiffSection
+                title='Added'
+                color='green.300'
+                items={diff.added.map(config => ({
+                  label: describeConfig(config),
+                }))}
+              />
+              <DiffSection
+                title='Changed'
+                color='yellow.300'
+                items={diff.changed.map(change => ({
+                  label: describeConfig(change.after),
+                  detail: change.fields.join(', '),
+                }))}
+              />
+              <DiffSection
+                title='Removed'
+                color='red.300'
+                items={diff.removed.map(config => ({
+                  label: describeConfig(config),
+                }))}
+              />
+
+              {diff.changed.length > 0 && (
+                <Text fontSize='10px' color='gray.500'>
+                  Running port forwards of changed configs will be restarted.
+                </Text>
+              )}
+
+              <HStack justify='flex-end' gap={2} mt={2}>
+                <Button
+                  size='xs'
+                  variant='ghost'
+                  onClick={dismissPreview}
+                  _hover={{ bg: 'whiteAlpha.50' }}
+                  height='28px'
+                >
+                  Dismiss
+                </Button>
+                <Button
+                  size='xs'
+                  bg='blue.500'
+                  _hover={{ bg: 'blue.600' }}
+                  onClick={handleApprove}
+                  disabled={isApplying}
+                  height='28px'
+                >
+                  Apply
+                </Button>
+              </HStack>
+            </Stack>
+          </Dialog.Body>
+        </Dialog.Content>
+      </Dialog.Positioner>
+    </Dialog.Root>
+  )
+}
+
+export default GitSyncPreviewModal
@@ -17,17 +17,29 @@ import Footer from '@/components/Footer'
 import PortForwardTable from '@/components/PortForwardTable'
 import { toaster } from '@/components/ui/toaster'
 import { useSyncManager } from '@/hooks/useSyncManager'
-import { Config } from '@/types'
+import { AuthPrompt, Config, ConfigState, ConfigValidation } from '@/types'
 
 const AddConfigModal = lazy(() => import('@/components/AddConfigModal'))
 const AutoImportModal = lazy(() => import('@/components/AutoImportModal'))
+const ConfigHistoryModal = lazy(
+  () => import('@/components/ConfigHistoryModal'),
+)
+const ContextProxyModal = lazy(() => import('@/components/ContextProxyModal'))
 const GitSyncModal = lazy(() => import('@/components/GitSyncModal'))
+const GitSyncPreviewModal = lazy(
+  () => import('@/components/GitSyncPreviewModal'),
+)
 const SettingsModal = lazy(() => import('@/components/SettingsModal'))
 const ShortcutModal = lazy(() => import('@/components/ShortcutModal'))
 
 const initialRemotePort = 0
 const initialLocalPort = 0
 const initialId = 0
+const configFileFilters = [
+  { name: 'JSON', extensions: ['json'] },
+  { name: 'YAML', extensions: ['yaml', 'yml'] },
+  { name: 'TOML', extensions: ['toml'] },
+]
 
 // eslint-disable-next-line max-statements
 const KFTray = () => {
@@ -62,19 +74,26 @@ const KFTray = () => {
   const [isAlertOpen, setIsAlertOpen] = useState(false)
   const [configToDelete, setConfigToDelete] = useState<number | undefined>()
   const [isAutoImportModalOpen, setIsAutoImportModalOpen] = useState(false)
+  const [historyTarget, setHistoryTarget] = useState<{
+    configId?: number
+  } | null>(null)
+  const [isContextProxyModalOpen, setIsContextProxyModalOpen] = useState(false)
   const [isShortcutModalOpen, setIsShortcutModalOpen] = useState(false)
   const [isSettingsModalOpen, setIsSettingsModalOpen] = useState(false)
   const fetchConfigsWithState = useCallback(async () => {
     try {
       const configsResponse = await invoke<Config[]>('get_configs_cmd')
-      const configStates = await invoke<Config[]>('get_config_states')
+      const configStates = await invoke<ConfigState[]>('get_config_states')
 
-      return configsResponse.map(config => ({
-        ...config,
-        is_running:
-          configStates.find(state => state.id === config.id)?.is_running ||
-          false,
-      }))
+      return configsResponse.map(config => {
+        const state = configStates.find(state => state.config_id === config.id)
+
+        return {
+          ...config,
+          is_running: state?.is_running || false,
+          local_port: state?.local_port ?? config.local_port,
+        }
+      })
     } catch (error) {
       console.error('Failed to fetch configs:', error)
       throw error
@@ -144,6 +163,20 @@ const KFTray = () => {
     }
   }, [fetchConfigsWithState, debouncedUpdateConfigs])
 
+  useEffect(() => {
+    const unlistenPromise = listen<AuthPrompt>('auth-prompt', event => {
+      toaster.warning({
+        title: `Authentication needed: ${event.payload.command}`,
+        description: event.payload.message,
+        duration: 15000,
+      })
+    })
+
+    return () => {
+      unlistenPromise.then(unlisten => unlisten())
+    }
+  }, [])
+
   const openModal = () => {
     setNewConfig({
       id: initialId,
@@ -196,9 +229,21 @@ const KFTray = () => {
     setIsSettingsModalOpen(false)
   }
 
+  const cleanGroups = (groups?: string[]) =>
+    (groups ?? []).map(group => group.trim()).filter(Boolean)
+
   const handleInputChange = (e: React.ChangeEvent<HTMLInputElement>) => {
     const { name, value } = e.target
 
+    if (name === 'groups' || name === 'impersonate_groups') {
+      setNewConfig(prev => ({
+        ...prev,
+        [name]: value.split(',').map(group => group.trimStart()),
+      }))
+
+      return
+    }
+
     setNewConfig(prev => ({
       ...prev,
       [name]:
@@ -211,21 +256,26 @@ const KFTray = () => {
   const handleExportConfigs = async () => {
     try {
       await invoke('open_save_dialog')
-      const json = await invoke('export_configs_cmd')
-
-      if (typeof json !== 'string') {
-        throw new Error('The exported config is not a string')
-      }
-
       const filePath = await save({
         defaultPath: 'configs.json',
-        filters: [{ name: 'JSON', extensions: ['json'] }],
+        filters: configFileFilters,
       })
 
       await invoke('close_save_dialog')
 
       if (filePath) {
-        await writeTextFile(filePath, json)
+        const extension = filePath.split('.').pop()?.toLowerCase()
+        const format =
+          extension === 'yaml' || extension === 'yml' || extension === 'toml'
+            ? extension
+            : 'json'
+        const content = await invoke('export_configs_as_cmd', { format })
+
+        if (typeof content !== 'string') {
+          throw new Error('The exported config is not a string')
+        }
+
+        await writeTextFile(filePath, content)
         toaster.success({
           title: 'Success',
           description: 'Configuration exported successfully.',
@@ -249,7 +299,7 @@ const KFTray = () => {
     try {
       await invoke('open_save_dialog')
       const selected = await open({
-        filters: [{ name: 'JSON', extensions: ['json'] }],
+        filters: configFileFilters,
         multiple: false,
       })
 
@@ -319,7 +369,13 @@ const KFTray = () => {
   const handleEditSubmit = async (e: React.FormEvent) => {
     e.preventDefault()
     try {
-      await invoke('update_config_cmd', { config: newConfig })
+      await invoke('update_config_cmd', {
+        config: {
+          ...newConfig,
+          groups: cleanGroups(newConfig.groups),
+          impersonate_groups: cleanGroups(newConfig.impersonate_groups),
+        },
+      })
       toaster.success({
         title: 'Success',
         description: 'Configuration updated successfully.',
@@ -340,7 +396,30 @@ const KFTray = () => {
       const updatedConfigToSave: Config = {
         ...newConfig,
         id: isEdit ? newConfig.id : 0,
+        groups: cleanGroups(newConfig.groups),
+        impersonate_groups: cleanGroups(newConfig.impersonate_groups),
       }
+
+      const validation = await invoke<ConfigValidation>('validate_config_cmd', {
+        config: updatedConfigToSave,
+      })
+      const describeIssues = (severity: 'warning' | 'error') =>
+        validation.issues
+          .filter(issue => issue.severity === severity)
+          .map(issue => `${issue.field}: ${issue.message}`)
+          .join('\n')
+      const errors = describeIssues('error')
+
+      if (errors) {
+        toaster.error({
+          title: 'Invalid configuration',
+          description: errors,
+          duration: 4000,
+        })
+
+        return
+      }
+
       let wasRunning = false
       const originalConfigsRunningState = new Map(
         configs.map(conf => [conf.id, conf.is_running]),
@@ -366,6 +445,16 @@ const KFTray = () => {
         description: `Configuration ${isEdit ? 'updated' : 'added'} successfully.`,
         duration: 1000,
       })
+
+      const warnings = describeIssues('warning')
+
+      if (warnings) {
+        toaster.warning({
+          title: 'Configuration saved with warnings',
+          description: warnings,
+          duration: 4000,
+        })
+      }
       closeModal()
     } catch (error) {
       console.error(`Failed to ${isEdit ? 'update' : 'add'} config:`, error)
@@ -720,11 +809,15 @@ const KFTray = () => {
               onSyncComplete={handleSyncComplete}
               openShortcutModal={openShortcutModal}
               setIsAutoImportModalOpen={setIsAutoImportModalOpen}
+              openConfigHistory={configId => setHistoryTarget({ configId })}
+              openContextProxies={() => setIsContextProxyModalOpen(true)}
             />
           </Box>
         </Box>
 
         <Suspense fallback={null}>
+          <GitSyncPreviewModal />
+
           {isGitSyncModalOpen && (
             <GitSyncModal
               isGitSyncModalOpen={isGitSyncModalOpen}
@@ -757,6 +850,21 @@ const KFTray = () => {
             />
           )}
 
+          {historyTarget && (
+            <ConfigHistoryModal
+              isOpen={!!historyTarget}
+              onClose={() => setHistoryTarget(null)}
+              configId={historyTarget.configId}
+            />
+          )}
+
+          {isContextProxyModalOpen && (
+            <ContextProxyModal
+              isOpen={isContextProxyModalOpen}
+              onClose={() => setIsContextProxyModalOpen(false)}
+            />
+          )}
+
           {isShortcutModalOpen && (
             <ShortcutModal
               isOpen={isShortcutModalOpen}
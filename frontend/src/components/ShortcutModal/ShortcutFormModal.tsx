@@ -17,7 +17,7 @@ import { Checkbox } from '@/components/ui/checkbox'
 import { DialogCloseTrigger } from '@/components/ui/dialog'
 import { toaster } from '@/components/ui/toaster'
 import { type Shortcut, useGlobalShortcuts } from '@/hooks/useGlobalShortcuts'
-import { type Config } from '@/types'
+import { type Config, type ConfigGroup } from '@/types'
 
 interface ShortcutFormModalProps {
   isOpen: boolean
@@ -32,6 +32,7 @@ interface ShortcutAction {
   name: string
   actionType: string
   requiresConfig: boolean
+  requiresGroup?: boolean
 }
 
 const SHORTCUT_ACTIONS: ShortcutAction[] = [
@@ -71,6 +72,20 @@ const SHORTCUT_ACTIONS: ShortcutAction[] = [
     actionType: 'toggle_port_forward',
     requiresConfig: true,
   },
+  {
+    id: 'start_group',
+    name: 'Start Config Group',
+    actionType: 'start_group',
+    requiresConfig: false,
+    requiresGroup: true,
+  },
+  {
+    id: 'stop_group',
+    name: 'Stop Config Group',
+    actionType: 'stop_group',
+    requiresConfig: false,
+    requiresGroup: true,
+  },
 ]
 
 const ShortcutFormModal: React.FC<ShortcutFormModalProps> = ({
@@ -84,34 +99,47 @@ const ShortcutFormModal: React.FC<ShortcutFormModalProps> = ({
     shortcutKey: '',
     actionType: '',
     configIds: [] as number[],
+    group: '',
   })
   const [isLoading, setIsLoading] = useState(false)
+  const [groups, setGroups] = useState<ConfigGroup[]>([])
 
   const { validateShortcut, normalizeShortcut } = useGlobalShortcuts()
 
+  useEffect(() => {
+    if (isOpen) {
+      invoke<ConfigGroup[]>('list_config_groups_cmd')
+        .then(setGroups)
+        .catch(error => console.error('Failed to load config groups:', error))
+    }
+  }, [isOpen])
+
   useEffect(() => {
     if (isOpen) {
       if (editingShortcut) {
+        const data = (() => {
+          try {
+            return editingShortcut.action_data
+              ? JSON.parse(editingShortcut.action_data)
+              : {}
+          } catch {
+            return {}
+          }
+        })()
+
+
         setFormData({
           shortcutKey: editingShortcut.shortcut_key,
           actionType: editingShortcut.action_type,
-          configIds: editingShortcut.action_data
-            ? (() => {
-                try {
-                  const data = JSON.parse(editingShortcut.action_data)
-
-                  return data.config_ids || []
-                } catch {
-                  return []
-                }
-              })()
-            : [],
+          configIds: data.config_ids || [],
+          group: data.group || '',
         })
       } else {
         setFormData({
           shortcutKey: '',
           actionType: '',
           configIds: [],
+          group: '',
         })
       }
     }
@@ -150,6 +178,16 @@ const ShortcutFormModal: React.FC<ShortcutFormModalProps> = ({
       return
     }
 
+    if (action.requiresGroup && !formData.group) {
+      toaster.error({
+        title: 'Invalid Input',
+        description: 'Please select a config group for this action',
+        duration: 3000,
+      })
+
+      return
+    }
+
     try {
       setIsLoading(true)
 
@@ -179,7 +217,9 @@ const ShortcutFormModal: React.FC<ShortcutFormModalProps> = ({
 
       const actionData = action.requiresConfig
         ? JSON.stringify({ config_ids: formData.configIds })
-        : undefined
+        : action.requiresGroup
+          ? JSON.stringify({ group: formData.group })
+          : undefined
 
       // Generate unique name by combining action name with shortcut key
       const uniqueName = `${action.name} (${normalizedShortcut})`
@@ -255,6 +295,7 @@ const ShortcutFormModal: React.FC<ShortcutFormModalProps> = ({
       ...prev,
       actionType,
       configIds: action?.requiresConfig ? prev.configIds : [],
+      group: action?.requiresGroup ? prev.group : '',
     }))
   }
 
@@ -366,6 +407,44 @@ const ShortcutFormModal: React.FC<ShortcutFormModalProps> = ({
                 />
               </Box>
 
+              {selectedAction?.requiresGroup && (
+                <Box
+                  bg='#161616'
+                  p={2}
+                  borderRadius='md'
+                  border='1px solid rgba(255, 255, 255, 0.08)'
+                >
+                  <Text fontSize='xs' color='gray.400' mb={1}>
+                    Select Group
+                  </Text>
+                  {groups.length === 0 ? (
+                    <Text fontSize='xs' color='gray.400' textAlign='center'>
+                      No config groups available
+                    </Text>
+                  ) : (
+                    <Stack gap={1}>
+                      {groups.map(group => (
+                        <Checkbox
+                          key={group.name}
+                          checked={formData.group === group.name}
+                          onCheckedChange={e =>
+                            setFormData(prev => ({
+                              ...prev,
+                              group: e.checked ? group.name : '',
+                            }))
+                          }
+                          size='sm'
+                        >
+                          <Text fontSize='xs' color='gray.200'>
+                            {group.name} ({group.config_ids.length})
+                          </Text>
+                        </Checkbox>
+                      ))}
+                    </Stack>
+                  )}
+                </Box>
+              )}
+
               {selectedAction?.requiresConfig && (
                 <Box
                   bg='#161616'
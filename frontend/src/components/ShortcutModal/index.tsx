@@ -70,6 +70,18 @@ const SHORTCUT_ACTIONS: ShortcutAction[] = [
     actionType: 'toggle_port_forward',
     requiresConfig: true,
   },
+  {
+    id: 'start_group',
+    name: 'Start Config Group',
+    actionType: 'start_group',
+    requiresConfig: false,
+  },
+  {
+    id: 'stop_group',
+    name: 'Stop Config Group',
+    actionType: 'stop_group',
+    requiresConfig: false,
+  },
 ]
 
 const ShortcutModal: React.FC<ShortcutModalProps> = ({ isOpen, onClose }) => {
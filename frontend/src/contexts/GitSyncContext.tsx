@@ -8,9 +8,11 @@ import React, {
 } from 'react'
 
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 
 import { toaster } from '@/components/ui/toaster'
-import { GitConfig, SyncStatus } from '@/types'
+import { gitService } from '@/services/gitService'
+import { GitConfig, SyncPreview, SyncStatus } from '@/types'
 
 interface GitSyncContextType {
   credentials: GitConfig | null
@@ -22,6 +24,9 @@ interface GitSyncContextType {
   deleteCredentials: () => Promise<void>
   syncConfigs: () => Promise<void>
   updatePollingInterval: (interval: number) => void
+  pendingPreview: SyncPreview | null
+  approvePreview: () => Promise<void>
+  dismissPreview: () => void
 }
 
 const GitSyncContext = createContext<GitSyncContextType | null>(null)
@@ -41,6 +46,9 @@ export const GitSyncProvider: React.FC<{ children: React.ReactNode }> = ({
     pollingInterval: 60,
     isSyncing: false,
   })
+  const [pendingPreview, setPendingPreview] = useState<SyncPreview | null>(
+    null,
+  )
 
   const cachedCredentials = useMemo(() => credentials, [credentials])
 
@@ -74,9 +82,31 @@ export const GitSyncProvider: React.FC<{ children: React.ReactNode }> = ({
     loadCredentials()
   }, [])
 
+  useEffect(() => {
+    const unlistenPromise = listen<SyncPreview>('git-sync-preview', event => {
+      setPendingPreview(event.payload)
+    })
+
+    return () => {
+      unlistenPromise.then(unlisten => unlisten())
+    }
+  }, [])
+
   const saveCredentials = useCallback(async (newCredentials: GitConfig) => {
     setIsLoading(true)
     try {
+      const subscriptionId = await gitService.saveSubscription(
+        ACCOUNT_NAME,
+        newCredentials,
+      )
+
+      newCredentials = { ...newCredentials, subscriptionId }
+
+      // Record the freshly imported state as the baseline for later diffs.
+      const baseline = await gitService.checkSubscription(subscriptionId)
+
+      await gitService.applyPreview(baseline)
+
       await invoke('store_key', {
         service: SERVICE_NAME,
         name: ACCOUNT_NAME,
@@ -99,6 +129,35 @@ export const GitSyncProvider: React.FC<{ children: React.ReactNode }> = ({
 
     setSyncStatus(prev => ({ ...prev, isSyncing: true }))
     try {
+      if (credentials.subscriptionId !== undefined) {
+        const preview = await gitService.checkSubscription(
+          credentials.subscriptionId,
+        )
+        const hasChanges =
+          preview.diff.added.length > 0 ||
+          preview.diff.removed.length > 0 ||
+          preview.diff.changed.length > 0
+
+        setSyncStatus(prev => ({
+          ...prev,
+          lastSyncTime: Date.now(),
+          isSuccessful: true,
+          isSyncing: false,
+        }))
+
+        if (hasChanges) {
+          setPendingPreview(preview)
+        } else {
+          toaster.success({
+            title: 'Up to date',
+            description: 'No config changes in the Git source',
+            duration: 1000,
+          })
+        }
+
+        return
+      }
+
       await invoke('import_configs_from_github', {
         repoUrl: credentials.repoUrl,
         configPath: credentials.configPath,
@@ -106,6 +165,10 @@ export const GitSyncProvider: React.FC<{ children: React.ReactNode }> = ({
         flush: credentials.flush ?? false,
         githubToken:
           credentials.authMethod === 'token' ? credentials.token : null,
+        gitRef: credentials.gitRef || null,
+        insecureSkipVerify: credentials.insecureSkipVerify ?? false,
+        caBundlePath: credentials.caBundlePath || null,
+        provider: credentials.provider || null,
       })
 
       const now = new Date()
@@ -132,6 +195,23 @@ export const GitSyncProvider: React.FC<{ children: React.ReactNode }> = ({
     }
   }, [credentials, syncStatus.isSyncing])
 
+  const approvePreview = useCallback(async () => {
+    if (!pendingPreview) {
+      return
+    }
+
+    const result = await gitService.applyPreview(pendingPreview)
+
+    setPendingPreview(null)
+    toaster.success({
+      title: 'Git sync applied',
+      description: `${result.added} added, ${result.changed_config_ids.length} changed, ${result.removed_config_ids.length} removed`,
+      duration: 2000,
+    })
+  }, [pendingPreview])
+
+  const dismissPreview = useCallback(() => setPendingPreview(null), [])
+
   const value = useMemo(
     () => ({
       credentials: cachedCredentials,
@@ -147,6 +227,9 @@ export const GitSyncProvider: React.FC<{ children: React.ReactNode }> = ({
         : null,
       saveCredentials,
       deleteCredentials: async () => {
+        if (credentials?.subscriptionId !== undefined) {
+          await gitService.deleteSubscription(credentials.subscriptionId)
+        }
         await invoke('delete_key', {
           service: SERVICE_NAME,
           name: ACCOUNT_NAME,
@@ -157,8 +240,21 @@ export const GitSyncProvider: React.FC<{ children: React.ReactNode }> = ({
       syncConfigs,
       updatePollingInterval: (interval: number) =>
         setSyncStatus(prev => ({ ...prev, pollingInterval: interval })),
+      pendingPreview,
+      approvePreview,
+      dismissPreview,
     }),
-    [cachedCredentials, isLoading, syncStatus, saveCredentials, syncConfigs],
+    [
+      cachedCredentials,
+      credentials,
+      isLoading,
+      syncStatus,
+      saveCredentials,
+      syncConfigs,
+      pendingPreview,
+      approvePreview,
+      dismissPreview,
+    ],
   )
 
   return (
@@ -41,6 +41,13 @@ export function useSyncManager({
         throw new Error('No git credentials found')
       }
 
+      // Subscriptions are polled by the backend and applied after approval.
+      if (credentials.subscriptionId !== undefined) {
+        updateSyncStatus({ isSyncing: false })
+
+        return
+      }
+
       await gitService.importConfigs(credentials)
       updateSyncStatus({
         lastSyncTime: Date.now(),
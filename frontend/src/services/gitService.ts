@@ -1,6 +1,11 @@
 import { invoke } from '@tauri-apps/api/core'
 
-import { GitConfig } from '@/types'
+import {
+  GitConfig,
+  GitSyncSubscription,
+  SyncApplyResult,
+  SyncPreview,
+} from '@/types'
 
 export const gitService = {
   async saveCredentials(
@@ -46,9 +51,48 @@ export const gitService = {
       flush: credentials.flush ?? false,
       githubToken:
         credentials.authMethod === 'token' ? credentials.token : null,
+      gitRef: credentials.gitRef || null,
+      insecureSkipVerify: credentials.insecureSkipVerify ?? false,
+      caBundlePath: credentials.caBundlePath || null,
+      provider: credentials.provider || null,
     })
   },
 
+  async saveSubscription(
+    accountName: string,
+    credentials: GitConfig,
+  ): Promise<number> {
+    const subscription: GitSyncSubscription = {
+      id: credentials.subscriptionId,
+      name: accountName,
+      repo_url: credentials.repoUrl,
+      config_path: credentials.configPath,
+      git_ref: credentials.gitRef || null,
+      credentials_ref: credentials.authMethod === 'token' ? accountName : null,
+      use_system_credentials: credentials.authMethod === 'system',
+      insecure_skip_verify: credentials.insecureSkipVerify ?? false,
+      ca_bundle_path: credentials.caBundlePath || null,
+      provider: credentials.provider || null,
+      interval_secs: Math.max(0, credentials.pollingInterval) * 60,
+      enabled: true,
+      applied_keys: [],
+    }
+
+    return invoke<number>('save_git_sync_subscription_cmd', { subscription })
+  },
+
+  async deleteSubscription(id: number) {
+    await invoke('delete_git_sync_subscription_cmd', { id })
+  },
+
+  async checkSubscription(id: number): Promise<SyncPreview> {
+    return invoke<SyncPreview>('check_git_sync_cmd', { id })
+  },
+
+  async applyPreview(preview: SyncPreview): Promise<SyncApplyResult> {
+    return invoke<SyncApplyResult>('apply_git_sync_cmd', { preview })
+  },
+
   async deleteCredentials(serviceName: string, accountName: string) {
     await invoke('delete_key', {
       service: serviceName,
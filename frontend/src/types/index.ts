@@ -22,6 +22,62 @@ export interface Config {
   cert_issuer_kind?: string
   ingress_class?: string
   ingress_annotations?: string
+  groups?: string[]
+  port_conflict_policy?: string
+  impersonate_user?: string
+  impersonate_groups?: string[]
+  pod_selection?: PodSelectionPreferences
+  drain_policy?: 'keep' | 'drain' | 'close'
+  drain_timeout_secs?: number
+  debug_container?: 'auto' | 'always'
+  proxy_relay?: 'shared' | 'dedicated'
+}
+
+export interface PodSelectionPreferences {
+  exclude_terminating?: boolean
+  restart_grace_secs?: number
+  required_container?: string
+  preferred_node?: string
+  preferred_zone?: string
+  preferred_annotation?: string
+  prefer_age?: 'newest' | 'oldest'
+}
+
+export interface ConfigState {
+  id?: number
+  config_id: number
+  is_running: boolean
+  process_id?: number
+  local_port?: number
+}
+
+export interface ConfigGroup {
+  id?: number
+  name: string
+  config_ids: number[]
+}
+
+export interface ConfigRevision {
+  id: number
+  config_id: number
+  action: 'create' | 'update' | 'delete' | 'restore'
+  source: 'gui' | 'tui' | 'import' | 'git_sync' | 'unknown'
+  label: string
+  before?: Config | null
+  after?: Config | null
+  changed_at: number
+}
+
+export interface ValidationIssue {
+  severity: 'warning' | 'error'
+  field: string
+  message: string
+}
+
+export interface ConfigValidation {
+  config_id?: number
+  label: string
+  issues: ValidationIssue[]
 }
 
 type AuthMethod = 'none' | 'system' | 'token'
@@ -29,11 +85,62 @@ type AuthMethod = 'none' | 'system' | 'token'
 export interface GitConfig {
   repoUrl: string
   configPath: string
+  gitRef?: string
+  provider?: string
+  caBundlePath?: string
+  insecureSkipVerify?: boolean
   authMethod: AuthMethod
   token?: string
   isPrivate?: boolean
   pollingInterval: number
   flush?: boolean
+  subscriptionId?: number
+}
+
+export interface GitSyncSubscription {
+  id?: number
+  name: string
+  repo_url: string
+  config_path: string
+  git_ref?: string | null
+  credentials_ref?: string | null
+  use_system_credentials: boolean
+  insecure_skip_verify: boolean
+  ca_bundle_path?: string | null
+  provider?: string | null
+  interval_secs: number
+  enabled: boolean
+  last_commit_sha?: string | null
+  applied_keys: string[]
+  last_checked_at?: number | null
+  last_synced_at?: number | null
+}
+
+export interface ConfigChange {
+  key: string
+  before: Config
+  after: Config
+  fields: string[]
+}
+
+export interface ConfigDiff {
+  added: Config[]
+  removed: Config[]
+  changed: ConfigChange[]
+  unchanged: number
+}
+
+export interface SyncPreview {
+  subscription_id: number
+  commit_sha: string
+  diff: ConfigDiff
+  incoming_keys: string[]
+}
+
+export interface SyncApplyResult {
+  changed_config_ids: number[]
+  removed_config_ids: number[]
+  added: number
 }
 
 export interface GitSyncModalProps {
@@ -106,6 +213,8 @@ export interface FooterProps {
   onSyncComplete: () => void
   openShortcutModal: () => void
   setIsAutoImportModalOpen: (open: boolean) => void
+  openConfigHistory: (configId?: number) => void
+  openContextProxies: () => void
 }
 
 export interface SyncConfigsButtonProps {
@@ -127,6 +236,37 @@ export interface KubeContext {
   user?: string
 }
 
+export interface AuthPrompt {
+  command: string
+  message: string
+  url?: string | null
+}
+
+export interface DiagnosticCheck {
+  check: string
+  status: 'ok' | 'warning' | 'error' | 'skipped'
+  detail: string
+  elapsed_ms?: number | null
+}
+
+export interface ConnectionReport {
+  context: string
+  server: string
+  auth_method: string
+  proxy?: string | null
+  checks: DiagnosticCheck[]
+  certificates: {
+    subject: string
+    issuer: string
+    not_after: string
+    days_remaining: number
+  }[]
+  strategies: {
+    strategy: string
+    checks: DiagnosticCheck[]
+  }[]
+}
+
 export interface CustomConfigProps {
   isModalOpen: boolean
   closeModal: () => void
@@ -195,6 +335,26 @@ export interface AutoImportModalProps {
   onClose: () => void
 }
 
+export interface ContextProxy {
+  context: string
+  proxy_url: string
+  username?: string
+  credentials_ref?: string
+  no_proxy: string[]
+  enabled: boolean
+}
+
+export interface ContextProxyModalProps {
+  isOpen: boolean
+  onClose: () => void
+}
+
+export interface ConfigHistoryModalProps {
+  isOpen: boolean
+  onClose: () => void
+  configId?: number
+}
+
 export interface ServiceData {
   name: string
   port?: number